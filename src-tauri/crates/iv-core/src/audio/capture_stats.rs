@@ -0,0 +1,165 @@
+//! When a recording started and stopped, and how much audio it lost
+//!
+//! On an overloaded machine the input callback can fall behind and the
+//! driver throws buffers away (an overrun, or xrun). The recording then has
+//! holes in it and the transcript comes out garbled, with nothing else to
+//! show why. cpal doesn't report overruns, so they're found from the
+//! callbacks' capture timestamps: audio captured later than the end of the
+//! previous buffer means the buffers in between were dropped.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use cpal::{InputCallbackInfo, StreamInstant};
+use serde::Serialize;
+
+/// Timing and dropped buffers for one recording
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct CaptureStats {
+    /// Wall-clock time of the first recorded sample, in milliseconds since
+    /// the Unix epoch
+    pub started_at_ms: Option<u64>,
+    /// Wall-clock time just after the last recorded sample
+    pub stopped_at_ms: Option<u64>,
+    /// Buffers the driver dropped while recording
+    pub dropped_buffers: u64,
+}
+
+/// Milliseconds since the Unix epoch
+fn epoch_ms(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Counts dropped buffers from the input callback's capture timestamps
+pub(crate) struct XrunDetector {
+    sample_rate: u32,
+    /// Capture time of the first buffer, which the others are measured from
+    first: Option<StreamInstant>,
+    /// When the next buffer's audio should start, after the first
+    expected: Option<Duration>,
+    /// Total dropped since the stream opened, read by the recording loop
+    dropped: Arc<AtomicU64>,
+}
+
+impl XrunDetector {
+    pub(crate) fn new(sample_rate: u32, dropped: Arc<AtomicU64>) -> Self {
+        Self {
+            sample_rate,
+            first: None,
+            expected: None,
+            dropped,
+        }
+    }
+
+    /// Note a callback delivering `frames` frames
+    pub(crate) fn on_callback(&mut self, info: &InputCallbackInfo, frames: usize) {
+        let capture = info.timestamp().capture;
+        let first = *self.first.get_or_insert(capture);
+        let Some(offset) = capture.duration_since(&first) else {
+            return;
+        };
+
+        let dropped = self.observe(offset, frames);
+        if dropped > 0 {
+            log::warn!("Audio input overrun, {} buffer(s) dropped", dropped);
+            self.dropped.fetch_add(dropped, Ordering::Relaxed);
+        }
+    }
+
+    /// Buffers dropped before one captured at `capture` (after the first)
+    fn observe(&mut self, capture: Duration, frames: usize) -> u64 {
+        if frames == 0 {
+            return 0;
+        }
+        let buffer = Duration::from_secs_f64(frames as f64 / self.sample_rate as f64);
+
+        // Timestamps jitter, so only a gap of over half a buffer counts
+        let dropped = match self.expected {
+            Some(expected) if capture > expected + buffer / 2 => {
+                let gap = capture - expected;
+                (gap.as_secs_f64() / buffer.as_secs_f64()).round().max(1.0) as u64
+            }
+            _ => 0,
+        };
+        self.expected = Some(capture + buffer);
+        dropped
+    }
+}
+
+/// Keeps a recorder's [`CaptureStats`] from the recording loop
+pub(crate) struct CaptureTracker {
+    sample_rate: u32,
+    /// The stream's dropped buffer count, for device streams
+    dropped: Option<Arc<AtomicU64>>,
+    dropped_at_start: u64,
+    current: CaptureStats,
+    /// Where the last recording's stats are left when it stops
+    last: Arc<Mutex<CaptureStats>>,
+}
+
+impl CaptureTracker {
+    pub(crate) fn new(
+        sample_rate: u32,
+        dropped: Option<Arc<AtomicU64>>,
+        last: Arc<Mutex<CaptureStats>>,
+    ) -> Self {
+        Self {
+            sample_rate,
+            dropped,
+            dropped_at_start: 0,
+            current: CaptureStats::default(),
+            last,
+        }
+    }
+
+    fn dropped_total(&self) -> u64 {
+        self.dropped
+            .as_ref()
+            .map_or(0, |dropped| dropped.load(Ordering::Relaxed))
+    }
+
+    pub(crate) fn start(&mut self) {
+        self.current = CaptureStats::default();
+        self.dropped_at_start = self.dropped_total();
+    }
+
+    /// Note `samples` mono samples just received while recording
+    pub(crate) fn on_samples(&mut self, samples: usize) {
+        let now = SystemTime::now();
+        if self.current.started_at_ms.is_none() {
+            // The first sample was captured a buffer's length ago
+            let buffer = Duration::from_secs_f64(samples as f64 / self.sample_rate as f64);
+            self.current.started_at_ms = Some(epoch_ms(now - buffer));
+        }
+        self.current.stopped_at_ms = Some(epoch_ms(now));
+    }
+
+    pub(crate) fn stop(&mut self) {
+        self.current.dropped_buffers = self.dropped_total() - self.dropped_at_start;
+        *self.last.lock().unwrap() = std::mem::take(&mut self.current);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xrun_detector_counts_gaps() {
+        let mut detector = XrunDetector::new(1000, Arc::new(AtomicU64::new(0)));
+        let ms = Duration::from_millis;
+
+        // Back to back 10ms buffers, with a little jitter
+        assert_eq!(detector.observe(ms(0), 10), 0);
+        assert_eq!(detector.observe(ms(10), 10), 0);
+        assert_eq!(detector.observe(ms(22), 10), 0);
+
+        // Two buffers missing before this one
+        assert_eq!(detector.observe(ms(52), 10), 2);
+        assert_eq!(detector.observe(ms(62), 10), 0);
+        assert_eq!(detector.observe(ms(72), 0), 0);
+    }
+}
@@ -0,0 +1,148 @@
+//! Per-device stream config overrides
+//!
+//! Input streams are normally opened with a config chosen automatically
+//! (see [`AudioRecorder::get_preferred_config`]). For devices it picks badly
+//! for, such as audio interfaces with the microphone on a second input or
+//! devices that crackle at their default rate, the sample rate, channel and
+//! sample format can be fixed instead.
+
+use cpal::traits::DeviceTrait;
+use cpal::{Device, SampleFormat, SampleRate, SupportedStreamConfig, SupportedStreamConfigRange};
+use serde::{Deserialize, Serialize};
+
+use super::{device_name, format_score, AudioRecorder, PREFERRED_SAMPLE_RATES};
+
+/// Stream settings replacing the automatic choice for a device. Fields left
+/// unset are still chosen automatically.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct DeviceConfigOverride {
+    /// Sample rate in Hz
+    pub sample_rate: Option<u32>,
+    /// Record only this input channel (1-based) instead of mixing them all
+    pub channel: Option<u16>,
+    /// Sample format, as named by [`device_capabilities`] (e.g. "f32")
+    pub sample_format: Option<String>,
+}
+
+impl DeviceConfigOverride {
+    /// The channel to record as a 0-based index, if one is set
+    pub(crate) fn channel_index(&self) -> Option<usize> {
+        self.channel.and_then(|c| (c as usize).checked_sub(1))
+    }
+}
+
+/// A range of stream configs a device supports
+#[derive(Serialize, Debug, Clone)]
+pub struct SupportedInputConfig {
+    pub channels: u16,
+    pub min_sample_rate: u32,
+    pub max_sample_rate: u32,
+    pub sample_format: String,
+}
+
+/// One stream config
+#[derive(Serialize, Debug, Clone)]
+pub struct InputConfig {
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub sample_format: String,
+}
+
+impl From<&SupportedStreamConfig> for InputConfig {
+    fn from(config: &SupportedStreamConfig) -> Self {
+        Self {
+            channels: config.channels(),
+            sample_rate: config.sample_rate().0,
+            sample_format: format_name(config.sample_format()),
+        }
+    }
+}
+
+/// What an input device supports, for picking an override
+#[derive(Serialize, Debug, Clone)]
+pub struct DeviceCapabilities {
+    pub device_name: String,
+    pub configs: Vec<SupportedInputConfig>,
+    /// The config used when nothing is overridden
+    pub automatic: Option<InputConfig>,
+}
+
+/// Lowercase name of a sample format ("f32", "i16", ...)
+pub fn format_name(format: SampleFormat) -> String {
+    format!("{:?}", format).to_lowercase()
+}
+
+/// Every config range a device supports, and the config chosen automatically
+pub fn device_capabilities(device: &Device) -> Result<DeviceCapabilities, anyhow::Error> {
+    let configs = device
+        .supported_input_configs()?
+        .map(|range| SupportedInputConfig {
+            channels: range.channels(),
+            min_sample_rate: range.min_sample_rate().0,
+            max_sample_rate: range.max_sample_rate().0,
+            sample_format: format_name(range.sample_format()),
+        })
+        .collect();
+
+    Ok(DeviceCapabilities {
+        device_name: device_name(device),
+        configs,
+        automatic: AudioRecorder::get_preferred_config(device)
+            .ok()
+            .map(|config| InputConfig::from(&config)),
+    })
+}
+
+/// The config to open a device with: the override's fields where the device
+/// supports them all together, otherwise the automatic choice
+pub(crate) fn choose_config(
+    device: &Device,
+    config_override: &DeviceConfigOverride,
+) -> Result<SupportedStreamConfig, anyhow::Error> {
+    if *config_override == DeviceConfigOverride::default() {
+        return AudioRecorder::get_preferred_config(device);
+    }
+
+    let supports = |range: &SupportedStreamConfigRange| {
+        let format_ok = config_override
+            .sample_format
+            .as_deref()
+            .is_none_or(|format| format_name(range.sample_format()) == format);
+        let rate_ok = config_override.sample_rate.is_none_or(|rate| {
+            range.min_sample_rate().0 <= rate && rate <= range.max_sample_rate().0
+        });
+        let channel_ok = config_override
+            .channel_index()
+            .is_none_or(|index| index < range.channels() as usize);
+        format_ok && rate_ok && channel_ok
+    };
+
+    let best = device
+        .supported_input_configs()?
+        .filter(supports)
+        .max_by_key(|range| format_score(range.sample_format()));
+
+    let Some(range) = best else {
+        log::warn!(
+            "{} doesn't support {:?}, choosing its config automatically",
+            device_name(device),
+            config_override
+        );
+        return AudioRecorder::get_preferred_config(device);
+    };
+
+    let in_range =
+        |rate: u32| range.min_sample_rate().0 <= rate && rate <= range.max_sample_rate().0;
+    let rate = config_override
+        .sample_rate
+        .or_else(|| {
+            PREFERRED_SAMPLE_RATES
+                .into_iter()
+                .find(|&rate| in_range(rate))
+        })
+        .unwrap_or(range.max_sample_rate().0);
+
+    log::info!("Using config override {:?}", config_override);
+    Ok(range.with_sample_rate(SampleRate(rate)))
+}
@@ -0,0 +1,191 @@
+//! Input devices across every audio host
+//!
+//! A device name alone is ambiguous: the same microphone shows up under both
+//! WASAPI and ASIO on Windows or ALSA and JACK on Linux, and two identical
+//! USB microphones share a name on any host. Devices are identified instead
+//! by their host, their name and, for repeated names, their position among
+//! them: "ALSA:USB Microphone", "ALSA:USB Microphone#2". Bare names, as
+//! saved by earlier versions, still select the first device with that name
+//! on the default host.
+
+use cpal::traits::{DeviceTrait, HostTrait};
+use cpal::{Device, Host, HostId};
+use serde::Serialize;
+
+use super::device_name;
+
+/// Sample rates reported in [`InputDeviceInfo::sample_rates`] when a device
+/// supports them
+const COMMON_SAMPLE_RATES: [u32; 11] = [
+    8000, 11025, 16000, 22050, 32000, 44100, 48000, 88200, 96000, 176400, 192000,
+];
+
+/// An input device, for choosing one in settings
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct InputDeviceInfo {
+    /// Unique across hosts, and stable as long as the same devices are connected
+    pub id: String,
+    pub name: String,
+    /// The audio host (backend) the device is on, e.g. "WASAPI" or "ALSA"
+    pub host: String,
+    /// Whether this is the default host's default input device
+    pub is_default: bool,
+    /// Common sample rates the device supports
+    pub sample_rates: Vec<u32>,
+}
+
+/// The id of the `occurrence`th (0-based) device named `name` on a host
+fn device_id(host: HostId, name: &str, occurrence: usize) -> String {
+    match occurrence {
+        0 => format!("{}:{}", host.name(), name),
+        n => format!("{}:{}#{}", host.name(), name, n + 1),
+    }
+}
+
+/// Every input device on a host, with its id
+fn host_input_devices(host: &Host) -> Result<Vec<(String, Device)>, anyhow::Error> {
+    let mut names: Vec<String> = Vec::new();
+    let devices = host
+        .input_devices()?
+        .map(|device| {
+            let name = device_name(&device);
+            let occurrence = names.iter().filter(|n| **n == name).count();
+            let id = device_id(host.id(), &name, occurrence);
+            names.push(name);
+            (id, device)
+        })
+        .collect();
+    Ok(devices)
+}
+
+/// Every available host, the default one first
+fn hosts() -> Vec<Host> {
+    let default = cpal::default_host();
+    let default_id = default.id();
+    let others = cpal::available_hosts()
+        .into_iter()
+        .filter(|&id| id != default_id)
+        .filter_map(|id| match cpal::host_from_id(id) {
+            Ok(host) => Some(host),
+            Err(e) => {
+                log::debug!("Audio host {} unavailable: {}", id.name(), e);
+                None
+            }
+        });
+    std::iter::once(default).chain(others).collect()
+}
+
+fn sample_rates(device: &Device) -> Vec<u32> {
+    let Ok(configs) = device.supported_input_configs() else {
+        return Vec::new();
+    };
+    let mut rates: Vec<u32> = configs
+        .flat_map(|range| {
+            COMMON_SAMPLE_RATES.into_iter().filter(move |&rate| {
+                range.min_sample_rate().0 <= rate && rate <= range.max_sample_rate().0
+            })
+        })
+        .collect();
+    rates.sort_unstable();
+    rates.dedup();
+    rates
+}
+
+/// Every input device on every available host
+pub fn list_input_devices() -> Result<Vec<InputDeviceInfo>, anyhow::Error> {
+    let default_id = default_input_device().ok().map(|(id, _)| id);
+
+    let mut infos = Vec::new();
+    for host in hosts() {
+        let devices = match host_input_devices(&host) {
+            Ok(devices) => devices,
+            Err(e) => {
+                log::warn!("Couldn't list {} input devices: {}", host.id().name(), e);
+                continue;
+            }
+        };
+        infos.extend(devices.into_iter().map(|(id, device)| InputDeviceInfo {
+            is_default: default_id.as_ref() == Some(&id),
+            name: device_name(&device),
+            host: host.id().name().to_string(),
+            sample_rates: sample_rates(&device),
+            id,
+        }));
+    }
+    Ok(infos)
+}
+
+/// The default host's default input device and its id
+fn default_input_device() -> Result<(String, Device), anyhow::Error> {
+    let host = cpal::default_host();
+    let device = host
+        .default_input_device()
+        .ok_or_else(|| anyhow::anyhow!("No default input device available"))?;
+    let name = device_name(&device);
+
+    // Its id is that of the first device listed with the same name
+    let id = host_input_devices(&host)
+        .ok()
+        .and_then(|devices| {
+            devices
+                .into_iter()
+                .find(|(_, d)| device_name(d) == name)
+                .map(|(id, _)| id)
+        })
+        .unwrap_or_else(|| device_id(host.id(), &name, 0));
+    Ok((id, device))
+}
+
+/// The input device with the given id (or bare name) and its id, or the
+/// default device when none is given or it isn't connected
+pub fn find_input_device(selection: Option<&str>) -> Result<(String, Device), anyhow::Error> {
+    let Some(selection) = selection else {
+        return default_input_device();
+    };
+
+    let by_id = hosts().into_iter().find_map(|host| {
+        host_input_devices(&host)
+            .ok()?
+            .into_iter()
+            .find(|(id, _)| id == selection)
+    });
+    if let Some(found) = by_id {
+        return Ok(found);
+    }
+
+    let host = cpal::default_host();
+    let by_name = host_input_devices(&host)?
+        .into_iter()
+        .find(|(_, device)| device_name(device) == selection);
+    match by_name {
+        Some(found) => Ok(found),
+        None => {
+            log::warn!("Input device '{}' not found, using the default", selection);
+            default_input_device()
+        }
+    }
+}
+
+/// The input device with the given id (or bare name), or the default device
+/// when none is given or it isn't connected
+pub fn input_device(selection: Option<&str>) -> Result<Device, anyhow::Error> {
+    find_input_device(selection).map(|(_, device)| device)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_device_id_numbers_repeated_names() {
+        let host = cpal::default_host().id();
+        assert_eq!(
+            device_id(host, "USB Microphone", 0),
+            format!("{}:USB Microphone", host.name())
+        );
+        assert_eq!(
+            device_id(host, "USB Microphone", 1),
+            format!("{}:USB Microphone#2", host.name())
+        );
+    }
+}
@@ -0,0 +1,780 @@
+//! Audio recorder that:
+//! 1. Opens a microphone stream
+//! 2. Records audio samples when started
+//! 3. Returns samples when stopped
+//! 4. Emits audio level updates during recording
+//! 5. Streams captured samples to an optional callback (e.g. live VAD)
+//!
+//! Audio can also come from a single application instead of a microphone
+//! (see [`crate::app_capture`]) or from the system output (see
+//! [`crate::system_capture`]).
+
+mod capture_stats;
+mod device_config;
+mod device_list;
+mod resample;
+
+use std::process::Child;
+use std::sync::atomic::AtomicU64;
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+
+use cpal::{
+    traits::{DeviceTrait, HostTrait, StreamTrait},
+    Device, Sample, SizedSample, Stream,
+};
+
+use crate::app_capture;
+use crate::system_capture::{self, LoopbackSource};
+use capture_stats::{CaptureTracker, XrunDetector};
+
+pub use capture_stats::CaptureStats;
+pub use device_config::{
+    device_capabilities, format_name, DeviceCapabilities, DeviceConfigOverride, InputConfig,
+    SupportedInputConfig,
+};
+pub use device_list::{find_input_device, input_device, list_input_devices, InputDeviceInfo};
+pub use resample::{resample, StreamResampler};
+
+enum RecorderCommand {
+    // Start recording - clear buffer and begin capturing
+    Start,
+    // Stop recording - return captured samples via the channel
+    Stop(mpsc::Sender<Vec<f32>>),
+    // Shutdown worker thread
+    Shutdown,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RecorderState {
+    Idle,
+    Recording,
+    Processing,
+}
+
+/// Callback for audio level updates (0.0 to 1.0)
+pub type AudioLevelCallback = Arc<dyn Fn(f32) + Send + Sync>;
+
+/// Callback receiving captured mono samples (at the device rate) while recording
+pub type SampleCallback = Arc<dyn Fn(&[f32]) + Send + Sync>;
+
+/// Callback told why the stream was lost (e.g. the device was unplugged)
+pub type StreamLostCallback = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// A recording stream that delivers nothing for this long is taken as lost,
+/// for platforms that don't report a disconnected device
+const STREAM_STALL_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Sample rates tried when choosing a device's config, best first
+const PREFERRED_SAMPLE_RATES: [u32; 5] = [16000, 44100, 48000, 22050, 8000];
+
+/// How much a sample format is preferred when choosing a device's config
+fn format_score(format: cpal::SampleFormat) -> u8 {
+    match format {
+        cpal::SampleFormat::F32 => 3,
+        cpal::SampleFormat::I16 => 2,
+        _ => 1,
+    }
+}
+
+pub struct AudioRecorder {
+    device: Option<Device>,
+    cmd_tx: Option<mpsc::Sender<RecorderCommand>>,
+    worker_handle: Option<std::thread::JoinHandle<()>>,
+    sample_rate: Arc<Mutex<u32>>,
+    audio_level_callback: Option<AudioLevelCallback>,
+    sample_callback: Option<SampleCallback>,
+    stream_lost_callback: Option<StreamLostCallback>,
+    buffer_samples: bool,
+    /// Replaces the automatic stream config choice when opening a device
+    config_override: DeviceConfigOverride,
+    /// Capture process when recording an application instead of a device
+    capture_process: Option<Child>,
+    /// Timing and dropped buffers of the last recording
+    capture_stats: Arc<Mutex<CaptureStats>>,
+}
+
+impl AudioRecorder {
+    /// Create a new audio recorder
+    pub fn new() -> Result<Self, anyhow::Error> {
+        Ok(AudioRecorder {
+            device: None,
+            cmd_tx: None,
+            worker_handle: None,
+            sample_rate: Arc::new(Mutex::new(16000)),
+            audio_level_callback: None,
+            sample_callback: None,
+            stream_lost_callback: None,
+            buffer_samples: true,
+            config_override: DeviceConfigOverride::default(),
+            capture_process: None,
+            capture_stats: Arc::new(Mutex::new(CaptureStats::default())),
+        })
+    }
+
+    /// Set the callback for audio level updates
+    pub fn set_audio_level_callback<F>(&mut self, callback: F)
+    where
+        F: Fn(f32) + Send + Sync + 'static,
+    {
+        self.audio_level_callback = Some(Arc::new(callback));
+    }
+
+    /// Set the callback receiving captured samples while recording
+    pub fn set_sample_callback<F>(&mut self, callback: F)
+    where
+        F: Fn(&[f32]) + Send + Sync + 'static,
+    {
+        self.sample_callback = Some(Arc::new(callback));
+    }
+
+    /// Set the callback told when the stream stops delivering audio while
+    /// recording, e.g. because the device was disconnected. Called from the
+    /// stream's threads, possibly more than once for the same loss.
+    pub fn set_stream_lost_callback<F>(&mut self, callback: F)
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        self.stream_lost_callback = Some(Arc::new(callback));
+    }
+
+    /// Whether to keep captured samples for `stop` (default true). Disable for
+    /// long-running streams that only feed the sample callback.
+    pub fn set_buffer_samples(&mut self, buffer: bool) {
+        self.buffer_samples = buffer;
+    }
+
+    /// Set the sample rate, channel or format to open the next device with,
+    /// instead of choosing them automatically
+    pub fn set_config_override(&mut self, config_override: DeviceConfigOverride) {
+        self.config_override = config_override;
+    }
+
+    /// Open the audio stream with the specified device (or default if None)
+    pub fn open(&mut self, device: Option<Device>) -> Result<(), anyhow::Error> {
+        if self.worker_handle.is_some() {
+            log::debug!("AudioRecorder already open");
+            return Ok(());
+        }
+
+        // Get the host and device
+        let host = cpal::default_host();
+        let device = match device {
+            Some(dev) => dev,
+            None => host
+                .default_input_device()
+                .ok_or_else(|| anyhow::anyhow!("No default input device available"))?,
+        };
+
+        let device_name = device.name().unwrap_or_else(|_| "Unknown".to_string());
+        log::info!("Using audio device: {}", device_name);
+
+        // Get the best config for this device, unless it's overridden
+        let config = device_config::choose_config(&device, &self.config_override)?;
+        let channel = self
+            .config_override
+            .channel_index()
+            .filter(|&index| index < config.channels() as usize);
+        self.open_stream(device, config, channel)
+    }
+
+    /// Open an input stream on a device with a specific config, recording
+    /// one channel (0-based) or a mix of all of them
+    fn open_stream(
+        &mut self,
+        device: Device,
+        config: cpal::SupportedStreamConfig,
+        channel: Option<usize>,
+    ) -> Result<(), anyhow::Error> {
+        // Create channels for communication
+        let (sample_tx, sample_rx) = mpsc::channel::<Vec<f32>>();
+        let (cmd_tx, cmd_rx) = mpsc::channel::<RecorderCommand>();
+
+        let sample_rate = config.sample_rate().0;
+        let channels = config.channels() as usize;
+
+        // Store the sample rate
+        *self.sample_rate.lock().unwrap() = sample_rate;
+
+        log::info!(
+            "Audio config: {} Hz, {} channel(s), format: {:?}",
+            sample_rate,
+            channels,
+            config.sample_format()
+        );
+        if let Some(channel) = channel {
+            log::info!("Recording channel {} only", channel + 1);
+        }
+
+        // Clone device for the thread
+        let thread_device = device.clone();
+
+        let dropped = Arc::new(AtomicU64::new(0));
+        let tracker = CaptureTracker::new(
+            sample_rate,
+            Some(Arc::clone(&dropped)),
+            Arc::clone(&self.capture_stats),
+        );
+
+        // Clone the audio level callback for the worker thread
+        let level_callback = self.audio_level_callback.clone();
+        let sample_callback = self.sample_callback.clone();
+        let lost_callback = self.stream_lost_callback.clone();
+        let buffer_samples = self.buffer_samples;
+
+        // Spawn worker thread
+        let worker = std::thread::spawn(move || {
+            // Build stream based on sample format
+            let stream = match config.sample_format() {
+                cpal::SampleFormat::F32 => Self::build_stream::<f32>(
+                    &thread_device,
+                    &config,
+                    sample_tx.clone(),
+                    channels,
+                    channel,
+                    lost_callback.clone(),
+                    Arc::clone(&dropped),
+                ),
+                cpal::SampleFormat::I16 => Self::build_stream::<i16>(
+                    &thread_device,
+                    &config,
+                    sample_tx.clone(),
+                    channels,
+                    channel,
+                    lost_callback.clone(),
+                    Arc::clone(&dropped),
+                ),
+                cpal::SampleFormat::I32 => Self::build_stream::<i32>(
+                    &thread_device,
+                    &config,
+                    sample_tx.clone(),
+                    channels,
+                    channel,
+                    lost_callback.clone(),
+                    Arc::clone(&dropped),
+                ),
+                cpal::SampleFormat::U8 => Self::build_stream::<u8>(
+                    &thread_device,
+                    &config,
+                    sample_tx.clone(),
+                    channels,
+                    channel,
+                    lost_callback.clone(),
+                    Arc::clone(&dropped),
+                ),
+                format => {
+                    log::error!("Unsupported sample format: {:?}", format);
+                    return;
+                }
+            };
+
+            let stream = match stream {
+                Ok(s) => s,
+                Err(e) => {
+                    log::error!("Failed to build audio stream: {}", e);
+                    return;
+                }
+            };
+
+            // Start the stream
+            if let Err(e) = stream.play() {
+                log::error!("Failed to play audio stream: {}", e);
+                return;
+            }
+
+            log::info!("Audio stream started");
+
+            run_recording_loop(
+                sample_rx,
+                cmd_rx,
+                level_callback,
+                sample_callback,
+                lost_callback,
+                buffer_samples,
+                tracker,
+            );
+
+            log::info!("Audio worker thread exiting");
+        });
+
+        self.device = Some(device);
+        self.cmd_tx = Some(cmd_tx);
+        self.worker_handle = Some(worker);
+
+        Ok(())
+    }
+
+    /// Open a stream capturing a single application's audio, using a target
+    /// id from [`crate::app_capture::list_capture_targets`]
+    pub fn open_app(&mut self, target_id: &str) -> Result<(), anyhow::Error> {
+        if self.worker_handle.is_some() {
+            log::debug!("AudioRecorder already open");
+            return Ok(());
+        }
+
+        log::info!("Capturing audio from application stream {}", target_id);
+        self.open_process(|sample_tx| app_capture::spawn_capture(target_id, sample_tx))
+    }
+
+    /// Open a stream of everything the system is playing (loopback) instead
+    /// of a microphone. See [`crate::system_capture`] for what each platform
+    /// needs.
+    pub fn open_system(&mut self) -> Result<(), anyhow::Error> {
+        if self.worker_handle.is_some() {
+            log::debug!("AudioRecorder already open");
+            return Ok(());
+        }
+
+        match system_capture::loopback_source()? {
+            LoopbackSource::Process(command) => {
+                log::info!("Capturing system audio with {:?}", command.get_program());
+                self.open_process(|sample_tx| {
+                    app_capture::spawn_capture_process(command, sample_tx)
+                })
+            }
+            LoopbackSource::Device(device, config) => {
+                log::info!(
+                    "Capturing system audio from {}",
+                    device.name().unwrap_or_else(|_| "Unknown".to_string())
+                );
+                self.open_stream(device, config, None)
+            }
+        }
+    }
+
+    /// Open a stream fed by a capture process writing samples at
+    /// [`app_capture::CAPTURE_SAMPLE_RATE`]
+    fn open_process(
+        &mut self,
+        spawn: impl FnOnce(mpsc::Sender<Vec<f32>>) -> Result<Child, anyhow::Error>,
+    ) -> Result<(), anyhow::Error> {
+        let (sample_tx, sample_rx) = mpsc::channel::<Vec<f32>>();
+        let (cmd_tx, cmd_rx) = mpsc::channel::<RecorderCommand>();
+
+        let process = spawn(sample_tx)?;
+
+        *self.sample_rate.lock().unwrap() = app_capture::CAPTURE_SAMPLE_RATE;
+
+        let level_callback = self.audio_level_callback.clone();
+        let sample_callback = self.sample_callback.clone();
+        let lost_callback = self.stream_lost_callback.clone();
+        let buffer_samples = self.buffer_samples;
+        // A capture process can't report dropped buffers
+        let tracker = CaptureTracker::new(
+            app_capture::CAPTURE_SAMPLE_RATE,
+            None,
+            Arc::clone(&self.capture_stats),
+        );
+
+        let worker = std::thread::spawn(move || {
+            run_recording_loop(
+                sample_rx,
+                cmd_rx,
+                level_callback,
+                sample_callback,
+                lost_callback,
+                buffer_samples,
+                tracker,
+            );
+            log::info!("Audio worker thread exiting");
+        });
+
+        self.capture_process = Some(process);
+        self.cmd_tx = Some(cmd_tx);
+        self.worker_handle = Some(worker);
+
+        Ok(())
+    }
+
+    /// Start recording audio
+    pub fn start(&self) -> Result<(), anyhow::Error> {
+        if let Some(tx) = &self.cmd_tx {
+            tx.send(RecorderCommand::Start)?;
+            log::debug!("Sent Start command to AudioRecorder");
+        } else {
+            return Err(anyhow::anyhow!("AudioRecorder not opened"));
+        }
+        Ok(())
+    }
+
+    /// Stop recording and return the captured samples
+    pub fn stop(&self) -> Result<Vec<f32>, anyhow::Error> {
+        let (resp_tx, resp_rx) = mpsc::channel();
+        if let Some(tx) = &self.cmd_tx {
+            tx.send(RecorderCommand::Stop(resp_tx))?;
+        } else {
+            return Err(anyhow::anyhow!("Recorder not opened"));
+        }
+
+        let samples = resp_rx.recv()?;
+        log::debug!("Received {} samples from AudioRecorder", samples.len());
+        Ok(samples)
+    }
+
+    /// Close the audio stream and clean it up
+    pub fn close(&mut self) -> Result<(), anyhow::Error> {
+        if let Some(tx) = &self.cmd_tx {
+            let _ = tx.send(RecorderCommand::Shutdown);
+        }
+
+        if let Some(handle) = self.worker_handle.take() {
+            let _ = handle.join();
+        }
+
+        if let Some(mut process) = self.capture_process.take() {
+            let _ = process.kill();
+            let _ = process.wait();
+        }
+
+        self.device = None;
+        log::debug!("AudioRecorder closed");
+        Ok(())
+    }
+
+    /// Whether the stream is open and still running. A stream whose device
+    /// went away has stopped, and needs opening again.
+    pub fn is_open(&self) -> bool {
+        self.worker_handle
+            .as_ref()
+            .is_some_and(|handle| !handle.is_finished())
+    }
+
+    /// Get the same rate of the recording
+    pub fn sample_rate(&self) -> u32 {
+        *self.sample_rate.lock().unwrap()
+    }
+
+    /// When the last stopped recording started and stopped, and how many
+    /// buffers it dropped
+    pub fn capture_stats(&self) -> CaptureStats {
+        self.capture_stats.lock().unwrap().clone()
+    }
+
+    /// Build an input stream for the given sample type
+    fn build_stream<T>(
+        device: &Device,
+        config: &cpal::SupportedStreamConfig,
+        sample_tx: mpsc::Sender<Vec<f32>>,
+        channels: usize,
+        channel: Option<usize>,
+        lost_callback: Option<StreamLostCallback>,
+        dropped: Arc<AtomicU64>,
+    ) -> Result<Stream, cpal::BuildStreamError>
+    where
+        T: Sample + SizedSample + Send + 'static,
+        f32: cpal::FromSample<T>,
+    {
+        let stream_config: cpal::StreamConfig = config.clone().into();
+        let mut xruns = XrunDetector::new(config.sample_rate().0, dropped);
+
+        device.build_input_stream(
+            &stream_config,
+            move |data: &[T], info: &cpal::InputCallbackInfo| {
+                xruns.on_callback(info, data.len() / channels);
+
+                // Convert samples to f32 and mono
+                let mono_samples: Vec<f32> = match channel {
+                    Some(channel) => data
+                        .chunks(channels)
+                        .filter_map(|frame| frame.get(channel))
+                        .map(|&s| s.to_sample::<f32>())
+                        .collect(),
+                    None if channels == 1 => data.iter().map(|&s| s.to_sample::<f32>()).collect(),
+                    None => data
+                        .chunks(channels)
+                        .map(|frame| {
+                            let sum: f32 = frame.iter().map(|&s| s.to_sample::<f32>()).sum();
+                            sum / channels as f32
+                        })
+                        .collect(),
+                };
+
+                // Send samples to the recording loop
+                if sample_tx.send(mono_samples).is_err() {
+                    // This is expected when the stream is closing - the receiver has been dropped
+                    log::debug!("Audio channel closed, stream is shutting down");
+                }
+            },
+            move |err| {
+                log::error!("Audio stream error: {}", err);
+                if let (cpal::StreamError::DeviceNotAvailable, Some(callback)) =
+                    (&err, &lost_callback)
+                {
+                    callback("the device was disconnected");
+                }
+            },
+            None, // No timeout
+        )
+    }
+
+    /// Get the preferred audio configuration for a device
+    pub(crate) fn get_preferred_config(
+        device: &Device,
+    ) -> Result<cpal::SupportedStreamConfig, anyhow::Error> {
+        let supported_configs = device.supported_input_configs()?;
+
+        let mut best_config: Option<cpal::SupportedStreamConfigRange> = None;
+
+        for config_range in supported_configs {
+            for &rate in &PREFERRED_SAMPLE_RATES {
+                if config_range.min_sample_rate().0 <= rate
+                    && config_range.max_sample_rate().0 >= rate
+                {
+                    let should_use = match &best_config {
+                        None => true,
+                        Some(current) => {
+                            format_score(config_range.sample_format())
+                                > format_score(current.sample_format())
+                        }
+                    };
+
+                    if should_use {
+                        best_config = Some(config_range);
+                        break;
+                    }
+                }
+            }
+        }
+
+        if let Some(config) = best_config {
+            for &rate in &PREFERRED_SAMPLE_RATES {
+                if config.min_sample_rate().0 <= rate && config.max_sample_rate().0 >= rate {
+                    return Ok(config.with_sample_rate(cpal::SampleRate(rate)));
+                }
+            }
+        }
+
+        log::warn!("No preferred config found, using default");
+        Ok(device.default_input_config()?)
+    }
+}
+
+/// Calculate RMS (Root Mean Square) audio level from samples
+/// Returns a value between 0.0 and 1.0
+fn calculate_audio_level(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+
+    // Calculate RMS
+    let sum_squares: f32 = samples.iter().map(|&s| s * s).sum();
+    let rms = (sum_squares / samples.len() as f32).sqrt();
+
+    // Convert to a more perceptually linear scale (0-1)
+    // RMS values are typically very small (0.0 - 0.3 for normal speech)
+    // We scale and clamp to get a useful 0-1 range
+    let scaled = (rms * 4.0).min(1.0);
+
+    // Apply slight curve for better visual response
+    scaled.powf(0.7)
+}
+
+fn run_recording_loop(
+    sample_rx: mpsc::Receiver<Vec<f32>>,
+    cmd_rx: mpsc::Receiver<RecorderCommand>,
+    level_callback: Option<AudioLevelCallback>,
+    sample_callback: Option<SampleCallback>,
+    lost_callback: Option<StreamLostCallback>,
+    buffer_samples: bool,
+    mut tracker: CaptureTracker,
+) {
+    let mut is_recording = false;
+    let mut last_samples = std::time::Instant::now();
+    let mut reported_lost = false;
+    let mut buffer: Vec<f32> = Vec::new();
+    let mut level_sample_buffer: Vec<f32> = Vec::new();
+    let mut last_level_update = std::time::Instant::now();
+    const LEVEL_UPDATE_INTERVAL_MS: u64 = 33; // ~30fps
+
+    loop {
+        match sample_rx.recv_timeout(std::time::Duration::from_millis(10)) {
+            Ok(samples) => {
+                last_samples = std::time::Instant::now();
+                if is_recording {
+                    tracker.on_samples(samples.len());
+                    if buffer_samples {
+                        buffer.extend(&samples);
+                    }
+
+                    if let Some(ref callback) = sample_callback {
+                        callback(&samples);
+                    }
+
+                    // Accumulate samples for level calculation
+                    if level_callback.is_some() {
+                        level_sample_buffer.extend(&samples);
+
+                        // Emit level updates at regular intervals
+                        if last_level_update.elapsed().as_millis()
+                            >= LEVEL_UPDATE_INTERVAL_MS as u128
+                        {
+                            let level = calculate_audio_level(&level_sample_buffer);
+                            if let Some(ref callback) = level_callback {
+                                callback(level);
+                            }
+                            level_sample_buffer.clear();
+                            last_level_update = std::time::Instant::now();
+                        }
+                    }
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                if is_recording && !reported_lost && last_samples.elapsed() >= STREAM_STALL_TIMEOUT
+                {
+                    log::warn!(
+                        "No audio for {:?}, the stream is lost",
+                        STREAM_STALL_TIMEOUT
+                    );
+                    reported_lost = true;
+                    if let Some(ref callback) = lost_callback {
+                        callback("the device stopped sending audio");
+                    }
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                // Stream closed
+                log::debug!("sample_rx disconnected, exiting recording loop");
+                if is_recording {
+                    if let Some(ref callback) = lost_callback {
+                        callback("the audio stream closed");
+                    }
+                }
+                break;
+            }
+        }
+
+        while let Ok(cmd) = cmd_rx.try_recv() {
+            match cmd {
+                RecorderCommand::Start => {
+                    buffer.clear();
+                    level_sample_buffer.clear();
+                    is_recording = true;
+                    last_samples = std::time::Instant::now();
+                    reported_lost = false;
+                    tracker.start();
+                    log::debug!("Recording started in worker");
+                }
+                RecorderCommand::Stop(reply_tx) => {
+                    is_recording = false;
+                    tracker.stop();
+                    let samples = std::mem::take(&mut buffer);
+                    level_sample_buffer.clear();
+                    log::debug!(
+                        "Recording stopped in worker, captured {} samples",
+                        samples.len()
+                    );
+                    let _ = reply_tx.send(samples);
+                }
+                RecorderCommand::Shutdown => {
+                    log::debug!("Shutdown command received, exiting recording loop");
+                    return;
+                }
+            }
+        }
+    }
+}
+
+impl Drop for AudioRecorder {
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
+
+pub fn device_name(device: &Device) -> String {
+    device.name().unwrap_or_else(|_| "Unknown".to_string())
+}
+
+/// Resample mono audio to 16kHz
+pub fn resample_to_16k(samples: &[f32], from_rate: u32) -> Vec<f32> {
+    resample(samples, from_rate, 16000)
+}
+
+/// Mix two mono 16kHz recordings that started together, e.g. the microphone
+/// and the system audio. The shorter one is treated as silent past its end.
+pub fn mix(a: &[f32], b: &[f32]) -> Vec<f32> {
+    let len = a.len().max(b.len());
+    (0..len)
+        .map(|i| {
+            let sum = a.get(i).copied().unwrap_or(0.0) + b.get(i).copied().unwrap_or(0.0);
+            (sum * 0.5).clamp(-1.0, 1.0)
+        })
+        .collect()
+}
+
+/// Most a recording is boosted by when normalizing, so a recording of
+/// silence isn't turned into loud noise
+const MAX_NORMALIZE_GAIN: f32 = 10.0;
+
+/// Boost quiet audio so its peak reaches `target_peak` (0.0 - 1.0). Audio
+/// already at or over the target is left alone. Returns the gain applied.
+pub fn normalize(samples: &mut [f32], target_peak: f32) -> f32 {
+    let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    if peak == 0.0 {
+        return 1.0;
+    }
+
+    let gain = (target_peak.clamp(0.0, 1.0) / peak).clamp(1.0, MAX_NORMALIZE_GAIN);
+    if gain > 1.0 {
+        for sample in samples.iter_mut() {
+            *sample = (*sample * gain).clamp(-1.0, 1.0);
+        }
+    }
+    gain
+}
+
+/// Silence the start of a recording, e.g. where the start sound was picked
+/// up, fading back in over 10ms so the cut doesn't click
+pub fn gate_start(samples: &mut [f32], sample_rate: u32, duration: Duration) {
+    let gated = ((duration.as_secs_f64() * sample_rate as f64) as usize).min(samples.len());
+    let fade = (sample_rate as usize / 100).min(samples.len() - gated);
+
+    samples[..gated].fill(0.0);
+    for (i, sample) in samples[gated..gated + fade].iter_mut().enumerate() {
+        *sample *= i as f32 / fade as f32;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_devices() {
+        let devices = list_input_devices();
+        println!("Available input devices: {:?}", devices);
+    }
+
+    #[test]
+    fn test_mix_pads_shorter_source() {
+        let mixed = mix(&[0.4, 0.4, 0.4], &[0.2]);
+        assert_eq!(mixed, vec![0.3, 0.2, 0.2]);
+    }
+
+    #[test]
+    fn test_normalize() {
+        let mut samples = vec![0.1, -0.2, 0.05];
+        assert_eq!(normalize(&mut samples, 0.8), 4.0);
+        assert_eq!(samples, vec![0.4, -0.8, 0.2]);
+
+        // Loud audio isn't turned down, and near-silence is only boosted so far
+        let mut loud = vec![0.9, -0.5];
+        assert_eq!(normalize(&mut loud, 0.8), 1.0);
+        assert_eq!(loud, vec![0.9, -0.5]);
+        let mut quiet = vec![0.001];
+        assert_eq!(normalize(&mut quiet, 0.8), MAX_NORMALIZE_GAIN);
+    }
+
+    #[test]
+    fn test_gate_start() {
+        let mut samples = vec![1.0; 400];
+        gate_start(&mut samples, 10000, Duration::from_millis(20));
+        assert!(samples[..200].iter().all(|&s| s == 0.0));
+        assert_eq!(samples[250], 0.5);
+        assert_eq!(samples[300], 1.0);
+
+        // Longer than the recording
+        let mut samples = vec![1.0; 100];
+        gate_start(&mut samples, 10000, Duration::from_secs(1));
+        assert!(samples.iter().all(|&s| s == 0.0));
+    }
+}
@@ -0,0 +1,355 @@
+//! Sample rate conversion
+//!
+//! Recordings are resampled (usually from 44.1 or 48kHz) to the 16kHz every
+//! model and the VAD expect. A windowed-sinc filter does it: it removes
+//! everything above the new Nyquist frequency before decimating, where plain
+//! linear interpolation folds those frequencies back into the speech band as
+//! aliasing. The old linear interpolation is kept behind the
+//! `linear-resampler` feature for slow machines and comparisons.
+
+/// Sinc zero crossings on each side of the filter's center, at the cutoff
+/// frequency. More is a steeper filter, at the cost of speed.
+const ZERO_CROSSINGS: usize = 16;
+
+/// Cutoff as a fraction of the new Nyquist frequency, leaving room for the
+/// filter's transition band
+const ROLLOFF: f64 = 0.94;
+
+/// Most filter phases precomputed. Rates with a larger ratio (in lowest
+/// terms) compute each output sample's filter as they go.
+const MAX_PHASES: usize = 1024;
+
+/// Resample mono audio between any two rates
+pub fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if cfg!(feature = "linear-resampler") {
+        linear(samples, from_rate, to_rate)
+    } else {
+        windowed_sinc(samples, from_rate, to_rate)
+    }
+}
+
+/// Resample using linear interpolation: fast, but aliases when downsampling
+pub fn linear(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    let ratio = to_rate as f64 / from_rate as f64;
+    let new_len = (samples.len() as f64 * ratio) as usize;
+    let mut output = Vec::with_capacity(new_len);
+
+    for i in 0..new_len {
+        let src_idx = i as f64 / ratio;
+        let idx_floor = src_idx.floor() as usize;
+        let idx_ceil = (idx_floor + 1).min(samples.len() - 1);
+        let frac = src_idx - idx_floor as f64;
+
+        let sample = samples[idx_floor] as f64 * (1.0 - frac) + samples[idx_ceil] as f64 * frac;
+        output.push(sample as f32);
+    }
+
+    output
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn sinc(x: f64) -> f64 {
+    if x == 0.0 {
+        1.0
+    } else {
+        let x = x * std::f64::consts::PI;
+        x.sin() / x
+    }
+}
+
+/// Blackman window over -1.0 - 1.0
+fn blackman(x: f64) -> f64 {
+    let x = x * std::f64::consts::PI;
+    0.42 + 0.5 * x.cos() + 0.08 * (2.0 * x).cos()
+}
+
+/// Low-pass filter taps for an output sample `frac` (0.0 - 1.0) of the way
+/// between two input samples, for input samples `1 - half ..= half` around
+/// it. Normalized so a constant signal keeps its level.
+fn filter_taps(frac: f64, cutoff: f64, half: usize) -> Vec<f32> {
+    let taps: Vec<f64> = (0..2 * half)
+        .map(|k| {
+            let distance = frac - (k as f64 + 1.0 - half as f64);
+            cutoff * sinc(cutoff * distance) * blackman(distance / half as f64)
+        })
+        .collect();
+    let sum: f64 = taps.iter().sum();
+    taps.iter().map(|&tap| (tap / sum) as f32).collect()
+}
+
+/// Resample with a Blackman-windowed sinc low-pass filter
+pub fn windowed_sinc(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || samples.is_empty() {
+        return samples.to_vec();
+    }
+
+    // Output sample i sits at input position i * down / up
+    let divisor = gcd(from_rate, to_rate);
+    let up = (to_rate / divisor) as usize;
+    let down = (from_rate / divisor) as usize;
+
+    // Downsampling cuts off at the new Nyquist frequency, which widens the
+    // filter in input samples
+    let cutoff = ROLLOFF * (to_rate as f64 / from_rate as f64).min(1.0);
+    let half = (ZERO_CROSSINGS as f64 / cutoff).ceil() as usize;
+
+    let phases: Option<Vec<Vec<f32>>> = (up <= MAX_PHASES).then(|| {
+        (0..up)
+            .map(|phase| filter_taps(phase as f64 / up as f64, cutoff, half))
+            .collect()
+    });
+
+    let new_len = (samples.len() as u64 * up as u64 / down as u64) as usize;
+    let last = samples.len() as isize - 1;
+    let mut output = Vec::with_capacity(new_len);
+
+    for i in 0..new_len {
+        let position = i * down;
+        let base = (position / up) as isize;
+        let phase = position % up;
+
+        let computed;
+        let taps = match &phases {
+            Some(phases) => &phases[phase],
+            None => {
+                computed = filter_taps(phase as f64 / up as f64, cutoff, half);
+                &computed
+            }
+        };
+
+        // Past either end, the edge sample is repeated
+        let first = base + 1 - half as isize;
+        let sample: f32 = taps
+            .iter()
+            .enumerate()
+            .map(|(k, tap)| tap * samples[(first + k as isize).clamp(0, last) as usize])
+            .sum();
+        output.push(sample);
+    }
+
+    output
+}
+
+/// Resamples audio that arrives in chunks, e.g. while recording, as one
+/// signal. Resampling each chunk on its own repeats its edge samples into
+/// the filter at every boundary and rounds each chunk's length down, so the
+/// output clicks and falls behind; this keeps the input the filter still
+/// needs, and its place in it, between chunks instead. Output lags the
+/// input by half the filter's width.
+pub struct StreamResampler {
+    up: usize,
+    down: usize,
+    cutoff: f64,
+    /// Input samples the filter reaches on each side
+    half: usize,
+    phases: Option<Vec<Vec<f32>>>,
+    /// Input the filter still needs, starting at input sample `start`
+    input: Vec<f32>,
+    start: usize,
+    /// Output samples produced so far
+    produced: usize,
+}
+
+impl StreamResampler {
+    pub fn new(from_rate: u32, to_rate: u32) -> Self {
+        let divisor = gcd(from_rate, to_rate);
+        let up = (to_rate / divisor) as usize;
+        let down = (from_rate / divisor) as usize;
+        let cutoff = ROLLOFF * (to_rate as f64 / from_rate as f64).min(1.0);
+        let half = if cfg!(feature = "linear-resampler") {
+            1
+        } else {
+            (ZERO_CROSSINGS as f64 / cutoff).ceil() as usize
+        };
+
+        let mut resampler = Self {
+            up,
+            down,
+            cutoff,
+            half,
+            phases: None,
+            input: Vec::new(),
+            start: 0,
+            produced: 0,
+        };
+        if up <= MAX_PHASES {
+            resampler.phases = Some((0..up).map(|phase| resampler.taps(phase)).collect());
+        }
+        resampler
+    }
+
+    /// Filter taps for input samples `1 - half ..= half` around an output
+    /// sample `phase / up` of the way between two input samples
+    fn taps(&self, phase: usize) -> Vec<f32> {
+        let frac = phase as f64 / self.up as f64;
+        if cfg!(feature = "linear-resampler") {
+            vec![(1.0 - frac) as f32, frac as f32]
+        } else {
+            filter_taps(frac, self.cutoff, self.half)
+        }
+    }
+
+    /// Resample the next chunk, returning the output samples it completes
+    pub fn push(&mut self, samples: &[f32]) -> Vec<f32> {
+        if self.up == self.down {
+            return samples.to_vec();
+        }
+        self.input.extend_from_slice(samples);
+        let received = self.start + self.input.len();
+        let mut output = Vec::new();
+
+        loop {
+            // Output sample i sits at input position i * down / up
+            let position = self.produced * self.down;
+            let base = position / self.up;
+            if base + self.half >= received {
+                break;
+            }
+            let phase = position % self.up;
+
+            let computed;
+            let taps = match &self.phases {
+                Some(phases) => &phases[phase],
+                None => {
+                    computed = self.taps(phase);
+                    &computed
+                }
+            };
+
+            // Before the first sample, it's repeated
+            let first = base as isize + 1 - self.half as isize;
+            let sample: f32 = taps
+                .iter()
+                .enumerate()
+                .map(|(k, tap)| {
+                    let index = (first + k as isize).max(0) as usize;
+                    tap * self.input[index - self.start]
+                })
+                .sum();
+            output.push(sample);
+            self.produced += 1;
+        }
+
+        // Drop the input no later output reaches back to
+        let next_base = self.produced * self.down / self.up;
+        let keep = (next_base + 1).saturating_sub(self.half);
+        if keep > self.start {
+            self.input.drain(..keep - self.start);
+            self.start = keep;
+        }
+        output
+    }
+
+    /// Start over for a new recording
+    pub fn reset(&mut self) {
+        self.input.clear();
+        self.start = 0;
+        self.produced = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(freq: f32, rate: u32, secs: f32) -> Vec<f32> {
+        (0..(rate as f32 * secs) as usize)
+            .map(|i| 0.5 * (std::f32::consts::TAU * freq * i as f32 / rate as f32).sin())
+            .collect()
+    }
+
+    /// Amplitude of one frequency in a signal (Goertzel), skipping the edges
+    fn amplitude(samples: &[f32], freq: f32, rate: u32) -> f32 {
+        let samples = &samples[samples.len() / 10..samples.len() * 9 / 10];
+        let coeff = 2.0 * (std::f32::consts::TAU * freq / rate as f32).cos();
+        let (mut s1, mut s2) = (0.0f32, 0.0f32);
+        for &sample in samples {
+            let s0 = sample + coeff * s1 - s2;
+            s2 = s1;
+            s1 = s0;
+        }
+        let power = s1 * s1 + s2 * s2 - coeff * s1 * s2;
+        2.0 * power.sqrt() / samples.len() as f32
+    }
+
+    #[test]
+    fn test_sinc_keeps_speech_band() {
+        let resampled = windowed_sinc(&tone(1000.0, 48000, 1.0), 48000, 16000);
+        assert_eq!(resampled.len(), 16000);
+        assert!((amplitude(&resampled, 1000.0, 16000) - 0.5).abs() < 0.01);
+
+        // 44.1kHz has far more filter phases than 48kHz
+        let resampled = windowed_sinc(&tone(3000.0, 44100, 1.0), 44100, 16000);
+        assert_eq!(resampled.len(), 16000);
+        assert!((amplitude(&resampled, 3000.0, 16000) - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_sinc_aliases_less_than_linear() {
+        // 12kHz is over the 8kHz Nyquist frequency at 16kHz, and folds back
+        // to 4kHz unless it's filtered out first
+        let input = tone(12000.0, 48000, 1.0);
+        let sinc_alias = amplitude(&windowed_sinc(&input, 48000, 16000), 4000.0, 16000);
+        let linear_alias = amplitude(&linear(&input, 48000, 16000), 4000.0, 16000);
+
+        assert!(linear_alias > 0.1, "linear alias {}", linear_alias);
+        assert!(sinc_alias < 0.001, "sinc alias {}", sinc_alias);
+    }
+
+    #[test]
+    fn test_sinc_same_rate_and_upsampling() {
+        let input = tone(440.0, 16000, 0.1);
+        assert_eq!(windowed_sinc(&input, 16000, 16000), input);
+
+        let upsampled = windowed_sinc(&input, 16000, 48000);
+        assert_eq!(upsampled.len(), input.len() * 3);
+        // Every third sample lands on an input sample
+        assert!((upsampled[300] - input[100]).abs() < 0.01);
+        assert!(windowed_sinc(&[], 48000, 16000).is_empty());
+    }
+
+    #[test]
+    fn test_stream_matches_whole() {
+        let input = tone(1000.0, 44100, 0.5);
+        let whole = resample(&input, 44100, 16000);
+
+        let mut resampler = StreamResampler::new(44100, 16000);
+        let streamed: Vec<f32> = input
+            .chunks(441)
+            .flat_map(|chunk| resampler.push(chunk))
+            .collect();
+
+        // Only the filter's reach short of the end is still to come
+        assert!(whole.len() - streamed.len() <= resampler.half);
+        for (a, b) in streamed.iter().zip(&whole) {
+            assert!((a - b).abs() < 1e-5);
+        }
+    }
+
+    /// Speed comparison, run with `cargo test -p iv-core --release -- --ignored`
+    #[test]
+    #[ignore]
+    fn bench_resample_one_minute() {
+        for rate in [44100, 48000] {
+            let input = tone(440.0, rate, 60.0);
+            let started = std::time::Instant::now();
+            windowed_sinc(&input, rate, 16000);
+            let sinc = started.elapsed();
+            let started = std::time::Instant::now();
+            linear(&input, rate, 16000);
+            println!(
+                "{}Hz, 60s: sinc {:?}, linear {:?}",
+                rate,
+                sinc,
+                started.elapsed()
+            );
+        }
+    }
+}
@@ -0,0 +1,174 @@
+//! Audio file decoding for file transcription
+//!
+//! Decodes WAV, MP3, M4A/AAC and OGG files (or the audio track of MP4 video)
+//! with symphonia, downmixes to mono and resamples to 16kHz. Long files are
+//! cut into chunks at pauses, so engines never see hours of audio at once and
+//! progress can be reported between chunks.
+
+use std::ops::Range;
+use std::path::Path;
+
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use crate::audio::resample_to_16k;
+
+const SAMPLE_RATE: usize = 16000;
+
+/// Longest stretch of audio transcribed in one go
+pub const MAX_CHUNK_SECS: usize = 30;
+
+/// How far back from a chunk's end to look for a pause to cut at
+const PAUSE_SEARCH_SECS: usize = 5;
+
+/// Window whose energy is compared when looking for the quietest point
+const PAUSE_WINDOW_SAMPLES: usize = SAMPLE_RATE / 10;
+
+/// Decode an audio file into 16kHz mono samples
+pub fn load_16k_mono(path: &Path) -> Result<Vec<f32>, anyhow::Error> {
+    let file = std::fs::File::open(path)?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(extension);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| anyhow::anyhow!("Unsupported audio file {:?}: {}", path, e))?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| anyhow::anyhow!("No audio track in {:?}", path))?;
+    let track_id = track.id;
+    let sample_rate = track
+        .codec_params
+        .sample_rate
+        .ok_or_else(|| anyhow::anyhow!("Unknown sample rate in {:?}", path))?;
+
+    let mut decoder =
+        symphonia::default::get_codecs().make(&track.codec_params, &DecoderOptions::default())?;
+
+    let mut samples = Vec::new();
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            // End of stream
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A corrupt packet loses a few milliseconds, not the whole file
+            Err(SymphoniaError::DecodeError(e)) => {
+                log::debug!("Skipping undecodable packet: {}", e);
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let spec = *decoded.spec();
+        let channels = spec.channels.count().max(1);
+        let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buffer.copy_interleaved_ref(decoded);
+
+        samples.extend(
+            buffer
+                .samples()
+                .chunks(channels)
+                .map(|frame| frame.iter().sum::<f32>() / channels as f32),
+        );
+    }
+
+    log::info!(
+        "Decoded {:?}: {:.1}s at {} Hz",
+        path,
+        samples.len() as f32 / sample_rate as f32,
+        sample_rate
+    );
+
+    if samples.is_empty() || sample_rate == SAMPLE_RATE as u32 {
+        return Ok(samples);
+    }
+    Ok(resample_to_16k(&samples, sample_rate))
+}
+
+fn energy(samples: &[f32]) -> f32 {
+    samples.iter().map(|s| s * s).sum()
+}
+
+/// Split 16kHz audio into chunks of at most `MAX_CHUNK_SECS`, cutting each
+/// one at the quietest point near its end so words aren't split
+pub fn chunk_at_pauses(samples: &[f32]) -> Vec<Range<usize>> {
+    let max_len = MAX_CHUNK_SECS * SAMPLE_RATE;
+    let search_len = PAUSE_SEARCH_SECS * SAMPLE_RATE;
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+
+    while samples.len() - start > max_len {
+        let limit = start + max_len;
+        let end = (limit - search_len..=limit - PAUSE_WINDOW_SAMPLES)
+            .step_by(PAUSE_WINDOW_SAMPLES / 2)
+            .min_by(|&a, &b| {
+                energy(&samples[a..a + PAUSE_WINDOW_SAMPLES])
+                    .total_cmp(&energy(&samples[b..b + PAUSE_WINDOW_SAMPLES]))
+            })
+            .map(|quietest| quietest + PAUSE_WINDOW_SAMPLES / 2)
+            .unwrap_or(limit);
+
+        chunks.push(start..end);
+        start = end;
+    }
+
+    if start < samples.len() {
+        chunks.push(start..samples.len());
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_at_pauses() {
+        // 70s of "speech" with a silent gap 28s in
+        let mut samples = vec![0.5; 70 * SAMPLE_RATE];
+        let gap = 28 * SAMPLE_RATE;
+        samples[gap..gap + SAMPLE_RATE / 2].fill(0.0);
+
+        let chunks = chunk_at_pauses(&samples);
+        assert_eq!(chunks.len(), 3);
+        assert!((gap..gap + SAMPLE_RATE / 2).contains(&chunks[0].end));
+        assert!(chunks
+            .iter()
+            .all(|c| c.len() <= MAX_CHUNK_SECS * SAMPLE_RATE));
+        assert_eq!(chunks.last().unwrap().end, samples.len());
+
+        assert_eq!(
+            chunk_at_pauses(&samples[..SAMPLE_RATE]),
+            vec![0..SAMPLE_RATE]
+        );
+    }
+}
@@ -0,0 +1,119 @@
+//! Input device calibration
+//!
+//! A few seconds of the user speaking (with a pause) are enough to measure a
+//! microphone: the quietest frames give the noise floor, the loudest the
+//! speech level. From those come a gain that brings speech to a common level
+//! and a VAD threshold that is stricter on noisy devices.
+
+use serde::{Deserialize, Serialize};
+
+/// Frame length used to measure levels (30ms at 16kHz, like the VAD)
+const FRAME_SECS: f32 = 0.03;
+
+/// Speech RMS the gain aims for
+const TARGET_SPEECH_RMS: f32 = 0.1;
+
+const MIN_GAIN: f32 = 0.5;
+const MAX_GAIN: f32 = 8.0;
+
+/// Signal-to-noise ratios (dB) mapped onto the threshold range: clean devices
+/// get the most sensitive threshold, noisy ones the strictest
+const CLEAN_SNR_DB: f32 = 30.0;
+const NOISY_SNR_DB: f32 = 10.0;
+const MIN_VAD_THRESHOLD: f32 = 0.4;
+const MAX_VAD_THRESHOLD: f32 = 0.7;
+
+/// Speech must be at least this much louder than the noise floor to calibrate
+const MIN_SNR_DB: f32 = 6.0;
+
+/// Calibration results for one input device
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct DeviceProfile {
+    /// Multiplier applied to captured samples
+    pub gain: f32,
+    /// Silero speech probability threshold
+    pub vad_threshold: f32,
+    /// RMS of the device's background noise, before gain
+    pub noise_floor: f32,
+}
+
+impl DeviceProfile {
+    /// Apply the gain to captured samples, clipping at full scale
+    pub fn apply_gain(&self, samples: &mut [f32]) {
+        if self.gain == 1.0 {
+            return;
+        }
+        for sample in samples.iter_mut() {
+            *sample = (*sample * self.gain).clamp(-1.0, 1.0);
+        }
+    }
+}
+
+fn rms(samples: &[f32]) -> f32 {
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+/// Level at a fraction of the way through sorted levels
+fn percentile(sorted: &[f32], fraction: f32) -> f32 {
+    let index = ((sorted.len() - 1) as f32 * fraction).round() as usize;
+    sorted[index]
+}
+
+/// Measure a device from a recording of the user speaking and pausing
+pub fn calibrate(samples: &[f32], sample_rate: u32) -> Result<DeviceProfile, anyhow::Error> {
+    let frame_len = ((sample_rate as f32 * FRAME_SECS) as usize).max(1);
+    let mut levels: Vec<f32> = samples.chunks_exact(frame_len).map(rms).collect();
+    if levels.len() < 10 {
+        return Err(anyhow::anyhow!("Calibration recording is too short"));
+    }
+    levels.sort_by(f32::total_cmp);
+
+    let noise_floor = percentile(&levels, 0.1);
+    let speech_level = percentile(&levels, 0.95);
+
+    let snr_db = 20.0 * (speech_level / noise_floor.max(1e-6)).log10();
+    if snr_db < MIN_SNR_DB {
+        return Err(anyhow::anyhow!(
+            "No speech heard during calibration, speak a sentence then pause"
+        ));
+    }
+
+    let noisiness = ((CLEAN_SNR_DB - snr_db) / (CLEAN_SNR_DB - NOISY_SNR_DB)).clamp(0.0, 1.0);
+
+    Ok(DeviceProfile {
+        gain: (TARGET_SPEECH_RMS / speech_level).clamp(MIN_GAIN, MAX_GAIN),
+        vad_threshold: MIN_VAD_THRESHOLD + noisiness * (MAX_VAD_THRESHOLD - MIN_VAD_THRESHOLD),
+        noise_floor,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One second of noise followed by one second of a quiet tone
+    fn recording(noise: f32, speech: f32) -> Vec<f32> {
+        let mut samples: Vec<f32> = (0..16000)
+            .map(|i| if i % 2 == 0 { noise } else { -noise })
+            .collect();
+        samples.extend((0..16000).map(|i| speech * (i as f32 * 0.1).sin()));
+        samples
+    }
+
+    #[test]
+    fn test_calibrate_quiet_clean_mic() {
+        let profile = calibrate(&recording(0.0005, 0.03), 16000).unwrap();
+        assert!(profile.gain > 4.0);
+        assert_eq!(profile.vad_threshold, MIN_VAD_THRESHOLD);
+        assert!((profile.noise_floor - 0.0005).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_calibrate_noisy_mic() {
+        let profile = calibrate(&recording(0.05, 0.3), 16000).unwrap();
+        assert_eq!(profile.gain, MIN_GAIN);
+        assert!(profile.vad_threshold > 0.6);
+
+        assert!(calibrate(&recording(0.05, 0.05), 16000).is_err());
+    }
+}
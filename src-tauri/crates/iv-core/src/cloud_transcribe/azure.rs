@@ -0,0 +1,209 @@
+//! Azure Speech short-audio REST API provider
+
+use async_trait::async_trait;
+
+use super::{
+    samples_to_wav, status_error, upload_body, CloudError, CloudProvider, ProviderCapabilities,
+    UploadProgressCallback,
+};
+use crate::transcript::{TranscriptSegment, TranscriptionResult};
+
+/// Azure reports offsets and durations in 100-nanosecond ticks
+const TICKS_PER_SECOND: f64 = 10_000_000.0;
+
+pub struct AzureProvider {
+    client: reqwest::Client,
+    api_key: Option<String>,
+    region: String,
+}
+
+impl AzureProvider {
+    pub fn new(api_key: Option<String>, region: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key,
+            region,
+        }
+    }
+}
+
+/// Each language's primary Azure Speech locale, for ISO 639-1 codes. A
+/// code's locale isn't always the code twice over ("ja-JP", "sv-SE"), so
+/// they're listed rather than derived.
+const AZURE_LOCALES: &[(&str, &str)] = &[
+    ("ar", "ar-SA"),
+    ("bg", "bg-BG"),
+    ("ca", "ca-ES"),
+    ("cs", "cs-CZ"),
+    ("da", "da-DK"),
+    ("de", "de-DE"),
+    ("el", "el-GR"),
+    ("en", "en-US"),
+    ("es", "es-ES"),
+    ("et", "et-EE"),
+    ("fi", "fi-FI"),
+    ("fr", "fr-FR"),
+    ("he", "he-IL"),
+    ("hi", "hi-IN"),
+    ("hr", "hr-HR"),
+    ("hu", "hu-HU"),
+    ("id", "id-ID"),
+    ("it", "it-IT"),
+    ("ja", "ja-JP"),
+    ("ko", "ko-KR"),
+    ("lt", "lt-LT"),
+    ("lv", "lv-LV"),
+    ("ms", "ms-MY"),
+    ("nb", "nb-NO"),
+    ("nl", "nl-NL"),
+    ("no", "nb-NO"),
+    ("pl", "pl-PL"),
+    ("pt", "pt-BR"),
+    ("ro", "ro-RO"),
+    ("ru", "ru-RU"),
+    ("sk", "sk-SK"),
+    ("sl", "sl-SI"),
+    ("sv", "sv-SE"),
+    ("ta", "ta-IN"),
+    ("th", "th-TH"),
+    ("tr", "tr-TR"),
+    ("uk", "uk-UA"),
+    ("vi", "vi-VN"),
+    ("zh", "zh-CN"),
+];
+
+/// Azure expects a full locale. A language code is looked up in
+/// [`AZURE_LOCALES`] and a full locale passed through; no language means
+/// English.
+fn to_azure_locale(language: Option<&str>) -> Option<String> {
+    let Some(language) = language else {
+        return Some("en-US".to_string());
+    };
+    if language.contains('-') {
+        return Some(language.to_string());
+    }
+    AZURE_LOCALES
+        .iter()
+        .find(|(code, _)| code.eq_ignore_ascii_case(language))
+        .map(|(_, locale)| locale.to_string())
+}
+
+#[async_trait]
+impl CloudProvider for AzureProvider {
+    fn name(&self) -> &'static str {
+        "Azure Speech"
+    }
+
+    async fn transcribe(
+        &self,
+        samples: &[f32],
+        sample_rate: u32,
+        language: Option<&str>,
+        progress: Option<&UploadProgressCallback>,
+    ) -> Result<TranscriptionResult, anyhow::Error> {
+        let api_key = self.api_key.as_deref().ok_or_else(|| {
+            CloudError::NotConfigured("Azure Speech key not configured".to_string())
+        })?;
+
+        if self.region.is_empty() {
+            return Err(CloudError::NotConfigured(
+                "Azure Speech region not configured".to_string(),
+            )
+            .into());
+        }
+
+        let locale = to_azure_locale(language).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Azure Speech doesn't support language '{}'",
+                language.unwrap_or_default()
+            )
+        })?;
+
+        let wav_bytes = samples_to_wav(samples, sample_rate)?;
+        let url = format!(
+            "https://{}.stt.speech.microsoft.com/speech/recognition/conversation/cognitiveservices/v1",
+            self.region
+        );
+
+        let response = self
+            .client
+            .post(&url)
+            .query(&[("language", locale), ("format", "simple".to_string())])
+            .header("Ocp-Apim-Subscription-Key", api_key)
+            .header(
+                "Content-Type",
+                format!("audio/wav; codecs=audio/pcm; samplerate={}", sample_rate),
+            )
+            // A streamed body is otherwise sent chunked
+            .header("Content-Length", wav_bytes.len())
+            .body(upload_body(wav_bytes, progress))
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            log::error!("Azure Speech API error ({}): {}", status, body);
+            return Err(status_error("Azure Speech", status));
+        }
+
+        let json: serde_json::Value = response.json().await?;
+        match json["RecognitionStatus"].as_str() {
+            Some("Success") => {
+                let text = json["DisplayText"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string();
+
+                // Short audio is recognized as one phrase, reported as one segment
+                let segments = match (json["Offset"].as_u64(), json["Duration"].as_u64()) {
+                    (Some(offset), Some(duration)) if !text.is_empty() => {
+                        vec![TranscriptSegment {
+                            start: (offset as f64 / TICKS_PER_SECOND) as f32,
+                            end: ((offset + duration) as f64 / TICKS_PER_SECOND) as f32,
+                            text: text.clone(),
+                            confidence: None,
+                            speaker: None,
+                        }]
+                    }
+                    _ => Vec::new(),
+                };
+
+                Ok(TranscriptionResult { text, segments })
+            }
+            Some("NoMatch") | Some("InitialSilenceTimeout") => Ok(TranscriptionResult::default()),
+            other => Err(anyhow::anyhow!(
+                "Azure Speech recognition failed: {}",
+                other.unwrap_or("unknown status")
+            )),
+        }
+    }
+
+    async fn capabilities(&self) -> Result<ProviderCapabilities, anyhow::Error> {
+        Ok(ProviderCapabilities {
+            // Only the short-audio REST API is implemented, not the
+            // websocket one
+            streaming: false,
+            // The simple output format has one offset for the whole phrase
+            word_timestamps: false,
+            // Speech translation is a separate Azure service
+            translation: false,
+            languages: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_azure_locale() {
+        assert_eq!(to_azure_locale(None).as_deref(), Some("en-US"));
+        assert_eq!(to_azure_locale(Some("ja")).as_deref(), Some("ja-JP"));
+        assert_eq!(to_azure_locale(Some("sv")).as_deref(), Some("sv-SE"));
+        assert_eq!(to_azure_locale(Some("fr-CA")).as_deref(), Some("fr-CA"));
+        assert_eq!(to_azure_locale(Some("xx")), None);
+    }
+}
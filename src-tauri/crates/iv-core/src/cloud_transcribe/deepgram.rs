@@ -0,0 +1,124 @@
+//! Deepgram pre-recorded audio API provider
+
+use async_trait::async_trait;
+
+use super::{
+    samples_to_wav, status_error, upload_body, CloudError, CloudProvider, ProviderCapabilities,
+    UploadProgressCallback,
+};
+use crate::transcript::{TranscriptSegment, TranscriptionResult};
+
+const DEEPGRAM_LISTEN_URL: &str = "https://api.deepgram.com/v1/listen";
+const DEEPGRAM_MODEL: &str = "nova-2";
+
+/// Languages nova-2 transcribes
+const DEEPGRAM_LANGUAGES: &[&str] = &[
+    "bg", "ca", "cs", "da", "de", "el", "en", "es", "et", "fi", "fr", "hi", "hu", "id", "it", "ja",
+    "ko", "lt", "lv", "ms", "nl", "no", "pl", "pt", "ro", "ru", "sk", "sv", "th", "tr", "uk", "vi",
+    "zh",
+];
+
+pub struct DeepgramProvider {
+    client: reqwest::Client,
+    api_key: Option<String>,
+}
+
+impl DeepgramProvider {
+    pub fn new(api_key: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key,
+        }
+    }
+}
+
+#[async_trait]
+impl CloudProvider for DeepgramProvider {
+    fn name(&self) -> &'static str {
+        "Deepgram"
+    }
+
+    async fn transcribe(
+        &self,
+        samples: &[f32],
+        sample_rate: u32,
+        language: Option<&str>,
+        progress: Option<&UploadProgressCallback>,
+    ) -> Result<TranscriptionResult, anyhow::Error> {
+        let api_key = self.api_key.as_deref().ok_or_else(|| {
+            CloudError::NotConfigured("Deepgram API key not configured".to_string())
+        })?;
+
+        let wav_bytes = samples_to_wav(samples, sample_rate)?;
+
+        let mut query = vec![
+            ("model", DEEPGRAM_MODEL.to_string()),
+            ("smart_format", "true".to_string()),
+            ("utterances", "true".to_string()),
+        ];
+        match language {
+            Some(lang) => query.push(("language", lang.to_string())),
+            None => query.push(("detect_language", "true".to_string())),
+        }
+
+        let response = self
+            .client
+            .post(DEEPGRAM_LISTEN_URL)
+            .query(&query)
+            .header("Authorization", format!("Token {}", api_key))
+            .header("Content-Type", "audio/wav")
+            // A streamed body is otherwise sent chunked
+            .header("Content-Length", wav_bytes.len())
+            .body(upload_body(wav_bytes, progress))
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            log::error!("Deepgram API error ({}): {}", status, body);
+            return Err(status_error("Deepgram", status));
+        }
+
+        let json: serde_json::Value = response.json().await?;
+        let transcript = json["results"]["channels"][0]["alternatives"][0]["transcript"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Unexpected Deepgram response format"))?;
+
+        // Utterances are Deepgram's phrase-level segments
+        let segments = json["results"]["utterances"]
+            .as_array()
+            .map(|utterances| {
+                utterances
+                    .iter()
+                    .filter_map(|u| {
+                        Some(TranscriptSegment {
+                            start: u["start"].as_f64()? as f32,
+                            end: u["end"].as_f64()? as f32,
+                            text: u["transcript"].as_str()?.trim().to_string(),
+                            confidence: u["confidence"].as_f64().map(|c| c as f32),
+                            speaker: None,
+                        })
+                    })
+                    .filter(|s| !s.text.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(TranscriptionResult {
+            text: transcript.trim().to_string(),
+            segments,
+        })
+    }
+
+    async fn capabilities(&self) -> Result<ProviderCapabilities, anyhow::Error> {
+        Ok(ProviderCapabilities {
+            // Only prerecorded audio is implemented, not the live
+            // websocket API
+            streaming: false,
+            word_timestamps: true,
+            translation: false,
+            languages: DEEPGRAM_LANGUAGES.iter().map(|l| l.to_string()).collect(),
+        })
+    }
+}
@@ -0,0 +1,174 @@
+//! Mock provider for offline development and tests
+//!
+//! Returns canned transcripts without any network, after a configurable delay
+//! and with a configurable share of requests failing, so the frontend and the
+//! cloud code paths can be exercised without an API key or credits. Only
+//! built with the `mock-cloud` feature.
+//!
+//! Configured through the environment:
+//! - `IV_MOCK_TRANSCRIPT`: the text to return (default: a rotating set)
+//! - `IV_MOCK_LATENCY_MS`: delay before responding (default 300)
+//! - `IV_MOCK_FAILURE_RATE`: share of requests that fail, 0.0 - 1.0 (default 0)
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use super::{CloudProvider, ProviderCapabilities, UploadProgressCallback};
+use crate::transcript::{TranscriptSegment, TranscriptionResult};
+
+const DEFAULT_LATENCY_MS: u64 = 300;
+
+const CANNED_TRANSCRIPTS: &[&str] = &[
+    "This is a mock transcript.",
+    "The quick brown fox jumps over the lazy dog.",
+    "Testing one two three, can you hear me?",
+];
+
+pub struct MockProvider {
+    transcript: Option<String>,
+    latency: Duration,
+    failure_rate: f64,
+    /// Requests so far, to rotate through the canned transcripts
+    requests: AtomicUsize,
+}
+
+impl MockProvider {
+    pub fn from_env() -> Self {
+        let env = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        Self {
+            transcript: env("IV_MOCK_TRANSCRIPT"),
+            latency: Duration::from_millis(
+                env("IV_MOCK_LATENCY_MS")
+                    .and_then(|ms| ms.trim().parse().ok())
+                    .unwrap_or(DEFAULT_LATENCY_MS),
+            ),
+            failure_rate: env("IV_MOCK_FAILURE_RATE")
+                .and_then(|rate| rate.trim().parse::<f64>().ok())
+                .unwrap_or(0.0)
+                .clamp(0.0, 1.0),
+            requests: AtomicUsize::new(0),
+        }
+    }
+
+    /// A random number in 0.0 - 1.0, from the standard library's randomly
+    /// seeded hasher
+    fn random() -> f64 {
+        let bits = RandomState::new().build_hasher().finish();
+        (bits >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    async fn respond(
+        &self,
+        samples: &[f32],
+        sample_rate: u32,
+        progress: Option<&UploadProgressCallback>,
+    ) -> Result<TranscriptionResult, anyhow::Error> {
+        let request = self.requests.fetch_add(1, Ordering::Relaxed);
+
+        // Half the latency "uploads" the 16-bit WAV that would be sent
+        let total = samples.len() as u64 * 2 + 44;
+        if let Some(progress) = progress {
+            progress(0, total);
+        }
+        tokio::time::sleep(self.latency / 2).await;
+        if let Some(progress) = progress {
+            progress(total, total);
+        }
+        tokio::time::sleep(self.latency / 2).await;
+
+        if Self::random() < self.failure_rate {
+            return Err(anyhow::anyhow!("Mock provider failed request {}", request));
+        }
+
+        let text = self
+            .transcript
+            .clone()
+            .unwrap_or_else(|| CANNED_TRANSCRIPTS[request % CANNED_TRANSCRIPTS.len()].to_string());
+        Ok(TranscriptionResult {
+            segments: vec![TranscriptSegment {
+                start: 0.0,
+                end: samples.len() as f32 / sample_rate as f32,
+                text: text.clone(),
+                confidence: Some(1.0),
+                speaker: None,
+            }],
+            text,
+        })
+    }
+}
+
+#[async_trait]
+impl CloudProvider for MockProvider {
+    fn name(&self) -> &'static str {
+        "Mock"
+    }
+
+    async fn transcribe(
+        &self,
+        samples: &[f32],
+        sample_rate: u32,
+        _language: Option<&str>,
+        progress: Option<&UploadProgressCallback>,
+    ) -> Result<TranscriptionResult, anyhow::Error> {
+        self.respond(samples, sample_rate, progress).await
+    }
+
+    async fn translate(
+        &self,
+        samples: &[f32],
+        sample_rate: u32,
+        progress: Option<&UploadProgressCallback>,
+    ) -> Result<TranscriptionResult, anyhow::Error> {
+        self.respond(samples, sample_rate, progress).await
+    }
+
+    async fn capabilities(&self) -> Result<ProviderCapabilities, anyhow::Error> {
+        Ok(ProviderCapabilities {
+            translation: true,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(failure_rate: f64) -> MockProvider {
+        MockProvider {
+            transcript: None,
+            latency: Duration::ZERO,
+            failure_rate,
+            requests: AtomicUsize::new(0),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mock_rotates_transcripts() {
+        let mock = provider(0.0);
+        let first = mock
+            .transcribe(&[0.0; 16000], 16000, None, None)
+            .await
+            .unwrap();
+        assert_eq!(first.text, CANNED_TRANSCRIPTS[0]);
+        assert_eq!(first.segments[0].end, 1.0);
+
+        let second = mock
+            .transcribe(&[0.0; 16000], 16000, None, None)
+            .await
+            .unwrap();
+        assert_eq!(second.text, CANNED_TRANSCRIPTS[1]);
+    }
+
+    #[tokio::test]
+    async fn test_mock_failure_rate() {
+        assert!(provider(1.0)
+            .transcribe(&[0.0], 16000, None, None)
+            .await
+            .is_err());
+    }
+}
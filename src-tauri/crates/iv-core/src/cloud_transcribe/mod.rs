@@ -0,0 +1,409 @@
+//! Cloud transcription module with pluggable providers
+//!
+//! Each provider implements [`CloudProvider`]; [`CloudTranscriber`] wraps the
+//! provider selected in settings.
+
+mod azure;
+mod deepgram;
+#[cfg(feature = "mock-cloud")]
+mod mock;
+mod openai;
+mod self_hosted;
+
+use std::io::Cursor;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use hound::{SampleFormat, WavSpec, WavWriter};
+use serde::{Deserialize, Serialize};
+
+use crate::transcript::TranscriptionResult;
+use azure::AzureProvider;
+use deepgram::DeepgramProvider;
+#[cfg(feature = "mock-cloud")]
+use mock::MockProvider;
+use openai::OpenAiProvider;
+use self_hosted::SelfHostedProvider;
+
+pub use self_hosted::{probe_server, ServerProtocol, ServerStatus};
+
+/// Told the bytes of audio sent so far and the total while a request uploads
+pub type UploadProgressCallback = Arc<dyn Fn(u64, u64) + Send + Sync>;
+
+/// Size of the chunks an upload is sent in, and so how often progress is reported
+const UPLOAD_CHUNK_BYTES: usize = 64 * 1024;
+
+/// A cloud transcription failure worth telling apart from the rest, e.g.
+/// to explain it on the overlay. Providers return it inside their
+/// `anyhow::Error`. Where a request couldn't be sent, most leave the
+/// `reqwest::Error` as it is instead.
+#[derive(Debug)]
+pub enum CloudError {
+    /// The key, region or server URL the provider needs isn't set
+    NotConfigured(String),
+    /// The provider turned down the key (HTTP 401 or 403)
+    Unauthorized(String),
+    /// The provider couldn't be reached or didn't answer in time
+    Unreachable(String),
+}
+
+impl std::fmt::Display for CloudError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CloudError::NotConfigured(message)
+            | CloudError::Unauthorized(message)
+            | CloudError::Unreachable(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for CloudError {}
+
+/// The error for a provider's reply with a failure status
+fn status_error(provider: &str, status: reqwest::StatusCode) -> anyhow::Error {
+    let message = format!("{} transcription failed with status {}", provider, status);
+    match status {
+        reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => {
+            CloudError::Unauthorized(message).into()
+        }
+        _ => anyhow::anyhow!(message),
+    }
+}
+
+/// Available cloud transcription providers
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CloudProviderType {
+    #[default]
+    OpenAi,
+    Groq,
+    Deepgram,
+    Azure,
+    /// A whisper server on the local network (faster-whisper-server, whisper.cpp)
+    #[serde(rename = "self_hosted")]
+    SelfHosted,
+    /// Canned transcripts without any network. Only works in builds with
+    /// the `mock-cloud` feature, but always exists so settings saved by one
+    /// still load in the others.
+    Mock,
+}
+
+impl CloudProviderType {
+    /// Environment variable consulted when no API key is configured
+    pub fn api_key_env_var(&self) -> &'static str {
+        match self {
+            CloudProviderType::OpenAi => "OPENAI_API_KEY",
+            CloudProviderType::Groq => "GROQ_API_KEY",
+            CloudProviderType::Deepgram => "DEEPGRAM_API_KEY",
+            CloudProviderType::Azure => "AZURE_SPEECH_KEY",
+            CloudProviderType::SelfHosted => "WHISPER_SERVER_API_KEY",
+            CloudProviderType::Mock => "IV_MOCK_API_KEY",
+        }
+    }
+
+    /// Whether requests fail without an API key
+    pub fn requires_api_key(&self) -> bool {
+        match self {
+            // Self-hosted servers usually run without authentication
+            CloudProviderType::SelfHosted => false,
+            CloudProviderType::Mock => false,
+            _ => true,
+        }
+    }
+}
+
+/// Configuration used to build a cloud provider
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct CloudConfig {
+    pub provider: CloudProviderType,
+    pub api_key: Option<String>,
+    /// Base URL override for OpenAI-compatible servers, or the address of a
+    /// self-hosted whisper server
+    pub base_url: Option<String>,
+    /// Model name override (e.g. "whisper-large-v3")
+    pub model: Option<String>,
+    /// Azure Speech resource region (e.g. "eastus")
+    pub azure_region: String,
+}
+
+/// Optional features a provider supports, so the UI can gray out options it
+/// would reject
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProviderCapabilities {
+    /// Partial results while audio is still being sent
+    pub streaming: bool,
+    /// Timings for each word, not just each segment
+    pub word_timestamps: bool,
+    /// Transcribing any language straight into English
+    pub translation: bool,
+    /// ISO 639-1 codes the provider accepts. Empty means any language.
+    pub languages: Vec<String>,
+}
+
+/// A cloud speech-to-text backend
+#[async_trait]
+pub trait CloudProvider: Send + Sync {
+    /// Human-readable provider name for logs
+    fn name(&self) -> &'static str;
+
+    /// Transcribe mono samples, optionally constrained to an ISO 639-1 language.
+    /// Segments are included when the provider reports timings. Providers
+    /// that control their request body report upload progress.
+    async fn transcribe(
+        &self,
+        samples: &[f32],
+        sample_rate: u32,
+        language: Option<&str>,
+        progress: Option<&UploadProgressCallback>,
+    ) -> Result<TranscriptionResult, anyhow::Error>;
+
+    /// Transcribe mono samples in any language straight into English
+    async fn translate(
+        &self,
+        _samples: &[f32],
+        _sample_rate: u32,
+        _progress: Option<&UploadProgressCallback>,
+    ) -> Result<TranscriptionResult, anyhow::Error> {
+        Err(anyhow::anyhow!(
+            "{} doesn't support translating to English",
+            self.name()
+        ))
+    }
+
+    /// What this provider supports. Providers that can't know without asking
+    /// (self-hosted servers) probe the backend, so this may fail.
+    async fn capabilities(&self) -> Result<ProviderCapabilities, anyhow::Error>;
+}
+
+/// Build the provider described by the config
+pub fn create_provider(config: &CloudConfig) -> Box<dyn CloudProvider> {
+    let api_key = config.api_key.clone();
+    match config.provider {
+        CloudProviderType::OpenAi => Box::new(OpenAiProvider::openai(
+            api_key,
+            config.base_url.clone(),
+            config.model.clone(),
+        )),
+        CloudProviderType::Groq => Box::new(OpenAiProvider::groq(api_key, config.model.clone())),
+        CloudProviderType::Deepgram => Box::new(DeepgramProvider::new(api_key)),
+        CloudProviderType::Azure => {
+            Box::new(AzureProvider::new(api_key, config.azure_region.clone()))
+        }
+        CloudProviderType::SelfHosted => Box::new(SelfHostedProvider::new(
+            config.base_url.clone(),
+            api_key,
+            config.model.clone(),
+        )),
+        #[cfg(feature = "mock-cloud")]
+        CloudProviderType::Mock => Box::new(MockProvider::from_env()),
+        #[cfg(not(feature = "mock-cloud"))]
+        CloudProviderType::Mock => Box::new(UnavailableProvider("Mock")),
+    }
+}
+
+/// Stands in for a provider left out of this build, failing every request
+#[cfg(not(feature = "mock-cloud"))]
+struct UnavailableProvider(&'static str);
+
+#[cfg(not(feature = "mock-cloud"))]
+#[async_trait]
+impl CloudProvider for UnavailableProvider {
+    fn name(&self) -> &'static str {
+        self.0
+    }
+
+    async fn transcribe(
+        &self,
+        _samples: &[f32],
+        _sample_rate: u32,
+        _language: Option<&str>,
+        _progress: Option<&UploadProgressCallback>,
+    ) -> Result<TranscriptionResult, anyhow::Error> {
+        Err(self.unavailable())
+    }
+
+    async fn capabilities(&self) -> Result<ProviderCapabilities, anyhow::Error> {
+        Err(self.unavailable())
+    }
+}
+
+#[cfg(not(feature = "mock-cloud"))]
+impl UnavailableProvider {
+    fn unavailable(&self) -> anyhow::Error {
+        anyhow::anyhow!(
+            "The {} provider isn't available in this build, choose another in the settings",
+            self.0
+        )
+    }
+}
+
+pub struct CloudTranscriber {
+    provider: Box<dyn CloudProvider>,
+}
+
+impl CloudTranscriber {
+    /// Create a new cloud transcriber for the configured provider
+    pub fn new(config: &CloudConfig) -> Self {
+        Self {
+            provider: create_provider(config),
+        }
+    }
+
+    /// Name of the active provider
+    pub fn provider_name(&self) -> &'static str {
+        self.provider.name()
+    }
+
+    /// Transcribe audio samples, reporting upload progress if asked
+    pub async fn transcribe(
+        &self,
+        samples: Vec<f32>,
+        sample_rate: u32,
+        language: Option<&str>,
+        progress: Option<&UploadProgressCallback>,
+    ) -> Result<TranscriptionResult, anyhow::Error> {
+        if samples.is_empty() {
+            return Err(anyhow::anyhow!("No audio samples provided"));
+        }
+
+        log::info!(
+            "Sending {:.2}s of audio to {} ({} samples at {} Hz)",
+            samples.len() as f32 / sample_rate as f32,
+            self.provider.name(),
+            samples.len(),
+            sample_rate
+        );
+
+        let result = self
+            .provider
+            .transcribe(&samples, sample_rate, language, progress)
+            .await?;
+
+        log::info!(
+            "Cloud transcription complete: {} chars, {} words, {} segments",
+            result.text.len(),
+            result.text.split_whitespace().count(),
+            result.segments.len()
+        );
+        log::debug!("Transcription text: {}", result.text);
+        Ok(result)
+    }
+
+    /// Transcribe audio samples into English, whatever language is spoken
+    pub async fn translate(
+        &self,
+        samples: Vec<f32>,
+        sample_rate: u32,
+        progress: Option<&UploadProgressCallback>,
+    ) -> Result<TranscriptionResult, anyhow::Error> {
+        if samples.is_empty() {
+            return Err(anyhow::anyhow!("No audio samples provided"));
+        }
+
+        log::info!(
+            "Sending {:.2}s of audio to {} for translation",
+            samples.len() as f32 / sample_rate as f32,
+            self.provider.name()
+        );
+
+        let result = self
+            .provider
+            .translate(&samples, sample_rate, progress)
+            .await?;
+
+        log::info!(
+            "Cloud translation complete: {} chars, {} segments",
+            result.text.len(),
+            result.segments.len()
+        );
+        log::debug!("Translation text: {}", result.text);
+        Ok(result)
+    }
+}
+
+/// A request body sent in chunks, reporting progress as each chunk is handed
+/// to the connection
+fn upload_body(bytes: Vec<u8>, progress: Option<&UploadProgressCallback>) -> reqwest::Body {
+    let Some(progress) = progress.cloned() else {
+        return bytes.into();
+    };
+
+    let total = bytes.len() as u64;
+    let chunks: Vec<Vec<u8>> = bytes
+        .chunks(UPLOAD_CHUNK_BYTES)
+        .map(<[u8]>::to_vec)
+        .collect();
+    let mut sent = 0;
+    let stream = futures_util::stream::iter(chunks).map(move |chunk| {
+        sent += chunk.len() as u64;
+        progress(sent, total);
+        Ok::<_, std::io::Error>(chunk)
+    });
+    reqwest::Body::wrap_stream(stream)
+}
+
+/// Convert f32 samples to WAV format bytes
+fn samples_to_wav(samples: &[f32], sample_rate: u32) -> Result<Vec<u8>, anyhow::Error> {
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+
+    let mut buffer = Cursor::new(Vec::new());
+    {
+        let mut writer = WavWriter::new(&mut buffer, spec)?;
+
+        for &sample in samples {
+            let clamped = sample.clamp(-1.0, 1.0);
+            let scaled = (clamped * 32767.0) as i16;
+            writer.write_sample(scaled)?;
+        }
+
+        writer.finalize()?;
+    }
+
+    Ok(buffer.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_samples_to_wav() {
+        let sample_rate = 16000;
+        let duration_secs = 0.1;
+        let num_samples = (sample_rate as f32 * duration_secs) as usize;
+
+        let samples: Vec<f32> = (0..num_samples)
+            .map(|i| {
+                let t = i as f32 / sample_rate as f32;
+                (t * 440.0 * 2.0 * std::f32::consts::PI).sin() * 0.5
+            })
+            .collect();
+
+        let wav_bytes = samples_to_wav(&samples, sample_rate).unwrap();
+
+        assert_eq!(&wav_bytes[0..4], b"RIFF");
+        assert_eq!(&wav_bytes[8..12], b"WAVE");
+
+        println!("Generated WAV bytes length: {}", wav_bytes.len());
+    }
+
+    #[test]
+    fn test_empty_samples() {
+        let wav_bytes = samples_to_wav(&[], 16000).unwrap();
+        assert!(wav_bytes.len() >= 44);
+    }
+
+    #[test]
+    fn test_provider_type_serialization() {
+        let json = serde_json::to_string(&CloudProviderType::OpenAi).unwrap();
+        assert_eq!(json, "\"openai\"");
+        let parsed: CloudProviderType = serde_json::from_str("\"deepgram\"").unwrap();
+        assert_eq!(parsed, CloudProviderType::Deepgram);
+    }
+}
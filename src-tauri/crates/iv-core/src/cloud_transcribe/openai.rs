@@ -0,0 +1,345 @@
+//! OpenAI whisper API provider (also used for OpenAI-compatible endpoints such as Groq)
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use async_openai::{
+    config::{Config, OpenAIConfig},
+    error::{ApiError, OpenAIError},
+    types::TranscriptionSegment,
+};
+use async_trait::async_trait;
+use reqwest::multipart::{Form, Part};
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use super::{
+    samples_to_wav, upload_body, CloudError, CloudProvider, ProviderCapabilities,
+    UploadProgressCallback,
+};
+use crate::transcript::{TranscriptSegment, TranscriptionResult};
+
+const GROQ_API_BASE: &str = "https://api.groq.com/openai/v1";
+
+pub struct OpenAiProvider {
+    name: &'static str,
+    client: reqwest::Client,
+    /// The API base and auth headers
+    config: OpenAIConfig,
+    model: String,
+    /// Model for the translations endpoint, which only whisper models serve
+    translation_model: String,
+    /// Set once a compatible server turned down `verbose_json`, so later
+    /// requests go straight to plain `json`
+    plain_json_only: AtomicBool,
+}
+
+impl OpenAiProvider {
+    /// OpenAI's hosted whisper-1 model, or any OpenAI-compatible server when
+    /// `base_url` is set
+    pub fn openai(
+        api_key: Option<String>,
+        base_url: Option<String>,
+        model: Option<String>,
+    ) -> Self {
+        let mut config = OpenAIConfig::new();
+        if let Some(key) = api_key {
+            config = config.with_api_key(key);
+        }
+
+        let name = match base_url {
+            Some(url) => {
+                log::info!("Using OpenAI-compatible endpoint at {}", url);
+                config = config.with_api_base(url.trim_end_matches('/'));
+                "OpenAI-compatible"
+            }
+            None => "OpenAI",
+        };
+
+        // OpenAI's gpt-4o transcription models don't translate
+        let translation_model = match (name, &model) {
+            ("OpenAI-compatible", Some(model)) => model.clone(),
+            _ => "whisper-1".to_string(),
+        };
+
+        Self {
+            name,
+            client: reqwest::Client::new(),
+            config,
+            model: model.unwrap_or_else(|| "whisper-1".to_string()),
+            translation_model,
+            plain_json_only: AtomicBool::new(false),
+        }
+    }
+
+    /// Groq's OpenAI-compatible whisper endpoint
+    pub fn groq(api_key: Option<String>, model: Option<String>) -> Self {
+        let config = OpenAIConfig::new()
+            .with_api_base(GROQ_API_BASE)
+            .with_api_key(api_key.unwrap_or_default());
+
+        Self {
+            name: "Groq",
+            client: reqwest::Client::new(),
+            config,
+            model: model.unwrap_or_else(|| "whisper-large-v3-turbo".to_string()),
+            // Turbo is transcription-only
+            translation_model: "whisper-large-v3".to_string(),
+            plain_json_only: AtomicBool::new(false),
+        }
+    }
+
+    /// Whether to ask `model` for `verbose_json`, which has the segment
+    /// timestamps. OpenAI's gpt-4o transcription models only answer with
+    /// plain `json`.
+    fn wants_verbose_json(&self, model: &str) -> bool {
+        !model.starts_with("gpt-4o") && !self.plain_json_only.load(Ordering::Relaxed)
+    }
+
+    /// Whether a failed `verbose_json` request is worth repeating with plain
+    /// `json`: a compatible server that rejected the format, or answered
+    /// with something other than verbose JSON
+    fn retry_plain_json(&self, error: &RequestError) -> bool {
+        let retry = self.name == "OpenAI-compatible"
+            && matches!(
+                error.error,
+                OpenAIError::ApiError(_) | OpenAIError::JSONDeserialize(_)
+            );
+        if retry {
+            log::warn!(
+                "{} doesn't return verbose JSON ({}), using plain JSON without timestamps",
+                self.name,
+                error.error
+            );
+            self.plain_json_only.store(true, Ordering::Relaxed);
+        }
+        retry
+    }
+
+    /// The multipart form for the transcription and translation endpoints.
+    /// The audio is streamed from `upload_body` so its upload progress can
+    /// be reported, which is why these requests don't go through
+    /// async-openai's client.
+    fn audio_form(
+        &self,
+        wav_bytes: Vec<u8>,
+        model: &str,
+        verbose: bool,
+        progress: Option<&UploadProgressCallback>,
+    ) -> Result<Form, OpenAIError> {
+        let length = wav_bytes.len() as u64;
+        let file = Part::stream_with_length(upload_body(wav_bytes, progress), length)
+            .file_name("audio.wav")
+            .mime_str("audio/wav")?;
+        let form = Form::new()
+            .part("file", file)
+            .text("model", model.to_string());
+
+        Ok(if verbose {
+            form.text("response_format", "verbose_json")
+                .text("timestamp_granularities[]", "segment")
+        } else {
+            form.text("response_format", "json")
+        })
+    }
+
+    /// Send an audio form to an endpoint like `/audio/transcriptions`
+    async fn post_audio(&self, path: &str, form: Form) -> Result<AudioResponse, RequestError> {
+        let response = self
+            .client
+            .post(self.config.url(path))
+            .headers(self.config.headers())
+            .multipart(form)
+            .send()
+            .await?;
+
+        let status = response.status();
+        let bytes = response.bytes().await?;
+        if !status.is_success() {
+            // Compatible servers don't always wrap their errors like OpenAI
+            let error = serde_json::from_slice::<WrappedError>(&bytes)
+                .map(|wrapped| wrapped.error)
+                .unwrap_or_else(|_| ApiError {
+                    message: format!("{} {}", status, String::from_utf8_lossy(&bytes).trim()),
+                    r#type: None,
+                    param: None,
+                    code: None,
+                });
+            return Err(RequestError {
+                status: Some(status),
+                error: OpenAIError::ApiError(error),
+            });
+        }
+        Ok(serde_json::from_slice(&bytes).map_err(OpenAIError::JSONDeserialize)?)
+    }
+
+    /// Transcribe or translate, asking for `verbose_json` when `model`
+    /// gives it and falling back to plain `json` on servers that turn it
+    /// down
+    async fn request(
+        &self,
+        path: &str,
+        wav_bytes: Vec<u8>,
+        model: &str,
+        language: Option<&str>,
+        progress: Option<&UploadProgressCallback>,
+    ) -> Result<AudioResponse, RequestError> {
+        let form = |wav_bytes, verbose| {
+            let form = self.audio_form(wav_bytes, model, verbose, progress)?;
+            Ok::<_, OpenAIError>(match language {
+                Some(lang) => form.text("language", lang.to_string()),
+                None => form,
+            })
+        };
+
+        if self.wants_verbose_json(model) {
+            match self.post_audio(path, form(wav_bytes.clone(), true)?).await {
+                Err(e) if self.retry_plain_json(&e) => {}
+                result => return result,
+            }
+        }
+        self.post_audio(path, form(wav_bytes, false)?).await
+    }
+
+    fn api_error(&self, action: &str, error: RequestError) -> anyhow::Error {
+        log::error!("{} API error: {}", self.name, error.error);
+        let message = format!("{} {} failed: {}", self.name, action, error.error);
+        match (error.status, &error.error) {
+            (Some(StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN), _) => {
+                CloudError::Unauthorized(message).into()
+            }
+            (_, OpenAIError::Reqwest(e)) if e.is_connect() || e.is_timeout() => {
+                CloudError::Unreachable(message).into()
+            }
+            _ => anyhow::anyhow!(message),
+        }
+    }
+}
+
+/// A failed request to either endpoint, with the status the server
+/// answered with if it got that far
+struct RequestError {
+    status: Option<StatusCode>,
+    error: OpenAIError,
+}
+
+impl From<OpenAIError> for RequestError {
+    fn from(error: OpenAIError) -> Self {
+        Self {
+            status: None,
+            error,
+        }
+    }
+}
+
+impl From<reqwest::Error> for RequestError {
+    fn from(error: reqwest::Error) -> Self {
+        OpenAIError::from(error).into()
+    }
+}
+
+/// OpenAI's error responses, `{"error": {...}}`
+#[derive(Deserialize)]
+struct WrappedError {
+    error: ApiError,
+}
+
+/// A `json` or `verbose_json` reply from either endpoint. Only the verbose
+/// one has segments.
+#[derive(Deserialize)]
+struct AudioResponse {
+    text: String,
+    #[serde(default)]
+    segments: Option<Vec<TranscriptionSegment>>,
+}
+
+impl From<AudioResponse> for TranscriptionResult {
+    fn from(response: AudioResponse) -> Self {
+        Self {
+            text: response.text.trim().to_string(),
+            segments: segments_from(response.segments),
+        }
+    }
+}
+
+/// Whisper segments with their text trimmed, skipping empty ones
+fn segments_from(segments: Option<Vec<TranscriptionSegment>>) -> Vec<TranscriptSegment> {
+    segments
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|s| !s.text.trim().is_empty())
+        .map(|s| TranscriptSegment {
+            start: s.start,
+            end: s.end,
+            text: s.text.trim().to_string(),
+            // Whisper reports the average token log probability
+            confidence: Some(s.avg_logprob.exp().clamp(0.0, 1.0)),
+            speaker: None,
+        })
+        .collect()
+}
+
+#[async_trait]
+impl CloudProvider for OpenAiProvider {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn transcribe(
+        &self,
+        samples: &[f32],
+        sample_rate: u32,
+        language: Option<&str>,
+        progress: Option<&UploadProgressCallback>,
+    ) -> Result<TranscriptionResult, anyhow::Error> {
+        let wav_bytes = samples_to_wav(samples, sample_rate)?;
+        log::debug!(
+            "Uploading {} bytes of WAV to {}",
+            wav_bytes.len(),
+            self.name
+        );
+
+        self.request(
+            "/audio/transcriptions",
+            wav_bytes,
+            &self.model,
+            language,
+            progress,
+        )
+        .await
+        .map(TranscriptionResult::from)
+        .map_err(|e| self.api_error("transcription", e))
+    }
+
+    async fn translate(
+        &self,
+        samples: &[f32],
+        sample_rate: u32,
+        progress: Option<&UploadProgressCallback>,
+    ) -> Result<TranscriptionResult, anyhow::Error> {
+        let wav_bytes = samples_to_wav(samples, sample_rate)?;
+
+        self.request(
+            "/audio/translations",
+            wav_bytes,
+            &self.translation_model,
+            None,
+            progress,
+        )
+        .await
+        .map(TranscriptionResult::from)
+        .map_err(|e| self.api_error("translation", e))
+    }
+
+    async fn capabilities(&self) -> Result<ProviderCapabilities, anyhow::Error> {
+        // The gpt-4o transcription models only time whole segments and
+        // don't translate (a translation would quietly come from
+        // whisper-1). Streaming isn't implemented for any model.
+        let gpt_4o = self.model.starts_with("gpt-4o");
+        Ok(ProviderCapabilities {
+            streaming: false,
+            word_timestamps: !gpt_4o,
+            translation: !gpt_4o,
+            languages: Vec::new(),
+        })
+    }
+}
@@ -0,0 +1,302 @@
+//! Self-hosted whisper servers on the local network
+//!
+//! Speaks both common homelab protocols: OpenAI-compatible servers such as
+//! faster-whisper-server (`/v1/audio/transcriptions`, `/v1/models`) and the
+//! whisper.cpp example server (`/inference`). The protocol is detected by
+//! probing the server on first use.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::multipart::{Form, Part};
+use reqwest::StatusCode;
+use serde::Serialize;
+
+use super::{
+    samples_to_wav, status_error, upload_body, CloudError, CloudProvider, ProviderCapabilities,
+    UploadProgressCallback,
+};
+use crate::transcript::{TranscriptSegment, TranscriptionResult};
+
+/// How long to wait for a probe before calling the server unreachable
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// API spoken by a self-hosted server
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerProtocol {
+    /// `/v1/audio/transcriptions` (faster-whisper-server, speaches, LocalAI)
+    OpenAiCompatible,
+    /// whisper.cpp's example server (`/inference`)
+    WhisperCpp,
+}
+
+/// Result of probing a self-hosted server
+#[derive(Serialize, Debug, Clone)]
+pub struct ServerStatus {
+    pub base_url: String,
+    pub protocol: ServerProtocol,
+    /// Whether the health endpoint answered successfully
+    pub healthy: bool,
+    /// Models the server offers (empty for whisper.cpp, which serves one model)
+    pub models: Vec<String>,
+}
+
+/// Strip trailing slashes and an API version suffix, so "http://box:8000/v1/"
+/// and "http://box:8000" point at the same server
+fn normalize_base_url(url: &str) -> String {
+    let url = url.trim().trim_end_matches('/');
+    url.strip_suffix("/v1").unwrap_or(url).to_string()
+}
+
+/// Probe a server for its protocol, health and available models
+pub async fn probe_server(base_url: &str) -> Result<ServerStatus, anyhow::Error> {
+    let base_url = normalize_base_url(base_url);
+    let client = reqwest::Client::builder().timeout(PROBE_TIMEOUT).build()?;
+
+    // OpenAI-compatible servers list their models; whisper.cpp 404s here
+    let models_response = client.get(format!("{}/v1/models", base_url)).send().await;
+
+    let (protocol, models) = match models_response {
+        Ok(response) if response.status().is_success() => {
+            let json: serde_json::Value = response.json().await.unwrap_or_default();
+            let models = json["data"]
+                .as_array()
+                .map(|data| {
+                    data.iter()
+                        .filter_map(|m| m["id"].as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default();
+            (ServerProtocol::OpenAiCompatible, models)
+        }
+        // A key is required to list models, but the endpoint exists
+        Ok(response)
+            if matches!(
+                response.status(),
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+            ) =>
+        {
+            (ServerProtocol::OpenAiCompatible, Vec::new())
+        }
+        Ok(response)
+            if matches!(
+                response.status(),
+                StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED
+            ) =>
+        {
+            (ServerProtocol::WhisperCpp, Vec::new())
+        }
+        // A server error says nothing about the protocol, so it's left for
+        // the next probe rather than cached
+        Ok(response) => {
+            return Err(anyhow::anyhow!(
+                "Whisper server at {} answered {}",
+                base_url,
+                response.status()
+            ))
+        }
+        Err(e) => {
+            return Err(anyhow::anyhow!(
+                "Whisper server at {} is unreachable: {}",
+                base_url,
+                e
+            ))
+        }
+    };
+
+    let healthy = client
+        .get(format!("{}/health", base_url))
+        .send()
+        .await
+        .map(|r| r.status().is_success())
+        .unwrap_or(false);
+
+    log::info!(
+        "Probed whisper server at {}: {:?}, healthy: {}, {} models",
+        base_url,
+        protocol,
+        healthy,
+        models.len()
+    );
+
+    Ok(ServerStatus {
+        base_url,
+        protocol,
+        healthy,
+        models,
+    })
+}
+
+pub struct SelfHostedProvider {
+    client: reqwest::Client,
+    base_url: Option<String>,
+    api_key: Option<String>,
+    model: Option<String>,
+    /// Protocol detected on first use
+    protocol: Mutex<Option<ServerProtocol>>,
+}
+
+impl SelfHostedProvider {
+    pub fn new(base_url: Option<String>, api_key: Option<String>, model: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.map(|url| normalize_base_url(&url)),
+            api_key,
+            model,
+            protocol: Mutex::new(None),
+        }
+    }
+
+    async fn protocol(&self, base_url: &str) -> Result<ServerProtocol, anyhow::Error> {
+        if let Some(protocol) = *self.protocol.lock().unwrap() {
+            return Ok(protocol);
+        }
+
+        let status = probe_server(base_url).await?;
+        *self.protocol.lock().unwrap() = Some(status.protocol);
+        Ok(status.protocol)
+    }
+}
+
+#[async_trait]
+impl CloudProvider for SelfHostedProvider {
+    fn name(&self) -> &'static str {
+        "Whisper server"
+    }
+
+    async fn transcribe(
+        &self,
+        samples: &[f32],
+        sample_rate: u32,
+        language: Option<&str>,
+        progress: Option<&UploadProgressCallback>,
+    ) -> Result<TranscriptionResult, anyhow::Error> {
+        let base_url = self.base_url.as_deref().ok_or_else(|| {
+            CloudError::NotConfigured("Whisper server URL not configured".to_string())
+        })?;
+
+        let protocol = self.protocol(base_url).await?;
+        let wav_bytes = samples_to_wav(samples, sample_rate)?;
+
+        let length = wav_bytes.len() as u64;
+        let file = Part::stream_with_length(upload_body(wav_bytes, progress), length)
+            .file_name("audio.wav")
+            .mime_str("audio/wav")?;
+        let mut form = Form::new()
+            .part("file", file)
+            .text("response_format", "verbose_json");
+        if let Some(lang) = language {
+            form = form.text("language", lang.to_string());
+        }
+
+        let url = match protocol {
+            ServerProtocol::OpenAiCompatible => {
+                if let Some(model) = &self.model {
+                    form = form.text("model", model.clone());
+                }
+                format!("{}/v1/audio/transcriptions", base_url)
+            }
+            ServerProtocol::WhisperCpp => format!("{}/inference", base_url),
+        };
+
+        let mut request = self.client.post(&url).multipart(form);
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            log::error!("Whisper server error ({}): {}", status, body);
+            return Err(status_error("Whisper server", status));
+        }
+
+        let json: serde_json::Value = response.json().await?;
+        let text = json["text"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Unexpected whisper server response format"))?;
+
+        Ok(TranscriptionResult {
+            text: text.trim().to_string(),
+            segments: parse_segments(&json),
+        })
+    }
+
+    async fn capabilities(&self) -> Result<ProviderCapabilities, anyhow::Error> {
+        let base_url = self.base_url.as_deref().ok_or_else(|| {
+            CloudError::NotConfigured("Whisper server URL not configured".to_string())
+        })?;
+
+        let protocol = self.protocol(base_url).await?;
+        Ok(ProviderCapabilities {
+            streaming: false,
+            // whisper.cpp's server only times segments
+            word_timestamps: protocol == ServerProtocol::OpenAiCompatible,
+            translation: false,
+            languages: Vec::new(),
+        })
+    }
+}
+
+/// Read `verbose_json` segments, which both protocols return in seconds
+fn parse_segments(json: &serde_json::Value) -> Vec<TranscriptSegment> {
+    let Some(segments) = json["segments"].as_array() else {
+        return Vec::new();
+    };
+
+    segments
+        .iter()
+        .filter_map(|s| {
+            let text = s["text"].as_str()?.trim();
+            if text.is_empty() {
+                return None;
+            }
+            Some(TranscriptSegment {
+                start: s["start"].as_f64()? as f32,
+                end: s["end"].as_f64()? as f32,
+                text: text.to_string(),
+                // Whisper reports the average token log probability
+                confidence: s["avg_logprob"]
+                    .as_f64()
+                    .map(|p| (p.exp() as f32).clamp(0.0, 1.0)),
+                speaker: None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_base_url() {
+        assert_eq!(
+            normalize_base_url("http://gpu-box:8000/v1/"),
+            "http://gpu-box:8000"
+        );
+        assert_eq!(
+            normalize_base_url(" http://10.0.0.5:8080 "),
+            "http://10.0.0.5:8080"
+        );
+    }
+
+    #[test]
+    fn test_parse_segments() {
+        let json = serde_json::json!({
+            "text": " Hello there.",
+            "segments": [
+                { "id": 0, "start": 0.0, "end": 1.2, "text": " Hello there." },
+                { "id": 1, "start": 1.2, "end": 1.5, "text": " " }
+            ]
+        });
+
+        let segments = parse_segments(&json);
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].text, "Hello there.");
+        assert_eq!(segments[0].end, 1.2);
+    }
+}
@@ -0,0 +1,80 @@
+//! Noise suppression before VAD and transcription
+//!
+//! Runs recordings through RNNoise (the `nnnoiseless` port) to take out
+//! steady background noise like fans and hum, which otherwise hurts both the
+//! VAD and the transcription models. RNNoise works on 48kHz audio in 10ms
+//! frames, so other rates are resampled there and back.
+
+use nnnoiseless::DenoiseState;
+
+use crate::audio;
+
+/// The only rate RNNoise works at
+const DENOISE_SAMPLE_RATE: u32 = 48000;
+
+/// RNNoise takes samples in 16-bit range rather than -1.0 - 1.0
+const SAMPLE_SCALE: f32 = i16::MAX as f32;
+
+/// Suppress background noise in mono audio, returning audio of the same
+/// length and sample rate
+pub fn denoise(samples: &[f32], sample_rate: u32) -> Vec<f32> {
+    if samples.is_empty() {
+        return Vec::new();
+    }
+
+    let input = if sample_rate == DENOISE_SAMPLE_RATE {
+        samples.to_vec()
+    } else {
+        audio::resample(samples, sample_rate, DENOISE_SAMPLE_RATE)
+    };
+
+    let mut state = DenoiseState::new();
+    let mut frame = [0.0; DenoiseState::FRAME_SIZE];
+    let mut denoised_frame = [0.0; DenoiseState::FRAME_SIZE];
+    let mut output = Vec::with_capacity(input.len() + DenoiseState::FRAME_SIZE);
+
+    // Output lags a frame behind the input, so one more frame of silence
+    // flushes the end through and the first frame (warm-up) is dropped
+    let frames = input.len().div_ceil(DenoiseState::FRAME_SIZE) + 1;
+    for i in 0..frames {
+        let start = (i * DenoiseState::FRAME_SIZE).min(input.len());
+        let end = (start + DenoiseState::FRAME_SIZE).min(input.len());
+        frame.fill(0.0);
+        for (scaled, &sample) in frame.iter_mut().zip(&input[start..end]) {
+            *scaled = sample * SAMPLE_SCALE;
+        }
+
+        state.process_frame(&mut denoised_frame, &frame);
+        if i > 0 {
+            output.extend(
+                denoised_frame
+                    .iter()
+                    .map(|&sample| (sample / SAMPLE_SCALE).clamp(-1.0, 1.0)),
+            );
+        }
+    }
+    output.truncate(input.len());
+
+    if sample_rate == DENOISE_SAMPLE_RATE {
+        output
+    } else {
+        let mut resampled = audio::resample(&output, DENOISE_SAMPLE_RATE, sample_rate);
+        resampled.resize(samples.len(), 0.0);
+        resampled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_denoise_keeps_length() {
+        let samples = (0..16000)
+            .map(|i| 0.1 * (i as f32 * 0.05).sin())
+            .collect::<Vec<_>>();
+        assert_eq!(denoise(&samples, 16000).len(), samples.len());
+        assert_eq!(denoise(&samples[..1234], 48000).len(), 1234);
+        assert!(denoise(&[], 16000).is_empty());
+    }
+}
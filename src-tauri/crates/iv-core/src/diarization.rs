@@ -0,0 +1,355 @@
+//! Speaker diarization
+//!
+//! Labels the segments of a transcript with who spoke them. Each segment's
+//! audio is turned into a speaker embedding by a small ONNX model (WeSpeaker
+//! ResNet34, fed Kaldi-style log mel filterbanks), and the embeddings are
+//! clustered by cosine similarity: one cluster per speaker. Segments too short
+//! to embed reliably take the speaker of the segment before them.
+
+use std::path::Path;
+
+use ort::session::Session;
+use ort::value::Tensor;
+
+use crate::transcript::TranscriptSegment;
+
+const SAMPLE_RATE: f32 = 16000.0;
+
+/// Filterbank frames are 25ms long, every 10ms
+const FRAME_LEN: usize = 400;
+const FRAME_SHIFT: usize = 160;
+const FFT_LEN: usize = 512;
+const NUM_MEL_BINS: usize = 80;
+const LOW_FREQ: f32 = 20.0;
+const PREEMPHASIS: f32 = 0.97;
+
+/// Segments shorter than this (0.5s) are too short for a reliable embedding
+const MIN_SEGMENT_SAMPLES: usize = 8000;
+
+/// Cosine similarity above which two segments are the same speaker
+const SIMILARITY_THRESHOLD: f32 = 0.5;
+
+/// Computes speaker embeddings with the downloaded model
+pub struct SpeakerEmbedder {
+    session: Session,
+}
+
+impl SpeakerEmbedder {
+    pub fn new(model_path: &Path) -> Result<Self, anyhow::Error> {
+        let session = Session::builder()?.commit_from_file(model_path)?;
+        Ok(Self { session })
+    }
+
+    /// Unit-length embedding of 16kHz speech
+    pub fn embed(&mut self, samples: &[f32]) -> Result<Vec<f32>, anyhow::Error> {
+        let features = fbank(samples);
+        let frames = features.len() / NUM_MEL_BINS;
+        if frames == 0 {
+            return Err(anyhow::anyhow!("Audio is too short to embed"));
+        }
+
+        let input = Tensor::from_array(([1, frames, NUM_MEL_BINS], features))?;
+        let outputs = self.session.run(ort::inputs![input])?;
+        let (_, embedding) = outputs[0].try_extract_tensor::<f32>()?;
+        Ok(normalize(embedding.to_vec()))
+    }
+}
+
+/// Label segments "Speaker 1", "Speaker 2"... in order of first appearance.
+/// `speech` is the audio the segment times refer to. Returns how many
+/// speakers were found; segments are only labelled when there's more than one.
+pub fn label_speakers(
+    embedder: &mut SpeakerEmbedder,
+    speech: &[f32],
+    segments: &mut [TranscriptSegment],
+) -> Result<usize, anyhow::Error> {
+    let embeddings = segments
+        .iter()
+        .map(|segment| {
+            let start = ((segment.start * SAMPLE_RATE) as usize).min(speech.len());
+            let end = ((segment.end * SAMPLE_RATE) as usize).clamp(start, speech.len());
+            if end - start < MIN_SEGMENT_SAMPLES {
+                return Ok(None);
+            }
+            embedder.embed(&speech[start..end]).map(Some)
+        })
+        .collect::<Result<Vec<_>, anyhow::Error>>()?;
+
+    let clusters = cluster(&embeddings, SIMILARITY_THRESHOLD);
+    Ok(apply_labels(segments, &clusters))
+}
+
+/// Assign each embedding to a speaker. Embeddings join the most similar
+/// speaker so far (or start a new one), then everything is reassigned to the
+/// nearest final speaker, so early guesses made with little to compare
+/// against get corrected.
+fn cluster(embeddings: &[Option<Vec<f32>>], threshold: f32) -> Vec<Option<usize>> {
+    let mut centroids: Vec<Vec<f32>> = Vec::new();
+    for embedding in embeddings.iter().flatten() {
+        match nearest(&centroids, embedding) {
+            Some((index, similarity)) if similarity >= threshold => {
+                for (c, e) in centroids[index].iter_mut().zip(embedding) {
+                    *c += e;
+                }
+            }
+            _ => centroids.push(embedding.clone()),
+        }
+    }
+    let centroids: Vec<Vec<f32>> = centroids.into_iter().map(normalize).collect();
+
+    // Number speakers by first appearance
+    let mut order: Vec<usize> = Vec::new();
+    embeddings
+        .iter()
+        .map(|embedding| {
+            let (index, _) = nearest(&centroids, embedding.as_ref()?)?;
+            let speaker = match order.iter().position(|&i| i == index) {
+                Some(speaker) => speaker,
+                None => {
+                    order.push(index);
+                    order.len() - 1
+                }
+            };
+            Some(speaker)
+        })
+        .collect()
+}
+
+/// Label segments from their clusters, returning the number of speakers
+fn apply_labels(segments: &mut [TranscriptSegment], clusters: &[Option<usize>]) -> usize {
+    let speakers = clusters.iter().flatten().max().map_or(0, |max| max + 1);
+    if speakers < 2 {
+        return speakers;
+    }
+
+    // Unembedded segments continue the previous speaker (or start with the first)
+    let mut previous = clusters.iter().flatten().next().copied();
+    for (segment, cluster) in segments.iter_mut().zip(clusters) {
+        let speaker = cluster.or(previous);
+        previous = speaker;
+        segment.speaker = speaker.map(|s| format!("Speaker {}", s + 1));
+    }
+    speakers
+}
+
+fn nearest(centroids: &[Vec<f32>], embedding: &[f32]) -> Option<(usize, f32)> {
+    centroids
+        .iter()
+        .map(|centroid| cosine_similarity(centroid, embedding))
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    dot / (norm(a) * norm(b)).max(f32::EPSILON)
+}
+
+fn normalize(mut v: Vec<f32>) -> Vec<f32> {
+    let norm = v
+        .iter()
+        .map(|x| x * x)
+        .sum::<f32>()
+        .sqrt()
+        .max(f32::EPSILON);
+    for x in v.iter_mut() {
+        *x /= norm;
+    }
+    v
+}
+
+fn hz_to_mel(hz: f32) -> f32 {
+    1127.0 * (1.0 + hz / 700.0).ln()
+}
+
+/// Triangular mel filters over the FFT bins, as (first bin, weights)
+fn mel_filters() -> Vec<(usize, Vec<f32>)> {
+    let mel_low = hz_to_mel(LOW_FREQ);
+    let mel_high = hz_to_mel(SAMPLE_RATE / 2.0);
+    let mel_step = (mel_high - mel_low) / (NUM_MEL_BINS + 1) as f32;
+    let bin_hz = SAMPLE_RATE / FFT_LEN as f32;
+
+    (0..NUM_MEL_BINS)
+        .map(|m| {
+            let left = mel_low + m as f32 * mel_step;
+            let center = left + mel_step;
+            let right = center + mel_step;
+
+            let weights: Vec<(usize, f32)> = (0..=FFT_LEN / 2)
+                .filter_map(|bin| {
+                    let mel = hz_to_mel(bin as f32 * bin_hz);
+                    let weight = if mel > left && mel <= center {
+                        (mel - left) / (center - left)
+                    } else if mel > center && mel < right {
+                        (right - mel) / (right - center)
+                    } else {
+                        return None;
+                    };
+                    Some((bin, weight))
+                })
+                .collect();
+
+            let first = weights.first().map_or(0, |(bin, _)| *bin);
+            (first, weights.into_iter().map(|(_, w)| w).collect())
+        })
+        .collect()
+}
+
+/// In-place radix-2 FFT
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * std::f32::consts::PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let t_re = re[b] * cos - im[b] * sin;
+                let t_im = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// Kaldi-style log mel filterbank features, mean-normalized, as frames of
+/// [`NUM_MEL_BINS`] values laid end to end
+fn fbank(samples: &[f32]) -> Vec<f32> {
+    if samples.len() < FRAME_LEN {
+        return Vec::new();
+    }
+    let frames = 1 + (samples.len() - FRAME_LEN) / FRAME_SHIFT;
+    let filters = mel_filters();
+    let window: Vec<f32> = (0..FRAME_LEN)
+        .map(|i| {
+            let hann =
+                0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / (FRAME_LEN - 1) as f32).cos();
+            hann.powf(0.85)
+        })
+        .collect();
+
+    let mut features = Vec::with_capacity(frames * NUM_MEL_BINS);
+    let mut re = vec![0.0; FFT_LEN];
+    let mut im = vec![0.0; FFT_LEN];
+
+    for frame in 0..frames {
+        // The model was trained on 16-bit sample values
+        let start = frame * FRAME_SHIFT;
+        let mut x: Vec<f32> = samples[start..start + FRAME_LEN]
+            .iter()
+            .map(|s| s * 32768.0)
+            .collect();
+
+        let mean = x.iter().sum::<f32>() / FRAME_LEN as f32;
+        x.iter_mut().for_each(|s| *s -= mean);
+        for i in (1..FRAME_LEN).rev() {
+            x[i] -= PREEMPHASIS * x[i - 1];
+        }
+        x[0] -= PREEMPHASIS * x[0];
+
+        re.fill(0.0);
+        im.fill(0.0);
+        for (i, (s, w)) in x.iter().zip(&window).enumerate() {
+            re[i] = s * w;
+        }
+        fft(&mut re, &mut im);
+
+        for (first, weights) in &filters {
+            let energy: f32 = weights
+                .iter()
+                .enumerate()
+                .map(|(k, w)| {
+                    let bin = first + k;
+                    w * (re[bin] * re[bin] + im[bin] * im[bin])
+                })
+                .sum();
+            features.push(energy.max(f32::EPSILON).ln());
+        }
+    }
+
+    for bin in 0..NUM_MEL_BINS {
+        let mean = (0..frames)
+            .map(|f| features[f * NUM_MEL_BINS + bin])
+            .sum::<f32>()
+            / frames as f32;
+        for f in 0..frames {
+            features[f * NUM_MEL_BINS + bin] -= mean;
+        }
+    }
+
+    features
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment() -> TranscriptSegment {
+        TranscriptSegment {
+            start: 0.0,
+            end: 1.0,
+            text: "Hello".to_string(),
+            confidence: None,
+            speaker: None,
+        }
+    }
+
+    #[test]
+    fn test_cluster_and_label_two_speakers() {
+        let a = Some(vec![1.0, 0.1, 0.0]);
+        let b = Some(vec![0.0, 0.1, 1.0]);
+        let a2 = Some(vec![0.9, 0.2, 0.1]);
+        let clusters = cluster(&[b.clone(), a, None, b, a2], SIMILARITY_THRESHOLD);
+        assert_eq!(clusters, vec![Some(0), Some(1), None, Some(0), Some(1)]);
+
+        let mut segments = vec![segment(); 5];
+        assert_eq!(apply_labels(&mut segments, &clusters), 2);
+        let labels: Vec<_> = segments.iter().map(|s| s.speaker.as_deref()).collect();
+        assert_eq!(
+            labels,
+            vec![
+                Some("Speaker 1"),
+                Some("Speaker 2"),
+                Some("Speaker 2"),
+                Some("Speaker 1"),
+                Some("Speaker 2")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_speaker_is_not_labelled() {
+        let clusters = cluster(&[Some(vec![1.0, 0.0]), Some(vec![0.9, 0.1])], 0.5);
+        let mut segments = vec![segment(); 2];
+        assert_eq!(apply_labels(&mut segments, &clusters), 1);
+        assert!(segments.iter().all(|s| s.speaker.is_none()));
+    }
+
+    #[test]
+    fn test_fbank_frames() {
+        let samples: Vec<f32> = (0..16000).map(|i| (i as f32 * 0.05).sin() * 0.1).collect();
+        let features = fbank(&samples);
+        assert_eq!(features.len(), 98 * NUM_MEL_BINS);
+        assert!(features.iter().all(|f| f.is_finite()));
+    }
+}
@@ -0,0 +1,20 @@
+//! Core transcription pipeline for iv
+//!
+//! Audio capture, resampling, voice activity detection and the local/cloud
+//! transcription engines, with no dependency on the Tauri runtime. The app,
+//! tests and alternative frontends all consume the same stack from here.
+
+pub mod app_capture;
+pub mod audio;
+pub mod audio_file;
+pub mod calibration;
+pub mod cloud_transcribe;
+pub mod denoise;
+pub mod diarization;
+pub mod local_transcribe;
+pub mod models;
+pub mod rewrite;
+pub mod speakers;
+pub mod system_capture;
+pub mod transcript;
+pub mod vad;
@@ -5,11 +5,14 @@ use std::sync::Mutex;
 
 use anyhow::Result;
 use transcribe_rs::{
-    engines::parakeet::{ParakeetEngine, ParakeetInferenceParams, ParakeetModelParams},
+    engines::parakeet::{
+        ParakeetEngine, ParakeetInferenceParams, ParakeetModelParams, TimestampGranularity,
+    },
     TranscriptionEngine,
 };
 
 use crate::models::{EngineType, ModelInfo};
+use crate::transcript::{TranscriptSegment, TranscriptionResult};
 
 pub struct LocalTranscriber {
     engine: Mutex<Option<ParakeetEngine>>,
@@ -34,6 +37,13 @@ impl LocalTranscriber {
         self.current_model_id.lock().unwrap().clone()
     }
 
+    /// Whether the loaded engine can bias decoding towards a phrase list
+    /// (hotword boosting). Parakeet's TDT decoder has no such option, so
+    /// callers correct transcripts against the list afterwards instead.
+    pub fn supports_phrase_hints(&self) -> bool {
+        false
+    }
+
     /// Load a model for transcription
     pub fn load_model(&self, model_info: &ModelInfo, model_path: &PathBuf) -> Result<()> {
         let load_start = std::time::Instant::now();
@@ -88,11 +98,12 @@ impl LocalTranscriber {
         log::info!("Model unloaded");
     }
 
-    /// Transcribe audio samples
-    pub fn transcribe(&self, samples: Vec<f32>) -> Result<String> {
+    /// Transcribe audio samples into text and sentence-level segments, with
+    /// timestamps relative to the start of `samples`
+    pub fn transcribe(&self, samples: Vec<f32>) -> Result<TranscriptionResult> {
         if samples.is_empty() {
-            log::debug!("Empty audio samples, returning empty string");
-            return Ok(String::new());
+            log::debug!("Empty audio samples, returning empty transcript");
+            return Ok(TranscriptionResult::default());
         }
 
         let transcribe_start = std::time::Instant::now();
@@ -108,7 +119,10 @@ impl LocalTranscriber {
             .as_mut()
             .ok_or_else(|| anyhow::anyhow!("No model loaded"))?;
 
-        let params = ParakeetInferenceParams::default();
+        let params = ParakeetInferenceParams {
+            timestamp_granularity: TimestampGranularity::Segment,
+            ..Default::default()
+        };
 
         let result = engine
             .transcribe_samples(samples, Some(params))
@@ -124,7 +138,24 @@ impl LocalTranscriber {
             result.text.trim()
         );
 
-        Ok(result.text.trim().to_string())
+        let segments = result
+            .segments
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|s| !s.text.trim().is_empty())
+            .map(|s| TranscriptSegment {
+                start: s.start,
+                end: s.end,
+                text: s.text.trim().to_string(),
+                confidence: None,
+                speaker: None,
+            })
+            .collect();
+
+        Ok(TranscriptionResult {
+            text: result.text.trim().to_string(),
+            segments,
+        })
     }
 }
 
@@ -6,6 +6,8 @@ use serde::{Deserialize, Serialize};
 pub enum EngineType {
     Parakeet,
     Cloud,
+    /// Speaker embedding model for diarization, not a transcription engine
+    Diarization,
 }
 
 impl Default for EngineType {
@@ -35,6 +37,13 @@ pub struct ModelInfo {
     pub is_downloading: bool,
     /// Partial download size (for resume)
     pub partial_size: u64,
+    /// Whether it's downloaded to the machine-wide models folder shared by
+    /// every account
+    #[serde(default)]
+    pub is_shared: bool,
+    /// Whether the team baseline recommends it
+    #[serde(default)]
+    pub is_recommended: bool,
     /// Whether this is a directory-based model (Parakeet) vs single file
     pub is_directory: bool,
     /// The engine type for this model
@@ -43,6 +52,9 @@ pub struct ModelInfo {
     pub accuracy_score: f32,
     /// Speed score (0.0 to 1.0, higher is faster)
     pub speed_score: f32,
+    /// ISO 639-1 codes the model can transcribe (empty = multilingual)
+    #[serde(default)]
+    pub supported_languages: Vec<String>,
 }
 
 impl ModelInfo {
@@ -60,13 +72,16 @@ impl ModelInfo {
             is_downloaded: true, // Always "available"
             is_downloading: false,
             partial_size: 0,
+            is_shared: false,
+            is_recommended: false,
             is_directory: false,
             engine_type: EngineType::Cloud,
             accuracy_score: 0.95,
             speed_score: 0.70, // Depends on network
+            supported_languages: Vec::new(),
         }
     }
-    
+
     pub fn parakeet_v3() -> Self {
         Self {
             id: "parakeet-v3".to_string(),
@@ -82,12 +97,66 @@ impl ModelInfo {
             is_downloaded: false,
             is_downloading: false,
             partial_size: 0,
+            is_shared: false,
+            is_recommended: false,
             is_directory: true,
             engine_type: EngineType::Parakeet,
             accuracy_score: 0.92,
             speed_score: 0.85,
+            supported_languages: vec!["en".to_string()],
         }
     }
+
+    /// Speaker embedding model used to label speakers in transcripts
+    pub fn speaker_embedding() -> Self {
+        Self {
+            id: "speaker-embedding".to_string(),
+            name: "Speaker Labels (WeSpeaker)".to_string(),
+            description: "Labels who spoke each part of multi-speaker recordings and files. Not a transcription model."
+                .to_string(),
+            filename: "wespeaker_en_voxceleb_resnet34.onnx".to_string(),
+            url: Some(
+                "https://github.com/k2-fsa/sherpa-onnx/releases/download/speaker-recongition-models/wespeaker_en_voxceleb_resnet34.onnx"
+                    .to_string(),
+            ),
+            size_mb: 26,
+            is_downloaded: false,
+            is_downloading: false,
+            partial_size: 0,
+            is_shared: false,
+            is_recommended: false,
+            is_directory: false,
+            engine_type: EngineType::Diarization,
+            accuracy_score: 0.0,
+            speed_score: 0.0,
+            supported_languages: Vec::new(),
+        }
+    }
+
+    /// Whether the model transcribes speech (rather than supporting a pipeline step)
+    pub fn is_transcription_model(&self) -> bool {
+        self.engine_type != EngineType::Diarization
+    }
+
+    /// Check whether the model can transcribe the given ISO 639-1 language
+    pub fn supports_language(&self, code: &str) -> bool {
+        self.supported_languages.is_empty() || self.supported_languages.iter().any(|l| l == code)
+    }
+
+    /// Explain why the model is a poor fit for the configured language, if it is.
+    /// "auto" never warns, since the language isn't known until it's detected.
+    pub fn language_warning(&self, language: &str) -> Option<String> {
+        if language.is_empty() || language == "auto" || self.supports_language(language) {
+            return None;
+        }
+
+        Some(format!(
+            "{} doesn't support the '{}' language (supported: {}). Transcripts will likely be wrong.",
+            self.name,
+            language,
+            self.supported_languages.join(", ")
+        ))
+    }
 }
 
 /// Download progress information
@@ -0,0 +1,131 @@
+//! LLM rewrite of finished transcripts
+//!
+//! An optional pass after transcription that sends the text to a chat model
+//! with a preset instruction ("fix grammar", "make it a formal email", ...) or
+//! a custom prompt. Works with OpenAI or any OpenAI-compatible endpoint, such
+//! as a local Ollama or llama.cpp server.
+
+use async_openai::{
+    config::OpenAIConfig,
+    types::{
+        ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
+        CreateChatCompletionRequestArgs,
+    },
+    Client,
+};
+use serde::{Deserialize, Serialize};
+
+/// What the rewrite does to the transcript
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RewritePreset {
+    #[default]
+    FixGrammar,
+    FormalEmail,
+    BulletPoints,
+    /// The user's own prompt
+    Custom,
+}
+
+impl RewritePreset {
+    /// The instruction sent to the model, or None for a custom preset
+    /// without a prompt
+    pub fn instructions(self, custom_prompt: &str) -> Option<String> {
+        let instructions = match self {
+            RewritePreset::FixGrammar => {
+                "Fix grammar, spelling and punctuation without changing the wording or meaning."
+            }
+            RewritePreset::FormalEmail => {
+                "Rewrite it as a formal email body, keeping every point it makes."
+            }
+            RewritePreset::BulletPoints => {
+                "Rewrite it as a concise bullet point list, one point per line starting with \"- \"."
+            }
+            RewritePreset::Custom => custom_prompt.trim(),
+        };
+        (!instructions.is_empty()).then(|| instructions.to_string())
+    }
+}
+
+/// Where the rewrite runs
+#[derive(Debug, Clone, Default)]
+pub struct RewriteConfig {
+    pub api_key: Option<String>,
+    /// OpenAI-compatible server (None = api.openai.com)
+    pub base_url: Option<String>,
+    pub model: String,
+}
+
+fn system_prompt(instructions: &str) -> String {
+    format!(
+        "You edit dictated text. {} Reply with only the edited text, without any \
+         introduction, quotes or commentary.",
+        instructions
+    )
+}
+
+/// Rewrite a transcript following the instructions
+pub async fn rewrite(
+    config: &RewriteConfig,
+    instructions: &str,
+    text: &str,
+) -> Result<String, anyhow::Error> {
+    let mut openai_config = OpenAIConfig::new();
+    if let Some(key) = &config.api_key {
+        openai_config = openai_config.with_api_key(key);
+    }
+    if let Some(url) = &config.base_url {
+        openai_config = openai_config.with_api_base(url.trim_end_matches('/'));
+    }
+    let client = Client::with_config(openai_config);
+
+    let request = CreateChatCompletionRequestArgs::default()
+        .model(&config.model)
+        .temperature(0.2)
+        .messages([
+            ChatCompletionRequestSystemMessageArgs::default()
+                .content(system_prompt(instructions))
+                .build()?
+                .into(),
+            ChatCompletionRequestUserMessageArgs::default()
+                .content(text)
+                .build()?
+                .into(),
+        ])
+        .build()?;
+
+    log::debug!("Rewriting {} chars with {}", text.len(), config.model);
+    let response = client
+        .chat()
+        .create(request)
+        .await
+        .map_err(|e| anyhow::anyhow!("Rewrite with {} failed: {}", config.model, e))?;
+
+    let rewritten = response
+        .choices
+        .into_iter()
+        .next()
+        .and_then(|choice| choice.message.content)
+        .map(|content| content.trim().to_string())
+        .filter(|content| !content.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("{} returned no text", config.model))?;
+    Ok(rewritten)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_instructions() {
+        assert!(RewritePreset::FixGrammar.instructions("").is_some());
+        assert_eq!(
+            RewritePreset::Custom.instructions(" Translate to French. "),
+            Some("Translate to French.".to_string())
+        );
+        assert_eq!(RewritePreset::Custom.instructions("  "), None);
+
+        let parsed: RewritePreset = serde_json::from_str("\"bullet_points\"").unwrap();
+        assert_eq!(parsed, RewritePreset::BulletPoints);
+    }
+}
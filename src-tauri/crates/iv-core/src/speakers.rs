@@ -0,0 +1,243 @@
+//! Two-party transcripts from separate microphone and system audio
+//!
+//! When the microphone and the system audio are recorded side by side, each
+//! 30ms frame is credited to whichever source is louder. Runs of frames become
+//! speaker turns ("Me" for the microphone, "Them" for the system audio), each
+//! turn is transcribed from its own source, and the results are put back
+//! together in order with every segment tagged with its speaker.
+
+use crate::transcript::{TranscriptSegment, TranscriptionResult};
+
+const SAMPLE_RATE: usize = 16000;
+
+/// Frame length the sources are compared over (30ms at 16kHz)
+const FRAME_LEN: usize = 480;
+
+/// Frames quieter than this (RMS) in both sources are silence and don't
+/// change who is speaking
+const SILENCE_RMS: f32 = 0.01;
+
+/// The other source must be louder for this many frames (~0.5s) to start a
+/// new turn, so a cough or a little echo doesn't split a turn
+const MIN_SWITCH_FRAMES: usize = 17;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Speaker {
+    /// The microphone
+    Me,
+    /// The system audio
+    Them,
+}
+
+impl Speaker {
+    pub fn label(self) -> &'static str {
+        match self {
+            Speaker::Me => "Me",
+            Speaker::Them => "Them",
+        }
+    }
+}
+
+/// A stretch of the recording where one side is talking
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Turn {
+    pub speaker: Speaker,
+    /// First sample (16kHz) of the turn
+    pub start: usize,
+    /// Sample (16kHz) after the turn's last
+    pub end: usize,
+}
+
+impl Turn {
+    /// The turn's audio from its speaker's source
+    pub fn samples<'a>(&self, me: &'a [f32], them: &'a [f32]) -> &'a [f32] {
+        let source = match self.speaker {
+            Speaker::Me => me,
+            Speaker::Them => them,
+        };
+        &source[self.start.min(source.len())..self.end.min(source.len())]
+    }
+}
+
+fn frame_rms(samples: &[f32], frame: usize) -> f32 {
+    let start = (frame * FRAME_LEN).min(samples.len());
+    let end = ((frame + 1) * FRAME_LEN).min(samples.len());
+    if start == end {
+        return 0.0;
+    }
+    let frame = &samples[start..end];
+    (frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32).sqrt()
+}
+
+/// Split two 16kHz recordings that started together into speaker turns,
+/// covering the whole recording. Empty when neither side spoke.
+pub fn speaker_turns(me: &[f32], them: &[f32]) -> Vec<Turn> {
+    let len = me.len().max(them.len());
+    let mut turns: Vec<Turn> = Vec::new();
+    // Frame where the other side started being louder, and for how many frames
+    let mut switch: Option<(usize, usize)> = None;
+
+    for frame in 0..len.div_ceil(FRAME_LEN) {
+        let me_rms = frame_rms(me, frame);
+        let them_rms = frame_rms(them, frame);
+        if me_rms.max(them_rms) < SILENCE_RMS {
+            continue;
+        }
+        let louder = if me_rms >= them_rms {
+            Speaker::Me
+        } else {
+            Speaker::Them
+        };
+
+        let Some(current) = turns.last_mut() else {
+            // The first turn takes in any silence before it
+            turns.push(Turn {
+                speaker: louder,
+                start: 0,
+                end: len,
+            });
+            continue;
+        };
+
+        if louder == current.speaker {
+            switch = None;
+            continue;
+        }
+
+        let (first, count) = switch.get_or_insert((frame, 0));
+        *count += 1;
+        if *count >= MIN_SWITCH_FRAMES {
+            let start = *first * FRAME_LEN;
+            current.end = start;
+            turns.push(Turn {
+                speaker: louder,
+                start,
+                end: len,
+            });
+            switch = None;
+        }
+    }
+
+    turns
+}
+
+/// Put transcribed turns back together, in order, as one transcript with a
+/// "Speaker: text" line per turn and segments tagged with their speaker
+pub fn merge_turns(turns: Vec<(Turn, TranscriptionResult)>) -> TranscriptionResult {
+    let mut lines: Vec<(Speaker, String)> = Vec::new();
+    let mut segments = Vec::new();
+
+    for (turn, result) in turns {
+        let text = result.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        let offset = turn.start as f32 / SAMPLE_RATE as f32;
+        let speaker = Some(turn.speaker.label().to_string());
+        if result.segments.is_empty() {
+            segments.push(TranscriptSegment {
+                start: offset,
+                end: turn.end as f32 / SAMPLE_RATE as f32,
+                text: text.to_string(),
+                confidence: None,
+                speaker,
+            });
+        } else {
+            segments.extend(
+                result
+                    .segments
+                    .into_iter()
+                    .map(|segment| TranscriptSegment {
+                        start: segment.start + offset,
+                        end: segment.end + offset,
+                        speaker: speaker.clone(),
+                        ..segment
+                    }),
+            );
+        }
+
+        // Turns split by silence on the other side read as one
+        match lines.last_mut() {
+            Some((last, line)) if *last == turn.speaker => {
+                line.push(' ');
+                line.push_str(text);
+            }
+            _ => lines.push((turn.speaker, text.to_string())),
+        }
+    }
+
+    TranscriptionResult {
+        text: lines
+            .iter()
+            .map(|(speaker, line)| format!("{}: {}", speaker.label(), line))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        segments,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(secs: f32, level: f32) -> Vec<f32> {
+        (0..(secs * SAMPLE_RATE as f32) as usize)
+            .map(|i| level * (i as f32 * 0.1).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_speaker_turns_follow_louder_source() {
+        // Me for 1s, a short blip of echo, them for 1s
+        let mut me = tone(1.0, 0.2);
+        me.extend(tone(1.0, 0.0));
+        let mut them = tone(0.5, 0.0);
+        them.extend(tone(0.1, 0.3));
+        them.extend(tone(0.4, 0.0));
+        them.extend(tone(1.0, 0.2));
+
+        let turns = speaker_turns(&me, &them);
+        assert_eq!(turns.len(), 2);
+        assert_eq!(turns[0].speaker, Speaker::Me);
+        assert_eq!(turns[0].start, 0);
+        assert_eq!(turns[1].speaker, Speaker::Them);
+        // Cut at the start of the frame where they get louder
+        assert_eq!(turns[1].start, 15840);
+        assert_eq!(turns[1].end, 32000);
+
+        assert!(speaker_turns(&tone(1.0, 0.0), &[]).is_empty());
+    }
+
+    #[test]
+    fn test_merge_turns_tags_speakers() {
+        let turn = |speaker, start, end| Turn {
+            speaker,
+            start,
+            end,
+        };
+        let merged = merge_turns(vec![
+            (
+                turn(Speaker::Me, 0, 16000),
+                TranscriptionResult::from_text("Can you hear me?"),
+            ),
+            (
+                turn(Speaker::Them, 16000, 32000),
+                TranscriptionResult::from_text("Yes."),
+            ),
+            (
+                turn(Speaker::Me, 32000, 40000),
+                TranscriptionResult::default(),
+            ),
+            (
+                turn(Speaker::Them, 40000, 48000),
+                TranscriptionResult::from_text("Go ahead."),
+            ),
+        ]);
+
+        assert_eq!(merged.text, "Me: Can you hear me?\nThem: Yes. Go ahead.");
+        assert_eq!(merged.segments.len(), 3);
+        assert_eq!(merged.segments[1].start, 1.0);
+        assert_eq!(merged.segments[1].speaker.as_deref(), Some("Them"));
+    }
+}
@@ -0,0 +1,105 @@
+//! Capturing the system audio output (loopback)
+//!
+//! Records whatever the computer is playing, e.g. the other side of a call,
+//! either on its own or mixed with the microphone.
+//!
+//! - Linux: `parec` records the default sink's monitor source through the
+//!   PulseAudio interface (served by PipeWire on modern desktops).
+//! - Windows: WASAPI loopback, by opening the default output device as an
+//!   input stream.
+//! - macOS: there's no loopback without a virtual device, so an installed
+//!   loopback driver (BlackHole, Loopback, Soundflower) is used as the input.
+//!   Without one the error explains how to set it up.
+
+use std::process::Command;
+
+use cpal::{Device, SupportedStreamConfig};
+
+/// Where system audio is read from
+pub(crate) enum LoopbackSource {
+    /// A process writing mono f32 samples at
+    /// [`crate::app_capture::CAPTURE_SAMPLE_RATE`] to stdout
+    Process(Command),
+    /// A device opened as an input stream with this config
+    Device(Device, SupportedStreamConfig),
+}
+
+pub(crate) fn loopback_source() -> Result<LoopbackSource, anyhow::Error> {
+    imp::loopback_source()
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use std::process::Command;
+
+    use super::LoopbackSource;
+    use crate::app_capture::CAPTURE_SAMPLE_RATE;
+
+    pub fn loopback_source() -> Result<LoopbackSource, anyhow::Error> {
+        let mut command = Command::new("parec");
+        command.args([
+            "--device=@DEFAULT_MONITOR@".to_string(),
+            "--format=float32le".to_string(),
+            format!("--rate={}", CAPTURE_SAMPLE_RATE),
+            "--channels=1".to_string(),
+            "--raw".to_string(),
+        ]);
+        Ok(LoopbackSource::Process(command))
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use cpal::traits::{DeviceTrait, HostTrait};
+
+    use super::LoopbackSource;
+
+    pub fn loopback_source() -> Result<LoopbackSource, anyhow::Error> {
+        let device = cpal::default_host()
+            .default_output_device()
+            .ok_or_else(|| anyhow::anyhow!("No default output device available"))?;
+        // WASAPI only loops back in the output's own format
+        let config = device.default_output_config()?;
+        Ok(LoopbackSource::Device(device, config))
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    use cpal::traits::{DeviceTrait, HostTrait};
+
+    use super::LoopbackSource;
+    use crate::audio::AudioRecorder;
+
+    /// Names of virtual devices that loop system output back as an input
+    const LOOPBACK_DEVICES: [&str; 3] = ["BlackHole", "Loopback", "Soundflower"];
+
+    pub fn loopback_source() -> Result<LoopbackSource, anyhow::Error> {
+        let device = cpal::default_host()
+            .input_devices()?
+            .find(|d| {
+                d.name()
+                    .is_ok_and(|name| LOOPBACK_DEVICES.iter().any(|l| name.contains(l)))
+            })
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Recording system audio on macOS needs a loopback device. Install \
+                     BlackHole, then in Audio MIDI Setup create a Multi-Output Device with \
+                     your speakers and BlackHole and select it as the system output."
+                )
+            })?;
+        let config = AudioRecorder::get_preferred_config(&device)?;
+        Ok(LoopbackSource::Device(device, config))
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+mod imp {
+    use super::LoopbackSource;
+
+    pub fn loopback_source() -> Result<LoopbackSource, anyhow::Error> {
+        Err(anyhow::anyhow!(
+            "Recording system audio isn't supported on this platform"
+        ))
+    }
+}
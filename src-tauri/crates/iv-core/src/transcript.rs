@@ -0,0 +1,79 @@
+//! Transcripts returned by the engines, with timed segments when available
+
+use serde::{Deserialize, Serialize};
+
+/// A stretch of the transcript with its position in the audio
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TranscriptSegment {
+    /// Start time in seconds from the beginning of the transcribed audio
+    pub start: f32,
+    /// End time in seconds
+    pub end: f32,
+    pub text: String,
+    /// How sure the engine is of this segment (0.0 - 1.0), when it says
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+    /// Who said this segment, in transcripts of more than one speaker
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+}
+
+/// Output of a transcription engine
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct TranscriptionResult {
+    pub text: String,
+    /// Timed segments, empty when the engine doesn't report timings
+    pub segments: Vec<TranscriptSegment>,
+}
+
+impl TranscriptionResult {
+    /// A result with no timing information
+    pub fn from_text(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            segments: Vec::new(),
+        }
+    }
+
+    /// Confidence of the least certain segment, if the engine reported any
+    pub fn confidence(&self) -> Option<f32> {
+        self.segments
+            .iter()
+            .filter_map(|s| s.confidence)
+            .min_by(|a, b| a.total_cmp(b))
+    }
+
+    /// Whether any segment falls below the confidence threshold
+    pub fn needs_review(&self, threshold: f32) -> bool {
+        self.confidence().is_some_and(|c| c < threshold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(text: &str, confidence: Option<f32>) -> TranscriptSegment {
+        TranscriptSegment {
+            start: 0.0,
+            end: 1.0,
+            text: text.to_string(),
+            confidence,
+            speaker: None,
+        }
+    }
+
+    #[test]
+    fn test_needs_review_uses_least_confident_segment() {
+        let result = TranscriptionResult {
+            text: "Hello there.".to_string(),
+            segments: vec![segment("Hello", Some(0.9)), segment("there.", Some(0.4))],
+        };
+        assert_eq!(result.confidence(), Some(0.4));
+        assert!(result.needs_review(0.6));
+        assert!(!result.needs_review(0.3));
+
+        // Engines without confidence never ask for review
+        assert!(!TranscriptionResult::from_text("Hello").needs_review(0.6));
+    }
+}
@@ -0,0 +1,154 @@
+//! Adaptive VAD threshold for noisy rooms
+//!
+//! In a café or next to a loud fan, background noise alone can push Silero's
+//! speech probability over a threshold tuned for a quiet room, so silence is
+//! kept and auto-stop never fires. The noise floor is tracked during capture
+//! and the threshold raised along with it, falling back to the configured
+//! threshold as the room quietens.
+//!
+//! The floor follows the quietest frames: it drops quickly to quieter audio,
+//! such as the gaps between words, and only creeps up while it's louder, so
+//! speech itself barely moves it but a fan left running does.
+
+/// Noise floor (dBFS) at or below which the configured threshold is used
+const QUIET_FLOOR_DB: f32 = -60.0;
+
+/// Noise floor at which the threshold is raised the most
+const LOUD_FLOOR_DB: f32 = -30.0;
+
+/// Most the threshold is raised by
+const MAX_BOOST: f32 = 0.3;
+
+/// Highest threshold used, so speech still gets through in the loudest room
+const MAX_THRESHOLD: f32 = 0.95;
+
+/// Share of the way the floor moves toward a quieter frame
+const FLOOR_FALL_RATE: f32 = 0.3;
+
+/// How far the floor rises per louder frame, in dB (about 1.7dB a second)
+const FLOOR_RISE_DB: f32 = 0.05;
+
+/// Smallest threshold change passed on to the detector
+const MIN_CHANGE: f32 = 0.01;
+
+/// Quietest level a frame is measured at, to keep silence finite
+const SILENCE_DB: f32 = -100.0;
+
+/// Raises a detector's threshold with the background noise
+pub(crate) struct AdaptiveThreshold {
+    /// The configured threshold, used in a quiet room
+    base: f32,
+    /// Estimated background noise level, in dBFS
+    floor_db: Option<f32>,
+    /// The threshold the detector currently has
+    applied: f32,
+}
+
+impl AdaptiveThreshold {
+    pub(crate) fn new(base: f32) -> Self {
+        Self {
+            base,
+            floor_db: None,
+            applied: base,
+        }
+    }
+
+    /// Change the configured threshold, which the detector was just set to
+    pub(crate) fn set_base(&mut self, base: f32) {
+        self.base = base;
+        self.applied = base;
+    }
+
+    /// Forget the noise floor, going back to the configured threshold
+    pub(crate) fn reset(&mut self) -> f32 {
+        self.floor_db = None;
+        self.applied = self.base;
+        self.base
+    }
+
+    fn level_db(frame: &[f32]) -> f32 {
+        if frame.is_empty() {
+            return SILENCE_DB;
+        }
+        let rms = (frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32).sqrt();
+        (20.0 * rms.log10()).max(SILENCE_DB)
+    }
+
+    /// The highest the threshold goes
+    fn ceiling(&self) -> f32 {
+        (self.base + MAX_BOOST).min(MAX_THRESHOLD.max(self.base))
+    }
+
+    /// The threshold for the current noise floor
+    fn threshold(&self) -> f32 {
+        let Some(floor_db) = self.floor_db else {
+            return self.base;
+        };
+        let loudness =
+            ((floor_db - QUIET_FLOOR_DB) / (LOUD_FLOOR_DB - QUIET_FLOOR_DB)).clamp(0.0, 1.0);
+        (self.base + loudness * MAX_BOOST).min(self.ceiling())
+    }
+
+    /// Track a frame's level, returning the threshold to switch the detector
+    /// to when it has moved enough to matter
+    pub(crate) fn observe(&mut self, frame: &[f32]) -> Option<f32> {
+        let level = Self::level_db(frame);
+        self.floor_db = Some(match self.floor_db {
+            None => level,
+            Some(floor) if level < floor => floor + (level - floor) * FLOOR_FALL_RATE,
+            Some(floor) => (floor + FLOOR_RISE_DB).min(level),
+        });
+
+        // Small steps are skipped, except the last one to either end
+        let threshold = self.threshold();
+        let at_end = threshold == self.base || threshold == self.ceiling();
+        if threshold == self.applied || (!at_end && (threshold - self.applied).abs() < MIN_CHANGE) {
+            return None;
+        }
+        log::debug!(
+            "Noise floor {:.1}dB, VAD threshold {:.2}",
+            self.floor_db.unwrap_or(SILENCE_DB),
+            threshold
+        );
+        self.applied = threshold;
+        Some(threshold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn noise(level: f32) -> Vec<f32> {
+        (0..480)
+            .map(|i| if i % 2 == 0 { level } else { -level })
+            .collect()
+    }
+
+    #[test]
+    fn test_threshold_follows_noise_floor() {
+        let mut adaptive = AdaptiveThreshold::new(0.5);
+
+        // A quiet room keeps the configured threshold
+        for _ in 0..100 {
+            assert_eq!(adaptive.observe(&noise(0.0005)), None);
+        }
+
+        // A loud fan (-26dB) raises it to the most it goes, over a while
+        let mut raised = None;
+        for _ in 0..1000 {
+            raised = adaptive.observe(&noise(0.05)).or(raised);
+        }
+        assert!((raised.unwrap() - 0.8).abs() < 1e-4);
+
+        // Once the fan's off it drops back quickly
+        let mut lowered = None;
+        for _ in 0..50 {
+            lowered = adaptive.observe(&noise(0.0005)).or(lowered);
+        }
+        assert_eq!(lowered, Some(0.5));
+
+        adaptive.observe(&noise(0.05));
+        assert_eq!(adaptive.reset(), 0.5);
+    }
+}
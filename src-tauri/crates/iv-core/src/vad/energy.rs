@@ -0,0 +1,148 @@
+//! Energy VAD - Lightweight fallback when Silero isn't available
+//!
+//! Compares each frame's energy against an adaptive estimate of the
+//! background noise, with the zero-crossing rate to tell voiced speech from
+//! hiss. Far less accurate than Silero in noisy rooms, but needs no model
+//! download or ONNX runtime.
+
+use anyhow::Result;
+
+use super::{VadFrame, VoiceActivityDetector, VAD_FRAME_SAMPLES};
+
+/// Frames below this RMS are never speech, however quiet the room
+const MIN_SPEECH_RMS: f32 = 0.004;
+
+/// Zero-crossing rate (crossings per sample) above which a frame that isn't
+/// clearly loud is taken as noise; voiced speech sits well below it
+const MAX_SPEECH_ZCR: f32 = 0.35;
+
+/// How fast the noise estimate follows quieter frames, per frame
+const NOISE_FALL_RATE: f32 = 0.2;
+
+/// How fast it follows louder frames: non-speech quickly, speech very slowly
+/// so a fan switching on is eventually taken as noise
+const NOISE_RISE_RATE: f32 = 0.02;
+const SPEECH_NOISE_RISE_RATE: f32 = 0.002;
+
+pub struct EnergyVad {
+    /// Energy over the noise estimate needed for speech
+    ratio: f32,
+    /// Running estimate of the background noise RMS
+    noise_floor: Option<f32>,
+}
+
+impl EnergyVad {
+    /// Create an energy VAD. `threshold` (0.0 - 1.0) works like Silero's:
+    /// higher needs louder speech.
+    pub fn new(threshold: f32) -> Self {
+        Self {
+            ratio: Self::ratio_for(threshold),
+            noise_floor: None,
+        }
+    }
+
+    /// Speech needs 1.5x (threshold 0) to 5.5x (threshold 1) the noise energy
+    fn ratio_for(threshold: f32) -> f32 {
+        1.5 + threshold.clamp(0.0, 1.0) * 4.0
+    }
+
+    fn rms(frame: &[f32]) -> f32 {
+        (frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32).sqrt()
+    }
+
+    fn zero_crossing_rate(frame: &[f32]) -> f32 {
+        let crossings = frame
+            .windows(2)
+            .filter(|pair| (pair[0] >= 0.0) != (pair[1] >= 0.0))
+            .count();
+        crossings as f32 / frame.len() as f32
+    }
+}
+
+impl VoiceActivityDetector for EnergyVad {
+    fn push_frame<'a>(&'a mut self, frame: &'a [f32]) -> Result<VadFrame<'a>> {
+        if frame.len() != VAD_FRAME_SAMPLES {
+            anyhow::bail!(
+                "expected {} samples (30ms at 16kHz), got {}",
+                VAD_FRAME_SAMPLES,
+                frame.len()
+            );
+        }
+
+        let rms = Self::rms(frame);
+        // The first frame is taken as background noise
+        let noise_floor = *self.noise_floor.get_or_insert(rms);
+        let threshold = (noise_floor * self.ratio).max(MIN_SPEECH_RMS);
+
+        let loud = rms > threshold;
+        let clearly_loud = rms > threshold * 2.0;
+        let is_speech = clearly_loud || (loud && Self::zero_crossing_rate(frame) < MAX_SPEECH_ZCR);
+
+        let rate = if rms < noise_floor {
+            NOISE_FALL_RATE
+        } else if is_speech {
+            SPEECH_NOISE_RISE_RATE
+        } else {
+            NOISE_RISE_RATE
+        };
+        self.noise_floor = Some(noise_floor + (rms - noise_floor) * rate);
+
+        if is_speech {
+            Ok(VadFrame::Speech(frame))
+        } else {
+            Ok(VadFrame::Noise)
+        }
+    }
+
+    fn reset(&mut self) {
+        self.noise_floor = None;
+    }
+
+    fn set_threshold(&mut self, threshold: f32) {
+        self.ratio = Self::ratio_for(threshold);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(level: f32, period: f32) -> Vec<f32> {
+        (0..VAD_FRAME_SAMPLES)
+            .map(|i| level * (i as f32 * std::f32::consts::TAU / period).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_energy_vad_detects_voice_over_noise() {
+        let mut vad = EnergyVad::new(0.5);
+        let quiet = frame(0.002, 7.0);
+        // 200Hz at 16kHz, like a voiced vowel
+        let voice = frame(0.1, 80.0);
+
+        for _ in 0..10 {
+            assert!(!vad.is_voice(&quiet).unwrap());
+        }
+        assert!(vad.is_voice(&voice).unwrap());
+        assert!(!vad.is_voice(&quiet).unwrap());
+    }
+
+    #[test]
+    fn test_energy_vad_adapts_to_steady_noise() {
+        let mut vad = EnergyVad::new(0.5);
+        vad.is_voice(&frame(0.002, 7.0)).unwrap();
+
+        // A hum that starts and doesn't stop is speech at first, then noise
+        let hum = frame(0.05, 80.0);
+        let detections = (0..300)
+            .map(|_| vad.is_voice(&hum).unwrap())
+            .collect::<Vec<_>>();
+        assert!(detections[0]);
+        assert!(!detections[299]);
+
+        // Hiss (high zero-crossing rate) slightly over the floor isn't speech
+        let mut vad = EnergyVad::new(0.5);
+        vad.is_voice(&frame(0.005, 80.0)).unwrap();
+        assert!(!vad.is_voice(&frame(0.02, 2.5)).unwrap());
+    }
+}
@@ -0,0 +1,356 @@
+//! Voice Activity Detection (VAD) module
+
+mod adaptive;
+mod energy;
+mod prefill;
+mod silero;
+mod smoothed;
+mod streaming;
+
+use std::path::Path;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::transcript::TranscriptSegment;
+
+pub use energy::EnergyVad;
+pub use prefill::{onset_clipped, AdaptivePrefill};
+pub use silero::SileroVad;
+pub use smoothed::SmoothedVad;
+pub use streaming::{StreamingVad, VadTransition};
+
+/// Result of processing a single VAD frame
+pub enum VadFrame<'a> {
+    /// Speech detected - contains the audio samples
+    Speech(&'a [f32]),
+    /// No speech (silence or noise)
+    Noise,
+}
+
+impl<'a> VadFrame<'a> {
+    #[inline]
+    pub fn is_speech(&self) -> bool {
+        matches!(self, VadFrame::Speech(_))
+    }
+}
+
+/// Common trait for voice activity detection
+pub trait VoiceActivityDetector: Send + Sync {
+    fn push_frame<'a>(&'a mut self, frame: &'a [f32]) -> Result<VadFrame<'a>>;
+
+    fn is_voice(&mut self, frame: &[f32]) -> Result<bool> {
+        Ok(self.push_frame(frame)?.is_speech())
+    }
+
+    fn reset(&mut self) {}
+
+    /// Change the speech probability threshold, for detectors that have one
+    fn set_threshold(&mut self, _threshold: f32) {}
+}
+
+/// Which detector the VAD runs
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum VadEngine {
+    /// Silero, falling back to the energy detector when its model is missing
+    /// or fails to load
+    #[default]
+    Auto,
+    Silero,
+    Energy,
+}
+
+/// Create the detector for an engine. `silero_model` is the Silero model
+/// file, if it's downloaded.
+pub fn build_detector(
+    engine: VadEngine,
+    silero_model: Option<&Path>,
+    threshold: f32,
+) -> Result<Box<dyn VoiceActivityDetector>> {
+    match (engine, silero_model) {
+        (VadEngine::Energy, _) => Ok(Box::new(EnergyVad::new(threshold))),
+        (VadEngine::Silero, None) => anyhow::bail!("the Silero VAD model isn't downloaded"),
+        (VadEngine::Silero, Some(path)) => Ok(Box::new(SileroVad::new(path, threshold)?)),
+        (VadEngine::Auto, Some(path)) => match SileroVad::new(path, threshold) {
+            Ok(silero) => Ok(Box::new(silero)),
+            Err(e) => {
+                log::warn!("Silero VAD failed to load, using the energy VAD: {}", e);
+                Ok(Box::new(EnergyVad::new(threshold)))
+            }
+        },
+        (VadEngine::Auto, None) => {
+            log::info!("Silero VAD model not downloaded, using the energy VAD");
+            Ok(Box::new(EnergyVad::new(threshold)))
+        }
+    }
+}
+
+/// Frame size for Silero VAD at 16kHz (30ms)
+pub const VAD_FRAME_SAMPLES: usize = 480; // 16000 * 30 / 1000
+
+/// Length of a VAD frame in milliseconds
+pub const VAD_FRAME_MS: u32 = 30;
+
+/// Sample rate of audio passed through the VAD
+const VAD_SAMPLE_RATE: f32 = 16000.0;
+
+/// Tunable parameters for the Silero + smoothing pipeline
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct VadParams {
+    /// Speech probability above which a frame counts as voice (0.0 - 1.0)
+    pub threshold: f32,
+    /// Frames kept before detected speech
+    pub prefill_frames: usize,
+    /// Frames kept after speech ends
+    pub hangover_frames: usize,
+    /// Consecutive voice frames needed to start speech
+    pub onset_frames: usize,
+    /// Raise the threshold while the background is noisy (see
+    /// [`SmoothedVad`])
+    pub adaptive: bool,
+}
+
+impl Default for VadParams {
+    fn default() -> Self {
+        Self {
+            threshold: 0.5,
+            prefill_frames: 3,
+            hangover_frames: 10,
+            onset_frames: 2,
+            adaptive: false,
+        }
+    }
+}
+
+impl VadParams {
+    /// Parameters with the prefill, hangover and onset given in milliseconds,
+    /// rounded to whole frames. Onset is at least one frame.
+    pub fn from_ms(threshold: f32, prefill_ms: u32, hangover_ms: u32, onset_ms: u32) -> Self {
+        let frames = |ms: u32| ((ms + VAD_FRAME_MS / 2) / VAD_FRAME_MS) as usize;
+        Self {
+            threshold: threshold.clamp(0.0, 1.0),
+            prefill_frames: frames(prefill_ms),
+            hangover_frames: frames(hangover_ms),
+            onset_frames: frames(onset_ms).max(1),
+            adaptive: false,
+        }
+    }
+}
+
+/// A stretch of audio the VAD kept as speech
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SpeechSegment {
+    pub start_secs: f32,
+    pub end_secs: f32,
+}
+
+/// Outcome of running a recording through the VAD
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VadAnalysis {
+    /// Length of the input audio
+    pub total_secs: f32,
+    /// Length of the audio that would be kept
+    pub retained_secs: f32,
+    /// Retained speech segments in order
+    pub segments: Vec<SpeechSegment>,
+}
+
+impl VadAnalysis {
+    /// Share of the audio kept, in percent
+    pub fn retained_percent(&self) -> f32 {
+        if self.total_secs > 0.0 {
+            self.retained_secs / self.total_secs * 100.0
+        } else {
+            0.0
+        }
+    }
+
+    /// Move transcript segments timed against the retained speech onto the
+    /// input audio's timeline, adding back the silence cut before them
+    pub fn restore_timeline(&self, segments: &mut [TranscriptSegment]) {
+        for segment in segments {
+            segment.start = self.input_secs(segment.start, true);
+            segment.end = self.input_secs(segment.end, false);
+        }
+    }
+
+    /// A time in the retained speech as a time in the input audio. A time
+    /// right where silence was cut is the start of the next segment for a
+    /// start and the end of the previous one for an end.
+    fn input_secs(&self, speech_secs: f32, is_start: bool) -> f32 {
+        let mut elapsed = 0.0;
+        for segment in &self.segments {
+            let length = segment.end_secs - segment.start_secs;
+            let within = if is_start {
+                speech_secs < elapsed + length
+            } else {
+                speech_secs <= elapsed + length
+            };
+            if within {
+                return segment.start_secs + (speech_secs - elapsed).max(0.0);
+            }
+            elapsed += length;
+        }
+        match self.segments.last() {
+            Some(last) => last.end_secs + (speech_secs - elapsed),
+            None => speech_secs,
+        }
+    }
+}
+
+/// Pad a trailing partial chunk to a full VAD frame
+fn to_frame(chunk: &[f32]) -> Vec<f32> {
+    let mut frame = chunk.to_vec();
+    frame.resize(VAD_FRAME_SAMPLES, 0.0);
+    frame
+}
+
+/// Run 16kHz samples through a detector and keep only the speech frames
+pub fn filter_speech(vad: &mut dyn VoiceActivityDetector, samples: &[f32]) -> Result<Vec<f32>> {
+    Ok(run_vad(vad, samples, true)?.0)
+}
+
+/// Run 16kHz samples through a detector and report which parts it keeps,
+/// without collecting the audio itself
+pub fn analyze_speech(vad: &mut dyn VoiceActivityDetector, samples: &[f32]) -> Result<VadAnalysis> {
+    Ok(run_vad(vad, samples, false)?.1)
+}
+
+/// Keep only the speech frames and report where they came from, in one pass
+pub fn filter_and_analyze(
+    vad: &mut dyn VoiceActivityDetector,
+    samples: &[f32],
+) -> Result<(Vec<f32>, VadAnalysis)> {
+    run_vad(vad, samples, true)
+}
+
+fn run_vad(
+    vad: &mut dyn VoiceActivityDetector,
+    samples: &[f32],
+    keep_audio: bool,
+) -> Result<(Vec<f32>, VadAnalysis)> {
+    let mut speech_samples = Vec::new();
+    // Segments in samples, as (start, end)
+    let mut segments: Vec<(usize, usize)> = Vec::new();
+
+    for (index, chunk) in samples.chunks(VAD_FRAME_SAMPLES).enumerate() {
+        let frame = to_frame(chunk);
+        let frame_start = index * VAD_FRAME_SAMPLES;
+        let frame_end = frame_start + chunk.len();
+
+        if let VadFrame::Speech(speech) = vad.push_frame(&frame)? {
+            if keep_audio {
+                speech_samples.extend_from_slice(speech);
+            }
+
+            // Speech output may include prefill frames from before this one
+            let start = (frame_start + VAD_FRAME_SAMPLES).saturating_sub(speech.len());
+            match segments.last_mut() {
+                Some(last) if last.1 >= start => last.1 = frame_end,
+                _ => segments.push((start, frame_end)),
+            }
+        }
+    }
+
+    let retained: usize = segments.iter().map(|(start, end)| end - start).sum();
+
+    let analysis = VadAnalysis {
+        total_secs: samples.len() as f32 / VAD_SAMPLE_RATE,
+        retained_secs: retained as f32 / VAD_SAMPLE_RATE,
+        segments: segments
+            .into_iter()
+            .map(|(start, end)| SpeechSegment {
+                start_secs: start as f32 / VAD_SAMPLE_RATE,
+                end_secs: end as f32 / VAD_SAMPLE_RATE,
+            })
+            .collect(),
+    };
+
+    Ok((speech_samples, analysis))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Treats any frame with a non-zero sample as speech
+    struct NonZeroVad;
+
+    impl VoiceActivityDetector for NonZeroVad {
+        fn push_frame<'a>(&'a mut self, frame: &'a [f32]) -> Result<VadFrame<'a>> {
+            if frame.iter().any(|&s| s != 0.0) {
+                Ok(VadFrame::Speech(frame))
+            } else {
+                Ok(VadFrame::Noise)
+            }
+        }
+    }
+
+    #[test]
+    fn test_filter_speech_drops_silent_frames() {
+        let mut samples = vec![0.0; VAD_FRAME_SAMPLES * 2];
+        samples.extend(vec![0.5; VAD_FRAME_SAMPLES]);
+
+        let speech = filter_speech(&mut NonZeroVad, &samples).unwrap();
+        assert_eq!(speech.len(), VAD_FRAME_SAMPLES);
+    }
+
+    #[test]
+    fn test_analyze_speech_reports_segments() {
+        let mut samples = vec![0.0; VAD_FRAME_SAMPLES * 2];
+        samples.extend(vec![0.5; VAD_FRAME_SAMPLES * 2]);
+        samples.extend(vec![0.0; VAD_FRAME_SAMPLES]);
+        samples.extend(vec![0.5; VAD_FRAME_SAMPLES]);
+
+        let analysis = analyze_speech(&mut NonZeroVad, &samples).unwrap();
+        assert_eq!(analysis.segments.len(), 2);
+        assert_eq!(analysis.segments[0].start_secs, 0.06);
+        assert_eq!(analysis.segments[0].end_secs, 0.12);
+        assert!((analysis.retained_secs - 0.09).abs() < 1e-6);
+        assert!((analysis.total_secs - 0.18).abs() < 1e-6);
+        assert!((analysis.retained_percent() - 50.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_restore_timeline() {
+        let analysis = VadAnalysis {
+            total_secs: 10.0,
+            retained_secs: 4.0,
+            segments: vec![
+                SpeechSegment {
+                    start_secs: 1.0,
+                    end_secs: 3.0,
+                },
+                SpeechSegment {
+                    start_secs: 6.0,
+                    end_secs: 8.0,
+                },
+            ],
+        };
+        let segment = |start, end| TranscriptSegment {
+            start,
+            end,
+            text: String::new(),
+            confidence: None,
+            speaker: None,
+        };
+
+        let mut segments = vec![segment(0.5, 2.0), segment(2.0, 3.5)];
+        analysis.restore_timeline(&mut segments);
+        assert_eq!((segments[0].start, segments[0].end), (1.5, 3.0));
+        assert_eq!((segments[1].start, segments[1].end), (6.0, 7.5));
+    }
+
+    #[test]
+    fn test_vad_params_from_ms() {
+        assert_eq!(VadParams::from_ms(0.5, 90, 300, 60), VadParams::default());
+
+        let params = VadParams::from_ms(1.5, 100, 0, 0);
+        assert_eq!(params.threshold, 1.0);
+        assert_eq!(params.prefill_frames, 3);
+        assert_eq!(params.hangover_frames, 0);
+        assert_eq!(params.onset_frames, 1);
+    }
+}
@@ -0,0 +1,148 @@
+//! Adaptive prefill - protection against clipped first words
+//!
+//! When the VAD's onset detection starts a segment late, the first syllable
+//! is cut off. That shows up as speech-level energy in the audio just before
+//! the first retained segment. Each recording is checked for it, and the
+//! prefill grows while clipping keeps happening, then shrinks back slowly
+//! once recordings come through clean.
+
+use serde::{Deserialize, Serialize};
+
+use super::{VadAnalysis, VadParams, VAD_FRAME_SAMPLES, VAD_SAMPLE_RATE};
+
+/// Audio checked before the first retained segment
+const ONSET_WINDOW_SECS: f32 = 0.3;
+
+/// RMS below which the trimmed audio is treated as silence
+const NOISE_FLOOR: f32 = 0.01;
+
+/// Trimmed audio at least this loud relative to the start of the speech
+/// counts as a clipped onset
+const CLIPPED_ENERGY_RATIO: f32 = 0.3;
+
+/// Frames added each time a clipped onset is seen (~60ms)
+const GROW_FRAMES: usize = 2;
+
+/// Upper bound on the extra prefill (~300ms)
+const MAX_EXTRA_FRAMES: usize = 10;
+
+/// Clean recordings in a row before a frame of extra prefill is dropped
+const SHRINK_AFTER_CLEAN: u32 = 10;
+
+fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+/// Whether the VAD trimmed speech-level audio right before the first segment
+/// it kept, i.e. the first word was probably cut off
+pub fn onset_clipped(samples: &[f32], analysis: &VadAnalysis) -> bool {
+    let Some(first) = analysis.segments.first() else {
+        return false;
+    };
+
+    let window = (ONSET_WINDOW_SECS * VAD_SAMPLE_RATE) as usize;
+    let start = ((first.start_secs * VAD_SAMPLE_RATE) as usize).min(samples.len());
+    if start == 0 {
+        // Speech runs from the very start; nothing was trimmed
+        return false;
+    }
+
+    let trimmed = rms(&samples[start.saturating_sub(window)..start]);
+    let speech = rms(&samples[start..(start + window).min(samples.len())]);
+
+    trimmed > NOISE_FLOOR && trimmed >= speech * CLIPPED_ENERGY_RATIO
+}
+
+/// Extra prefill learned from past recordings
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct AdaptivePrefill {
+    /// Frames added on top of the configured prefill
+    pub extra_frames: usize,
+    /// Recordings in a row without a clipped onset
+    pub clean_streak: u32,
+}
+
+impl AdaptivePrefill {
+    /// Parameters with the learned prefill added
+    pub fn apply(&self, params: &VadParams) -> VadParams {
+        VadParams {
+            prefill_frames: params.prefill_frames + self.extra_frames,
+            ..*params
+        }
+    }
+
+    /// Learn from a recording, returning whether the extra prefill changed
+    pub fn observe(&mut self, samples: &[f32], analysis: &VadAnalysis) -> bool {
+        let before = self.extra_frames;
+
+        if onset_clipped(samples, analysis) {
+            self.clean_streak = 0;
+            self.extra_frames = (self.extra_frames + GROW_FRAMES).min(MAX_EXTRA_FRAMES);
+        } else if !analysis.segments.is_empty() {
+            self.clean_streak += 1;
+            if self.clean_streak >= SHRINK_AFTER_CLEAN && self.extra_frames > 0 {
+                self.extra_frames -= 1;
+                self.clean_streak = 0;
+            }
+        }
+
+        self.extra_frames != before
+    }
+
+    /// Length of the extra prefill in milliseconds
+    pub fn extra_ms(&self) -> u32 {
+        (self.extra_frames * VAD_FRAME_SAMPLES) as u32 * 1000 / VAD_SAMPLE_RATE as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vad::SpeechSegment;
+
+    fn analysis_starting_at(start_secs: f32) -> VadAnalysis {
+        VadAnalysis {
+            total_secs: 2.0,
+            retained_secs: 1.0,
+            segments: vec![SpeechSegment {
+                start_secs,
+                end_secs: start_secs + 1.0,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_detects_clipped_onset() {
+        // Loud audio right up to the segment start
+        let mut samples = vec![0.0; 8000];
+        samples.extend(vec![0.2; 24000]);
+        assert!(onset_clipped(&samples, &analysis_starting_at(0.75)));
+
+        // Silence before the segment
+        assert!(!onset_clipped(&samples, &analysis_starting_at(0.5)));
+    }
+
+    #[test]
+    fn test_grows_then_shrinks() {
+        let mut samples = vec![0.0; 8000];
+        samples.extend(vec![0.2; 24000]);
+
+        let mut prefill = AdaptivePrefill::default();
+        assert!(prefill.observe(&samples, &analysis_starting_at(0.75)));
+        assert_eq!(prefill.extra_frames, GROW_FRAMES);
+        assert_eq!(
+            prefill.apply(&VadParams::default()).prefill_frames,
+            3 + GROW_FRAMES
+        );
+
+        for _ in 0..SHRINK_AFTER_CLEAN - 1 {
+            assert!(!prefill.observe(&samples, &analysis_starting_at(0.5)));
+        }
+        assert!(prefill.observe(&samples, &analysis_starting_at(0.5)));
+        assert_eq!(prefill.extra_frames, GROW_FRAMES - 1);
+    }
+}
@@ -57,4 +57,14 @@ impl VoiceActivityDetector for SileroVad {
             Ok(VadFrame::Noise)
         }
     }
+
+    /// Clear the model's recurrent state, so a new recording doesn't start
+    /// with the context of the previous one
+    fn reset(&mut self) {
+        self.engine.reset();
+    }
+
+    fn set_threshold(&mut self, threshold: f32) {
+        self.threshold = threshold.clamp(0.0, 1.0);
+    }
 }
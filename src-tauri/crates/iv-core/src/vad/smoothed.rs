@@ -1,13 +1,16 @@
 //! Smoothed VAD - Adds temporal smoothing on top of raw VAD output
 //!
 //! This module provides prefill buffering, onset detection, and hangover
-//! to prevent rapid state changes between speech and silence.
+//! to prevent rapid state changes between speech and silence. Optionally it
+//! also raises the detector's threshold in noisy rooms (see
+//! [`super::adaptive`]).
 
 use std::collections::VecDeque;
 
 use anyhow::Result;
 
-use super::{VadFrame, VoiceActivityDetector};
+use super::adaptive::AdaptiveThreshold;
+use super::{VadFrame, VadParams, VoiceActivityDetector};
 
 /// Smoothed VAD wrapper that adds temporal filtering
 pub struct SmoothedVad {
@@ -18,6 +21,8 @@ pub struct SmoothedVad {
     hangover_frames: usize,
     /// Number of consecutive voice frames needed to trigger speech
     onset_frames: usize,
+    /// Raises the threshold with the noise floor, in adaptive mode
+    adaptive: Option<AdaptiveThreshold>,
 
     /// Buffer of recent frames for prefill
     frame_buffer: VecDeque<Vec<f32>>,
@@ -51,6 +56,7 @@ impl SmoothedVad {
             prefill_frames,
             hangover_frames,
             onset_frames,
+            adaptive: None,
             frame_buffer: VecDeque::new(),
             hangover_counter: 0,
             onset_counter: 0,
@@ -67,6 +73,33 @@ impl SmoothedVad {
             2,  // ~60ms onset
         )
     }
+
+    /// Create with the smoothing values from a parameter set
+    pub fn from_params(inner_vad: Box<dyn VoiceActivityDetector>, params: &VadParams) -> Self {
+        let mut vad = Self::new(
+            inner_vad,
+            params.prefill_frames,
+            params.hangover_frames,
+            params.onset_frames,
+        );
+        vad.adaptive = params
+            .adaptive
+            .then(|| AdaptiveThreshold::new(params.threshold));
+        vad
+    }
+
+    /// Switch to another parameter set, keeping the loaded detector
+    pub fn set_params(&mut self, params: &VadParams) {
+        self.prefill_frames = params.prefill_frames;
+        self.hangover_frames = params.hangover_frames;
+        self.onset_frames = params.onset_frames;
+        self.inner_vad.set_threshold(params.threshold);
+        match (&mut self.adaptive, params.adaptive) {
+            (Some(adaptive), true) => adaptive.set_base(params.threshold),
+            (None, true) => self.adaptive = Some(AdaptiveThreshold::new(params.threshold)),
+            (_, false) => self.adaptive = None,
+        }
+    }
 }
 
 impl VoiceActivityDetector for SmoothedVad {
@@ -77,7 +110,12 @@ impl VoiceActivityDetector for SmoothedVad {
             self.frame_buffer.pop_front();
         }
 
-        // 2. Delegate to the wrapped VAD
+        // 2. Follow the background noise, in adaptive mode
+        if let Some(threshold) = self.adaptive.as_mut().and_then(|a| a.observe(frame)) {
+            self.inner_vad.set_threshold(threshold);
+        }
+
+        // 3. Delegate to the wrapped VAD
         let is_voice = self.inner_vad.is_voice(frame)?;
 
         match (self.in_speech, is_voice) {
@@ -128,6 +166,10 @@ impl VoiceActivityDetector for SmoothedVad {
     }
 
     fn reset(&mut self) {
+        self.inner_vad.reset();
+        if let Some(adaptive) = &mut self.adaptive {
+            self.inner_vad.set_threshold(adaptive.reset());
+        }
         self.frame_buffer.clear();
         self.hangover_counter = 0;
         self.onset_counter = 0;
@@ -0,0 +1,104 @@
+//! Streaming VAD - Runs a detector incrementally on audio as it is captured
+//!
+//! Captured chunks arrive at the device sample rate and arbitrary sizes; they
+//! are resampled to 16kHz as one signal and cut into VAD frames so speech/silence changes
+//! can be reported while recording is still in progress.
+
+use anyhow::Result;
+
+use super::{VoiceActivityDetector, VAD_FRAME_SAMPLES};
+use crate::audio::StreamResampler;
+
+/// Duration of one VAD frame in seconds
+const FRAME_SECS: f32 = VAD_FRAME_SAMPLES as f32 / 16000.0;
+
+/// Change in speech state reported by the streaming VAD
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VadTransition {
+    SpeechStarted,
+    SilenceStarted,
+}
+
+pub struct StreamingVad {
+    vad: Box<dyn VoiceActivityDetector>,
+    /// Carries the filter's state from one captured chunk to the next
+    resampler: StreamResampler,
+    /// 16kHz samples not yet making up a full frame
+    pending: Vec<f32>,
+    in_speech: bool,
+    heard_speech: bool,
+    /// Consecutive non-speech frames since speech last ended (or since start)
+    silence_frames: usize,
+}
+
+impl StreamingVad {
+    /// Wrap a detector for audio captured at `source_rate`
+    pub fn new(vad: Box<dyn VoiceActivityDetector>, source_rate: u32) -> Self {
+        Self {
+            vad,
+            resampler: StreamResampler::new(source_rate, 16000),
+            pending: Vec::new(),
+            in_speech: false,
+            heard_speech: false,
+            silence_frames: 0,
+        }
+    }
+
+    /// Feed captured mono samples, returning the last speech state change they caused
+    pub fn push_samples(&mut self, samples: &[f32]) -> Result<Option<VadTransition>> {
+        self.pending.extend(self.resampler.push(samples));
+
+        let mut transition = None;
+        let mut offset = 0;
+
+        while self.pending.len() - offset >= VAD_FRAME_SAMPLES {
+            let frame = &self.pending[offset..offset + VAD_FRAME_SAMPLES];
+            offset += VAD_FRAME_SAMPLES;
+
+            let is_speech = self.vad.push_frame(frame)?.is_speech();
+            if is_speech {
+                self.silence_frames = 0;
+                self.heard_speech = true;
+            } else {
+                self.silence_frames += 1;
+            }
+
+            if is_speech != self.in_speech {
+                self.in_speech = is_speech;
+                transition = Some(if is_speech {
+                    VadTransition::SpeechStarted
+                } else {
+                    VadTransition::SilenceStarted
+                });
+            }
+        }
+
+        self.pending.drain(..offset);
+        Ok(transition)
+    }
+
+    /// Whether the speaker is currently talking
+    pub fn is_speech(&self) -> bool {
+        self.in_speech
+    }
+
+    /// Whether any speech has been detected so far
+    pub fn has_heard_speech(&self) -> bool {
+        self.heard_speech
+    }
+
+    /// How long it has been silent, in seconds
+    pub fn trailing_silence_secs(&self) -> f32 {
+        self.silence_frames as f32 * FRAME_SECS
+    }
+
+    /// Start over for a new recording
+    pub fn reset(&mut self) {
+        self.vad.reset();
+        self.resampler.reset();
+        self.pending.clear();
+        self.in_speech = false;
+        self.heard_speech = false;
+        self.silence_frames = 0;
+    }
+}
@@ -0,0 +1,70 @@
+//! Screen reader announcements
+//!
+//! With announcements on, completions and errors are reported to screen
+//! readers as well as shown in the overlay, so feedback doesn't depend on
+//! sight or on the feedback sounds. Every announcement is emitted to the
+//! overlay, which reads it out through an ARIA live region (NVDA, Narrator and
+//! Orca pick these up). On macOS it's also sent to VoiceOver directly when
+//! VoiceOver is running, since it doesn't announce unfocused windows.
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::settings;
+
+/// How urgently a screen reader should speak an announcement
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Politeness {
+    /// Spoken after whatever the screen reader is currently saying
+    Polite,
+    /// Interrupts the screen reader (errors)
+    Assertive,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct Announcement<'a> {
+    message: &'a str,
+    politeness: Politeness,
+}
+
+#[cfg(target_os = "macos")]
+fn announce_with_voiceover(message: String) {
+    // The message is passed as an argument so it never needs AppleScript quoting,
+    // and the running check keeps the script from launching VoiceOver
+    std::thread::spawn(move || {
+        let result = std::process::Command::new("osascript")
+            .args([
+                "-e",
+                "on run argv",
+                "-e",
+                "if application \"VoiceOver\" is running then tell application \"VoiceOver\" to output (item 1 of argv)",
+                "-e",
+                "end run",
+                &message,
+            ])
+            .status();
+        if let Err(e) = result {
+            log::debug!("Failed to announce with VoiceOver: {}", e);
+        }
+    });
+}
+
+/// Announce a message to screen readers, if announcements are enabled
+pub fn announce(app_handle: &AppHandle, message: &str, politeness: Politeness) {
+    if !settings::get_settings(app_handle).accessibility_announcements {
+        return;
+    }
+
+    log::debug!("Accessibility announcement: {}", message);
+    let _ = app_handle.emit(
+        "accessibility-announcement",
+        Announcement {
+            message,
+            politeness,
+        },
+    );
+
+    #[cfg(target_os = "macos")]
+    announce_with_voiceover(message.to_string());
+}
@@ -1,18 +1,52 @@
-//! Audio feedback for recording start/stop sounds
+//! Audio feedback sounds for recording starting and stopping, and for
+//! transcriptions completing, failing or being cancelled
+//!
+//! Each sound is a bundled file unless the user picked their own in
+//! `custom_sounds`. With a visual `feedback_mode`, the overlay flashes or the
+//! tray icon blinks instead.
 
-use crate::settings;
+use crate::overlay;
+use crate::recording_manager::RecordingManager;
+use crate::settings::{self, ActivationMode, AppSettings, FeedbackMode};
+use crate::tray;
 use log::{debug, error, warn};
 use rodio::{Decoder, OutputStream, Sink};
+use serde::{Deserialize, Serialize};
 use std::fs::File;
 use std::io::BufReader;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::thread;
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Emitter, Manager};
 
 /// Types of feedback sounds
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
 pub enum SoundType {
     Start,
     Stop,
+    Complete,
+    Error,
+    Cancelled,
+}
+
+impl SoundType {
+    fn name(&self) -> &'static str {
+        match self {
+            SoundType::Start => "start",
+            SoundType::Stop => "stop",
+            SoundType::Complete => "complete",
+            SoundType::Error => "error",
+            SoundType::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// Sent when a test sound finishes playing (or fails to)
+#[derive(Serialize, Clone)]
+struct TestSoundFinished {
+    sound: &'static str,
+    error: Option<String>,
 }
 
 /// Get the sound file path for a given sound type
@@ -20,28 +54,114 @@ fn get_sound_path(sound_type: &SoundType) -> &'static str {
     match sound_type {
         SoundType::Start => "resources/sound/start.mp3",
         SoundType::Stop => "resources/sound/stop.mp3",
+        SoundType::Complete => "resources/sound/complete.wav",
+        SoundType::Error => "resources/sound/error.wav",
+        SoundType::Cancelled => "resources/sound/cancelled.wav",
     }
 }
 
-/// Resolve the full path to a sound file
-fn resolve_sound_path(app: &AppHandle, sound_type: &SoundType) -> Option<PathBuf> {
+/// The user's own file for a sound, if they picked one that exists
+fn custom_sound_path(settings: &AppSettings, sound_type: &SoundType) -> Option<PathBuf> {
+    let custom = &settings.custom_sounds;
+    let path = match sound_type {
+        SoundType::Start => &custom.start,
+        SoundType::Stop => &custom.stop,
+        SoundType::Complete => &custom.complete,
+        SoundType::Error => &custom.error,
+        SoundType::Cancelled => &custom.cancelled,
+    };
+    let path = PathBuf::from(path.as_deref().filter(|p| !p.trim().is_empty())?);
+    if path.is_file() {
+        Some(path)
+    } else {
+        warn!(
+            "Custom {} sound '{}' not found, using the built-in one",
+            sound_type.name(),
+            path.display()
+        );
+        None
+    }
+}
+
+/// Resolve the full path to a sound file, the user's own or the bundled one
+fn resolve_sound_path(
+    app: &AppHandle,
+    settings: &AppSettings,
+    sound_type: &SoundType,
+) -> Option<PathBuf> {
+    if let Some(path) = custom_sound_path(settings, sound_type) {
+        return Some(path);
+    }
+
     let sound_file = get_sound_path(sound_type);
     app.path()
         .resolve(sound_file, tauri::path::BaseDirectory::Resource)
         .ok()
 }
 
+/// Check whether a sound should play for the given activation mode
+fn is_sound_enabled(settings: &AppSettings, sound_type: &SoundType, mode: ActivationMode) -> bool {
+    if !settings.audio_feedback {
+        return false;
+    }
+
+    let mode_feedback = settings.feedback.for_mode(mode);
+    match sound_type {
+        SoundType::Start => mode_feedback.start_sound,
+        SoundType::Stop => mode_feedback.stop_sound,
+        SoundType::Complete => settings.sound_events.complete,
+        SoundType::Error => settings.sound_events.error,
+        SoundType::Cancelled => settings.sound_events.cancelled,
+    }
+}
+
+/// Flash the overlay and/or blink the tray icon in place of a sound.
+/// Returns false in sound mode, when the sound should play.
+fn show_visual_feedback(app: &AppHandle, settings: &AppSettings, sound_type: &SoundType) -> bool {
+    let (flash, blink) = match settings.feedback_mode {
+        FeedbackMode::Sound => return false,
+        FeedbackMode::OverlayFlash => (true, false),
+        FeedbackMode::TrayBlink => (false, true),
+        FeedbackMode::Visual => (true, true),
+    };
+
+    debug!("Showing {} feedback without sound", sound_type.name());
+    if flash {
+        overlay::flash_overlay(app, sound_type.name());
+    }
+    if blink {
+        tray::blink_tray_icon(app);
+    }
+    true
+}
+
+/// Play the sound for a finished transcription or command: the stop sound,
+/// or the complete sound if the user turned it on
+pub fn play_completion_sound(app: &AppHandle, mode: ActivationMode) {
+    let sound_type = if settings::get_settings(app).sound_events.complete {
+        SoundType::Complete
+    } else {
+        SoundType::Stop
+    };
+    play_feedback_sound(app, sound_type, mode);
+}
+
 /// Play a feedback sound asynchronously (non-blocking)
-pub fn play_feedback_sound(app: &AppHandle, sound_type: SoundType) {
+pub fn play_feedback_sound(app: &AppHandle, sound_type: SoundType, mode: ActivationMode) {
     let settings = settings::get_settings(app);
 
-    if !settings.audio_feedback {
+    if !is_sound_enabled(&settings, &sound_type, mode)
+        || show_visual_feedback(app, &settings, &sound_type)
+    {
         return;
     }
 
-    if let Some(path) = resolve_sound_path(app, &sound_type) {
+    if let Some(path) = resolve_sound_path(app, &settings, &sound_type) {
         let volume = settings.audio_feedback_volume;
-        play_sound_async(path, volume);
+        match sound_type {
+            SoundType::Start if settings.gate_start_sound => play_start_sound(app, path, volume),
+            _ => play_sound_async(path, volume),
+        }
     } else {
         warn!(
             "Could not resolve sound path for {:?}",
@@ -51,14 +171,16 @@ pub fn play_feedback_sound(app: &AppHandle, sound_type: SoundType) {
 }
 
 /// Play a feedback sound and block until complete
-pub fn play_feedback_sound_blocking(app: &AppHandle, sound_type: SoundType) {
+pub fn play_feedback_sound_blocking(app: &AppHandle, sound_type: SoundType, mode: ActivationMode) {
     let settings = settings::get_settings(app);
 
-    if !settings.audio_feedback {
+    if !is_sound_enabled(&settings, &sound_type, mode)
+        || show_visual_feedback(app, &settings, &sound_type)
+    {
         return;
     }
 
-    if let Some(path) = resolve_sound_path(app, &sound_type) {
+    if let Some(path) = resolve_sound_path(app, &settings, &sound_type) {
         let volume = settings.audio_feedback_volume;
         play_sound_blocking(&path, volume);
     } else {
@@ -69,14 +191,34 @@ pub fn play_feedback_sound_blocking(app: &AppHandle, sound_type: SoundType) {
     }
 }
 
-/// Play a test sound (ignores audio_feedback setting)
+/// Play a test sound (ignores audio_feedback setting) without blocking the
+/// caller, emitting `test-sound-finished` when it's done
 pub fn play_test_sound(app: &AppHandle, sound_type: SoundType) {
     let settings = settings::get_settings(app);
+    let volume = settings.audio_feedback_volume;
+    let path = resolve_sound_path(app, &settings, &sound_type);
+    let app = app.clone();
 
-    if let Some(path) = resolve_sound_path(app, &sound_type) {
-        let volume = settings.audio_feedback_volume;
-        play_sound_blocking(&path, volume);
-    }
+    thread::spawn(move || {
+        let result = match path {
+            Some(path) => play_audio_file(&path, volume).map_err(|e| {
+                error!("Failed to play sound '{}': {}", path.display(), e);
+                e.to_string()
+            }),
+            None => Err(format!(
+                "Could not resolve sound path for {:?}",
+                get_sound_path(&sound_type)
+            )),
+        };
+
+        let _ = app.emit(
+            "test-sound-finished",
+            TestSoundFinished {
+                sound: sound_type.name(),
+                error: result.err(),
+            },
+        );
+    });
 }
 
 /// Play sound asynchronously in a separate thread
@@ -88,6 +230,26 @@ fn play_sound_async(path: PathBuf, volume: f32) {
     });
 }
 
+/// Play the start sound, telling the recording manager while it plays so
+/// the beep is kept out of the recording
+fn play_start_sound(app: &AppHandle, path: PathBuf, volume: f32) {
+    let manager = app
+        .try_state::<Arc<RecordingManager>>()
+        .map(|manager| Arc::clone(&manager));
+
+    thread::spawn(move || {
+        if let Some(manager) = &manager {
+            manager.set_start_sound_playing(true);
+        }
+        if let Err(e) = play_audio_file(&path, volume) {
+            error!("Failed to play sound '{}': {}", path.display(), e);
+        }
+        if let Some(manager) = &manager {
+            manager.set_start_sound_playing(false);
+        }
+    });
+}
+
 /// Play sound and block until complete
 fn play_sound_blocking(path: &PathBuf, volume: f32) {
     if let Err(e) = play_audio_file(path, volume) {
@@ -1,23 +1,180 @@
 //! Clipboard handling and pasting functionality
 
+use crate::foreground;
+use crate::formatting;
 use crate::input::{self};
-use crate::settings::{get_settings, ClipboardHandling, PasteMethod};
+use crate::settings::{
+    get_settings, AppSettings, ClipboardHandling, LargeClipboardRestore, PasteMethod, Terminator,
+};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
 use log::info;
-use tauri::AppHandle;
-use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri::image::Image;
+use tauri::{AppHandle, Manager, Wry};
+use tauri_plugin_clipboard_manager::{Clipboard, ClipboardExt};
+
+/// What the last paste inserted, so it can be undone
+#[derive(Debug, Clone)]
+struct LastPaste {
+    text: String,
+    method: PasteMethod,
+    /// Whether it went into a terminal, where Ctrl+Z suspends the program
+    /// instead of undoing
+    in_terminal: bool,
+    /// Whether Enter was pressed after it
+    pressed_enter: bool,
+    at: Instant,
+}
+
+impl LastPaste {
+    /// Backspaces that remove it, or None when the app's undo should. Only
+    /// clipboard pastes into other apps are undone, as one undo step; the
+    /// rest are deleted a character at a time, terminator included.
+    fn backspaces(&self) -> Option<usize> {
+        let clipboard = matches!(
+            self.method,
+            PasteMethod::CtrlV | PasteMethod::CtrlShiftV | PasteMethod::ShiftInsert
+        );
+        if clipboard && !self.in_terminal {
+            return None;
+        }
+        Some(self.text.chars().count())
+    }
+}
+
+/// Pastes kept for undoing, so undoing a draft still leaves the one before
+const MAX_UNDO_PASTES: usize = 10;
+
+/// Tracks the most recent pastes
+#[derive(Default)]
+pub struct PasteState {
+    /// Oldest first
+    pastes: Mutex<Vec<LastPaste>>,
+}
+
+impl PasteState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn record(&self, text: &str, method: PasteMethod, in_terminal: bool, pressed_enter: bool) {
+        let mut pastes = self.pastes.lock().unwrap();
+        if pastes.len() == MAX_UNDO_PASTES {
+            pastes.remove(0);
+        }
+        pastes.push(LastPaste {
+            text: text.to_string(),
+            method,
+            in_terminal,
+            pressed_enter,
+            at: Instant::now(),
+        });
+    }
+
+    fn last(&self) -> Option<LastPaste> {
+        self.pastes.lock().unwrap().last().cloned()
+    }
+
+    fn take(&self) -> Option<LastPaste> {
+        self.pastes.lock().unwrap().pop()
+    }
+}
+
+/// Clipboard contents saved before pasting so they can be put back afterwards
+enum ClipboardSnapshot {
+    Empty,
+    Text(String),
+    /// Rich text, with the plain text version other apps fall back to
+    Html {
+        html: String,
+        text: String,
+    },
+    Image(Image<'static>),
+}
+
+impl ClipboardSnapshot {
+    fn capture(clipboard: &Clipboard<Wry>) -> Self {
+        let text = clipboard.read_text().ok().filter(|t| !t.is_empty());
+
+        // The clipboard plugin can write HTML but not read it
+        let html = arboard::Clipboard::new()
+            .and_then(|mut c| c.get().html())
+            .ok()
+            .filter(|h| !h.is_empty());
+
+        match (html, text) {
+            (Some(html), text) => Self::Html {
+                html,
+                text: text.unwrap_or_default(),
+            },
+            (None, Some(text)) => Self::Text(text),
+            (None, None) => match clipboard.read_image() {
+                Ok(image) => Self::Image(image),
+                Err(_) => Self::Empty,
+            },
+        }
+    }
+
+    /// Approximate size of the saved contents in bytes
+    fn size(&self) -> usize {
+        match self {
+            Self::Empty => 0,
+            Self::Text(text) => text.len(),
+            Self::Html { html, text } => html.len() + text.len(),
+            Self::Image(image) => image.rgba().len(),
+        }
+    }
+
+    fn restore(self, clipboard: &Clipboard<Wry>) -> Result<(), String> {
+        let result = match self {
+            Self::Empty => return Ok(()),
+            Self::Text(text) => clipboard.write_text(text),
+            Self::Html { html, text } => clipboard.write_html(html, Some(text)),
+            Self::Image(image) => clipboard.write_image(&image),
+        };
+        result.map_err(|e| format!("Failed to restore clipboard: {}", e))
+    }
+}
+
+/// Held while a paste borrows the clipboard or a restore writes it back, so
+/// they take turns. Holds the contents a background restore has yet to
+/// write back.
+static CLIPBOARD: Mutex<Option<ClipboardSnapshot>> = Mutex::new(None);
+
+fn lock_clipboard() -> MutexGuard<'static, Option<ClipboardSnapshot>> {
+    CLIPBOARD.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Put text on the clipboard to stay, dropping any restore still pending
+fn write_clipboard_text(app_handle: &AppHandle, text: &str) -> Result<(), String> {
+    let mut pending_restore = lock_clipboard();
+    *pending_restore = None;
+    app_handle
+        .clipboard()
+        .write_text(text)
+        .map_err(|e| format!("Failed to copy to clipboard: {}", e))
+}
 
 /// Pastes text using the clipboard: saves current content, writes text, sends paste keystroke, restores clipboard.
 fn paste_via_clipboard(
     app_handle: &AppHandle,
     text: &str,
     paste_method: &PasteMethod,
+    settings: &AppSettings,
 ) -> Result<(), String> {
     let mut enigo = input::new_enigo()?;
 
     let clipboard = app_handle.clipboard();
 
-    // Save current clipboard content
-    let original_content = clipboard.read_text().unwrap_or_default();
+    // Save current clipboard content, including images and rich text. A
+    // background restore that hasn't run yet has the user's contents, and
+    // this paste takes it over; the clipboard holds the last transcript.
+    let mut pending_restore = lock_clipboard();
+    let original_content = match pending_restore.take() {
+        Some(pending) => pending,
+        None => ClipboardSnapshot::capture(clipboard),
+    };
 
     // Write our text to clipboard
     clipboard
@@ -35,30 +192,119 @@ fn paste_via_clipboard(
         _ => return Err("Invalid paste method for clipboard paste".into()),
     }
 
-    // Small delay after paste
-    std::thread::sleep(std::time::Duration::from_millis(50));
+    // Give the target app time to read the clipboard before it changes back
+    std::thread::sleep(std::time::Duration::from_millis(
+        settings.clipboard_restore_delay_ms,
+    ));
+
+    // Writing back megabytes of image data can take long enough to hold up
+    // the next dictation, or fail outright
+    let size = original_content.size();
+    if size as u64 > settings.large_clipboard_threshold_kb * 1024 {
+        match settings.large_clipboard_restore {
+            LargeClipboardRestore::Skip => {
+                info!("Not restoring {} KB of clipboard contents", size / 1024);
+                return Ok(());
+            }
+            LargeClipboardRestore::Background => {
+                info!(
+                    "Restoring {} KB of clipboard contents in the background",
+                    size / 1024
+                );
+                *pending_restore = Some(original_content);
+                drop(pending_restore);
+                let app_handle = app_handle.clone();
+                std::thread::spawn(move || {
+                    // Unless a paste started meanwhile and took it over
+                    let mut pending_restore = lock_clipboard();
+                    if let Some(original_content) = pending_restore.take() {
+                        if let Err(e) = original_content.restore(app_handle.clipboard()) {
+                            log::warn!("{}", e);
+                        }
+                    }
+                });
+                return Ok(());
+            }
+            LargeClipboardRestore::Blocking => {}
+        }
+    }
 
     // Restore original clipboard content
-    if !original_content.is_empty() {
-        clipboard
-            .write_text(&original_content)
-            .map_err(|e| format!("Failed to restore clipboard: {}", e))?;
+    original_content.restore(clipboard)
+}
+
+/// Whether one of the configured terminal apps has focus
+fn terminal_focused(settings: &AppSettings) -> bool {
+    foreground::get_foreground_app().is_some_and(|app| app.matches_any(&settings.terminal_apps))
+}
+
+/// Ctrl+V pastes nothing (or a control character) in most terminals, so switch
+/// to Ctrl+Shift+V when one has focus
+fn resolve_paste_method(settings: &AppSettings, in_terminal: bool) -> PasteMethod {
+    if cfg!(target_os = "macos") || settings.paste_method != PasteMethod::CtrlV || !in_terminal {
+        return settings.paste_method;
     }
 
-    Ok(())
+    info!("Terminal focused, pasting with Ctrl+Shift+V");
+    PasteMethod::CtrlShiftV
 }
 
-/// Main paste function - routes to appropriate paste method based on settings
-pub fn paste(text: String, app_handle: &AppHandle) -> Result<(), String> {
-    let settings = get_settings(app_handle);
-    let paste_method = settings.paste_method;
+/// What ends a transcript from `binding_id`: the binding's terminator, then
+/// the focused app's, then the global one
+pub fn resolve_terminator(settings: &AppSettings, binding_id: &str) -> Terminator {
+    if let Some(terminator) = settings
+        .bindings
+        .get(binding_id)
+        .and_then(|binding| binding.terminator)
+    {
+        return terminator;
+    }
 
-    // Append trailing space if setting is enabled
-    let text = if settings.append_trailing_space {
-        format!("{} ", text)
-    } else {
-        text
-    };
+    if !settings.app_terminators.is_empty() {
+        if let Some(app) = foreground::get_foreground_app() {
+            if let Some((_, &terminator)) = settings
+                .app_terminators
+                .iter()
+                .find(|(name, _)| app.matches_any(std::slice::from_ref(name)))
+            {
+                info!("Using {:?} terminator for '{}'", terminator, app.app_name);
+                return terminator;
+            }
+        }
+    }
+
+    settings.terminator
+}
+
+/// What ends a transcript from `binding_id` wherever it's pasted: the
+/// binding's terminator, then the global one
+pub fn binding_terminator(settings: &AppSettings, binding_id: &str) -> Terminator {
+    settings
+        .bindings
+        .get(binding_id)
+        .and_then(|binding| binding.terminator)
+        .unwrap_or(settings.terminator)
+}
+
+/// The text as it's inserted: wrapped in the binding's output template and
+/// ended with the terminator's characters
+pub fn paste_text(
+    text: &str,
+    settings: &AppSettings,
+    binding_id: &str,
+    terminator: Terminator,
+) -> String {
+    terminator.append_to(formatting::apply_template(text, settings, binding_id))
+}
+
+/// Main paste function - routes to appropriate paste method based on settings.
+/// `binding_id` selects the binding's output template and terminator.
+pub fn paste(text: String, app_handle: &AppHandle, binding_id: &str) -> Result<(), String> {
+    let settings = get_settings(app_handle);
+    let in_terminal = terminal_focused(&settings);
+    let paste_method = resolve_paste_method(&settings, in_terminal);
+    let terminator = resolve_terminator(&settings, binding_id);
+    let text = paste_text(&text, &settings, binding_id, terminator);
 
     info!("Using paste method: {:?}", paste_method);
 
@@ -72,17 +318,132 @@ pub fn paste(text: String, app_handle: &AppHandle) -> Result<(), String> {
             input::paste_text_direct(&mut enigo, &text)?;
         }
         PasteMethod::CtrlV | PasteMethod::CtrlShiftV | PasteMethod::ShiftInsert => {
-            paste_via_clipboard(app_handle, &text, &paste_method)?;
+            paste_via_clipboard(app_handle, &text, &paste_method, &settings)?;
+        }
+    }
+
+    if paste_method != PasteMethod::None {
+        if terminator == Terminator::Enter {
+            let mut enigo = input::new_enigo()?;
+            input::send_enter(&mut enigo)?;
+        }
+        if let Some(state) = app_handle.try_state::<PasteState>() {
+            state.record(
+                &text,
+                paste_method,
+                in_terminal,
+                terminator == Terminator::Enter,
+            );
         }
     }
 
     // After pasting, optionally copy to clipboard based on settings
     if settings.clipboard_handling == ClipboardHandling::CopyToClipboard {
-        let clipboard = app_handle.clipboard();
-        clipboard
-            .write_text(&text)
-            .map_err(|e| format!("Failed to copy to clipboard: {}", e))?;
+        write_clipboard_text(app_handle, &text)?;
     }
 
     Ok(())
 }
+
+/// Write a transcription to the clipboard without pasting it, whatever the
+/// paste method. `binding_id` selects the binding's output template.
+pub fn copy(text: String, app_handle: &AppHandle, binding_id: &str) -> Result<(), String> {
+    let settings = get_settings(app_handle);
+    let text = formatting::apply_template(&text, &settings, binding_id);
+
+    info!("Copying transcription to the clipboard");
+    write_clipboard_text(app_handle, &text)
+}
+
+/// Remove the last pasted transcription from the focused app: the app's undo
+/// for clipboard pastes, else Backspace for each character inserted (see
+/// [`LastPaste::backspaces`]). Called again, it removes the one before. A
+/// paste followed by Enter is refused, as it's already been sent.
+pub fn undo_last_paste(app_handle: &AppHandle) -> Result<(), String> {
+    let state = app_handle
+        .try_state::<PasteState>()
+        .ok_or_else(|| "Nothing to undo".to_string())?;
+    // Enter already sent or submitted it, and neither undo nor backspacing
+    // can take that back. It's kept, so the pastes before it stay put too.
+    if state.last().is_some_and(|last| last.pressed_enter) {
+        return Err("The last paste was sent with Enter and can't be undone".to_string());
+    }
+    let last = state.take().ok_or_else(|| "Nothing to undo".to_string())?;
+
+    if last.method == PasteMethod::None {
+        return Ok(());
+    }
+
+    let mut enigo = input::new_enigo()?;
+    match last.backspaces() {
+        Some(count) => {
+            info!("Undoing last paste with {} backspaces", count);
+            input::send_backspaces(&mut enigo, count)
+        }
+        None => {
+            info!("Undoing last paste with the undo shortcut");
+            input::send_undo(&mut enigo)
+        }
+    }
+}
+
+/// Replace the last pasted transcription with `edit` applied to it, undoing
+/// it and inserting the edited text the same way. The output template and
+/// terminator aren't applied again, as the pasted text already has them.
+pub fn replace_last_paste(
+    app_handle: &AppHandle,
+    edit: impl FnOnce(&str) -> String,
+) -> Result<(), String> {
+    let state = app_handle
+        .try_state::<PasteState>()
+        .ok_or_else(|| "Nothing to edit".to_string())?;
+    let last = state.last().ok_or_else(|| "Nothing to edit".to_string())?;
+    let text = edit(&last.text);
+
+    undo_last_paste(app_handle)?;
+    // Let the target app process the undo first
+    std::thread::sleep(std::time::Duration::from_millis(50));
+
+    match last.method {
+        PasteMethod::None => {}
+        PasteMethod::Direct => {
+            let mut enigo = input::new_enigo()?;
+            input::paste_text_direct(&mut enigo, &text)?;
+        }
+        PasteMethod::CtrlV | PasteMethod::CtrlShiftV | PasteMethod::ShiftInsert => {
+            paste_via_clipboard(app_handle, &text, &last.method, &get_settings(app_handle))?;
+        }
+    }
+    state.record(&text, last.method, last.in_terminal, false);
+    Ok(())
+}
+
+/// Whether there's a pasted transcription to undo or edit
+pub fn has_pasted(app_handle: &AppHandle) -> bool {
+    app_handle
+        .try_state::<PasteState>()
+        .is_some_and(|state| state.last().is_some())
+}
+
+/// Whether pasting `text` with `binding_id` would insert exactly what was
+/// pasted last, less than `within` ago
+pub fn is_repeat_paste(
+    app_handle: &AppHandle,
+    text: &str,
+    binding_id: &str,
+    within: Duration,
+) -> bool {
+    let Some(last) = app_handle
+        .try_state::<PasteState>()
+        .and_then(|state| state.last())
+    else {
+        return false;
+    };
+    if last.at.elapsed() >= within {
+        return false;
+    }
+
+    let settings = get_settings(app_handle);
+    let terminator = resolve_terminator(&settings, binding_id);
+    paste_text(text, &settings, binding_id, terminator) == last.text
+}
@@ -0,0 +1,308 @@
+//! Voice command mode
+//!
+//! Transcripts from the command binding are matched against user-defined
+//! phrases ("new line", "select all") and run as actions instead of being
+//! pasted. Matching ignores case and punctuation, since engines add their own.
+//!
+//! A few edit commands ("scratch that", "cap that") work from any dictation
+//! binding instead, when they're all that was said, and act on the text
+//! pasted last.
+
+use enigo::{Direction, Key, Keyboard};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_opener::OpenerExt;
+
+use crate::clipboard;
+use crate::input;
+use crate::settings;
+
+/// What a voice command does
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CommandAction {
+    /// Press key combinations in order, separated by spaces (e.g. "mod+a",
+    /// "enter enter"). "mod" is Cmd on macOS and Ctrl elsewhere.
+    Keys { keys: String },
+    /// Type text as if pasted
+    Text { text: String },
+    /// Open a URL or file with the default application
+    Open { target: String },
+}
+
+/// A spoken phrase and the action it runs
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VoiceCommand {
+    pub phrase: String,
+    pub action: CommandAction,
+}
+
+pub fn default_voice_commands() -> Vec<VoiceCommand> {
+    [
+        ("new line", "enter"),
+        ("new paragraph", "enter enter"),
+        ("select all", "mod+a"),
+        ("undo", "mod+z"),
+        ("redo", "mod+shift+z"),
+        ("copy", "mod+c"),
+        ("paste", "mod+v"),
+        ("tab", "tab"),
+    ]
+    .iter()
+    .map(|(phrase, keys)| VoiceCommand {
+        phrase: phrase.to_string(),
+        action: CommandAction::Keys {
+            keys: keys.to_string(),
+        },
+    })
+    .collect()
+}
+
+fn normalize(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A quick edit to the last dictation, said on its own right after it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditCommand {
+    /// "scratch that": remove the last dictation
+    ScratchThat,
+    /// "cap that": paste it again with each word capitalized
+    CapThat,
+}
+
+impl EditCommand {
+    /// The edit command a transcript is, if it says nothing else
+    pub fn parse(transcript: &str) -> Option<Self> {
+        match normalize(transcript).as_str() {
+            "scratch that" => Some(Self::ScratchThat),
+            "cap that" => Some(Self::CapThat),
+            _ => None,
+        }
+    }
+
+    pub fn phrase(self) -> &'static str {
+        match self {
+            Self::ScratchThat => "scratch that",
+            Self::CapThat => "cap that",
+        }
+    }
+
+    /// Apply the edit to the text pasted last
+    pub fn execute(self, app_handle: &AppHandle) -> Result<(), String> {
+        log::info!("Running edit command '{}'", self.phrase());
+        match self {
+            Self::ScratchThat => clipboard::undo_last_paste(app_handle),
+            Self::CapThat => clipboard::replace_last_paste(app_handle, capitalize_words),
+        }
+    }
+}
+
+/// Capitalize the first letter of every word, leaving the rest as it is
+fn capitalize_words(text: &str) -> String {
+    let mut word_start = true;
+    text.chars()
+        .flat_map(|c| {
+            let capitalize = word_start && c.is_alphabetic();
+            word_start = c.is_whitespace() || (word_start && !c.is_alphanumeric());
+            if capitalize {
+                c.to_uppercase().collect::<Vec<_>>()
+            } else {
+                vec![c]
+            }
+        })
+        .collect()
+}
+
+/// Find the command whose phrase matches the transcript
+fn find_command<'a>(commands: &'a [VoiceCommand], transcript: &str) -> Option<&'a VoiceCommand> {
+    let spoken = normalize(transcript);
+    commands
+        .iter()
+        .find(|command| !spoken.is_empty() && normalize(&command.phrase) == spoken)
+}
+
+/// A key combination: modifiers held while the key is clicked
+#[derive(Debug, PartialEq)]
+struct KeyCombo {
+    modifiers: Vec<Key>,
+    key: Key,
+}
+
+fn parse_key(name: &str) -> Option<Key> {
+    let key = match name {
+        "enter" | "return" => Key::Return,
+        "tab" => Key::Tab,
+        "escape" | "esc" => Key::Escape,
+        "backspace" => Key::Backspace,
+        "delete" | "del" => Key::Delete,
+        "space" => Key::Space,
+        "up" => Key::UpArrow,
+        "down" => Key::DownArrow,
+        "left" => Key::LeftArrow,
+        "right" => Key::RightArrow,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "pageup" => Key::PageUp,
+        "pagedown" => Key::PageDown,
+        "f1" => Key::F1,
+        "f2" => Key::F2,
+        "f3" => Key::F3,
+        "f4" => Key::F4,
+        "f5" => Key::F5,
+        "f6" => Key::F6,
+        "f7" => Key::F7,
+        "f8" => Key::F8,
+        "f9" => Key::F9,
+        "f10" => Key::F10,
+        "f11" => Key::F11,
+        "f12" => Key::F12,
+        _ => {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Key::Unicode(c),
+                _ => return None,
+            }
+        }
+    };
+    Some(key)
+}
+
+fn parse_modifier(name: &str) -> Option<Key> {
+    match name {
+        "ctrl" | "control" => Some(Key::Control),
+        "shift" => Some(Key::Shift),
+        "alt" | "option" => Some(Key::Alt),
+        "cmd" | "meta" | "super" | "win" => Some(Key::Meta),
+        "mod" if cfg!(target_os = "macos") => Some(Key::Meta),
+        "mod" => Some(Key::Control),
+        _ => None,
+    }
+}
+
+/// Parse a space-separated sequence of combos like "mod+shift+z enter"
+fn parse_keys(keys: &str) -> Result<Vec<KeyCombo>, String> {
+    keys.split_whitespace()
+        .map(|combo| {
+            let combo = combo.to_lowercase();
+            let mut parts: Vec<&str> = combo.split('+').collect();
+            let key_name = parts.pop().unwrap_or_default();
+            let key = parse_key(key_name).ok_or_else(|| format!("Unknown key '{}'", key_name))?;
+            let modifiers = parts
+                .into_iter()
+                .map(|m| parse_modifier(m).ok_or_else(|| format!("Unknown modifier '{}'", m)))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(KeyCombo { modifiers, key })
+        })
+        .collect()
+}
+
+fn send_keys(keys: &str) -> Result<(), String> {
+    let combos = parse_keys(keys)?;
+    let mut enigo = input::new_enigo()?;
+
+    for combo in combos {
+        for modifier in &combo.modifiers {
+            enigo
+                .key(*modifier, Direction::Press)
+                .map_err(|e| format!("Failed to press modifier: {}", e))?;
+        }
+
+        let result = enigo
+            .key(combo.key, Direction::Click)
+            .map_err(|e| format!("Failed to press key: {}", e));
+
+        std::thread::sleep(std::time::Duration::from_millis(20));
+
+        for modifier in combo.modifiers.iter().rev() {
+            let _ = enigo.key(*modifier, Direction::Release);
+        }
+        result?;
+    }
+
+    Ok(())
+}
+
+fn run_action(app_handle: &AppHandle, action: &CommandAction) -> Result<(), String> {
+    match action {
+        CommandAction::Keys { keys } => send_keys(keys),
+        CommandAction::Text { text } => {
+            let mut enigo = input::new_enigo()?;
+            input::paste_text_direct(&mut enigo, text)
+        }
+        CommandAction::Open { target } => {
+            let opener = app_handle.opener();
+            let result = if target.contains("://") {
+                opener.open_url(target, None::<&str>)
+            } else {
+                opener.open_path(target, None::<&str>)
+            };
+            result.map_err(|e| format!("Failed to open '{}': {}", target, e))
+        }
+    }
+}
+
+/// Run the command matching a transcript, returning its phrase
+pub fn execute(app_handle: &AppHandle, transcript: &str) -> Result<String, String> {
+    let commands = settings::get_settings(app_handle).voice_commands;
+    let command = find_command(&commands, transcript)
+        .ok_or_else(|| format!("No voice command matches '{}'", transcript))?;
+
+    log::info!("Running voice command '{}'", command.phrase);
+    run_action(app_handle, &command.action)?;
+    Ok(command.phrase.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_command_ignores_case_and_punctuation() {
+        let commands = default_voice_commands();
+        assert_eq!(
+            find_command(&commands, "Select all.").map(|c| c.phrase.as_str()),
+            Some("select all")
+        );
+        assert!(find_command(&commands, "select all of it").is_none());
+    }
+
+    #[test]
+    fn test_edit_commands() {
+        assert_eq!(
+            EditCommand::parse("Scratch that."),
+            Some(EditCommand::ScratchThat)
+        );
+        assert_eq!(EditCommand::parse("cap that"), Some(EditCommand::CapThat));
+        assert_eq!(EditCommand::parse("scratch that idea"), None);
+        assert_eq!(
+            capitalize_words("hello \"big\" world-wide web. "),
+            "Hello \"Big\" World-wide Web. "
+        );
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        let combos = parse_keys("ctrl+shift+T enter").unwrap();
+        assert_eq!(
+            combos,
+            vec![
+                KeyCombo {
+                    modifiers: vec![Key::Control, Key::Shift],
+                    key: Key::Unicode('t'),
+                },
+                KeyCombo {
+                    modifiers: Vec::new(),
+                    key: Key::Return,
+                },
+            ]
+        );
+        assert!(parse_keys("hyper+x").is_err());
+    }
+}
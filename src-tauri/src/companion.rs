@@ -0,0 +1,424 @@
+//! Companion capture from a phone
+//!
+//! An optional HTTP endpoint on the local network that a phone pushes
+//! recordings to, so the phone's microphone can be used for dictation when
+//! away from the desk. Pairing is a QR code of the endpoint's URL, which
+//! carries a random token; every request needs the token, either as
+//! `Authorization: Bearer <token>` or as the `token` query parameter. The
+//! token is kept in the OS keyring, and only falls back to the settings
+//! store where there's no keyring.
+//!
+//! - `GET /v1/ping` checks the pairing
+//! - `POST /v1/audio` takes a recording as the request body (WAV, M4A, MP3
+//!   or OGG) and responds with `{ "text": "...", "pasted": true }`. The
+//!   transcript is saved to the history and, with `companion_paste`, pasted
+//!   into the focused app.
+//!
+//! Phone browsers only allow the microphone on HTTPS pages, so recordings
+//! come from an app or a shortcut (e.g. iOS Shortcuts' "Record Audio" then
+//! "Get Contents of URL") rather than a page served from here.
+
+use std::io::Read;
+use std::net::{IpAddr, Ipv4Addr, UdpSocket};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use qrcode::render::svg;
+use qrcode::QrCode;
+use serde::Serialize;
+use serde_json::json;
+use tauri::{AppHandle, Emitter, Manager};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::clipboard;
+use crate::credentials;
+use crate::formatting;
+use crate::recording_manager::{ManagerState, RecordingManager};
+use crate::settings;
+use crate::shortcut::{events, COMPANION_BINDING};
+
+/// Largest recording accepted, about half an hour of 16kHz mono WAV
+const MAX_AUDIO_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Size of the pairing QR code, in pixels
+const QR_CODE_SIZE: u32 = 240;
+
+/// What the settings need to pair a phone
+#[derive(Serialize, Debug, Clone)]
+pub struct CompanionPairing {
+    /// URL recordings are posted to, with the token
+    pub url: String,
+    pub token: String,
+    /// The URL as a QR code, for the phone to scan
+    pub qr_svg: String,
+    /// Whether the endpoint is listening
+    pub running: bool,
+}
+
+/// A random token for pairing, 128 bits from the OS's secure random
+/// number generator, as hex
+fn new_token() -> Result<String, anyhow::Error> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes)
+        .map_err(|e| anyhow::anyhow!("Couldn't generate a pairing token: {}", e))?;
+    Ok(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+/// Keep the pairing token in the keyring, or in the settings where there's
+/// no keyring. A token left in the settings by an older version is removed
+/// once it's in the keyring.
+fn save_token(app: &AppHandle, token: &str) -> Result<(), anyhow::Error> {
+    let in_settings = match credentials::store_companion_token(token) {
+        Ok(()) => String::new(),
+        Err(e) => {
+            log::warn!(
+                "Couldn't save the companion token in the keyring, keeping it in the settings: {}",
+                e
+            );
+            token.to_string()
+        }
+    };
+    if settings::get_settings(app).companion_token != in_settings {
+        settings::update_setting(app, |s| s.companion_token = in_settings)
+            .map_err(|e| anyhow::anyhow!(e))?;
+    }
+    Ok(())
+}
+
+/// The pairing token, created the first time it's needed
+fn pairing_token(app: &AppHandle) -> Result<String, anyhow::Error> {
+    if let Some(token) = credentials::companion_token() {
+        return Ok(token);
+    }
+
+    let saved = settings::get_settings(app).companion_token;
+    let token = if saved.is_empty() {
+        new_token()?
+    } else {
+        saved
+    };
+    save_token(app, &token)?;
+    Ok(token)
+}
+
+/// Replace the pairing token, unpairing any phone paired with the old one
+pub fn reset_token(app: &AppHandle) -> Result<(), anyhow::Error> {
+    save_token(app, &new_token()?)
+}
+
+/// Compare tokens in constant time, so response timing doesn't give
+/// away how much of a guess was right
+fn tokens_match(given: &str, expected: &str) -> bool {
+    !expected.is_empty()
+        && given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// A query parameter's value, from a URL's query string
+fn query_param<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    query.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        (key == name).then_some(value)
+    })
+}
+
+/// File extension for a recording's content type, to help decoding
+fn extension_for(content_type: &str) -> &'static str {
+    let mime = content_type.split(';').next().unwrap_or("").trim();
+    match mime.to_ascii_lowercase().as_str() {
+        "audio/wav" | "audio/x-wav" | "audio/wave" => "wav",
+        "audio/mpeg" | "audio/mp3" => "mp3",
+        "audio/mp4" | "audio/m4a" | "audio/x-m4a" | "audio/aac" => "m4a",
+        "audio/ogg" => "ogg",
+        _ => "audio",
+    }
+}
+
+/// This machine's address on the local network: the one it reaches other
+/// networks from. Connecting a UDP socket doesn't send anything.
+fn lan_address() -> IpAddr {
+    UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .and_then(|socket| {
+            socket.connect((Ipv4Addr::new(8, 8, 8, 8), 80))?;
+            socket.local_addr()
+        })
+        .map(|addr| addr.ip())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+}
+
+fn header<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv(name))
+        .map(|header| header.value.as_str())
+}
+
+fn respond(request: Request, status: u16, body: serde_json::Value) {
+    let content_type = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+        .expect("static header is valid");
+    let response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(content_type);
+    if let Err(e) = request.respond(response) {
+        log::debug!("Couldn't respond to the companion: {}", e);
+    }
+}
+
+/// Transcribe a posted recording, then paste the transcript if enabled
+fn transcribe(app: &AppHandle, request: &mut Request) -> (u16, serde_json::Value) {
+    let Some(manager) = app.try_state::<Arc<RecordingManager>>() else {
+        return (503, json!({ "error": "Not ready yet" }));
+    };
+    if manager.get_state() != ManagerState::Idle {
+        return (409, json!({ "error": "Busy with another recording" }));
+    }
+    if request.body_length().unwrap_or(0) as u64 > MAX_AUDIO_BYTES {
+        return (413, json!({ "error": "Recording too large" }));
+    }
+
+    let mut audio = Vec::new();
+    if let Err(e) = request
+        .as_reader()
+        .take(MAX_AUDIO_BYTES + 1)
+        .read_to_end(&mut audio)
+    {
+        return (
+            400,
+            json!({ "error": format!("Couldn't read the recording: {}", e) }),
+        );
+    }
+    if audio.is_empty() {
+        return (400, json!({ "error": "No recording in the request" }));
+    }
+    if audio.len() as u64 > MAX_AUDIO_BYTES {
+        return (413, json!({ "error": "Recording too large" }));
+    }
+
+    let extension = extension_for(header(request, "Content-Type").unwrap_or(""));
+    let path =
+        std::env::temp_dir().join(format!("iv-companion-{}.{}", std::process::id(), extension));
+    if let Err(e) = std::fs::write(&path, &audio) {
+        log::error!("Failed to save the phone recording: {}", e);
+        return (500, json!({ "error": "Couldn't save the recording" }));
+    }
+
+    let outcome = tauri::async_runtime::block_on(manager.transcribe_companion_audio(&path));
+    let _ = std::fs::remove_file(&path);
+
+    let mut result = match outcome {
+        Ok(result) => result,
+        Err(e) => {
+            log::error!("Phone recording transcription failed: {}", e);
+            let _ = app.emit(events::TRANSCRIPTION_ERROR, e.to_string());
+            return (422, json!({ "error": e.to_string() }));
+        }
+    };
+
+    let settings = settings::get_settings(app);
+    result.text = formatting::process_transcript(&result, &settings, COMPANION_BINDING);
+    log::info!("Phone recording transcribed: {}", result.text);
+    let _ = app.emit(events::TRANSCRIPTION_COMPLETED, &result);
+
+    let pasted = settings.companion_paste
+        && !result.text.is_empty()
+        && match clipboard::paste(result.text.clone(), app, COMPANION_BINDING) {
+            Ok(()) => true,
+            Err(e) => {
+                log::error!("Failed to paste phone transcription: {}", e);
+                false
+            }
+        };
+
+    (200, json!({ "text": result.text, "pasted": pasted }))
+}
+
+fn handle_request(app: &AppHandle, mut request: Request, token: &str) {
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+
+    let given = header(&request, "Authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .or_else(|| query_param(query, "token"))
+        .unwrap_or("");
+    if !tokens_match(given.trim(), token) {
+        log::warn!(
+            "Rejected a companion request from {:?} without a valid token",
+            request.remote_addr()
+        );
+        respond(request, 401, json!({ "error": "Not paired" }));
+        return;
+    }
+
+    let method = request.method().clone();
+    let (status, body) = match (method, path) {
+        (Method::Get, "/v1/ping") => (
+            200,
+            json!({ "app": "iv", "version": env!("CARGO_PKG_VERSION") }),
+        ),
+        (Method::Post, "/v1/audio") => transcribe(app, &mut request),
+        _ => (404, json!({ "error": "Not found" })),
+    };
+    respond(request, status, body);
+}
+
+struct Running {
+    server: Arc<Server>,
+    thread: JoinHandle<()>,
+    port: u16,
+    token: String,
+}
+
+/// The companion endpoint, when it's enabled
+#[derive(Default)]
+pub struct CompanionServer {
+    running: Mutex<Option<Running>>,
+    /// The thread of a stopped endpoint, until its last request is done and
+    /// the port is free again
+    stopping: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl CompanionServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.lock().unwrap().is_some()
+    }
+
+    /// Listen on all interfaces on `port`, restarting if the port or token
+    /// changed
+    pub fn start(&self, app: &AppHandle, port: u16, token: &str) -> Result<(), anyhow::Error> {
+        let unchanged = self
+            .running
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|r| r.port == port && r.token == token);
+        if unchanged {
+            return Ok(());
+        }
+
+        // The previous endpoint has to let go of the port first
+        self.stop();
+        let stopping = self.stopping.lock().unwrap().take();
+        if let Some(thread) = stopping {
+            let _ = thread.join();
+        }
+
+        let server = Server::http((Ipv4Addr::UNSPECIFIED, port))
+            .map_err(|e| anyhow::anyhow!("Couldn't listen on port {}: {}", port, e))?;
+        let server = Arc::new(server);
+
+        let thread = {
+            let server = Arc::clone(&server);
+            let app = app.clone();
+            let token = token.to_string();
+            std::thread::spawn(move || {
+                // Requests are handled one at a time, like dictations
+                for request in server.incoming_requests() {
+                    handle_request(&app, request, &token);
+                }
+            })
+        };
+
+        log::info!("Companion endpoint listening on port {}", port);
+        *self.running.lock().unwrap() = Some(Running {
+            server,
+            thread,
+            port,
+            token: token.to_string(),
+        });
+        Ok(())
+    }
+
+    /// Stop taking requests. A request being handled finishes in the
+    /// background rather than holding up the caller.
+    pub fn stop(&self) {
+        let running = self.running.lock().unwrap().take();
+        if let Some(running) = running {
+            running.server.unblock();
+            *self.stopping.lock().unwrap() = Some(running.thread);
+            log::info!("Companion endpoint stopped");
+        }
+    }
+}
+
+/// Start or stop the endpoint to match the current settings, creating the
+/// pairing token the first time it's enabled
+pub fn sync_with_settings(app: &AppHandle) {
+    let Some(server) = app.try_state::<Arc<CompanionServer>>() else {
+        return;
+    };
+
+    let settings = settings::get_settings(app);
+    if !settings.companion_enabled {
+        server.stop();
+        return;
+    }
+
+    let token = match pairing_token(app) {
+        Ok(token) => token,
+        Err(e) => {
+            log::error!("Failed to create the companion token: {}", e);
+            return;
+        }
+    };
+
+    if let Err(e) = server.start(app, settings.companion_port, &token) {
+        log::error!("Failed to start the companion endpoint: {}", e);
+    }
+}
+
+/// The pairing URL and its QR code
+pub fn pairing(app: &AppHandle) -> Result<CompanionPairing, anyhow::Error> {
+    let token = pairing_token(app)?;
+    let url = format!(
+        "http://{}:{}/v1/audio?token={}",
+        lan_address(),
+        settings::get_settings(app).companion_port,
+        token
+    );
+    let qr_svg = QrCode::new(url.as_bytes())?
+        .render::<svg::Color>()
+        .min_dimensions(QR_CODE_SIZE, QR_CODE_SIZE)
+        .build();
+
+    Ok(CompanionPairing {
+        url,
+        token,
+        qr_svg,
+        running: app
+            .try_state::<Arc<CompanionServer>>()
+            .is_some_and(|server| server.is_running()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_match() {
+        let token = new_token().unwrap();
+        assert_eq!(token.len(), 32);
+        assert!(tokens_match(&token, &token));
+        assert!(!tokens_match(&token[1..], &token));
+        assert!(!tokens_match("", ""));
+        assert_ne!(new_token().unwrap(), token);
+    }
+
+    #[test]
+    fn test_request_parsing() {
+        assert_eq!(query_param("a=1&token=abc", "token"), Some("abc"));
+        assert_eq!(query_param("tokens=abc", "token"), None);
+        assert_eq!(extension_for("audio/x-m4a"), "m4a");
+        assert_eq!(extension_for("audio/wav; codecs=1"), "wav");
+        assert_eq!(extension_for("application/octet-stream"), "audio");
+    }
+}
@@ -0,0 +1,112 @@
+//! API keys for the cloud providers
+//!
+//! A provider's key is looked up, in order, in:
+//! 1. the OS keyring (Keychain, Credential Manager or the Secret Service)
+//! 2. the settings store (`cloud_api_keys`)
+//! 3. the provider's environment variable (e.g. `OPENAI_API_KEY`)
+//!
+//! Every cloud request resolves its key through here, rather than reading
+//! settings or the environment itself. The phone companion's pairing token
+//! is kept in the keyring too.
+
+use iv_core::cloud_transcribe::CloudProviderType;
+use serde::Serialize;
+
+use crate::settings::AppSettings;
+
+/// Service name keys are stored under in the OS keyring
+const KEYRING_SERVICE: &str = "iv";
+
+/// Keyring account of the phone companion's pairing token
+const COMPANION_TOKEN_ACCOUNT: &str = "companion_token";
+
+/// Where a provider's API key was found
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CredentialSource {
+    Keyring,
+    Settings,
+    Environment,
+}
+
+/// Keyring account a provider's key is stored under
+fn keyring_account(provider: CloudProviderType) -> &'static str {
+    match provider {
+        CloudProviderType::OpenAi => "openai",
+        CloudProviderType::Groq => "groq",
+        CloudProviderType::Deepgram => "deepgram",
+        CloudProviderType::Azure => "azure",
+        CloudProviderType::SelfHosted => "self_hosted",
+        CloudProviderType::Mock => "mock",
+    }
+}
+
+fn keyring_entry(account: &str) -> keyring::Result<keyring::Entry> {
+    keyring::Entry::new(KEYRING_SERVICE, account)
+}
+
+fn read_keyring(account: &str) -> Option<String> {
+    match keyring_entry(account).and_then(|entry| entry.get_password()) {
+        Ok(secret) => Some(secret).filter(|secret| !secret.trim().is_empty()),
+        Err(keyring::Error::NoEntry) => None,
+        Err(e) => {
+            log::debug!("Couldn't read '{}' from the keyring: {}", account, e);
+            None
+        }
+    }
+}
+
+/// Save a secret, or remove it with None or an empty string
+fn write_keyring(account: &str, secret: Option<&str>) -> anyhow::Result<()> {
+    let entry = keyring_entry(account)?;
+    match secret.map(str::trim).filter(|secret| !secret.is_empty()) {
+        Some(secret) => entry.set_password(secret)?,
+        None => match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => {}
+            Err(e) => return Err(e.into()),
+        },
+    }
+    Ok(())
+}
+
+fn from_env(provider: CloudProviderType) -> Option<String> {
+    std::env::var(provider.api_key_env_var())
+        .ok()
+        .filter(|key| !key.trim().is_empty())
+}
+
+/// A provider's API key and where it came from
+pub fn resolve(
+    settings: &AppSettings,
+    provider: CloudProviderType,
+) -> Option<(String, CredentialSource)> {
+    read_keyring(keyring_account(provider))
+        .map(|key| (key, CredentialSource::Keyring))
+        .or_else(|| {
+            settings
+                .cloud_api_keys
+                .get(provider)
+                .map(|key| (key, CredentialSource::Settings))
+        })
+        .or_else(|| from_env(provider).map(|key| (key, CredentialSource::Environment)))
+}
+
+/// A provider's API key, if one is configured anywhere
+pub fn api_key(settings: &AppSettings, provider: CloudProviderType) -> Option<String> {
+    resolve(settings, provider).map(|(key, _)| key)
+}
+
+/// Save a provider's key in the OS keyring, or remove it with None or an
+/// empty key
+pub fn store_in_keyring(provider: CloudProviderType, key: Option<&str>) -> anyhow::Result<()> {
+    write_keyring(keyring_account(provider), key)
+}
+
+/// The phone companion's pairing token, if one is stored
+pub fn companion_token() -> Option<String> {
+    read_keyring(COMPANION_TOKEN_ACCOUNT)
+}
+
+pub fn store_companion_token(token: &str) -> anyhow::Result<()> {
+    write_keyring(COMPANION_TOKEN_ACCOUNT, Some(token))
+}
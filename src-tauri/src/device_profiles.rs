@@ -0,0 +1,67 @@
+//! Per-device calibration profiles
+//!
+//! Calibration results are kept per input device name, so each microphone
+//! gets its own gain and VAD threshold and switching devices needs no
+//! retuning. Profiles are applied whenever a recording opens that device.
+
+use std::collections::HashMap;
+
+use iv_core::calibration::DeviceProfile;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::settings::SETTINGS_STORE_PATH;
+
+const DEVICE_PROFILES_KEY: &str = "device_profiles";
+
+pub fn load_profiles(app: &AppHandle) -> HashMap<String, DeviceProfile> {
+    let Ok(store) = app.store(SETTINGS_STORE_PATH) else {
+        return HashMap::new();
+    };
+
+    store
+        .get(DEVICE_PROFILES_KEY)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+fn save_profiles(app: &AppHandle, profiles: &HashMap<String, DeviceProfile>) {
+    let store = match app.store(SETTINGS_STORE_PATH) {
+        Ok(s) => s,
+        Err(e) => {
+            log::warn!("Failed to get settings store: {}", e);
+            return;
+        }
+    };
+
+    match serde_json::to_value(profiles) {
+        Ok(value) => {
+            store.set(DEVICE_PROFILES_KEY, value);
+            if let Err(e) = store.save() {
+                log::warn!("Failed to save device profiles: {}", e);
+            }
+        }
+        Err(e) => log::warn!("Failed to serialize device profiles: {}", e),
+    }
+}
+
+/// The calibration profile for a device, if it has been calibrated
+pub fn get_profile(app: &AppHandle, device_name: &str) -> Option<DeviceProfile> {
+    load_profiles(app).remove(device_name)
+}
+
+pub fn set_profile(app: &AppHandle, device_name: &str, profile: DeviceProfile) {
+    let mut profiles = load_profiles(app);
+    profiles.insert(device_name.to_string(), profile);
+    save_profiles(app, &profiles);
+}
+
+/// Forget a device's calibration, returning whether it had one
+pub fn remove_profile(app: &AppHandle, device_name: &str) -> bool {
+    let mut profiles = load_profiles(app);
+    let removed = profiles.remove(device_name).is_some();
+    if removed {
+        save_profiles(app, &profiles);
+    }
+    removed
+}
@@ -0,0 +1,162 @@
+//! Fuzzy correction of transcripts against dictionary terms
+//!
+//! Runs of words that spell something close to a term (ignoring case,
+//! spacing and punctuation) are replaced with the term, so "Chat GPT" and
+//! "kubernetees" become "ChatGPT" and "Kubernetes".
+
+use serde::Serialize;
+
+/// Terms shorter than this (in letters) are only matched exactly by default,
+/// since short words are too easy to confuse
+const MIN_FUZZY_LEN: usize = 5;
+
+/// A term to correct towards, with the number of edits it tolerates
+pub struct FuzzyTarget<'a> {
+    pub term: &'a str,
+    pub max_distance: usize,
+}
+
+/// A correction made (or, in a dry run, that would be made)
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Correction {
+    /// The words as transcribed
+    pub heard: String,
+    pub corrected: String,
+    /// Edit distance between the two, ignoring case and punctuation
+    pub distance: usize,
+}
+
+/// Edits tolerated for a term when the user hasn't set a limit: roughly one
+/// per five letters
+pub fn default_max_distance(term: &str) -> usize {
+    let len = normalize(term).chars().count();
+    if len < MIN_FUZZY_LEN {
+        0
+    } else {
+        len / 5
+    }
+}
+
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut row = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            row[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(row[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut row);
+    }
+
+    prev[b.len()]
+}
+
+/// Punctuation the engine put after a word, to keep after a correction
+fn trailing_punctuation(word: &str) -> &str {
+    let end = word.trim_end_matches(|c: char| !c.is_alphanumeric()).len();
+    &word[end..]
+}
+
+/// Replace runs of words that closely match a target term, returning the
+/// corrected text and the corrections made
+pub fn correct(text: &str, targets: &[FuzzyTarget]) -> (String, Vec<Correction>) {
+    let targets: Vec<(&FuzzyTarget, String, usize)> = targets
+        .iter()
+        .map(|t| (t, normalize(t.term), t.term.split_whitespace().count()))
+        .filter(|(_, normalized, _)| !normalized.is_empty())
+        .collect();
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut output: Vec<String> = Vec::with_capacity(words.len());
+    let mut corrections = Vec::new();
+    let mut i = 0;
+
+    while i < words.len() {
+        // Closest (distance, words consumed, term) starting at this word
+        let mut best: Option<(usize, usize, &str)> = None;
+
+        for (target, normalized, word_count) in &targets {
+            // Engines often split or join words ("Chat GPT" vs "ChatGPT")
+            let min_len = word_count.saturating_sub(1).max(1);
+            let max_len = word_count + 1;
+
+            for len in min_len..=max_len {
+                let Some(run) = words.get(i..i + len) else {
+                    break;
+                };
+                let distance = levenshtein(&normalize(&run.concat()), normalized);
+                if distance <= target.max_distance && best.is_none_or(|(d, _, _)| distance < d) {
+                    best = Some((distance, len, target.term));
+                }
+            }
+        }
+
+        match best {
+            Some((distance, len, term)) => {
+                let heard = words[i..i + len].join(" ");
+                let corrected = format!("{}{}", term, trailing_punctuation(&heard));
+                if corrected != heard {
+                    corrections.push(Correction {
+                        heard,
+                        corrected: corrected.clone(),
+                        distance,
+                    });
+                }
+                output.push(corrected);
+                i += len;
+            }
+            None => {
+                output.push(words[i].to_string());
+                i += 1;
+            }
+        }
+    }
+
+    (output.join(" "), corrections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn targets(terms: &[&'static str]) -> Vec<FuzzyTarget<'static>> {
+        terms
+            .iter()
+            .map(|term| FuzzyTarget {
+                term,
+                max_distance: default_max_distance(term),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_corrects_close_matches() {
+        let targets = targets(&["ChatGPT", "Kubernetes"]);
+        let (text, corrections) = correct("I asked Chat GPT about kubernetees.", &targets);
+
+        assert_eq!(text, "I asked ChatGPT about Kubernetes.");
+        assert_eq!(corrections.len(), 2);
+        assert_eq!(corrections[1].heard, "kubernetees.");
+        assert_eq!(corrections[1].distance, 1);
+    }
+
+    #[test]
+    fn test_leaves_unrelated_words() {
+        let targets = targets(&["Kubernetes", "iv"]);
+        let (text, corrections) = correct("I've got a cube in it", &targets);
+
+        assert_eq!(text, "I've got a cube in it");
+        assert!(corrections.is_empty());
+    }
+}
@@ -0,0 +1,218 @@
+//! User dictionary
+//!
+//! Words and phrases the user wants transcribed a particular way:
+//! replacements rewrite what the engine heard (e.g. "eye vee" -> "iv"),
+//! phrase hints list names and product terms the engine tends to get wrong,
+//! and glossary terms correct near-misses in every transcript.
+//! Engines that can bias decoding towards the phrase list get it up front;
+//! for the rest, transcripts are corrected against it afterwards.
+
+mod fuzzy;
+mod pack;
+
+pub use pack::{ImportSummary, VocabularyPack};
+
+use fuzzy::{Correction, FuzzyTarget};
+use serde::{Deserialize, Serialize};
+
+/// Rewrite of a word or phrase, matched case-insensitively on word boundaries
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Replacement {
+    pub from: String,
+    pub to: String,
+}
+
+/// Term that transcribed words within a few edits of are corrected to
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GlossaryTerm {
+    pub term: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Edits tolerated, or a length-based default when unset
+    #[serde(default)]
+    pub max_distance: Option<usize>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct Dictionary {
+    pub replacements: Vec<Replacement>,
+    /// Names and terms to favour when transcribing
+    pub phrases: Vec<String>,
+    pub glossary: Vec<GlossaryTerm>,
+}
+
+/// Result of running the dictionary over a transcript
+#[derive(Serialize, Debug, Clone)]
+pub struct DictionaryResult {
+    pub text: String,
+    /// Fuzzy corrections made against phrase hints and the glossary
+    pub corrections: Vec<Correction>,
+}
+
+impl Dictionary {
+    /// Add entries from `base` that this dictionary has no entry of its own
+    /// for, so its own entries take precedence
+    pub fn merge_under(&mut self, base: &Dictionary) {
+        for replacement in &base.replacements {
+            if !self
+                .replacements
+                .iter()
+                .any(|r| r.from.eq_ignore_ascii_case(&replacement.from))
+            {
+                self.replacements.push(replacement.clone());
+            }
+        }
+
+        for phrase in &base.phrases {
+            if !self.phrases.iter().any(|p| p.eq_ignore_ascii_case(phrase)) {
+                self.phrases.push(phrase.clone());
+            }
+        }
+
+        for term in &base.glossary {
+            if !self
+                .glossary
+                .iter()
+                .any(|g| g.term.eq_ignore_ascii_case(&term.term))
+            {
+                self.glossary.push(term.clone());
+            }
+        }
+    }
+
+    /// Apply the dictionary to a transcript. `hints_used` says whether the
+    /// engine already received the phrase list during decoding, in which case
+    /// fuzzy correction against it is skipped.
+    pub fn apply(&self, text: &str, hints_used: bool) -> String {
+        self.process(text, hints_used).text
+    }
+
+    /// Apply the dictionary, also reporting the fuzzy corrections made (used
+    /// for dry runs from the settings UI)
+    pub fn process(&self, text: &str, hints_used: bool) -> DictionaryResult {
+        let mut text = text.to_string();
+
+        for replacement in &self.replacements {
+            text = replace_words(&text, &replacement.from, &replacement.to);
+        }
+
+        let mut targets: Vec<FuzzyTarget> = self
+            .glossary
+            .iter()
+            .filter(|g| g.enabled && !g.term.trim().is_empty())
+            .map(|g| FuzzyTarget {
+                term: g.term.trim(),
+                max_distance: g
+                    .max_distance
+                    .unwrap_or_else(|| fuzzy::default_max_distance(&g.term)),
+            })
+            .collect();
+
+        if !hints_used {
+            targets.extend(
+                self.phrases
+                    .iter()
+                    .map(|p| p.trim())
+                    .filter(|p| !p.is_empty())
+                    .map(|term| FuzzyTarget {
+                        term,
+                        max_distance: fuzzy::default_max_distance(term),
+                    }),
+            );
+        }
+
+        if targets.is_empty() {
+            return DictionaryResult {
+                text,
+                corrections: Vec::new(),
+            };
+        }
+
+        let (text, corrections) = fuzzy::correct(&text, &targets);
+        DictionaryResult { text, corrections }
+    }
+}
+
+/// Replace whole-word occurrences of `from` (case-insensitive) with `to`
+fn replace_words(text: &str, from: &str, to: &str) -> String {
+    let from = from.trim();
+    if from.is_empty() {
+        return text.to_string();
+    }
+
+    let lower = text.to_lowercase();
+    let needle = from.to_lowercase();
+    // Lowercasing can change byte lengths outside ASCII; only do the
+    // index-based rewrite when offsets line up
+    if lower.len() != text.len() {
+        return text.to_string();
+    }
+
+    let is_boundary = |c: Option<char>| c.is_none_or(|c| !c.is_alphanumeric());
+
+    let mut output = String::with_capacity(text.len());
+    let mut last = 0;
+    for (start, _) in lower.match_indices(&needle) {
+        let end = start + needle.len();
+        if start < last
+            || !is_boundary(text[..start].chars().next_back())
+            || !is_boundary(text[end..].chars().next())
+        {
+            continue;
+        }
+        output.push_str(&text[last..start]);
+        output.push_str(to);
+        last = end;
+    }
+    output.push_str(&text[last..]);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replacements() {
+        let dictionary = Dictionary {
+            replacements: vec![Replacement {
+                from: "eye vee".to_string(),
+                to: "iv".to_string(),
+            }],
+            phrases: Vec::new(),
+            glossary: Vec::new(),
+        };
+
+        assert_eq!(
+            dictionary.apply("Eye vee is running. Eye veer isn't.", false),
+            "iv is running. Eye veer isn't."
+        );
+    }
+
+    #[test]
+    fn test_disabled_glossary_terms() {
+        let mut dictionary = Dictionary {
+            glossary: vec![GlossaryTerm {
+                term: "Kubernetes".to_string(),
+                enabled: true,
+                max_distance: None,
+            }],
+            ..Default::default()
+        };
+
+        let result = dictionary.process("deploy to kubernetees", true);
+        assert_eq!(result.text, "deploy to Kubernetes");
+        assert_eq!(result.corrections.len(), 1);
+
+        dictionary.glossary[0].enabled = false;
+        assert_eq!(
+            dictionary.apply("deploy to kubernetees", true),
+            "deploy to kubernetees"
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! Vocabulary packs
+//!
+//! A portable JSON file holding the dictionary and output templates, so a
+//! team can share a common vocabulary without sharing full settings (which
+//! include API keys and machine-specific options).
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::Dictionary;
+use crate::settings::AppSettings;
+
+/// Bumped on incompatible changes to the pack format
+const PACK_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct VocabularyPack {
+    pub version: u32,
+    pub dictionary: Dictionary,
+    /// Output templates by name
+    pub templates: HashMap<String, String>,
+}
+
+/// Entries added (or updated) by an import
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct ImportSummary {
+    pub replacements: usize,
+    pub phrases: usize,
+    pub glossary: usize,
+    pub templates: usize,
+}
+
+impl VocabularyPack {
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        Self {
+            version: PACK_VERSION,
+            dictionary: settings.dictionary.clone(),
+            templates: settings.templates.clone(),
+        }
+    }
+
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read vocabulary pack: {}", e))?;
+        let pack: Self = serde_json::from_str(&contents)
+            .map_err(|e| format!("Invalid vocabulary pack: {}", e))?;
+
+        if pack.version > PACK_VERSION {
+            return Err(format!(
+                "Vocabulary pack version {} is newer than supported ({})",
+                pack.version, PACK_VERSION
+            ));
+        }
+        Ok(pack)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize vocabulary pack: {}", e))?;
+        std::fs::write(path, contents)
+            .map_err(|e| format!("Failed to write vocabulary pack: {}", e))
+    }
+
+    /// Merge the pack into the settings. Entries for the same word, term or
+    /// template name are replaced by the pack's version; everything else is
+    /// kept. With `replace`, the existing vocabulary is dropped first.
+    pub fn merge_into(self, settings: &mut AppSettings, replace: bool) -> ImportSummary {
+        let dictionary = &mut settings.dictionary;
+        if replace {
+            *dictionary = Dictionary::default();
+            settings.templates.clear();
+        }
+
+        let summary = ImportSummary {
+            replacements: self.dictionary.replacements.len(),
+            phrases: self.dictionary.phrases.len(),
+            glossary: self.dictionary.glossary.len(),
+            templates: self.templates.len(),
+        };
+
+        for replacement in self.dictionary.replacements {
+            dictionary
+                .replacements
+                .retain(|r| !r.from.eq_ignore_ascii_case(&replacement.from));
+            dictionary.replacements.push(replacement);
+        }
+
+        for phrase in self.dictionary.phrases {
+            dictionary
+                .phrases
+                .retain(|p| !p.eq_ignore_ascii_case(&phrase));
+            dictionary.phrases.push(phrase);
+        }
+
+        for term in self.dictionary.glossary {
+            dictionary
+                .glossary
+                .retain(|g| !g.term.eq_ignore_ascii_case(&term.term));
+            dictionary.glossary.push(term);
+        }
+
+        settings.templates.extend(self.templates);
+
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::Replacement;
+
+    fn replacement(from: &str, to: &str) -> Replacement {
+        Replacement {
+            from: from.to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_merge_replaces_matching_entries() {
+        let mut settings = AppSettings::default();
+        settings.dictionary.replacements = vec![
+            replacement("eye vee", "IV"),
+            replacement("k8s", "Kubernetes"),
+        ];
+        settings.dictionary.phrases = vec!["Tauri".to_string()];
+
+        let pack = VocabularyPack {
+            version: PACK_VERSION,
+            dictionary: Dictionary {
+                replacements: vec![replacement("Eye Vee", "iv")],
+                phrases: vec!["tauri".to_string(), "ChatGPT".to_string()],
+                glossary: Vec::new(),
+            },
+            templates: HashMap::from([("todo".to_string(), "- [ ] {text}".to_string())]),
+        };
+
+        let summary = pack.merge_into(&mut settings, false);
+
+        assert_eq!(summary.replacements, 1);
+        assert_eq!(
+            settings.dictionary.replacements,
+            vec![
+                replacement("k8s", "Kubernetes"),
+                replacement("Eye Vee", "iv")
+            ]
+        );
+        assert_eq!(settings.dictionary.phrases, vec!["tauri", "ChatGPT"]);
+        assert_eq!(settings.templates["todo"], "- [ ] {text}");
+    }
+}
@@ -0,0 +1,58 @@
+//! Draft-then-refine dictation
+//!
+//! With a cloud model selected, the fastest downloaded local model transcribes
+//! the recording first and its draft is pasted right away. When the cloud
+//! transcription arrives it replaces the draft in place, by undoing the paste
+//! and pasting again, but only if the wording changed: case and punctuation
+//! differences alone aren't worth rewriting text the user may already be
+//! reading.
+
+use std::time::Duration;
+
+use tauri::AppHandle;
+
+use crate::clipboard;
+
+/// Pause between undoing the draft and pasting the refined text, so the
+/// target app has processed the undo
+const REPASTE_DELAY_MS: u64 = 50;
+
+fn words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+                .to_lowercase()
+        })
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// Whether the refined transcription says something different from the draft
+pub fn differs_materially(draft: &str, refined: &str) -> bool {
+    words(draft) != words(refined)
+}
+
+/// Replace the pasted draft with the refined text
+pub fn replace_draft(
+    app_handle: &AppHandle,
+    refined: String,
+    binding_id: &str,
+) -> Result<(), String> {
+    clipboard::undo_last_paste(app_handle)?;
+    std::thread::sleep(Duration::from_millis(REPASTE_DELAY_MS));
+    clipboard::paste(refined, app_handle, binding_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_differs_materially() {
+        assert!(!differs_materially("hello world", "Hello, world."));
+        assert!(differs_materially("hello word", "Hello, world."));
+        assert!(differs_materially("hello", "hello there"));
+    }
+}
@@ -0,0 +1,157 @@
+//! Rate limiting for high-frequency frontend events
+//!
+//! Download progress and audio level updates can fire hundreds of times per
+//! second. Events sent through the throttler are coalesced per key: at most one
+//! is emitted per interval, and the most recent payload is always delivered
+//! once the interval has passed, so the UI never gets stuck on a stale value.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Default maximum rate per event key
+pub const DEFAULT_MAX_EVENT_RATE_HZ: u32 = 30;
+
+#[derive(Default)]
+struct Channel {
+    last_emit: Option<Instant>,
+    /// Latest payload waiting for the trailing emit
+    pending: Option<Value>,
+    flush_scheduled: bool,
+}
+
+pub struct EventThrottler {
+    app_handle: AppHandle,
+    interval: Mutex<Duration>,
+    channels: Mutex<HashMap<String, Channel>>,
+}
+
+impl EventThrottler {
+    pub fn new(app_handle: &AppHandle, max_rate_hz: u32) -> Self {
+        Self {
+            app_handle: app_handle.clone(),
+            interval: Mutex::new(rate_to_interval(max_rate_hz)),
+            channels: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Change the maximum rate per event key (0 disables throttling)
+    pub fn set_max_rate(&self, max_rate_hz: u32) {
+        *self.interval.lock().unwrap() = rate_to_interval(max_rate_hz);
+        log::debug!("Event rate limit set to {} Hz", max_rate_hz);
+    }
+
+    /// Emit `event`, coalescing with other emits that share `key`
+    fn emit(self: &Arc<Self>, event: &str, key: &str, payload: Value) {
+        let interval = *self.interval.lock().unwrap();
+        let mut channels = self.channels.lock().unwrap();
+        let channel = channels.entry(key.to_string()).or_default();
+
+        let elapsed = channel.last_emit.map(|t| t.elapsed());
+        match elapsed {
+            Some(elapsed) if elapsed < interval => {
+                channel.pending = Some(payload);
+                if !channel.flush_scheduled {
+                    channel.flush_scheduled = true;
+                    self.schedule_flush(event, key, interval - elapsed);
+                }
+            }
+            _ => {
+                channel.last_emit = Some(Instant::now());
+                channel.pending = None;
+                let _ = self.app_handle.emit(event, payload);
+            }
+        }
+    }
+
+    /// Emit immediately, dropping any pending coalesced payload for `key`
+    fn emit_now(&self, event: &str, key: &str, payload: Value) {
+        {
+            let mut channels = self.channels.lock().unwrap();
+            let channel = channels.entry(key.to_string()).or_default();
+            channel.last_emit = Some(Instant::now());
+            channel.pending = None;
+        }
+        let _ = self.app_handle.emit(event, payload);
+    }
+
+    fn schedule_flush(self: &Arc<Self>, event: &str, key: &str, delay: Duration) {
+        let throttler = Arc::clone(self);
+        let event = event.to_string();
+        let key = key.to_string();
+
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(delay).await;
+
+            let payload = {
+                let mut channels = throttler.channels.lock().unwrap();
+                let Some(channel) = channels.get_mut(&key) else {
+                    return;
+                };
+                channel.flush_scheduled = false;
+                let payload = channel.pending.take();
+                if payload.is_some() {
+                    channel.last_emit = Some(Instant::now());
+                }
+                payload
+            };
+
+            if let Some(payload) = payload {
+                let _ = throttler.app_handle.emit(&event, payload);
+            }
+        });
+    }
+}
+
+fn rate_to_interval(max_rate_hz: u32) -> Duration {
+    if max_rate_hz == 0 {
+        Duration::ZERO
+    } else {
+        Duration::from_secs_f64(1.0 / max_rate_hz as f64)
+    }
+}
+
+fn to_value<S: Serialize>(payload: S) -> Option<Value> {
+    serde_json::to_value(payload)
+        .map_err(|e| log::warn!("Failed to serialize event payload: {}", e))
+        .ok()
+}
+
+/// Emit a high-frequency event through the throttler
+pub fn emit<S: Serialize>(app: &AppHandle, event: &str, payload: S) {
+    emit_keyed(app, event, event, payload);
+}
+
+/// Emit a high-frequency event, coalescing only with emits that share `key`
+/// (e.g. one progress stream per model)
+pub fn emit_keyed<S: Serialize>(app: &AppHandle, event: &str, key: &str, payload: S) {
+    let Some(payload) = to_value(payload) else {
+        return;
+    };
+
+    match app.try_state::<Arc<EventThrottler>>() {
+        Some(throttler) => throttler.emit(event, key, payload),
+        None => {
+            let _ = app.emit(event, payload);
+        }
+    }
+}
+
+/// Emit the final value of a throttled stream right away, so it can't arrive
+/// after a follow-up event such as "download complete"
+pub fn emit_final<S: Serialize>(app: &AppHandle, event: &str, key: &str, payload: S) {
+    let Some(payload) = to_value(payload) else {
+        return;
+    };
+
+    match app.try_state::<Arc<EventThrottler>>() {
+        Some(throttler) => throttler.emit_now(event, key, payload),
+        None => {
+            let _ = app.emit(event, payload);
+        }
+    }
+}
@@ -0,0 +1,50 @@
+//! Foreground application detection
+//!
+//! Used to adapt output to the app that will receive it (e.g. code mode in
+//! editors).
+
+use std::path::Path;
+
+/// The application owning the focused window
+#[derive(Debug, Clone)]
+pub struct ForegroundApp {
+    /// Display name of the application (e.g. "Code")
+    pub app_name: String,
+    /// Executable name without extension (e.g. "code")
+    pub process_name: String,
+    /// Title of the focused window
+    pub title: String,
+}
+
+impl ForegroundApp {
+    /// Whether the app matches any of the given names (case-insensitive,
+    /// compared against both the app and process name)
+    pub fn matches_any(&self, names: &[String]) -> bool {
+        names.iter().any(|name| {
+            name.eq_ignore_ascii_case(&self.app_name)
+                || name.eq_ignore_ascii_case(&self.process_name)
+        })
+    }
+}
+
+/// Get the application owning the focused window, if it can be determined
+pub fn get_foreground_app() -> Option<ForegroundApp> {
+    let window = match active_win_pos_rs::get_active_window() {
+        Ok(window) => window,
+        Err(()) => {
+            log::debug!("Could not determine the foreground window");
+            return None;
+        }
+    };
+
+    let process_name = Path::new(&window.process_path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    Some(ForegroundApp {
+        app_name: window.app_name,
+        process_name,
+        title: window.title,
+    })
+}
@@ -0,0 +1,118 @@
+//! Output casing styles
+
+use serde::{Deserialize, Serialize};
+
+/// Words kept lowercase inside Title Case (unless first)
+const TITLE_SMALL_WORDS: &[&str] = &[
+    "a", "an", "and", "as", "at", "but", "by", "for", "in", "nor", "of", "on", "or", "the", "to",
+    "vs", "via",
+];
+
+/// Casing applied to transcripts before output
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CasingStyle {
+    /// Leave the engine's output untouched
+    #[default]
+    Original,
+    /// Capitalize the first letter of each sentence
+    Sentence,
+    /// Everything lowercase with no trailing period ("chat style")
+    Lowercase,
+    /// Capitalize each word for headings, with no trailing period
+    Title,
+}
+
+impl CasingStyle {
+    pub fn apply(&self, text: &str) -> String {
+        match self {
+            CasingStyle::Original => text.to_string(),
+            CasingStyle::Sentence => sentence_case(text),
+            CasingStyle::Lowercase => strip_trailing_period(&text.to_lowercase()),
+            CasingStyle::Title => strip_trailing_period(&title_case(text)),
+        }
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn sentence_case(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut at_sentence_start = true;
+
+    for c in text.chars() {
+        if at_sentence_start && c.is_alphabetic() {
+            result.extend(c.to_uppercase());
+            at_sentence_start = false;
+        } else {
+            result.push(c);
+            if matches!(c, '.' | '!' | '?') {
+                at_sentence_start = true;
+            } else if !c.is_whitespace() {
+                at_sentence_start = false;
+            }
+        }
+    }
+
+    result
+}
+
+fn title_case(text: &str) -> String {
+    text.split(' ')
+        .enumerate()
+        .map(|(i, word)| {
+            let lower = word.to_lowercase();
+            if i > 0 && TITLE_SMALL_WORDS.contains(&lower.as_str()) {
+                lower
+            } else {
+                capitalize(word)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn strip_trailing_period(text: &str) -> String {
+    let trimmed = text.trim_end();
+    match trimmed.strip_suffix('.') {
+        // Keep ellipses
+        Some(rest) if !rest.ends_with('.') => rest.to_string(),
+        _ => trimmed.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lowercase_is_chat_style() {
+        assert_eq!(
+            CasingStyle::Lowercase.apply("Sounds good. See you at 5."),
+            "sounds good. see you at 5"
+        );
+        assert_eq!(CasingStyle::Lowercase.apply("Really?"), "really?");
+    }
+
+    #[test]
+    fn test_sentence_case() {
+        assert_eq!(
+            CasingStyle::Sentence.apply("hello there. how are you? fine"),
+            "Hello there. How are you? Fine"
+        );
+    }
+
+    #[test]
+    fn test_title_case() {
+        assert_eq!(
+            CasingStyle::Title.apply("release notes for the new version."),
+            "Release Notes for the New Version"
+        );
+    }
+}
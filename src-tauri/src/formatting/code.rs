@@ -0,0 +1,180 @@
+//! Code mode - programmer-friendly formatting for dictating into editors
+//!
+//! Spoken identifiers and symbols are turned into code: "camel case foo bar"
+//! becomes `fooBar`, "foo underscore bar" becomes `foo_bar`, and the engine's
+//! automatic punctuation is dropped.
+
+/// Identifier styles that can be spoken as "<style> case ..."
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IdentifierCase {
+    Camel,
+    Pascal,
+    Snake,
+    Kebab,
+    Constant,
+}
+
+impl IdentifierCase {
+    fn from_word(word: &str) -> Option<Self> {
+        match word {
+            "camel" => Some(Self::Camel),
+            "pascal" => Some(Self::Pascal),
+            "snake" => Some(Self::Snake),
+            "kebab" => Some(Self::Kebab),
+            "constant" | "screaming" => Some(Self::Constant),
+            _ => None,
+        }
+    }
+
+    fn join(&self, words: &[String]) -> String {
+        match self {
+            Self::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(i, w)| if i == 0 { w.clone() } else { capitalize(w) })
+                .collect(),
+            Self::Pascal => words.iter().map(|w| capitalize(w)).collect(),
+            Self::Snake => words.join("_"),
+            Self::Kebab => words.join("-"),
+            Self::Constant => words.join("_").to_uppercase(),
+        }
+    }
+}
+
+/// A spoken symbol: (text, attaches to the previous token, attaches to the next token)
+fn symbol(words: &[String]) -> Option<(&'static str, bool, bool, usize)> {
+    let first = words.first()?.as_str();
+    let second = words.get(1).map(|w| w.as_str());
+
+    let two_word = match (first, second) {
+        ("open", Some("paren")) => Some(("(", true, true)),
+        ("close", Some("paren")) => Some((")", true, false)),
+        ("open", Some("bracket")) => Some(("[", true, true)),
+        ("close", Some("bracket")) => Some(("]", true, false)),
+        ("open", Some("brace")) => Some(("{", false, false)),
+        ("close", Some("brace")) => Some(("}", false, false)),
+        ("new", Some("line")) => Some(("\n", true, true)),
+        _ => None,
+    };
+    if let Some((text, left, right)) = two_word {
+        return Some((text, left, right, 2));
+    }
+
+    let one_word = match first {
+        "underscore" => ("_", true, true),
+        "dash" | "hyphen" => ("-", true, true),
+        "dot" => (".", true, true),
+        "slash" => ("/", true, true),
+        "colon" => (":", true, false),
+        "semicolon" => (";", true, false),
+        "comma" => (",", true, false),
+        "equals" => ("=", false, false),
+        "arrow" => ("->", false, false),
+        "quote" => ("\"", false, false),
+        _ => return None,
+    };
+    Some((one_word.0, one_word.1, one_word.2, 1))
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Normalize a spoken word: drop engine punctuation, lowercase unless it's an acronym
+fn normalize(word: &str) -> String {
+    let trimmed = word.trim_matches(|c: char| matches!(c, '.' | ',' | '?' | '!' | ';' | ':'));
+    let is_acronym = trimmed.len() > 1 && trimmed.chars().all(|c| !c.is_lowercase());
+    if is_acronym {
+        trimmed.to_string()
+    } else {
+        trimmed.to_lowercase()
+    }
+}
+
+/// Format a transcript as code
+pub fn format_code(text: &str) -> String {
+    let words: Vec<String> = text
+        .split_whitespace()
+        .map(normalize)
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    // (text, attaches to previous, attaches to next)
+    let mut tokens: Vec<(String, bool, bool)> = Vec::new();
+    let mut i = 0;
+
+    while i < words.len() {
+        let rest = &words[i..];
+
+        if let Some((text, left, right, consumed)) = symbol(rest) {
+            tokens.push((text.to_string(), left, right));
+            i += consumed;
+            continue;
+        }
+
+        let case = IdentifierCase::from_word(&rest[0])
+            .filter(|_| rest.get(1).map(|w| w.as_str()) == Some("case"));
+        if let Some(case) = case {
+            // The identifier runs until the next symbol or case command
+            let mut parts = Vec::new();
+            i += 2;
+            while i < words.len() {
+                let next = &words[i..];
+                let is_command = symbol(next).is_some()
+                    || (IdentifierCase::from_word(&next[0]).is_some()
+                        && next.get(1).map(|w| w.as_str()) == Some("case"));
+                if is_command {
+                    break;
+                }
+                parts.push(words[i].to_lowercase());
+                i += 1;
+            }
+
+            if !parts.is_empty() {
+                tokens.push((case.join(&parts), false, false));
+            }
+            continue;
+        }
+
+        tokens.push((rest[0].clone(), false, false));
+        i += 1;
+    }
+
+    let mut output = String::new();
+    let mut glue_next = true;
+    for (text, left, right) in tokens {
+        if !glue_next && !left {
+            output.push(' ');
+        }
+        output.push_str(&text);
+        glue_next = right;
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identifier_cases() {
+        assert_eq!(format_code("Camel case foo bar."), "fooBar");
+        assert_eq!(format_code("snake case user id"), "user_id");
+        assert_eq!(format_code("constant case max size"), "MAX_SIZE");
+    }
+
+    #[test]
+    fn test_symbols() {
+        assert_eq!(format_code("foo underscore bar"), "foo_bar");
+        assert_eq!(
+            format_code("Self dot camel case get name open paren close paren."),
+            "self.getName()"
+        );
+        assert_eq!(format_code("let x equals 5"), "let x = 5");
+    }
+}
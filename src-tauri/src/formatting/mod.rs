@@ -0,0 +1,144 @@
+//! Output formatting stage
+//!
+//! Transcripts pass through here after transcription and before they're
+//! pasted, so output styles apply the same way to every engine.
+
+mod casing;
+mod code;
+mod plugin;
+mod template;
+mod typography;
+
+pub use casing::CasingStyle;
+pub use plugin::PostProcessPlugin;
+
+use iv_core::transcript::TranscriptionResult;
+use serde::{Deserialize, Serialize};
+
+use crate::foreground;
+use crate::settings::AppSettings;
+use template::TemplateContext;
+use typography::Typography;
+
+/// When to use the code formatting pipeline instead of prose formatting
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CodeMode {
+    #[default]
+    Off,
+    /// Only when one of the configured code editors is in the foreground
+    Auto,
+    Always,
+}
+
+/// Format a transcript for output, using the options of the shortcut binding
+/// that triggered it where they override the global settings
+pub fn format_output(text: &str, settings: &AppSettings, binding_id: &str) -> String {
+    if use_code_mode(settings) {
+        return code::format_code(text);
+    }
+
+    let binding = settings.bindings.get(binding_id);
+    let casing = binding
+        .and_then(|binding| binding.casing)
+        .unwrap_or(settings.output_casing);
+    let text = casing.apply(text);
+
+    if !settings.locale_typography {
+        return text;
+    }
+    let translated = settings.translate_to_english || binding.is_some_and(|b| b.translate);
+    let locale = match settings.typography_locale.as_deref() {
+        Some(locale) if !locale.is_empty() => locale,
+        _ if translated => "en",
+        // "auto" has no typography, as the language isn't known
+        _ => &settings.transcription_language,
+    };
+    match Typography::for_locale(locale) {
+        Some(typography) => typography.apply(&text),
+        None => text,
+    }
+}
+
+/// Format a transcription for output, then pass it through the
+/// post-processing plugins registered for the binding. Plugins can take
+/// seconds, so async code goes through [`process_transcript_blocking`].
+pub fn process_transcript(
+    result: &TranscriptionResult,
+    settings: &AppSettings,
+    binding_id: &str,
+) -> String {
+    let text = format_output(&result.text, settings, binding_id);
+    plugin::run_plugins(
+        &settings.post_process_plugins,
+        &text,
+        &result.segments,
+        binding_id,
+    )
+}
+
+/// [`process_transcript`] on a blocking thread. Falls back to the unformatted
+/// text if that thread panics.
+pub async fn process_transcript_blocking(
+    result: &TranscriptionResult,
+    settings: &AppSettings,
+    binding_id: &str,
+) -> String {
+    let (job_result, job_settings, job_binding) =
+        (result.clone(), settings.clone(), binding_id.to_string());
+    tauri::async_runtime::spawn_blocking(move || {
+        process_transcript(&job_result, &job_settings, &job_binding)
+    })
+    .await
+    .unwrap_or_else(|e| {
+        log::error!("Formatting the transcript failed: {}", e);
+        result.text.clone()
+    })
+}
+
+/// Wrap text in the output template selected for the binding, if any
+pub fn apply_template(text: &str, settings: &AppSettings, binding_id: &str) -> String {
+    let Some(name) = settings
+        .bindings
+        .get(binding_id)
+        .and_then(|binding| binding.template.as_ref())
+    else {
+        return text.to_string();
+    };
+
+    let Some(template) = settings.templates.get(name) else {
+        log::warn!("Output template '{}' not found", name);
+        return text.to_string();
+    };
+
+    let now = chrono::Local::now();
+    let date = now.format("%Y-%m-%d").to_string();
+    let time = now.format("%H:%M").to_string();
+    let app_name = foreground::get_foreground_app()
+        .map(|app| app.app_name)
+        .unwrap_or_default();
+
+    template::expand(
+        template,
+        &TemplateContext {
+            text,
+            date: &date,
+            time: &time,
+            app_name: &app_name,
+        },
+    )
+}
+
+fn use_code_mode(settings: &AppSettings) -> bool {
+    match settings.code_mode {
+        CodeMode::Off => false,
+        CodeMode::Always => true,
+        CodeMode::Auto => foreground::get_foreground_app().is_some_and(|app| {
+            let is_editor = app.matches_any(&settings.code_mode_apps);
+            if is_editor {
+                log::debug!("'{}' is a code editor, using code mode", app.app_name);
+            }
+            is_editor
+        }),
+    }
+}
@@ -0,0 +1,110 @@
+//! Post-processing plugins
+//!
+//! A plugin is an external executable registered in the settings. It gets the
+//! transcript as JSON on stdin and writes the replacement text to stdout:
+//!
+//! ```json
+//! { "text": "...", "segments": [...], "binding_id": "transcribe" }
+//! ```
+//!
+//! Plugins run in order after the built-in formatting, each one receiving the
+//! previous one's output. A plugin that fails, exits non-zero, prints nothing
+//! or runs past its timeout is skipped, so a broken script never loses a
+//! dictation.
+
+use std::process::Stdio;
+use std::time::Duration;
+
+use anyhow::Result;
+use iv_core::transcript::TranscriptSegment;
+use serde::{Deserialize, Serialize};
+
+use crate::subprocess::ExternalCommand;
+
+/// How long a plugin may run before it's killed and skipped, unless it sets
+/// its own timeout
+const DEFAULT_TIMEOUT: Duration = Duration::from_millis(2000);
+
+/// An external program run on every transcript
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PostProcessPlugin {
+    pub name: String,
+    #[serde(flatten)]
+    pub program: ExternalCommand,
+}
+
+/// What a plugin receives on stdin
+#[derive(Serialize)]
+struct PluginInput<'a> {
+    text: &'a str,
+    segments: &'a [TranscriptSegment],
+    binding_id: &'a str,
+}
+
+fn run_plugin(plugin: &PostProcessPlugin, input: &PluginInput) -> Result<String> {
+    let output = plugin.program.run(input, Stdio::piped(), DEFAULT_TIMEOUT)?;
+    let output = trim_output(&output);
+    if output.is_empty() {
+        return Err(anyhow::anyhow!("printed nothing"));
+    }
+    Ok(output.to_string())
+}
+
+/// Drop the newline most programs end their output with
+fn trim_output(output: &str) -> &str {
+    output
+        .strip_suffix('\n')
+        .map(|o| o.strip_suffix('\r').unwrap_or(o))
+        .unwrap_or(output)
+}
+
+/// Run the plugins that apply to a binding over a transcript. Blocks for up
+/// to each plugin's timeout, so it's kept off the async runtime.
+pub fn run_plugins(
+    plugins: &[PostProcessPlugin],
+    text: &str,
+    segments: &[TranscriptSegment],
+    binding_id: &str,
+) -> String {
+    let mut text = text.to_string();
+
+    for plugin in plugins.iter().filter(|p| p.program.applies_to(binding_id)) {
+        let input = PluginInput {
+            text: &text,
+            segments,
+            binding_id,
+        };
+        match run_plugin(plugin, &input) {
+            Ok(output) => {
+                log::debug!("Plugin '{}' processed the transcript", plugin.name);
+                text = output;
+            }
+            Err(e) => log::warn!("Skipping post-processing plugin '{}': {}", plugin.name, e),
+        }
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(unix)]
+    fn test_empty_output_keeps_text() {
+        let plugin: PostProcessPlugin =
+            serde_json::from_str(r#"{ "name": "silent", "command": "true" }"#).unwrap();
+        assert_eq!(
+            run_plugins(&[plugin], "Hello.", &[], "transcribe"),
+            "Hello."
+        );
+    }
+
+    #[test]
+    fn test_trim_output() {
+        assert_eq!(trim_output("Hello.\r\n"), "Hello.");
+        assert_eq!(trim_output("Hello.\n\n"), "Hello.\n");
+        assert_eq!(trim_output("Hello."), "Hello.");
+    }
+}
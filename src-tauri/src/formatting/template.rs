@@ -0,0 +1,53 @@
+//! Output templates
+//!
+//! A template wraps a transcript before it's pasted, e.g. `- [ ] {text}` for
+//! a checklist item or `> {text}\n` for a quote. Placeholders:
+//!
+//! - `{text}` - the transcript
+//! - `{timestamp}` - local date and time (`2024-05-01 14:30`)
+//! - `{date}` / `{time}` - the date or time alone
+//! - `{app}` - name of the focused application
+//!
+//! `\n` and `\t` in a template become a newline and a tab.
+
+/// Values substituted into a template
+pub struct TemplateContext<'a> {
+    pub text: &'a str,
+    pub date: &'a str,
+    pub time: &'a str,
+    pub app_name: &'a str,
+}
+
+/// Expand a template's placeholders and escapes
+pub fn expand(template: &str, context: &TemplateContext) -> String {
+    // Escapes first, so a transcript containing "\n" is left alone
+    let template = template.replace("\\n", "\n").replace("\\t", "\t");
+
+    template
+        .replace("{timestamp}", &format!("{} {}", context.date, context.time))
+        .replace("{date}", context.date)
+        .replace("{time}", context.time)
+        .replace("{app}", context.app_name)
+        .replace("{text}", context.text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand() {
+        let context = TemplateContext {
+            text: "buy milk {app}",
+            date: "2024-05-01",
+            time: "14:30",
+            app_name: "Notes",
+        };
+
+        assert_eq!(expand("- [ ] {text}", &context), "- [ ] buy milk {app}");
+        assert_eq!(
+            expand("> {text}\\n({app}, {timestamp})", &context),
+            "> buy milk {app}\n(Notes, 2024-05-01 14:30)"
+        );
+    }
+}
@@ -0,0 +1,285 @@
+//! Locale typography
+//!
+//! Engines punctuate the English way whatever the language: straight quotes
+//! and a decimal point. With `locale_typography` on, transcripts get the
+//! conventions of the output language instead:
+//!
+//! - quotes: “…” in English, „…“ in German, «…» in French, and so on, with
+//!   apostrophes as ’
+//! - French spacing: no-break spaces inside «…» and before : ; ! ?
+//! - decimal comma: `3.5` becomes `3,5`, `1,200.5` becomes `1.200,5` and
+//!   `1,200` becomes `1.200` in languages that write decimals with a comma
+//!
+//! Mostly only the language part of a locale counts, so "de-AT" is treated
+//! as "de". Switzerland and Liechtenstein are the exception: German there
+//! keeps the decimal point and quotes with «…».
+
+const NBSP: char = '\u{00A0}';
+const NARROW_NBSP: char = '\u{202F}';
+
+/// A language's typographic conventions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Typography {
+    /// Opening and closing double quotes
+    quotes: (char, char),
+    /// Opening and closing quotes within quotes
+    inner_quotes: (char, char),
+    french_spacing: bool,
+    /// Thousands separator, set for languages that use a decimal comma
+    decimal_comma: Option<char>,
+}
+
+impl Typography {
+    /// The conventions for a locale like "fr" or "pt-BR", if it's one we know
+    pub fn for_locale(locale: &str) -> Option<Self> {
+        let mut parts = locale.split(['-', '_']).map(str::to_ascii_lowercase);
+        let language = parts.next()?;
+        let swiss_german = language == "de" && parts.any(|region| region == "ch" || region == "li");
+
+        let (quotes, inner_quotes) = match language.as_str() {
+            _ if swiss_german => (('«', '»'), ('‹', '›')),
+            "en" | "nl" | "pt" | "tr" | "zh" | "ko" => (('“', '”'), ('‘', '’')),
+            "de" | "cs" | "sk" | "sl" | "lt" | "bg" => (('„', '“'), ('‚', '‘')),
+            "pl" | "ro" => (('„', '”'), ('«', '»')),
+            "fr" | "es" | "it" | "ca" | "el" | "no" | "nb" => (('«', '»'), ('“', '”')),
+            "ru" | "uk" => (('«', '»'), ('„', '“')),
+            "sv" | "fi" => (('”', '”'), ('’', '’')),
+            "da" => (('»', '«'), ('›', '‹')),
+            "ja" => (('「', '」'), ('『', '』')),
+            _ => return None,
+        };
+
+        let decimal_comma = match language.as_str() {
+            _ if swiss_german => None,
+            "en" | "zh" | "ko" | "ja" => None,
+            "fr" | "ru" | "uk" | "cs" | "sk" | "pl" | "sv" | "fi" | "no" | "nb" | "bg" | "lt" => {
+                Some(NARROW_NBSP)
+            }
+            _ => Some('.'),
+        };
+
+        Some(Self {
+            quotes,
+            inner_quotes,
+            french_spacing: language == "fr",
+            decimal_comma,
+        })
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        let text = match self.decimal_comma {
+            Some(separator) => decimal_comma(text, separator),
+            None => text.to_string(),
+        };
+        let text = self.curl_quotes(&text);
+        if self.french_spacing {
+            self.french_spacing(&text)
+        } else {
+            text
+        }
+    }
+
+    fn curl_quotes(&self, text: &str) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        let mut result = String::with_capacity(text.len());
+        let mut inner_open = false;
+
+        for (i, &c) in chars.iter().enumerate() {
+            let prev = i.checked_sub(1).map(|j| chars[j]);
+            let next = chars.get(i + 1).copied();
+            let after_space = prev.is_none_or(|p| p.is_whitespace() || "([{".contains(p));
+
+            match c {
+                '"' if after_space => result.push(self.quotes.0),
+                '"' => result.push(self.quotes.1),
+                '\'' => {
+                    let in_word = prev.is_some_and(char::is_alphanumeric)
+                        && next.is_some_and(char::is_alphanumeric);
+                    if in_word {
+                        result.push('’');
+                    } else if after_space && next.is_some_and(|n| !n.is_whitespace()) {
+                        result.push(self.inner_quotes.0);
+                        inner_open = true;
+                    } else if inner_open {
+                        result.push(self.inner_quotes.1);
+                        inner_open = false;
+                    } else {
+                        // e.g. the possessive in "the users' settings"
+                        result.push('’');
+                    }
+                }
+                _ => result.push(c),
+            }
+        }
+
+        result
+    }
+
+    /// No-break spaces inside «…» and before high punctuation, replacing
+    /// any ordinary space there
+    fn french_spacing(&self, text: &str) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        let mut result = String::with_capacity(text.len());
+        let mut skip_spaces = false;
+
+        for (i, &c) in chars.iter().enumerate() {
+            if skip_spaces && c == ' ' {
+                continue;
+            }
+            skip_spaces = false;
+
+            let next = chars.get(i + 1).copied();
+            // Not in URLs, times or "?!"
+            let ends_clause =
+                next.is_none_or(|n| n.is_whitespace() || n == self.quotes.1 || ":;!?".contains(n));
+            let after_punctuation = result.ends_with([':', ';', '!', '?']);
+
+            if c == self.quotes.0 {
+                result.push(c);
+                result.push(NBSP);
+                skip_spaces = true;
+            } else if c == self.quotes.1 || (c == ':' && ends_clause && !after_punctuation) {
+                push_spaced(&mut result, NBSP, c);
+            } else if ";!?".contains(c) && ends_clause && !after_punctuation {
+                push_spaced(&mut result, NARROW_NBSP, c);
+            } else {
+                result.push(c);
+            }
+        }
+
+        result
+    }
+}
+
+/// Push `c` after `space`, replacing any spaces already before it
+fn push_spaced(result: &mut String, space: char, c: char) {
+    let trimmed = result.trim_end_matches(' ').len();
+    result.truncate(trimmed);
+    if !result.is_empty() {
+        result.push(space);
+    }
+    result.push(c);
+}
+
+/// Rewrite English-style numbers with a decimal comma and the given
+/// thousands separator, leaving anything else with digits and points alone
+fn decimal_comma(text: &str, thousands_separator: char) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let starts_number = chars[i].is_ascii_digit()
+            && i.checked_sub(1)
+                .is_none_or(|j| !chars[j].is_alphanumeric() && !".,".contains(chars[j]));
+        if !starts_number {
+            result.push(chars[i]);
+            i += 1;
+            continue;
+        }
+
+        let mut end = i;
+        while end < chars.len() && (chars[end].is_ascii_digit() || ".,".contains(chars[end])) {
+            end += 1;
+        }
+        // Sentence punctuation after the number isn't part of it
+        while ".,".contains(chars[end - 1]) {
+            end -= 1;
+        }
+        let followed_by_word = chars.get(end).is_some_and(|c| c.is_alphanumeric());
+
+        let token: String = chars[i..end].iter().collect();
+        match convert_number(&token, thousands_separator) {
+            Some(converted) if !followed_by_word => result.push_str(&converted),
+            _ => result.push_str(&token),
+        }
+        i = end;
+    }
+
+    result
+}
+
+/// `1,234.5` as `1.234,5` and `1,234` as `1.234` (with `.` as the
+/// separator). Other numbers without a decimal point are left alone, as is
+/// a point followed by exactly three digits, which may already be a
+/// thousands separator.
+fn convert_number(token: &str, thousands_separator: char) -> Option<String> {
+    let (integer, fraction) = match token.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (token, None),
+    };
+    if fraction.is_some_and(|f| f.is_empty() || !f.chars().all(|c| c.is_ascii_digit())) {
+        return None;
+    }
+
+    let groups: Vec<&str> = integer.split(',').collect();
+    let grouped = groups.len() > 1;
+    if grouped {
+        let valid =
+            (1..=3).contains(&groups[0].len()) && groups[1..].iter().all(|group| group.len() == 3);
+        if !valid {
+            return None;
+        }
+    } else if fraction.is_none_or(|f| f.len() == 3) {
+        return None;
+    }
+
+    let integer = groups.join(&thousands_separator.to_string());
+    Some(match fraction {
+        Some(fraction) => format!("{},{}", integer, fraction),
+        None => integer,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(locale: &str, text: &str) -> String {
+        Typography::for_locale(locale).unwrap().apply(text)
+    }
+
+    #[test]
+    fn test_quotes() {
+        assert_eq!(
+            apply("en", r#"She said "it's 'fine'" to me"#),
+            "She said “it’s ‘fine’” to me"
+        );
+        assert_eq!(apply("de-AT", r#"Er sagte "ja""#), "Er sagte „ja“");
+        assert_eq!(apply("de-CH", r#"Er sagte "ja""#), "Er sagte «ja»");
+        assert_eq!(apply("en", "the users' settings"), "the users’ settings");
+    }
+
+    #[test]
+    fn test_french_spacing() {
+        assert_eq!(
+            apply("fr", r#"Il a dit "bonjour" : vraiment ?! Oui ; voir https://x.fr"#),
+            "Il a dit «\u{a0}bonjour\u{a0}»\u{a0}: vraiment\u{202f}?! Oui\u{202f}; voir https://x.fr"
+        );
+    }
+
+    #[test]
+    fn test_decimal_comma() {
+        assert_eq!(apply("de", "Es kostet 3.5 Euro."), "Es kostet 3,5 Euro.");
+        assert_eq!(apply("de", "Total: 1,234.56."), "Total: 1.234,56.");
+        assert_eq!(apply("fr", "1,234.5"), "1\u{202f}234,5");
+        assert_eq!(
+            apply("de", "Etwa 1,200 oder 2,500,000 Leute"),
+            "Etwa 1.200 oder 2.500.000 Leute"
+        );
+        // Versions, possible thousands and identifiers stay as they are
+        assert_eq!(apply("de", "Version 1.2.3"), "Version 1.2.3");
+        assert_eq!(apply("de", "1.500 Leute"), "1.500 Leute");
+        assert_eq!(apply("de", "v1.5 und 2.5x"), "v1.5 und 2.5x");
+        assert_eq!(apply("de", "1,2,3 und 12,34"), "1,2,3 und 12,34");
+        // English and Swiss German keep the decimal point
+        assert_eq!(apply("en", "3.5"), "3.5");
+        assert_eq!(apply("de-CH", "3.5 und 1,200"), "3.5 und 1,200");
+    }
+
+    #[test]
+    fn test_unknown_locale() {
+        assert_eq!(Typography::for_locale("auto"), None);
+        assert_eq!(Typography::for_locale(""), None);
+    }
+}
@@ -0,0 +1,243 @@
+//! Export history entries as Markdown, plain text, JSON or SRT subtitles
+//!
+//! Entries are written oldest first. Markdown and text list each entry under
+//! its date, with segment timestamps when the engine reported them. SRT lays
+//! the selected recordings end to end, one cue per segment, falling back to a
+//! single cue spanning the recording when there are no segments.
+
+use std::fmt::Write;
+use std::path::Path;
+
+use anyhow::Result;
+use chrono::{Local, TimeZone};
+use serde::Deserialize;
+
+use super::HistoryEntry;
+
+/// Output format of an export
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Markdown,
+    Text,
+    Json,
+    Srt,
+}
+
+/// Which entries to export
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExportRange {
+    All,
+    /// Specific entries (a single id exports one recording's transcript)
+    Entries {
+        ids: Vec<String>,
+    },
+    /// Entries created between two times, in milliseconds since the Unix epoch
+    Between {
+        start: u64,
+        end: u64,
+    },
+}
+
+impl ExportRange {
+    fn includes(&self, entry: &HistoryEntry) -> bool {
+        match self {
+            ExportRange::All => true,
+            ExportRange::Entries { ids } => ids.contains(&entry.id),
+            ExportRange::Between { start, end } => (*start..=*end).contains(&entry.timestamp),
+        }
+    }
+}
+
+/// Pick the entries in the range, oldest first
+pub fn select(entries: Vec<HistoryEntry>, range: &ExportRange) -> Vec<HistoryEntry> {
+    let mut selected: Vec<HistoryEntry> =
+        entries.into_iter().filter(|e| range.includes(e)).collect();
+    selected.sort_by_key(|e| e.timestamp);
+    selected
+}
+
+/// Render entries in the given format and write them to `path`
+pub fn write(entries: &[HistoryEntry], format: ExportFormat, path: &Path) -> Result<()> {
+    let contents = render(entries, format)?;
+    std::fs::write(path, contents)?;
+    log::info!(
+        "Exported {} history entries as {:?} to {:?}",
+        entries.len(),
+        format,
+        path
+    );
+    Ok(())
+}
+
+pub fn render(entries: &[HistoryEntry], format: ExportFormat) -> Result<String> {
+    let contents = match format {
+        ExportFormat::Markdown => render_markdown(entries),
+        ExportFormat::Text => render_text(entries),
+        ExportFormat::Json => serde_json::to_string_pretty(entries)?,
+        ExportFormat::Srt => render_srt(entries),
+    };
+    Ok(contents)
+}
+
+fn format_date(timestamp_ms: u64) -> String {
+    Local
+        .timestamp_millis_opt(timestamp_ms as i64)
+        .single()
+        .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+/// "mm:ss" offset within a recording
+fn format_offset(secs: f32) -> String {
+    let total = secs.max(0.0) as u64;
+    format!("{:02}:{:02}", total / 60, total % 60)
+}
+
+/// Entry text, one timestamped line per segment when available
+fn body_lines(entry: &HistoryEntry) -> Vec<String> {
+    if entry.segments.is_empty() {
+        return vec![entry.text.clone()];
+    }
+    entry
+        .segments
+        .iter()
+        .map(|s| match &s.speaker {
+            Some(speaker) => format!("[{}] {}: {}", format_offset(s.start), speaker, s.text),
+            None => format!("[{}] {}", format_offset(s.start), s.text),
+        })
+        .collect()
+}
+
+fn render_markdown(entries: &[HistoryEntry]) -> String {
+    let mut out = String::from("# Transcriptions\n");
+    for entry in entries {
+        let _ = write!(out, "\n## {}\n\n", format_date(entry.timestamp));
+        for line in body_lines(entry) {
+            // Two trailing spaces keep segment lines from being joined
+            let _ = writeln!(out, "{}  ", line);
+        }
+    }
+    out
+}
+
+fn render_text(entries: &[HistoryEntry]) -> String {
+    entries
+        .iter()
+        .map(|entry| {
+            format!(
+                "{}\n{}\n",
+                format_date(entry.timestamp),
+                body_lines(entry).join("\n")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// "HH:MM:SS,mmm" as used by SRT cues
+fn srt_timestamp(secs: f32) -> String {
+    let total_ms = (secs.max(0.0) as f64 * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02},{:03}",
+        total_ms / 3_600_000,
+        (total_ms / 60_000) % 60,
+        (total_ms / 1000) % 60,
+        total_ms % 1000
+    )
+}
+
+fn render_srt(entries: &[HistoryEntry]) -> String {
+    let mut out = String::new();
+    let mut cue = 1;
+    let mut offset = 0.0;
+
+    for entry in entries {
+        let cues: Vec<(f32, f32, &str)> = if entry.segments.is_empty() {
+            vec![(0.0, entry.duration_secs, entry.text.as_str())]
+        } else {
+            entry
+                .segments
+                .iter()
+                .map(|s| (s.start, s.end, s.text.as_str()))
+                .collect()
+        };
+
+        for (start, end, text) in cues {
+            let _ = write!(
+                out,
+                "{}\n{} --> {}\n{}\n\n",
+                cue,
+                srt_timestamp(offset + start),
+                srt_timestamp(offset + end),
+                text
+            );
+            cue += 1;
+        }
+
+        offset += entry.duration_secs;
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iv_core::transcript::TranscriptSegment;
+
+    fn entry(text: &str, duration_secs: f32, segments: Vec<TranscriptSegment>) -> HistoryEntry {
+        HistoryEntry {
+            id: text.to_string(),
+            timestamp: 0,
+            text: text.to_string(),
+            segments,
+            model_id: "test".to_string(),
+            duration_secs,
+            audio_file: None,
+            source: None,
+            paste_suppressed: false,
+        }
+    }
+
+    #[test]
+    fn test_srt_timestamp() {
+        assert_eq!(srt_timestamp(0.0), "00:00:00,000");
+        assert_eq!(srt_timestamp(3723.456), "01:02:03,456");
+    }
+
+    #[test]
+    fn test_srt_uses_segments_and_offsets_entries() {
+        let entries = vec![
+            entry("First clip.", 2.5, Vec::new()),
+            entry(
+                "Hello there. How are you?",
+                4.0,
+                vec![
+                    TranscriptSegment {
+                        start: 0.2,
+                        end: 1.4,
+                        text: "Hello there.".to_string(),
+                        confidence: None,
+                        speaker: None,
+                    },
+                    TranscriptSegment {
+                        start: 1.6,
+                        end: 3.1,
+                        text: "How are you?".to_string(),
+                        confidence: None,
+                        speaker: None,
+                    },
+                ],
+            ),
+        ];
+
+        assert_eq!(
+            render_srt(&entries),
+            "1\n00:00:00,000 --> 00:00:02,500\nFirst clip.\n\n\
+             2\n00:00:02,700 --> 00:00:03,900\nHello there.\n\n\
+             3\n00:00:04,100 --> 00:00:05,600\nHow are you?\n\n"
+        );
+    }
+}
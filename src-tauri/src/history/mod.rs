@@ -0,0 +1,342 @@
+//! Transcription history and saved recordings
+//!
+//! Every successful transcription is stored as a history entry together with
+//! the 16kHz audio it was produced from, so clips can be re-run later.
+
+pub mod export;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+use iv_core::audio::CaptureStats;
+use iv_core::transcript::TranscriptSegment;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_store::StoreExt;
+
+pub const HISTORY_STORE_PATH: &str = "history_store.json";
+
+/// Maximum number of entries (and recordings) kept on disk
+const MAX_HISTORY_ENTRIES: usize = 100;
+
+/// Sample rate of saved recordings
+const RECORDING_SAMPLE_RATE: u32 = 16000;
+
+/// One page of history entries
+#[derive(Serialize, Debug, Clone)]
+pub struct HistoryPage {
+    /// Entries on this page, newest first
+    pub entries: Vec<HistoryEntry>,
+    /// Number of entries matching the query across all pages
+    pub total: usize,
+}
+
+/// Where a recording's audio came from and how it was processed, so changes
+/// in accuracy can be traced to a device or a setting
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct RecordingSource {
+    /// Input device name, or the application or system audio recorded instead
+    pub device: String,
+    /// Rate the audio was captured at, before resampling to 16kHz
+    pub sample_rate: u32,
+    /// Processing applied before transcription, in order (e.g. "device_gain", "vad")
+    pub stages: Vec<String>,
+    /// Wall-clock time of the first recorded sample, in milliseconds since
+    /// the Unix epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at_ms: Option<u64>,
+    /// Wall-clock time just after the last recorded sample
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stopped_at_ms: Option<u64>,
+    /// Audio buffers the driver dropped, each leaving a gap in the recording
+    #[serde(default)]
+    pub dropped_buffers: u64,
+}
+
+impl RecordingSource {
+    /// Add a recorder's stats. Called for each device the recording used in
+    /// turn, so the start is kept from the first and the stop from the last.
+    pub fn add_capture_stats(&mut self, stats: &CaptureStats) {
+        self.started_at_ms = self.started_at_ms.or(stats.started_at_ms);
+        self.stopped_at_ms = stats.stopped_at_ms.or(self.stopped_at_ms);
+        self.dropped_buffers += stats.dropped_buffers;
+    }
+}
+
+/// A single transcription in the history
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HistoryEntry {
+    /// Unique identifier (also used as the recording file stem)
+    pub id: String,
+    /// Creation time in milliseconds since the Unix epoch
+    pub timestamp: u64,
+    /// Transcribed text
+    pub text: String,
+    /// Timed segments, when the engine reported them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub segments: Vec<TranscriptSegment>,
+    /// Model that produced the transcription
+    pub model_id: String,
+    /// Length of the saved recording in seconds
+    pub duration_secs: f32,
+    /// Recording filename in the recordings folder (None if not saved)
+    pub audio_file: Option<String>,
+    /// Device and processing the recording went through (None for older entries)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<RecordingSource>,
+    /// Not pasted, as it repeated the paste just before it
+    #[serde(default)]
+    pub paste_suppressed: bool,
+}
+
+pub struct HistoryManager {
+    app_handle: AppHandle,
+    recordings_dir: PathBuf,
+    entries: Mutex<Vec<HistoryEntry>>,
+}
+
+impl HistoryManager {
+    pub fn new(app_handle: &AppHandle) -> Result<Self> {
+        let recordings_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| anyhow::anyhow!("Failed to get app data dir: {}", e))?
+            .join("recordings");
+
+        if !recordings_dir.exists() {
+            fs::create_dir_all(&recordings_dir)?;
+        }
+
+        log::info!("Recordings directory: {:?}", recordings_dir);
+
+        let entries = load_entries(app_handle);
+        log::debug!("Loaded {} history entries", entries.len());
+
+        Ok(Self {
+            app_handle: app_handle.clone(),
+            recordings_dir,
+            entries: Mutex::new(entries),
+        })
+    }
+
+    /// Get all history entries, newest first
+    pub fn get_entries(&self) -> Vec<HistoryEntry> {
+        let entries = self.entries.lock().unwrap();
+        entries.iter().rev().cloned().collect()
+    }
+
+    /// Get a page of entries, newest first, optionally only those whose text
+    /// contains every word of `query` (case-insensitive) and that were
+    /// recorded from `device`
+    pub fn get_page(
+        &self,
+        offset: usize,
+        limit: usize,
+        query: Option<&str>,
+        device: Option<&str>,
+    ) -> HistoryPage {
+        let terms: Vec<String> = query
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(|t| t.to_lowercase())
+            .collect();
+
+        let entries = self.entries.lock().unwrap();
+        let matching: Vec<&HistoryEntry> = entries
+            .iter()
+            .rev()
+            .filter(|e| {
+                let text = e.text.to_lowercase();
+                terms.iter().all(|term| text.contains(term.as_str()))
+            })
+            .filter(|e| {
+                device.is_none_or(|device| e.source.as_ref().is_some_and(|s| s.device == device))
+            })
+            .collect();
+
+        HistoryPage {
+            total: matching.len(),
+            entries: matching
+                .into_iter()
+                .skip(offset)
+                .take(limit)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Get a single history entry
+    pub fn get_entry(&self, id: &str) -> Option<HistoryEntry> {
+        let entries = self.entries.lock().unwrap();
+        entries.iter().find(|e| e.id == id).cloned()
+    }
+
+    /// Add a transcription to the history, saving its 16kHz recording
+    pub fn add_entry(
+        &self,
+        text: &str,
+        segments: &[TranscriptSegment],
+        model_id: &str,
+        samples: &[f32],
+        source: RecordingSource,
+    ) -> Result<HistoryEntry> {
+        let mut entries = self.entries.lock().unwrap();
+
+        let timestamp = now_millis();
+        let mut id_value = timestamp;
+        while entries.iter().any(|e| e.id == id_value.to_string()) {
+            id_value += 1;
+        }
+        let id = id_value.to_string();
+
+        let audio_file = format!("{}.wav", id);
+        let audio_path = self.recordings_dir.join(&audio_file);
+        let audio_file = match write_recording(&audio_path, samples) {
+            Ok(()) => Some(audio_file),
+            Err(e) => {
+                log::warn!("Failed to save recording for history entry {}: {}", id, e);
+                None
+            }
+        };
+
+        let entry = HistoryEntry {
+            id,
+            timestamp,
+            text: text.to_string(),
+            segments: segments.to_vec(),
+            model_id: model_id.to_string(),
+            duration_secs: samples.len() as f32 / RECORDING_SAMPLE_RATE as f32,
+            audio_file,
+            source: Some(source),
+            paste_suppressed: false,
+        };
+        entries.push(entry.clone());
+
+        // Drop the oldest entries beyond the limit
+        while entries.len() > MAX_HISTORY_ENTRIES {
+            let removed = entries.remove(0);
+            self.remove_recording(&removed);
+        }
+
+        self.persist(&entries)?;
+        let _ = self.app_handle.emit("history-updated", ());
+
+        Ok(entry)
+    }
+
+    /// Note that an entry's transcription wasn't pasted because it repeated
+    /// the one before
+    pub fn mark_paste_suppressed(&self, id: &str) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries
+            .iter_mut()
+            .find(|e| e.id == id)
+            .ok_or_else(|| anyhow::anyhow!("History entry not found: {}", id))?;
+        entry.paste_suppressed = true;
+
+        self.persist(&entries)?;
+        let _ = self.app_handle.emit("history-updated", ());
+
+        Ok(())
+    }
+
+    /// Load the saved 16kHz recording for a history entry
+    pub fn load_recording(&self, id: &str) -> Result<Vec<f32>> {
+        let entry = self
+            .get_entry(id)
+            .ok_or_else(|| anyhow::anyhow!("History entry not found: {}", id))?;
+
+        let audio_file = entry
+            .audio_file
+            .ok_or_else(|| anyhow::anyhow!("No recording saved for entry {}", id))?;
+
+        let mut reader = WavReader::open(self.recordings_dir.join(audio_file))?;
+        let samples = reader.samples::<f32>().collect::<Result<Vec<f32>, _>>()?;
+
+        Ok(samples)
+    }
+
+    /// Delete a history entry and its recording
+    pub fn delete_entry(&self, id: &str) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+
+        let index = entries
+            .iter()
+            .position(|e| e.id == id)
+            .ok_or_else(|| anyhow::anyhow!("History entry not found: {}", id))?;
+        let removed = entries.remove(index);
+        self.remove_recording(&removed);
+
+        self.persist(&entries)?;
+        let _ = self.app_handle.emit("history-updated", ());
+
+        Ok(())
+    }
+
+    fn remove_recording(&self, entry: &HistoryEntry) {
+        if let Some(audio_file) = &entry.audio_file {
+            let _ = fs::remove_file(self.recordings_dir.join(audio_file));
+        }
+    }
+
+    fn persist(&self, entries: &[HistoryEntry]) -> Result<()> {
+        let store = self
+            .app_handle
+            .store(HISTORY_STORE_PATH)
+            .map_err(|e| anyhow::anyhow!("Failed to get history store: {}", e))?;
+
+        store.set("entries", serde_json::to_value(entries)?);
+        store
+            .save()
+            .map_err(|e| anyhow::anyhow!("Failed to save history: {}", e))?;
+
+        Ok(())
+    }
+}
+
+fn load_entries(app_handle: &AppHandle) -> Vec<HistoryEntry> {
+    let store = match app_handle.store(HISTORY_STORE_PATH) {
+        Ok(s) => s,
+        Err(e) => {
+            log::warn!("Failed to get history store: {}", e);
+            return Vec::new();
+        }
+    };
+
+    match store.get("entries") {
+        Some(value) => serde_json::from_value(value).unwrap_or_else(|e| {
+            log::warn!("Failed to deserialize history, starting empty: {}", e);
+            Vec::new()
+        }),
+        None => Vec::new(),
+    }
+}
+
+/// Write 16kHz mono samples as a 32-bit float WAV file
+fn write_recording(path: &Path, samples: &[f32]) -> Result<()> {
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate: RECORDING_SAMPLE_RATE,
+        bits_per_sample: 32,
+        sample_format: SampleFormat::Float,
+    };
+
+    let mut writer = WavWriter::create(path, spec)?;
+    for &sample in samples {
+        writer.write_sample(sample)?;
+    }
+    writer.finalize()?;
+
+    Ok(())
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
@@ -0,0 +1,70 @@
+//! History window management
+//!
+//! A separate window for browsing, searching and reusing past transcriptions.
+//! It's created on first open and hidden (not destroyed) when closed.
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::clipboard;
+
+const HISTORY_WINDOW_LABEL: &str = "history";
+const HISTORY_WIDTH: f64 = 520.0;
+const HISTORY_HEIGHT: f64 = 640.0;
+
+/// Time for the previously focused app to get focus back after the history
+/// window hides, before pasting into it
+const REFOCUS_DELAY_MS: u64 = 250;
+
+/// Show the history window, creating it if needed
+pub fn show_history_window(app_handle: &AppHandle) -> Result<(), String> {
+    if let Some(window) = app_handle.get_webview_window(HISTORY_WINDOW_LABEL) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+        return Ok(());
+    }
+
+    WebviewWindowBuilder::new(
+        app_handle,
+        HISTORY_WINDOW_LABEL,
+        WebviewUrl::App("src/history/index.html".into()),
+    )
+    .title("History")
+    .inner_size(HISTORY_WIDTH, HISTORY_HEIGHT)
+    .min_inner_size(360.0, 320.0)
+    .center()
+    .focused(true)
+    .build()
+    .map_err(|e| format!("Failed to create history window: {}", e))?;
+
+    log::debug!("History window created");
+    Ok(())
+}
+
+/// Hide the history window instead of closing it, so reopening is instant
+pub fn handle_close_requested(window: &tauri::Window, api: &tauri::CloseRequestApi) {
+    api.prevent_close();
+    let _ = window.hide();
+}
+
+/// Copy a past transcription to the clipboard
+pub fn copy_text(app_handle: &AppHandle, text: &str) -> Result<(), String> {
+    app_handle
+        .clipboard()
+        .write_text(text)
+        .map_err(|e| format!("Failed to copy to clipboard: {}", e))
+}
+
+/// Paste a past transcription into the app that was focused before the
+/// history window
+pub async fn repaste_text(app_handle: &AppHandle, text: String) -> Result<(), String> {
+    if let Some(window) = app_handle.get_webview_window(HISTORY_WINDOW_LABEL) {
+        let _ = window.hide();
+    }
+    tokio::time::sleep(Duration::from_millis(REFOCUS_DELAY_MS)).await;
+
+    clipboard::paste(text, app_handle, "")
+}
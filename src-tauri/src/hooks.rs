@@ -0,0 +1,141 @@
+//! Scripting hooks around a recording
+//!
+//! A hook is an external executable registered in the settings and run at a
+//! point in a recording's life, e.g. to mute music while dictating and unmute
+//! it afterwards:
+//!
+//! - `pre_record` hooks run as a recording starts. They run alongside the
+//!   recording rather than holding it up, so nothing said while one runs is
+//!   lost.
+//! - `post_paste` hooks run once the recording is over: after its transcript
+//!   is pasted, or when it ends without a paste (cancelled, failed, nothing
+//!   said, copied to the clipboard, held for review or read-back, or skipped
+//!   as a repeat). `pasted` tells the two apart; text held for review or
+//!   read-back may still be pasted later, after the hook has run.
+//!
+//! Each hook gets the event as JSON on stdin, which it's free to ignore:
+//!
+//! ```json
+//! { "event": "post_paste", "binding_id": "transcribe", "text": "...", "pasted": true }
+//! ```
+//!
+//! Hooks run one at a time, in the order they were triggered, so a
+//! recording's post_paste hooks never overtake its pre_record ones. A hook
+//! that fails or runs past its timeout is killed and logged.
+
+use std::process::Stdio;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::settings;
+use crate::subprocess::ExternalCommand;
+
+/// How long a hook may run before it's killed, unless it sets its own timeout
+const DEFAULT_TIMEOUT: Duration = Duration::from_millis(5000);
+
+/// When a hook runs
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    /// As a recording starts
+    PreRecord,
+    /// Once a recording is over, pasted or not
+    PostPaste,
+}
+
+/// An external program run around recordings
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordingHook {
+    pub name: String,
+    pub event: HookEvent,
+    #[serde(flatten)]
+    pub program: ExternalCommand,
+}
+
+impl RecordingHook {
+    fn applies_to(&self, event: HookEvent, binding_id: &str) -> bool {
+        self.event == event && self.program.applies_to(binding_id)
+    }
+}
+
+/// What a hook receives on stdin
+#[derive(Serialize)]
+struct HookInput {
+    event: HookEvent,
+    binding_id: String,
+    /// The final transcript, for post_paste hooks when there was one
+    text: Option<String>,
+    /// Whether the transcript was pasted into the focused app
+    pasted: bool,
+}
+
+fn run_hook(hook: &RecordingHook, input: &HookInput) -> Result<()> {
+    hook.program.run(input, Stdio::null(), DEFAULT_TIMEOUT)?;
+    Ok(())
+}
+
+type HookJob = (Vec<RecordingHook>, HookInput);
+
+/// The thread hooks run on, started on first use
+fn runner() -> &'static Mutex<Sender<HookJob>> {
+    static RUNNER: OnceLock<Mutex<Sender<HookJob>>> = OnceLock::new();
+    RUNNER.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<HookJob>();
+        std::thread::spawn(move || {
+            for (hooks, input) in receiver {
+                for hook in &hooks {
+                    match run_hook(hook, &input) {
+                        Ok(()) => log::debug!("Ran {:?} hook '{}'", input.event, hook.name),
+                        Err(e) => {
+                            log::warn!("{:?} hook '{}' failed: {}", input.event, hook.name, e)
+                        }
+                    }
+                }
+            }
+        });
+        Mutex::new(sender)
+    })
+}
+
+/// Run the hooks for an event in a binding's recording, in the background
+pub fn run(app: &AppHandle, event: HookEvent, binding_id: &str, text: Option<&str>, pasted: bool) {
+    let hooks: Vec<RecordingHook> = settings::get_settings(app)
+        .recording_hooks
+        .into_iter()
+        .filter(|hook| hook.applies_to(event, binding_id))
+        .collect();
+    if hooks.is_empty() {
+        return;
+    }
+
+    let input = HookInput {
+        event,
+        binding_id: binding_id.to_string(),
+        text: text.map(str::to_string),
+        pasted,
+    };
+    if runner().lock().unwrap().send((hooks, input)).is_err() {
+        log::error!("Hook runner stopped, skipping {:?} hooks", event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_applies_to() {
+        let hook: RecordingHook = serde_json::from_str(
+            r#"{ "name": "mute", "event": "pre_record", "command": "true", "bindings": ["transcribe_chat"] }"#,
+        )
+        .unwrap();
+        assert!(hook.applies_to(HookEvent::PreRecord, "transcribe_chat"));
+        assert!(!hook.applies_to(HookEvent::PostPaste, "transcribe_chat"));
+        assert!(!hook.applies_to(HookEvent::PreRecord, "transcribe"));
+    }
+}
@@ -50,6 +50,22 @@ pub fn get_cursor_position(_app_handle: &AppHandle) -> Option<(i32, i32)> {
     enigo.location().ok()
 }
 
+/// Get the center of the window with keyboard focus, in the same screen
+/// coordinates as `get_cursor_position`.
+/// Returns None if there's no focused window or its bounds are unavailable.
+pub fn get_focused_window_center() -> Option<(i32, i32)> {
+    let window = active_win_pos_rs::get_active_window().ok()?;
+    let bounds = window.position;
+    if bounds.width <= 0.0 || bounds.height <= 0.0 {
+        return None;
+    }
+
+    Some((
+        (bounds.x + bounds.width / 2.0) as i32,
+        (bounds.y + bounds.height / 2.0) as i32,
+    ))
+}
+
 /// Sends a Ctrl+V paste command using platform-specific virtual key codes.
 /// On Windows, uses VK_V (0x56) for correct behavior regardless of keyboard layout.
 pub fn send_paste_ctrl_v(enigo: &mut Enigo) -> Result<(), String> {
@@ -155,3 +171,51 @@ pub fn paste_text_direct(enigo: &mut Enigo, text: &str) -> Result<(), String> {
 
     Ok(())
 }
+
+/// Sends an undo command (Ctrl+Z, or Cmd+Z on macOS).
+pub fn send_undo(enigo: &mut Enigo) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    let (modifier_key, z_key_code) = (Key::Control, Key::Other(0x5A)); // VK_Z on Windows
+
+    #[cfg(target_os = "macos")]
+    let (modifier_key, z_key_code) = (Key::Meta, Key::Other(6)); // Cmd+Z on macOS
+
+    #[cfg(target_os = "linux")]
+    let (modifier_key, z_key_code) = (Key::Control, Key::Unicode('z'));
+
+    enigo
+        .key(modifier_key, enigo::Direction::Press)
+        .map_err(|e| format!("Failed to press modifier key: {}", e))?;
+
+    enigo
+        .key(z_key_code, enigo::Direction::Click)
+        .map_err(|e| format!("Failed to click Z key: {}", e))?;
+
+    std::thread::sleep(std::time::Duration::from_millis(100));
+
+    enigo
+        .key(modifier_key, enigo::Direction::Release)
+        .map_err(|e| format!("Failed to release modifier key: {}", e))?;
+
+    Ok(())
+}
+
+/// Sends an Enter press, e.g. to send a pasted chat message.
+pub fn send_enter(enigo: &mut Enigo) -> Result<(), String> {
+    enigo
+        .key(Key::Return, enigo::Direction::Click)
+        .map_err(|e| format!("Failed to click Enter: {}", e))?;
+
+    Ok(())
+}
+
+/// Sends `count` Backspace presses to delete recently typed text.
+pub fn send_backspaces(enigo: &mut Enigo, count: usize) -> Result<(), String> {
+    for _ in 0..count {
+        enigo
+            .key(Key::Backspace, enigo::Direction::Click)
+            .map_err(|e| format!("Failed to click Backspace: {}", e))?;
+    }
+
+    Ok(())
+}
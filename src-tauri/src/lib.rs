@@ -1,32 +1,153 @@
-mod audio;
+mod accessibility;
 mod audio_feedback;
 mod clipboard;
-mod cloud_transcribe;
+mod commands;
+mod companion;
+mod credentials;
+mod device_profiles;
+mod dictionary;
+mod draft;
+mod event_throttle;
+mod foreground;
+mod formatting;
+mod history;
+mod history_window;
+mod hooks;
 mod input;
-mod local_transcribe;
 mod models;
 mod overlay;
+mod permissions;
+mod power;
+mod read_back;
 mod recording_manager;
+mod review_window;
+mod rewrite;
 mod settings;
 mod shortcut;
+mod subprocess;
+mod team_config;
+mod transcribe_queue;
 mod tray;
 mod vad;
+mod wake_word;
+mod window_state;
 
+use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 
-use models::{ModelInfo, ModelManager};
-use recording_manager::RecordingManager;
+use companion::{CompanionPairing, CompanionServer};
+use credentials::CredentialSource;
+use dictionary::{Dictionary, DictionaryResult, ImportSummary, VocabularyPack};
+use event_throttle::EventThrottler;
+use history::export::{self, ExportFormat, ExportRange};
+use history::{HistoryEntry, HistoryManager, HistoryPage};
+use iv_core::app_capture::CaptureTarget;
+use iv_core::audio::{DeviceCapabilities, InputDeviceInfo};
+use iv_core::calibration::DeviceProfile;
+use iv_core::cloud_transcribe::{CloudProviderType, ProviderCapabilities, ServerStatus};
+use iv_core::transcript::TranscriptionResult;
+use iv_core::vad::{VadAnalysis, VadParams};
+use models::{ModelInfo, ModelManager, UnusedModel};
+use permissions::{OnboardingStatus, PermissionStatus};
+use recording_manager::{RecordingManager, VadTest};
+use review_window::PendingReview;
 use settings::AppSettings;
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Listener, Manager};
+use team_config::TeamConfig;
+use transcribe_queue::{QueueJob, TranscribeQueue};
+use wake_word::WakeWordListener;
 
+/// The user's own settings, without the team baseline (see `get_team_config`)
 #[tauri::command]
 fn get_settings(app_handle: AppHandle) -> AppSettings {
-    settings::get_settings(&app_handle)
+    settings::get_local_settings(&app_handle)
+}
+
+#[tauri::command]
+fn get_team_config(app_handle: AppHandle) -> Option<TeamConfig> {
+    team_config::cached(&app_handle)
+}
+
+#[tauri::command]
+async fn refresh_team_config(app_handle: AppHandle) -> Result<Option<TeamConfig>, String> {
+    team_config::refresh(&app_handle).await
 }
 
 #[tauri::command]
 fn save_settings(app_handle: AppHandle, new_settings: AppSettings) -> Result<(), String> {
-    settings::write_settings(&app_handle, &new_settings)
+    settings::write_settings(&app_handle, &new_settings)?;
+
+    if let Some(throttler) = app_handle.try_state::<Arc<EventThrottler>>() {
+        throttler.set_max_rate(new_settings.max_event_rate_hz);
+    }
+
+    if !new_settings.keep_stream_warm {
+        if let Some(manager) = app_handle.try_state::<Arc<RecordingManager>>() {
+            manager.close_warm_stream();
+        }
+    }
+
+    if let Some(model_manager) = app_handle.try_state::<Arc<ModelManager>>() {
+        if let Some(warning) = model_manager.language_warning(
+            &new_settings.selected_model,
+            &new_settings.transcription_language,
+        ) {
+            log::warn!("{}", warning);
+        }
+    }
+
+    companion::sync_with_settings(&app_handle);
+
+    tauri::async_runtime::spawn(async move {
+        wake_word::sync_with_settings(&app_handle).await;
+    });
+
+    Ok(())
+}
+
+/// Check settings for problems worth showing before they're saved, such as a
+/// transcription language the selected model can't handle
+#[tauri::command]
+fn validate_settings(
+    new_settings: AppSettings,
+    model_manager: tauri::State<Arc<ModelManager>>,
+) -> Vec<String> {
+    model_manager
+        .language_warning(
+            &new_settings.selected_model,
+            &new_settings.transcription_language,
+        )
+        .into_iter()
+        .collect()
+}
+
+#[tauri::command]
+async fn set_wake_word_enabled(app_handle: AppHandle, enabled: bool) -> Result<(), String> {
+    settings::update_setting(&app_handle, |s| s.wake_word_enabled = enabled)?;
+    wake_word::sync_with_settings(&app_handle).await;
+    Ok(())
+}
+
+#[tauri::command]
+fn set_companion_enabled(app_handle: AppHandle, enabled: bool) -> Result<(), String> {
+    settings::update_setting(&app_handle, |s| s.companion_enabled = enabled)?;
+    companion::sync_with_settings(&app_handle);
+    Ok(())
+}
+
+/// The URL and QR code a phone pairs with
+#[tauri::command]
+fn get_companion_pairing(app_handle: AppHandle) -> Result<CompanionPairing, String> {
+    companion::pairing(&app_handle).map_err(|e| e.to_string())
+}
+
+/// Unpair every phone by replacing the token, returning the new pairing
+#[tauri::command]
+fn reset_companion_token(app_handle: AppHandle) -> Result<CompanionPairing, String> {
+    companion::reset_token(&app_handle).map_err(|e| e.to_string())?;
+    companion::sync_with_settings(&app_handle);
+    companion::pairing(&app_handle).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -35,18 +156,290 @@ fn get_recording_state(manager: tauri::State<Arc<RecordingManager>>) -> String {
 }
 
 #[tauri::command]
-fn cancel_recording(manager: tauri::State<Arc<RecordingManager>>) {
-    manager.cancel();
+fn check_microphone_permission() -> PermissionStatus {
+    permissions::microphone_status()
+}
+
+/// Ask for microphone access, or open its settings page if it was denied
+#[tauri::command]
+async fn request_microphone_permission(app_handle: AppHandle) -> Result<PermissionStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || permissions::request_microphone(&app_handle))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Whether the app may simulate the paste keystroke (macOS only asks)
+#[tauri::command]
+fn check_accessibility_permission() -> PermissionStatus {
+    permissions::accessibility_status()
+}
+
+#[tauri::command]
+fn open_accessibility_settings(app_handle: AppHandle) {
+    permissions::open_accessibility_settings(&app_handle);
+}
+
+/// Permissions, devices and model, for the first-run wizard
+#[tauri::command]
+async fn get_onboarding_status(app_handle: AppHandle) -> Result<OnboardingStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || permissions::onboarding_status(&app_handle))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Throw away the current recording, resetting the tray and overlay
+#[tauri::command]
+fn cancel_recording(app_handle: AppHandle) {
+    shortcut::cancel_active_recording(&app_handle);
+}
+
+/// Give up on the transcription in progress (e.g. after a slow-transcription
+/// hint), returning whether there was one
+#[tauri::command]
+fn cancel_transcription(manager: tauri::State<Arc<RecordingManager>>) -> bool {
+    manager.cancel_transcription()
+}
+
+/// Stop the current recording and transcribe it, from a click on the overlay
+#[tauri::command]
+fn stop_recording_from_overlay(app_handle: AppHandle) {
+    shortcut::stop_active_recording(&app_handle);
+}
+
+/// Throw away the current recording, from the overlay
+#[tauri::command]
+fn cancel_recording_from_overlay(app_handle: AppHandle) {
+    shortcut::cancel_active_recording(&app_handle);
+}
+
+/// Switch between the cloud and a local model from the overlay's model
+/// badge, returning the id of the model now selected
+#[tauri::command]
+async fn toggle_cloud_model(
+    manager: tauri::State<'_, Arc<RecordingManager>>,
+) -> Result<String, String> {
+    let manager = Arc::clone(&manager);
+    tauri::async_runtime::spawn_blocking(move || manager.toggle_cloud_model())
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Names of the connected monitors, for choosing a fixed overlay monitor
+#[tauri::command]
+fn list_monitors(app_handle: AppHandle) -> Result<Vec<String>, String> {
+    let monitors = app_handle
+        .available_monitors()
+        .map_err(|e| format!("Failed to list monitors: {}", e))?;
+    Ok(monitors
+        .iter()
+        .filter_map(|monitor| monitor.name().cloned())
+        .collect())
+}
+
+/// Show the overlay for the user to drag into place
+#[tauri::command]
+fn position_overlay(app_handle: AppHandle) -> Result<(), String> {
+    overlay::start_positioning(&app_handle)
+}
+
+/// Save where the overlay was dragged and hide it
+#[tauri::command]
+fn finish_overlay_positioning(app_handle: AppHandle) -> Result<(), String> {
+    overlay::finish_positioning(&app_handle)
+}
+
+#[tauri::command]
+fn get_language_override(manager: tauri::State<Arc<RecordingManager>>) -> Option<String> {
+    manager.get_language_override()
+}
+
+#[tauri::command]
+fn set_language_override(language: Option<String>, manager: tauri::State<Arc<RecordingManager>>) {
+    manager.set_language_override(language);
+}
+
+/// Dry run of the dictionary over sample text, using `dictionary` (e.g. unsaved
+/// edits) or the saved one
+#[tauri::command]
+fn test_dictionary(
+    text: String,
+    dictionary: Option<Dictionary>,
+    app_handle: AppHandle,
+) -> DictionaryResult {
+    let dictionary = dictionary.unwrap_or_else(|| settings::get_settings(&app_handle).dictionary);
+    dictionary.process(&text, false)
+}
+
+/// Dry run of the output pipeline (dictionary, formatting, template and
+/// terminator) over sample text, returning what would be pasted for
+/// `binding_id` (default push to talk). Uses `new_settings` (e.g. unsaved
+/// edits) or the saved settings. Plugins aren't run, since previewing
+/// shouldn't set off user scripts, and per-app terminators are left out, as
+/// the focused app is the settings window itself.
+#[tauri::command]
+fn preview_paste(
+    text: String,
+    binding_id: Option<String>,
+    new_settings: Option<AppSettings>,
+    app_handle: AppHandle,
+) -> String {
+    let settings = new_settings.unwrap_or_else(|| settings::get_settings(&app_handle));
+    let binding_id = binding_id.unwrap_or_else(|| "transcribe".to_string());
+
+    let text = settings.dictionary.apply(&text, false);
+    let text = formatting::format_output(&text, &settings, &binding_id);
+    let terminator = clipboard::binding_terminator(&settings, &binding_id);
+    clipboard::paste_text(&text, &settings, &binding_id, terminator)
+}
+
+/// Export the dictionary and output templates as a vocabulary pack file
+#[tauri::command]
+fn export_vocabulary(path: String, app_handle: AppHandle) -> Result<(), String> {
+    let settings = settings::get_settings(&app_handle);
+    VocabularyPack::from_settings(&settings).write(std::path::Path::new(&path))
 }
 
+/// Import a vocabulary pack, merging it into (or with `replace`, replacing)
+/// the current dictionary and templates
 #[tauri::command]
-fn list_audio_devices() -> Result<Vec<String>, String> {
-    audio::list_input_devices().map_err(|e| e.to_string())
+fn import_vocabulary(
+    path: String,
+    replace: bool,
+    app_handle: AppHandle,
+) -> Result<ImportSummary, String> {
+    let pack = VocabularyPack::read(std::path::Path::new(&path))?;
+    let mut summary = ImportSummary::default();
+    settings::update_setting(&app_handle, |s| {
+        summary = pack.merge_into(s, replace);
+    })?;
+    Ok(summary)
 }
 
+/// Remove the last pasted transcription from the app it was pasted into
 #[tauri::command]
-fn get_available_models(model_manager: tauri::State<Arc<ModelManager>>) -> Vec<ModelInfo> {
-    model_manager.get_available_models()
+fn undo_last_paste(app_handle: AppHandle) -> Result<(), String> {
+    clipboard::undo_last_paste(&app_handle)
+}
+
+/// Input devices on every audio host, with the ids `selected_input_device`
+/// takes
+#[tauri::command]
+fn list_audio_devices() -> Result<Vec<InputDeviceInfo>, String> {
+    iv_core::audio::list_input_devices().map_err(|e| e.to_string())
+}
+
+/// Configs an input device (the default one if none is given) supports, for
+/// choosing a sample rate, channel and format override
+#[tauri::command]
+fn get_device_capabilities(device_name: Option<String>) -> Result<DeviceCapabilities, String> {
+    let device = iv_core::audio::input_device(device_name.as_deref()).map_err(|e| e.to_string())?;
+    iv_core::audio::device_capabilities(&device).map_err(|e| e.to_string())
+}
+
+/// Record a few seconds from a device (the selected one by default) while
+/// the user speaks, and save its gain and VAD threshold
+#[tauri::command]
+async fn calibrate_input_device(
+    device_name: Option<String>,
+    manager: tauri::State<'_, Arc<RecordingManager>>,
+) -> Result<DeviceProfile, String> {
+    let manager = Arc::clone(&manager);
+    tauri::async_runtime::spawn_blocking(move || manager.calibrate_device(device_name.as_deref()))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Stream a device's level (the default device's when none is given) as
+/// `mic-test-level` events until `stop_mic_test`, for a live meter
+#[tauri::command]
+async fn start_mic_test(
+    device_name: Option<String>,
+    manager: tauri::State<'_, Arc<RecordingManager>>,
+) -> Result<(), String> {
+    let manager = Arc::clone(&manager);
+    tauri::async_runtime::spawn_blocking(move || manager.start_mic_test(device_name.as_deref()))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn stop_mic_test(manager: tauri::State<Arc<RecordingManager>>) {
+    manager.stop_mic_test();
+}
+
+#[tauri::command]
+fn get_device_profiles(app_handle: AppHandle) -> HashMap<String, DeviceProfile> {
+    device_profiles::load_profiles(&app_handle)
+}
+
+#[tauri::command]
+fn delete_device_profile(app_handle: AppHandle, device_name: String) -> bool {
+    device_profiles::remove_profile(&app_handle, &device_name)
+}
+
+#[tauri::command]
+fn list_capture_targets() -> Result<Vec<CaptureTarget>, String> {
+    iv_core::app_capture::list_capture_targets().map_err(|e| e.to_string())
+}
+
+/// Save a cloud provider's API key in the OS keyring, where it's used over
+/// the settings and environment (None or empty removes it)
+#[tauri::command]
+fn set_cloud_api_key(provider: CloudProviderType, key: Option<String>) -> Result<(), String> {
+    credentials::store_in_keyring(provider, key.as_deref()).map_err(|e| e.to_string())
+}
+
+/// Where a cloud provider's API key currently comes from, if anywhere
+#[tauri::command]
+fn get_credential_source(
+    app_handle: AppHandle,
+    provider: CloudProviderType,
+) -> Option<CredentialSource> {
+    let settings = settings::get_settings(&app_handle);
+    credentials::resolve(&settings, provider).map(|(_, source)| source)
+}
+
+/// Check a self-hosted whisper server (the configured one if no URL is given)
+/// and list the models it offers
+#[tauri::command]
+async fn probe_whisper_server(
+    app_handle: AppHandle,
+    base_url: Option<String>,
+) -> Result<ServerStatus, String> {
+    let base_url = base_url
+        .or_else(|| settings::get_settings(&app_handle).cloud_base_url)
+        .filter(|url| !url.trim().is_empty())
+        .ok_or_else(|| "No whisper server URL configured".to_string())?;
+
+    iv_core::cloud_transcribe::probe_server(&base_url)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// What a cloud provider supports with the current settings (streaming, word
+/// timestamps, translation, languages), so the UI can gray out the rest
+#[tauri::command]
+async fn get_provider_capabilities(
+    manager: tauri::State<'_, Arc<RecordingManager>>,
+    provider: CloudProviderType,
+) -> Result<ProviderCapabilities, String> {
+    manager
+        .provider_capabilities(provider)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_available_models(
+    app_handle: AppHandle,
+    model_manager: tauri::State<Arc<ModelManager>>,
+) -> Vec<ModelInfo> {
+    let mut models = model_manager.get_available_models();
+    team_config::mark_recommended(&app_handle, &mut models);
+    models
 }
 
 #[tauri::command]
@@ -71,23 +464,50 @@ fn is_model_downloaded(model_id: String, model_manager: tauri::State<Arc<ModelMa
 
 #[tauri::command]
 async fn download_model(
+    app_handle: AppHandle,
     model_id: String,
     model_manager: tauri::State<'_, Arc<ModelManager>>,
 ) -> Result<(), String> {
     model_manager
         .download_model(&model_id)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    tray::refresh_tray_menu(&app_handle);
+    Ok(())
 }
 
+/// Delete a model. Its machine-wide copy is only removed with
+/// `remove_shared`, once the user has confirmed it.
 #[tauri::command]
 fn delete_model(
+    app_handle: AppHandle,
     model_id: String,
+    remove_shared: Option<bool>,
     model_manager: tauri::State<Arc<ModelManager>>,
 ) -> Result<(), String> {
     model_manager
-        .delete_model(&model_id)
-        .map_err(|e| e.to_string())
+        .delete_model(&model_id, remove_shared.unwrap_or(false))
+        .map_err(|e| e.to_string())?;
+    tray::refresh_tray_menu(&app_handle);
+    Ok(())
+}
+
+#[tauri::command]
+fn get_unused_models(
+    app_handle: AppHandle,
+    model_manager: tauri::State<Arc<ModelManager>>,
+) -> Vec<UnusedModel> {
+    let settings = settings::get_settings(&app_handle);
+    model_manager.get_unused_models(settings.model_cleanup_days, &settings.selected_model)
+}
+
+/// Remove models the user confirmed from the unused models prompt
+#[tauri::command]
+fn cleanup_unused_models(
+    model_ids: Vec<String>,
+    model_manager: tauri::State<Arc<ModelManager>>,
+) -> Vec<String> {
+    model_manager.remove_models(&model_ids)
 }
 
 #[tauri::command]
@@ -95,6 +515,218 @@ fn unload_model(manager: tauri::State<Arc<RecordingManager>>) {
     manager.unload_local_model();
 }
 
+#[tauri::command]
+fn get_history(history: tauri::State<Arc<HistoryManager>>) -> Vec<HistoryEntry> {
+    history.get_entries()
+}
+
+#[tauri::command]
+fn delete_history_entry(
+    entry_id: String,
+    history: tauri::State<Arc<HistoryManager>>,
+) -> Result<(), String> {
+    history.delete_entry(&entry_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn open_history_window(app_handle: AppHandle) -> Result<(), String> {
+    history_window::show_history_window(&app_handle)
+}
+
+#[tauri::command]
+fn get_history_page(
+    offset: usize,
+    limit: usize,
+    query: Option<String>,
+    device: Option<String>,
+    history: tauri::State<Arc<HistoryManager>>,
+) -> HistoryPage {
+    history.get_page(offset, limit, query.as_deref(), device.as_deref())
+}
+
+#[tauri::command]
+fn copy_history_entry(
+    entry_id: String,
+    app_handle: AppHandle,
+    history: tauri::State<Arc<HistoryManager>>,
+) -> Result<(), String> {
+    let entry = history
+        .get_entry(&entry_id)
+        .ok_or_else(|| format!("History entry not found: {}", entry_id))?;
+    history_window::copy_text(&app_handle, &entry.text)
+}
+
+#[tauri::command]
+async fn repaste_history_entry(
+    entry_id: String,
+    app_handle: AppHandle,
+    history: tauri::State<'_, Arc<HistoryManager>>,
+) -> Result<(), String> {
+    let entry = history
+        .get_entry(&entry_id)
+        .ok_or_else(|| format!("History entry not found: {}", entry_id))?;
+    history_window::repaste_text(&app_handle, entry.text).await
+}
+
+#[tauri::command]
+fn get_pending_review(app_handle: AppHandle) -> Option<PendingReview> {
+    review_window::pending_review(&app_handle)
+}
+
+/// Paste the reviewed (possibly edited) transcription
+#[tauri::command]
+async fn accept_review(app_handle: AppHandle, text: String) -> Result<(), String> {
+    review_window::accept(&app_handle, text).await
+}
+
+#[tauri::command]
+fn discard_review(app_handle: AppHandle) {
+    review_window::discard(&app_handle);
+}
+
+/// Save the selected entries (oldest first) as a JSON file
+#[tauri::command]
+fn export_history_entries(
+    entry_ids: Vec<String>,
+    path: String,
+    history: tauri::State<Arc<HistoryManager>>,
+) -> Result<(), String> {
+    let range = ExportRange::Entries { ids: entry_ids };
+    let entries = export::select(history.get_entries(), &range);
+    export::write(&entries, ExportFormat::Json, Path::new(&path))
+        .map_err(|e| format!("Failed to write export: {}", e))
+}
+
+/// Export history entries to a file, returning how many were written
+#[tauri::command]
+fn export_history(
+    format: ExportFormat,
+    range: ExportRange,
+    path: String,
+    history: tauri::State<Arc<HistoryManager>>,
+) -> Result<usize, String> {
+    let entries = export::select(history.get_entries(), &range);
+    if entries.is_empty() {
+        return Err("No history entries in the selected range".to_string());
+    }
+
+    export::write(&entries, format, Path::new(&path))
+        .map_err(|e| format!("Failed to write export: {}", e))?;
+    Ok(entries.len())
+}
+
+#[tauri::command]
+async fn retranscribe(
+    recording_id: String,
+    model_id: String,
+    manager: tauri::State<'_, Arc<RecordingManager>>,
+) -> Result<TranscriptionResult, String> {
+    manager
+        .retranscribe(&recording_id, &model_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Transcribe an audio or video file, with the selected model unless one is
+/// given, translating to English if asked to (or, by default, if the
+/// `translate_to_english` setting is on)
+#[tauri::command]
+async fn transcribe_file(
+    app_handle: AppHandle,
+    path: String,
+    model_id: Option<String>,
+    translate: Option<bool>,
+    manager: tauri::State<'_, Arc<RecordingManager>>,
+) -> Result<TranscriptionResult, String> {
+    let model_id = model_id.unwrap_or_else(|| manager.get_selected_model());
+    let translate =
+        translate.unwrap_or_else(|| settings::get_settings(&app_handle).translate_to_english);
+    manager
+        .transcribe_file(Path::new(&path), &model_id, translate)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Queue files for background transcription, with the selected model
+/// unless one is given. Like `transcribe_file`, `translate` defaults to the
+/// `translate_to_english` setting.
+#[tauri::command]
+fn enqueue_transcriptions(
+    paths: Vec<String>,
+    model_id: Option<String>,
+    save_transcripts: bool,
+    translate: Option<bool>,
+    app_handle: AppHandle,
+    queue: tauri::State<'_, TranscribeQueue>,
+    manager: tauri::State<'_, Arc<RecordingManager>>,
+) -> Vec<QueueJob> {
+    let model_id = model_id.unwrap_or_else(|| manager.get_selected_model());
+    let translate =
+        translate.unwrap_or_else(|| settings::get_settings(&app_handle).translate_to_english);
+    queue.enqueue(&app_handle, paths, model_id, save_transcripts, translate)
+}
+
+#[tauri::command]
+fn get_queue_jobs(queue: tauri::State<'_, TranscribeQueue>) -> Vec<QueueJob> {
+    queue.jobs()
+}
+
+#[tauri::command]
+fn pause_queue_job(
+    id: u64,
+    app_handle: AppHandle,
+    queue: tauri::State<'_, TranscribeQueue>,
+) -> Result<(), String> {
+    queue.pause(&app_handle, id)
+}
+
+#[tauri::command]
+fn resume_queue_job(
+    id: u64,
+    app_handle: AppHandle,
+    queue: tauri::State<'_, TranscribeQueue>,
+) -> Result<(), String> {
+    queue.resume(&app_handle, id)
+}
+
+#[tauri::command]
+fn cancel_queue_job(
+    id: u64,
+    app_handle: AppHandle,
+    queue: tauri::State<'_, TranscribeQueue>,
+) -> Result<(), String> {
+    queue.cancel(&app_handle, id)
+}
+
+#[tauri::command]
+fn clear_finished_queue_jobs(queue: tauri::State<'_, TranscribeQueue>) {
+    queue.clear_finished();
+}
+
+#[tauri::command]
+async fn replay_vad_session(
+    recording_id: String,
+    params: VadParams,
+    manager: tauri::State<'_, Arc<RecordingManager>>,
+) -> Result<VadAnalysis, String> {
+    manager
+        .replay_vad(&recording_id, &params)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Run the last saved recording through the current VAD settings, so they
+/// can be tuned against real audio
+#[tauri::command]
+async fn test_vad_on_last_recording(
+    manager: tauri::State<'_, Arc<RecordingManager>>,
+) -> Result<VadTest, String> {
+    manager
+        .test_vad_on_last_recording()
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 fn is_vad_enabled(manager: tauri::State<Arc<RecordingManager>>) -> bool {
     manager.is_vad_enabled()
@@ -131,6 +763,13 @@ fn play_test_stop_sound(app_handle: AppHandle) {
     audio_feedback::play_test_sound(&app_handle, audio_feedback::SoundType::Stop);
 }
 
+/// Play any feedback sound ("start", "stop", "complete", "error" or
+/// "cancelled"), e.g. to try a custom sound file
+#[tauri::command]
+fn play_test_feedback_sound(app_handle: AppHandle, sound: audio_feedback::SoundType) {
+    audio_feedback::play_test_sound(&app_handle, sound);
+}
+
 #[tauri::command]
 fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
@@ -166,18 +805,68 @@ pub fn run() {
         .setup(|app| {
             log::info!("App starting up...");
 
-            // Initialize Model Manager
-            let model_manager = Arc::new(
-                ModelManager::new(app.handle()).expect("Failed to initialize ModelManager"),
-            );
+            // Fetch the team baseline in the background. Managers below start
+            // with the cached copy; a changed endpoint applies from next launch.
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = team_config::refresh(&app_handle).await {
+                    log::warn!("{}", e);
+                }
+            });
+
+            // Initialize the event throttler before anything emits progress
+            let max_event_rate = settings::get_settings(app.handle()).max_event_rate_hz;
+            app.manage(Arc::new(EventThrottler::new(app.handle(), max_event_rate)));
+
+            app.manage(clipboard::PasteState::new());
+            app.manage(review_window::ReviewState::default());
+            app.manage(read_back::ReadBackState::default());
+            app.manage(transcribe_queue::TranscribeQueue::default());
+
+            // The model and history managers both start from disk, so they
+            // load side by side
+            let handle = app.handle();
+            let (model_manager, history_manager) = std::thread::scope(|scope| {
+                let models = scope.spawn(|| ModelManager::new(handle));
+                let history = HistoryManager::new(handle);
+                (
+                    models.join().expect("ModelManager thread panicked"),
+                    history,
+                )
+            });
+            let model_manager = Arc::new(model_manager.expect("Failed to initialize ModelManager"));
             app.manage(model_manager.clone());
+            let history_manager =
+                Arc::new(history_manager.expect("Failed to initialize HistoryManager"));
+            app.manage(history_manager.clone());
+
+            // Scan the models folder in the background, then offer to remove
+            // (or remove) models that haven't been used in a while
+            let app_handle = app.handle().clone();
+            let cleanup_manager = model_manager.clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                cleanup_manager.ensure_scanned();
+                tray::refresh_tray_menu(&app_handle);
+                models::run_cleanup_policy(&app_handle, &cleanup_manager);
+            });
 
             // Initialize Recording Manager
             let recording_manager = Arc::new(
-                RecordingManager::new(app.handle(), model_manager)
+                RecordingManager::new(app.handle(), model_manager, history_manager)
                     .expect("Failed to initialize RecordingManager"),
             );
-            app.manage(recording_manager);
+            app.manage(recording_manager.clone());
+
+            // Wake word listener (only opens the mic when enabled)
+            app.manage(Arc::new(WakeWordListener::new()));
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                wake_word::sync_with_settings(&app_handle).await;
+            });
+
+            // Phone companion endpoint (only listens when enabled)
+            app.manage(Arc::new(CompanionServer::new()));
+            companion::sync_with_settings(app.handle());
 
             // Initialize system tray
             match tray::create_tray(app.handle()) {
@@ -190,24 +879,68 @@ pub fn run() {
                 }
             }
 
-            // Create recording overlay window (hidden by default)
-            overlay::create_recording_overlay(app.handle());
+            // Warm up the local model in the background so the first dictation is fast
+            if settings::get_settings(app.handle()).preload_model_on_startup {
+                let app_handle = app.handle().clone();
+                tauri::async_runtime::spawn_blocking(move || {
+                    tray::set_model_loading(&app_handle, true);
+                    if let Err(e) = recording_manager.preload_selected_model() {
+                        log::warn!("Failed to preload model: {}", e);
+                    }
+                    tray::set_model_loading(&app_handle, false);
+                });
+            }
+
+            // Restore main window layout (window starts hidden to avoid a flash)
+            window_state::restore_main_window(app.handle());
+
+            // Create recording overlay window (hidden by default) once the
+            // main window and tray are up
+            overlay::create_recording_overlay_deferred(app.handle());
 
             // Initialize global shortcut
             if let Err(e) = shortcut::init_shortcut(app.handle()) {
                 log::error!("Failed to initialize shortcut: {}", e);
             }
 
+            // The tray's "Cancel Recording" item
+            let app_handle = app.handle().clone();
+            app.listen("cancel-recording", move |_| {
+                shortcut::cancel_active_recording(&app_handle);
+            });
+
+            power::start(app.handle(), power::default_monitors());
+
             log::info!("App setup complete.");
 
             Ok(())
         })
         .on_window_event(|window, event| {
             if window.label() == "main" {
+                match event {
+                    tauri::WindowEvent::CloseRequested { api, .. } => {
+                        api.prevent_close();
+                        let _ = window.hide();
+                        window_state::save_main_window_layout(window.app_handle());
+                        log::info!("Main window hidden instead of closed.");
+                    }
+                    tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) => {
+                        window_state::schedule_layout_save(window.app_handle());
+                    }
+                    _ => {}
+                }
+            } else if window.label() == "history" {
+                if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                    history_window::handle_close_requested(window, api);
+                }
+            } else if window.label() == "review" {
                 if let tauri::WindowEvent::CloseRequested { api, .. } = event {
-                    api.prevent_close();
-                    let _ = window.hide();
-                    log::info!("Main window hidden instead of closed.");
+                    review_window::handle_close_requested(window, api);
+                }
+            } else if window.label() == "recording_overlay" {
+                // Moved to a monitor with different DPI
+                if let tauri::WindowEvent::ScaleFactorChanged { .. } = event {
+                    overlay::refresh_overlay_geometry(window.app_handle());
                 }
             }
         })
@@ -215,27 +948,89 @@ pub fn run() {
             // Settings
             get_settings,
             save_settings,
+            get_team_config,
+            refresh_team_config,
+            validate_settings,
+            test_dictionary,
+            preview_paste,
+            export_vocabulary,
+            import_vocabulary,
             // Recording
+            set_wake_word_enabled,
+            set_companion_enabled,
+            get_companion_pairing,
+            reset_companion_token,
             greet,
             get_recording_state,
             cancel_recording,
+            check_microphone_permission,
+            request_microphone_permission,
+            check_accessibility_permission,
+            open_accessibility_settings,
+            get_onboarding_status,
+            cancel_transcription,
+            undo_last_paste,
+            get_pending_review,
+            accept_review,
+            discard_review,
+            get_language_override,
+            set_language_override,
+            list_monitors,
+            position_overlay,
+            finish_overlay_positioning,
+            stop_recording_from_overlay,
+            cancel_recording_from_overlay,
+            toggle_cloud_model,
             list_audio_devices,
+            get_device_capabilities,
+            calibrate_input_device,
+            start_mic_test,
+            stop_mic_test,
+            get_device_profiles,
+            delete_device_profile,
+            list_capture_targets,
             // Models
+            set_cloud_api_key,
+            get_credential_source,
+            probe_whisper_server,
+            get_provider_capabilities,
             get_available_models,
             get_selected_model,
             set_selected_model,
             is_model_downloaded,
             download_model,
             delete_model,
+            get_unused_models,
+            cleanup_unused_models,
             unload_model,
+            // History
+            get_history,
+            delete_history_entry,
+            open_history_window,
+            get_history_page,
+            copy_history_entry,
+            repaste_history_entry,
+            export_history_entries,
+            export_history,
+            retranscribe,
+            transcribe_file,
+            enqueue_transcriptions,
+            get_queue_jobs,
+            pause_queue_job,
+            resume_queue_job,
+            cancel_queue_job,
+            clear_finished_queue_jobs,
             // VAD
             is_vad_enabled,
             set_vad_enabled,
+            replay_vad_session,
+            test_vad_on_last_recording,
             ensure_vad_model,
             is_vad_model_downloaded,
             // Audio Feedback
             play_test_start_sound,
             play_test_stop_sound,
+            play_test_feedback_sound,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
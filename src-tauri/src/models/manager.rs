@@ -3,21 +3,31 @@
 use std::collections::HashMap;
 use std::fs::{self, File};
 use std::io::Write;
-use std::path::PathBuf;
-use std::sync::Mutex;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, Once};
 
 use anyhow::Result;
 use flate2::read::GzDecoder;
 use futures_util::StreamExt;
+use iv_core::models::{DownloadProgress, EngineType, ModelInfo};
 use tar::Archive;
 use tauri::{AppHandle, Emitter, Manager};
 
-use super::types::{DownloadProgress, EngineType, ModelInfo};
+use super::shared;
+use super::usage::{self, UnusedModel};
+use crate::event_throttle;
 
 pub struct ModelManager {
     app_handle: AppHandle,
+    /// This account's models folder, used when a model isn't in the
+    /// machine-wide one (see [`shared`])
     models_dir: PathBuf,
     available_models: Mutex<HashMap<String, ModelInfo>>,
+    /// Download status is scanned from the models folder on first use rather
+    /// than at startup, where a slow disk would hold up the launch
+    scanned: Once,
+    /// Last use of each model in milliseconds since the Unix epoch
+    last_used: Mutex<HashMap<String, u64>>,
 }
 
 impl ModelManager {
@@ -44,33 +54,60 @@ impl ModelManager {
         let parakeet_v3 = ModelInfo::parakeet_v3();
         available_models.insert(parakeet_v3.id.clone(), parakeet_v3);
 
-        let manager = Self {
+        // Optional, only used for speaker labels
+        let speaker_embedding = ModelInfo::speaker_embedding();
+        available_models.insert(speaker_embedding.id.clone(), speaker_embedding);
+
+        Ok(Self {
             app_handle: app_handle.clone(),
             models_dir,
             available_models: Mutex::new(available_models),
-        };
+            scanned: Once::new(),
+            last_used: Mutex::new(usage::load_last_used(app_handle)),
+        })
+    }
 
-        // Update download status for all models
-        manager.refresh_download_status()?;
+    /// Scan the models folder for download status, unless that's been done.
+    /// Anything needing the status before then waits for the scan.
+    pub fn ensure_scanned(&self) {
+        self.scanned.call_once(|| {
+            if let Err(e) = self.refresh_download_status() {
+                log::error!("Failed to scan the models folder: {}", e);
+            }
+        });
+    }
+
+    /// Whether the models folder has been scanned for download status
+    pub fn is_scanned(&self) -> bool {
+        self.scanned.is_completed()
+    }
 
-        Ok(manager)
+    /// The catalog, with download status
+    fn models(&self) -> MutexGuard<'_, HashMap<String, ModelInfo>> {
+        self.ensure_scanned();
+        self.available_models.lock().unwrap()
     }
 
     /// Get all available models
     pub fn get_available_models(&self) -> Vec<ModelInfo> {
-        let models = self.available_models.lock().unwrap();
+        let models = self.models();
         models.values().cloned().collect()
     }
 
     /// Get info for a specific model
     pub fn get_model_info(&self, model_id: &str) -> Option<ModelInfo> {
-        let models = self.available_models.lock().unwrap();
+        let models = self.models();
         models.get(model_id).cloned()
     }
 
+    /// Warning when the model can't transcribe the given language, if any
+    pub fn language_warning(&self, model_id: &str, language: &str) -> Option<String> {
+        self.get_model_info(model_id)?.language_warning(language)
+    }
+
     /// Get the path to a model's files
     pub fn get_model_path(&self, model_id: &str) -> Result<PathBuf> {
-        let models = self.available_models.lock().unwrap();
+        let models = self.models();
         let model = models
             .get(model_id)
             .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
@@ -79,12 +116,110 @@ impl ModelManager {
             return Err(anyhow::anyhow!("Cloud model has no local path"));
         }
 
-        Ok(self.models_dir.join(&model.filename))
+        Ok(self
+            .shared_model_path(model)
+            .unwrap_or_else(|| self.models_dir.join(&model.filename)))
+    }
+
+    /// The machine-wide copy of a model, if it's downloaded there
+    fn shared_model_path(&self, model: &ModelInfo) -> Option<PathBuf> {
+        let path = shared::shared_models_dir(&self.app_handle)?.join(&model.filename);
+        shared::is_present(&path, model.is_directory).then_some(path)
+    }
+
+    /// Folders a model's files may be in, the machine-wide one first
+    fn model_dirs(&self) -> Vec<PathBuf> {
+        shared::shared_models_dir(&self.app_handle)
+            .into_iter()
+            .chain(std::iter::once(self.models_dir.clone()))
+            .collect()
+    }
+
+    /// The folder to download into: the machine-wide one when this account
+    /// can write to it, so other accounts needn't download the model again
+    fn download_dir(&self) -> PathBuf {
+        shared::shared_models_dir(&self.app_handle)
+            .filter(|dir| shared::is_writable(dir))
+            .unwrap_or_else(|| self.models_dir.clone())
+    }
+
+    /// Record that a model was just used for a transcription
+    pub fn mark_used(&self, model_id: &str) {
+        let mut last_used = self.last_used.lock().unwrap();
+        last_used.insert(model_id.to_string(), usage::now_millis());
+        usage::save_last_used(&self.app_handle, &last_used);
+    }
+
+    /// The downloaded local transcription model used most recently
+    pub fn most_recent_local_model(&self) -> Option<ModelInfo> {
+        let last_used = self.last_used.lock().unwrap();
+        self.get_available_models()
+            .into_iter()
+            .filter(|m| m.engine_type == EngineType::Parakeet && m.is_downloaded)
+            .max_by_key(|m| last_used.get(&m.id).copied().unwrap_or(0))
+    }
+
+    /// Downloaded local models not used in at least `days` days, other than `keep_id`.
+    /// Models with no recorded use start their clock now rather than counting as unused.
+    pub fn get_unused_models(&self, days: u32, keep_id: &str) -> Vec<UnusedModel> {
+        let models = self.get_available_models();
+        let mut last_used = self.last_used.lock().unwrap();
+        let mut changed = false;
+        let mut unused = Vec::new();
+
+        for model in models {
+            // Other accounts may be using a machine-wide copy
+            if model.engine_type == EngineType::Cloud
+                || !model.is_downloaded
+                || model.is_shared
+                || model.id == keep_id
+            {
+                continue;
+            }
+
+            let timestamp = *last_used.entry(model.id.clone()).or_insert_with(|| {
+                changed = true;
+                usage::now_millis()
+            });
+
+            let days_unused = usage::days_since(timestamp);
+            if days_unused >= days as u64 {
+                unused.push(UnusedModel {
+                    model_id: model.id,
+                    name: model.name,
+                    size_mb: model.size_mb,
+                    last_used: timestamp,
+                    days_unused,
+                });
+            }
+        }
+
+        if changed {
+            usage::save_last_used(&self.app_handle, &last_used);
+        }
+
+        unused
+    }
+
+    /// Delete this account's copies of several models, returning the IDs
+    /// that were removed
+    pub fn remove_models(&self, model_ids: &[String]) -> Vec<String> {
+        model_ids
+            .iter()
+            .filter(|id| match self.delete_model(id, false) {
+                Ok(()) => true,
+                Err(e) => {
+                    log::warn!("Failed to remove model '{}': {}", id, e);
+                    false
+                }
+            })
+            .cloned()
+            .collect()
     }
 
     /// Check if a model is downloaded
     pub fn is_model_downloaded(&self, model_id: &str) -> bool {
-        let models = self.available_models.lock().unwrap();
+        let models = self.models();
         models
             .get(model_id)
             .map(|m| m.is_downloaded)
@@ -93,6 +228,8 @@ impl ModelManager {
 
     /// Refresh the download status of all models
     pub fn refresh_download_status(&self) -> Result<()> {
+        let shared_dir = shared::shared_models_dir(&self.app_handle);
+        let dirs = self.model_dirs();
         let mut models = self.available_models.lock().unwrap();
 
         for model in models.values_mut() {
@@ -101,13 +238,17 @@ impl ModelManager {
                 continue;
             }
 
-            let model_path = self.models_dir.join(&model.filename);
+            // Directory-based models (Parakeet) or single files (Whisper)
+            model.is_shared = shared_dir.as_ref().is_some_and(|dir| {
+                shared::is_present(&dir.join(&model.filename), model.is_directory)
+            });
+            model.is_downloaded = dirs
+                .iter()
+                .any(|dir| shared::is_present(&dir.join(&model.filename), model.is_directory));
 
             if model.is_directory {
-                // Directory-based models (Parakeet)
-                model.is_downloaded = model_path.exists() && model_path.is_dir();
-
-                // Clean up interrupted extractions
+                // Clean up interrupted extractions. One in the machine-wide
+                // folder may be another account's still running.
                 let extracting_path = self
                     .models_dir
                     .join(format!("{}.extracting", &model.filename));
@@ -115,18 +256,15 @@ impl ModelManager {
                     log::warn!("Cleaning up interrupted extraction: {:?}", extracting_path);
                     let _ = fs::remove_dir_all(&extracting_path);
                 }
-            } else {
-                // Single file models (Whisper)
-                model.is_downloaded = model_path.exists() && model_path.is_file();
             }
 
-            // Check for partial downloads
-            let partial_path = self.models_dir.join(format!("{}.partial", &model.filename));
-            if partial_path.exists() {
-                model.partial_size = partial_path.metadata().map(|m| m.len()).unwrap_or(0);
-            } else {
-                model.partial_size = 0;
-            }
+            // Check for partial downloads, in whichever folder it was going to
+            model.partial_size = dirs
+                .iter()
+                .map(|dir| dir.join(format!("{}.partial", &model.filename)))
+                .find(|path| path.exists())
+                .map(|path| path.metadata().map(|m| m.len()).unwrap_or(0))
+                .unwrap_or(0);
 
             model.is_downloading = false;
 
@@ -145,7 +283,7 @@ impl ModelManager {
     pub async fn download_model(&self, model_id: &str) -> Result<()> {
         // Get model info
         let model = {
-            let models = self.available_models.lock().unwrap();
+            let models = self.models();
             models
                 .get(model_id)
                 .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?
@@ -163,7 +301,7 @@ impl ModelManager {
 
         // Mark as downloading
         {
-            let mut models = self.available_models.lock().unwrap();
+            let mut models = self.models();
             if let Some(m) = models.get_mut(model_id) {
                 m.is_downloading = true;
             }
@@ -181,7 +319,7 @@ impl ModelManager {
 
         // Mark as not downloading
         {
-            let mut models = self.available_models.lock().unwrap();
+            let mut models = self.models();
             if let Some(m) = models.get_mut(model_id) {
                 m.is_downloading = false;
             }
@@ -217,19 +355,19 @@ impl ModelManager {
     async fn do_download(&self, model: &ModelInfo, url: &str) -> Result<()> {
         let client = reqwest::Client::new();
 
+        let dir = self.download_dir();
+        log::info!("Downloading '{}' into {:?}", model.id, dir);
+
         // Determine paths
         let partial_path = if model.is_directory {
-            self.models_dir
-                .join(format!("{}.partial.tar.gz", &model.filename))
+            dir.join(format!("{}.partial.tar.gz", &model.filename))
         } else {
-            self.models_dir.join(format!("{}.partial", &model.filename))
+            dir.join(format!("{}.partial", &model.filename))
         };
 
         // Clean up any failed extraction attempts
         if model.is_directory {
-            let extracting_path = self
-                .models_dir
-                .join(format!("{}.extracting", &model.filename));
+            let extracting_path = dir.join(format!("{}.extracting", &model.filename));
             if extracting_path.exists() {
                 log::warn!("Cleaning up interrupted extraction: {:?}", extracting_path);
                 let _ = fs::remove_dir_all(&extracting_path);
@@ -309,11 +447,14 @@ impl ModelManager {
                     file.write_all(&chunk)?;
                     downloaded += chunk.len() as u64;
 
-                    // Emit progress every ~100KB
-                    if downloaded % (100 * 1024) < chunk.len() as u64 {
-                        let progress = DownloadProgress::new(&model.id, downloaded, total_size);
-                        let _ = self.app_handle.emit("model-download-progress", &progress);
-                    }
+                    // Progress is coalesced by the event throttler
+                    let progress = DownloadProgress::new(&model.id, downloaded, total_size);
+                    event_throttle::emit_keyed(
+                        &self.app_handle,
+                        "model-download-progress",
+                        &model.id,
+                        &progress,
+                    );
                 }
 
                 // Ensure all data is written
@@ -322,30 +463,40 @@ impl ModelManager {
 
                 // Emit final progress
                 let progress = DownloadProgress::new(&model.id, downloaded, total_size);
-                let _ = self.app_handle.emit("model-download-progress", &progress);
+                event_throttle::emit_final(
+                    &self.app_handle,
+                    "model-download-progress",
+                    &model.id,
+                    &progress,
+                );
             }
         }
 
         // Handle directory models (extract tar.gz)
         if model.is_directory {
-            self.extract_model(&partial_path, &model.filename)?;
+            self.extract_model(&dir, &partial_path, &model.filename)?;
             // Remove the archive
             let _ = fs::remove_file(&partial_path);
         } else {
             // Rename partial to final
-            let final_path = self.models_dir.join(&model.filename);
+            let final_path = dir.join(&model.filename);
             fs::rename(&partial_path, &final_path)?;
         }
 
         Ok(())
     }
 
-    /// Extract a tar.gz archive to a model directory
-    fn extract_model(&self, archive_path: &PathBuf, dir_name: &str) -> Result<()> {
+    /// Extract a tar.gz archive to a model directory in `models_dir`
+    fn extract_model(
+        &self,
+        models_dir: &Path,
+        archive_path: &PathBuf,
+        dir_name: &str,
+    ) -> Result<()> {
         log::info!("Extracting model archive to '{}'", dir_name);
 
         // Extract to a temp directory first
-        let extracting_path = self.models_dir.join(format!("{}.extracting", dir_name));
+        let extracting_path = models_dir.join(format!("{}.extracting", dir_name));
         if extracting_path.exists() {
             fs::remove_dir_all(&extracting_path)?;
         }
@@ -360,7 +511,7 @@ impl ModelManager {
         archive.unpack(&extracting_path)?;
 
         // Find the actual model directory inside (might be nested)
-        let final_path = self.models_dir.join(dir_name);
+        let final_path = models_dir.join(dir_name);
         if final_path.exists() {
             fs::remove_dir_all(&final_path)?;
         }
@@ -379,10 +530,11 @@ impl ModelManager {
         Ok(())
     }
 
-    /// Delete a downloaded model
-    pub fn delete_model(&self, model_id: &str) -> Result<()> {
+    /// Delete a downloaded model. The machine-wide copy, which other accounts
+    /// may be using, is only removed with `remove_shared`.
+    pub fn delete_model(&self, model_id: &str, remove_shared: bool) -> Result<()> {
         let model = {
-            let models = self.available_models.lock().unwrap();
+            let models = self.models();
             models
                 .get(model_id)
                 .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?
@@ -393,28 +545,35 @@ impl ModelManager {
             return Err(anyhow::anyhow!("Cannot delete cloud model"));
         }
 
-        let model_path = self.models_dir.join(&model.filename);
-
-        if model_path.exists() {
-            if model.is_directory {
-                fs::remove_dir_all(&model_path)?;
-            } else {
-                fs::remove_file(&model_path)?;
+        // This account's copy, and the machine-wide one if asked to and this
+        // account is allowed to remove it
+        let mut result = Ok(());
+        for dir in self.model_dirs() {
+            let is_own = dir == self.models_dir;
+            if !is_own && !remove_shared {
+                continue;
             }
-            log::info!("Deleted model '{}'", model_id);
-        }
 
-        // Also clean up any partial files
-        let partial_path = self.models_dir.join(format!("{}.partial", &model.filename));
-        let _ = fs::remove_file(&partial_path);
+            let model_path = dir.join(&model.filename);
+            if model_path.exists() {
+                let removed = if is_own {
+                    shared::remove(&model_path, model.is_directory).map_err(anyhow::Error::from)
+                } else {
+                    shared::remove_shared(&model_path, model.is_directory)
+                };
+                match removed {
+                    Ok(()) => log::info!("Deleted model '{}' from {:?}", model_id, dir),
+                    Err(e) => result = Err(e),
+                }
+            }
 
-        let partial_tar_path = self
-            .models_dir
-            .join(format!("{}.partial.tar.gz", &model.filename));
-        let _ = fs::remove_file(&partial_tar_path);
+            // Also clean up any partial files
+            let _ = fs::remove_file(dir.join(format!("{}.partial", &model.filename)));
+            let _ = fs::remove_file(dir.join(format!("{}.partial.tar.gz", &model.filename)));
+        }
 
         self.refresh_download_status()?;
 
-        Ok(())
+        result
     }
 }
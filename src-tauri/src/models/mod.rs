@@ -1,7 +1,9 @@
 //! Model management module
 
 mod manager;
-mod types;
+mod shared;
+mod usage;
 
+pub use iv_core::models::{EngineType, ModelInfo};
 pub use manager::ModelManager;
-pub use types::{EngineType, ModelInfo};
+pub use usage::{run_cleanup_policy, UnusedModel};
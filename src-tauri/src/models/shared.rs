@@ -0,0 +1,96 @@
+//! Machine-wide models folder
+//!
+//! On a shared workstation every account would otherwise download its own
+//! copy of each model. Models found in a machine-wide folder (ProgramData on
+//! Windows, /Library/Application Support on macOS, /usr/share elsewhere) are
+//! used from there by everyone, and downloads go there too when the account
+//! can write to it, falling back to the per-user models folder when it can't.
+
+use std::fs::{self, File};
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use tauri::AppHandle;
+
+use crate::settings;
+
+/// The platform's machine-wide application data folder
+fn platform_data_dir() -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        std::env::var_os("ProgramData")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(r"C:\ProgramData"))
+    }
+    #[cfg(target_os = "macos")]
+    {
+        PathBuf::from("/Library/Application Support")
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        PathBuf::from("/usr/share")
+    }
+}
+
+/// The machine-wide models folder, unless turned off in settings
+pub(super) fn shared_models_dir(app: &AppHandle) -> Option<PathBuf> {
+    let settings = settings::get_settings(app);
+    if !settings.shared_models {
+        return None;
+    }
+    let custom = settings
+        .shared_models_dir
+        .filter(|dir| !dir.trim().is_empty());
+    Some(match custom {
+        Some(dir) => PathBuf::from(dir),
+        None => platform_data_dir()
+            .join(&app.config().identifier)
+            .join("models"),
+    })
+}
+
+/// Whether this account can create files in `dir`, creating it if needed.
+/// Checked by writing a file, since mode bits don't account for ACLs or
+/// read-only mounts.
+pub(super) fn is_writable(dir: &Path) -> bool {
+    if fs::create_dir_all(dir).is_err() {
+        return false;
+    }
+    let probe = dir.join(format!(".write-test-{}", std::process::id()));
+    match File::create(&probe) {
+        Ok(_) => {
+            let _ = fs::remove_file(&probe);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Whether a model's file or folder is at `path`
+pub(super) fn is_present(path: &Path, is_directory: bool) -> bool {
+    if is_directory {
+        path.is_dir()
+    } else {
+        path.is_file()
+    }
+}
+
+/// Remove a model's file or folder
+pub(super) fn remove(path: &Path, is_directory: bool) -> io::Result<()> {
+    if is_directory {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+/// Remove a model from the machine-wide folder, explaining a permission error
+pub(super) fn remove_shared(path: &Path, is_directory: bool) -> anyhow::Result<()> {
+    match remove(path, is_directory) {
+        Err(e) if e.kind() == ErrorKind::PermissionDenied => Err(anyhow::anyhow!(
+            "{:?} is shared by all users and needs an administrator to remove",
+            path
+        )),
+        other => Ok(other?),
+    }
+}
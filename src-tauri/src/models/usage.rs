@@ -0,0 +1,100 @@
+//! Model usage tracking
+//!
+//! Records when each local model was last used for a transcription so models
+//! that haven't been needed in a while can be offered for removal (or removed
+//! automatically) to reclaim disk space.
+
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_store::StoreExt;
+
+use super::ModelManager;
+use crate::settings::{self, ModelCleanupPolicy, SETTINGS_STORE_PATH};
+
+const MODEL_LAST_USED_KEY: &str = "model_last_used";
+
+const MILLIS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+/// A downloaded model that hasn't been used within the cleanup window
+#[derive(Serialize, Debug, Clone)]
+pub struct UnusedModel {
+    pub model_id: String,
+    pub name: String,
+    pub size_mb: u64,
+    /// Last use in milliseconds since the Unix epoch
+    pub last_used: u64,
+    pub days_unused: u64,
+}
+
+pub(super) fn load_last_used(app: &AppHandle) -> HashMap<String, u64> {
+    let Ok(store) = app.store(SETTINGS_STORE_PATH) else {
+        return HashMap::new();
+    };
+
+    store
+        .get(MODEL_LAST_USED_KEY)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+pub(super) fn save_last_used(app: &AppHandle, last_used: &HashMap<String, u64>) {
+    let store = match app.store(SETTINGS_STORE_PATH) {
+        Ok(s) => s,
+        Err(e) => {
+            log::warn!("Failed to get settings store: {}", e);
+            return;
+        }
+    };
+
+    match serde_json::to_value(last_used) {
+        Ok(value) => {
+            store.set(MODEL_LAST_USED_KEY, value);
+            if let Err(e) = store.save() {
+                log::warn!("Failed to save model usage: {}", e);
+            }
+        }
+        Err(e) => log::warn!("Failed to serialize model usage: {}", e),
+    }
+}
+
+pub(super) fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+pub(super) fn days_since(timestamp: u64) -> u64 {
+    now_millis().saturating_sub(timestamp) / MILLIS_PER_DAY
+}
+
+/// Apply the configured cleanup policy at startup: either tell the frontend
+/// which models could be removed, or remove them right away
+pub fn run_cleanup_policy(app: &AppHandle, model_manager: &ModelManager) {
+    let settings = settings::get_settings(app);
+    if settings.model_cleanup == ModelCleanupPolicy::Off {
+        return;
+    }
+
+    let unused =
+        model_manager.get_unused_models(settings.model_cleanup_days, &settings.selected_model);
+    if unused.is_empty() {
+        return;
+    }
+
+    match settings.model_cleanup {
+        ModelCleanupPolicy::Off => {}
+        ModelCleanupPolicy::Prompt => {
+            log::info!("{} model(s) unused for a while", unused.len());
+            let _ = app.emit("unused-models", &unused);
+        }
+        ModelCleanupPolicy::Automatic => {
+            let ids: Vec<String> = unused.into_iter().map(|m| m.model_id).collect();
+            let removed = model_manager.remove_models(&ids);
+            let _ = app.emit("models-cleaned-up", &removed);
+        }
+    }
+}
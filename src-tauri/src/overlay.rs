@@ -1,27 +1,59 @@
 //! Recording overlay window management
 
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
 use crate::input;
-use crate::settings::{self, OverlayPosition};
+use crate::recording_manager::TranscriptionError;
+use crate::settings::{self, AppSettings, OverlayCoordinates, OverlayMonitor, OverlayPosition};
+use iv_core::cloud_transcribe::CloudError;
 use log::debug;
-use tauri::{AppHandle, Emitter, Manager, PhysicalPosition, PhysicalSize};
+use tauri::{AppHandle, Emitter, Manager, PhysicalPosition, PhysicalSize, WebviewWindow};
 
 #[cfg(not(target_os = "macos"))]
 use tauri::WebviewWindowBuilder;
 
-#[cfg(target_os = "windows")]
-use tauri::WebviewWindow;
-
 #[cfg(target_os = "macos")]
 use tauri::WebviewUrl;
 
 #[cfg(target_os = "macos")]
 use tauri_nspanel::{tauri_panel, CollectionBehavior, PanelBuilder, PanelLevel, StyleMask};
 
+/// Overlay dimensions in logical pixels at 100% size
 const OVERLAY_WIDTH: f64 = 180.0;
 const OVERLAY_HEIGHT: f64 = 48.0;
 const OVERLAY_TOP_OFFSET: f64 = 20.0;
 const OVERLAY_BOTTOM_OFFSET: f64 = 0.0;
 
+/// Range allowed for the user's overlay size preference
+const MIN_OVERLAY_SCALE: f64 = 0.5;
+const MAX_OVERLAY_SCALE: f64 = 2.0;
+
+/// Bumped whenever the overlay is shown or hidden, so a pending linger timer
+/// can tell whether it's still responsible for hiding the overlay
+static OVERLAY_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Wait after startup before creating the overlay, as building its webview
+/// holds up the launch on slow machines
+const OVERLAY_CREATE_DELAY: Duration = Duration::from_millis(1500);
+
+/// How long an error stays on the overlay before it hides
+const ERROR_DISPLAY_MS: u64 = 2500;
+
+/// How long a visual feedback flash lasts
+const FLASH_MS: u64 = 400;
+
+/// When the current flash ends, so hiding the overlay can wait for it
+static FLASH_UNTIL: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// How long the overlay can be dragged around before its position is saved
+/// without the user clicking Done
+const POSITIONING_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Set while the overlay is shown for the user to drag into place
+static POSITIONING: AtomicBool = AtomicBool::new(false);
+
 /// Overlay states
 #[derive(Clone, Debug, serde::Serialize)]
 #[serde(rename_all = "lowercase")]
@@ -29,6 +61,59 @@ pub enum OverlayState {
     Hidden,
     Recording,
     Transcribing,
+    /// Transcription finished, shown while the overlay lingers
+    Done,
+    /// Transcription failed, shown briefly with the reason
+    Error {
+        code: OverlayErrorCode,
+    },
+    /// Shown on its own, for the user to drag into place
+    Positioning,
+}
+
+/// Why a transcription failed, as shown on the overlay
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayErrorCode {
+    NoApiKey,
+    ModelMissing,
+    Network,
+    NoSpeech,
+    /// A voice command didn't match any configured phrase
+    UnknownCommand,
+    /// An edit command was said with no pasted text to apply it to
+    NothingToEdit,
+    /// The recording's device was disconnected with none to fall back to
+    DeviceLost,
+    /// The system went to sleep mid-recording
+    Interrupted,
+    Unknown,
+}
+
+impl OverlayErrorCode {
+    /// Classify a transcription error by the typed errors in its chain
+    pub fn from_error(error: &anyhow::Error) -> Self {
+        for cause in error.chain() {
+            if let Some(error) = cause.downcast_ref::<TranscriptionError>() {
+                return match error {
+                    TranscriptionError::ModelMissing(_) => Self::ModelMissing,
+                    TranscriptionError::NoSpeech(_) => Self::NoSpeech,
+                };
+            }
+            if let Some(error) = cause.downcast_ref::<CloudError>() {
+                return match error {
+                    CloudError::NotConfigured(_) | CloudError::Unauthorized(_) => Self::NoApiKey,
+                    CloudError::Unreachable(_) => Self::Network,
+                };
+            }
+            if let Some(error) = cause.downcast_ref::<reqwest::Error>() {
+                if error.is_connect() || error.is_timeout() {
+                    return Self::Network;
+                }
+            }
+        }
+        Self::Unknown
+    }
 }
 
 #[cfg(target_os = "macos")]
@@ -42,35 +127,36 @@ tauri_panel! {
     })
 }
 
-/// Get the monitor that contains the mouse cursor
-fn get_monitor_with_cursor(app_handle: &AppHandle) -> Option<tauri::Monitor> {
-    if let Some(mouse_location) = input::get_cursor_position(app_handle) {
-        debug!(
-            "Mouse cursor position: ({}, {})",
-            mouse_location.0, mouse_location.1
-        );
-        if let Ok(monitors) = app_handle.available_monitors() {
-            for monitor in monitors {
-                let pos = monitor.position();
-                let size = monitor.size();
-                let monitor_name = monitor
-                    .name()
-                    .map(|s| s.as_str())
-                    .unwrap_or("unknown");
-                debug!(
-                    "Checking monitor '{}': pos=({}, {}), size={}x{}",
-                    monitor_name,
-                    pos.x,
-                    pos.y,
-                    size.width,
-                    size.height
-                );
-                if is_mouse_within_monitor(mouse_location, monitor.position(), monitor.size()) {
-                    debug!("Mouse is within monitor '{}'", monitor_name);
-                    return Some(monitor);
-                }
+/// Get the monitor the overlay should appear on, as chosen in settings
+fn get_target_monitor(app_handle: &AppHandle, settings: &AppSettings) -> Option<tauri::Monitor> {
+    let monitor = match settings.overlay_monitor {
+        OverlayMonitor::Cursor => input::get_cursor_position(app_handle).and_then(|location| {
+            debug!("Mouse cursor position: ({}, {})", location.0, location.1);
+            get_monitor_containing(app_handle, location)
+        }),
+        // The cursor can be anywhere while typing, so it's only a fallback
+        OverlayMonitor::FocusedWindow => input::get_focused_window_center()
+            .and_then(|center| {
+                debug!("Focused window center: ({}, {})", center.0, center.1);
+                get_monitor_containing(app_handle, center)
+            })
+            .or_else(|| {
+                let location = input::get_cursor_position(app_handle)?;
+                get_monitor_containing(app_handle, location)
+            }),
+        OverlayMonitor::Fixed => settings.overlay_fixed_monitor.as_ref().and_then(|name| {
+            let monitors = app_handle.available_monitors().ok()?;
+            let monitor = monitors
+                .into_iter()
+                .find(|monitor| monitor.name() == Some(name));
+            if monitor.is_none() {
+                debug!("Monitor '{}' not connected", name);
             }
-        }
+            monitor
+        }),
+    };
+    if monitor.is_some() {
+        return monitor;
     }
 
     // Fallback to primary monitor
@@ -78,12 +164,31 @@ fn get_monitor_with_cursor(app_handle: &AppHandle) -> Option<tauri::Monitor> {
     app_handle.primary_monitor().ok().flatten()
 }
 
-fn is_mouse_within_monitor(
-    mouse_pos: (i32, i32),
+/// Get the monitor that contains a point in screen coordinates
+fn get_monitor_containing(app_handle: &AppHandle, point: (i32, i32)) -> Option<tauri::Monitor> {
+    let monitors = app_handle.available_monitors().ok()?;
+    for monitor in monitors {
+        let pos = monitor.position();
+        let size = monitor.size();
+        let monitor_name = monitor.name().map(|s| s.as_str()).unwrap_or("unknown");
+        debug!(
+            "Checking monitor '{}': pos=({}, {}), size={}x{}",
+            monitor_name, pos.x, pos.y, size.width, size.height
+        );
+        if is_point_within_monitor(point, monitor.position(), monitor.size()) {
+            debug!("Point is within monitor '{}'", monitor_name);
+            return Some(monitor);
+        }
+    }
+    None
+}
+
+fn is_point_within_monitor(
+    point: (i32, i32),
     monitor_pos: &PhysicalPosition<i32>,
     monitor_size: &PhysicalSize<u32>,
 ) -> bool {
-    let (mouse_x, mouse_y) = mouse_pos;
+    let (point_x, point_y) = point;
     let PhysicalPosition {
         x: monitor_x,
         y: monitor_y,
@@ -93,17 +198,47 @@ fn is_mouse_within_monitor(
         height: monitor_height,
     } = *monitor_size;
 
-    mouse_x >= monitor_x
-        && mouse_x < (monitor_x + monitor_width as i32)
-        && mouse_y >= monitor_y
-        && mouse_y < (monitor_y + monitor_height as i32)
+    point_x >= monitor_x
+        && point_x < (monitor_x + monitor_width as i32)
+        && point_y >= monitor_y
+        && point_y < (monitor_y + monitor_height as i32)
 }
 
-/// Calculate the overlay position based on settings and monitor
-fn calculate_overlay_position(app_handle: &AppHandle) -> Option<(f64, f64)> {
-    let monitor = get_monitor_with_cursor(app_handle)?;
+/// Identifies a monitor in `overlay_custom_positions`
+fn monitor_key(monitor: &tauri::Monitor) -> String {
+    monitor
+        .name()
+        .cloned()
+        .unwrap_or_else(|| "unknown".to_string())
+}
 
+/// Where and how large the overlay is on its target monitor, in physical pixels
+struct OverlayGeometry {
+    position: PhysicalPosition<i32>,
+    size: PhysicalSize<u32>,
+    /// The monitor's scale factor
+    scale_factor: f64,
+    /// The user's size preference, applied to the overlay's content as zoom
+    zoom: f64,
+}
+
+impl OverlayGeometry {
+    /// Position and size in logical pixels, for window builders
+    fn logical(&self) -> (f64, f64, f64, f64) {
+        (
+            self.position.x as f64 / self.scale_factor,
+            self.position.y as f64 / self.scale_factor,
+            self.size.width as f64 / self.scale_factor,
+            self.size.height as f64 / self.scale_factor,
+        )
+    }
+}
+
+/// Calculate the overlay geometry based on settings and the target monitor,
+/// scaled by that monitor's DPI and the overlay size preference
+fn calculate_overlay_geometry(app_handle: &AppHandle) -> Option<OverlayGeometry> {
     let settings = settings::get_settings(app_handle);
+    let monitor = get_target_monitor(app_handle, &settings)?;
 
     // Don't show overlay if position is None
     if settings.overlay_position == OverlayPosition::None {
@@ -111,27 +246,78 @@ fn calculate_overlay_position(app_handle: &AppHandle) -> Option<(f64, f64)> {
     }
 
     let work_area = monitor.work_area();
-    let scale = monitor.scale_factor();
-    let work_area_width = work_area.size.width as f64 / scale;
-    let work_area_height = work_area.size.height as f64 / scale;
-    let work_area_x = work_area.position.x as f64 / scale;
-    let work_area_y = work_area.position.y as f64 / scale;
-
-    let x = work_area_x + (work_area_width - OVERLAY_WIDTH) / 2.0;
-    let y = match settings.overlay_position {
-        OverlayPosition::Top => work_area_y + OVERLAY_TOP_OFFSET,
-        OverlayPosition::Bottom | OverlayPosition::None => {
-            work_area_y + work_area_height - OVERLAY_HEIGHT - OVERLAY_BOTTOM_OFFSET
-        }
+    let scale_factor = monitor.scale_factor();
+    let zoom = settings
+        .overlay_scale
+        .clamp(MIN_OVERLAY_SCALE, MAX_OVERLAY_SCALE);
+    let scale = scale_factor * zoom;
+
+    let width = (OVERLAY_WIDTH * scale).round();
+    let height = (OVERLAY_HEIGHT * scale).round();
+    let work_area_x = work_area.position.x as f64;
+    let work_area_y = work_area.position.y as f64;
+
+    let centered_x = work_area_x + (work_area.size.width as f64 - width) / 2.0;
+    let bottom_y =
+        work_area_y + work_area.size.height as f64 - height - OVERLAY_BOTTOM_OFFSET * scale_factor;
+    let (x, y) = match settings.overlay_position {
+        OverlayPosition::Top => (centered_x, work_area_y + OVERLAY_TOP_OFFSET * scale_factor),
+        OverlayPosition::Custom => settings
+            .overlay_custom_positions
+            .get(&monitor_key(&monitor))
+            .map(|coordinates| {
+                // Kept on screen, in case the work area shrank since
+                let max_x = work_area_x + (work_area.size.width as f64 - width).max(0.0);
+                let max_y = work_area_y + (work_area.size.height as f64 - height).max(0.0);
+                (
+                    (work_area_x + coordinates.x * scale_factor).clamp(work_area_x, max_x),
+                    (work_area_y + coordinates.y * scale_factor).clamp(work_area_y, max_y),
+                )
+            })
+            .unwrap_or((centered_x, bottom_y)),
+        OverlayPosition::Bottom | OverlayPosition::None => (centered_x, bottom_y),
     };
 
-    Some((x, y))
+    Some(OverlayGeometry {
+        position: PhysicalPosition::new(x.round() as i32, y.round() as i32),
+        size: PhysicalSize::new(width as u32, height as u32),
+        scale_factor,
+        zoom,
+    })
+}
+
+/// Move and resize the overlay for the monitor it's about to appear on
+fn apply_overlay_geometry(app_handle: &AppHandle, overlay: &WebviewWindow) {
+    let Some(geometry) = calculate_overlay_geometry(app_handle) else {
+        return;
+    };
+
+    let _ = overlay.set_size(geometry.size);
+    let _ = overlay.set_position(geometry.position);
+    if let Err(e) = overlay.set_zoom(geometry.zoom) {
+        debug!("Failed to set overlay zoom: {}", e);
+    }
+}
+
+/// Recompute the overlay geometry, e.g. after its monitor's scale factor
+/// changed or the size preference was edited
+pub fn refresh_overlay_geometry(app_handle: &AppHandle) {
+    if let Some(overlay) = app_handle.get_webview_window("recording_overlay") {
+        apply_overlay_geometry(app_handle, &overlay);
+    }
+}
+
+/// Initial logical position and size for the overlay window
+fn initial_overlay_bounds(app_handle: &AppHandle) -> (f64, f64, f64, f64) {
+    calculate_overlay_geometry(app_handle)
+        .map(|g| g.logical())
+        .unwrap_or((100.0, 100.0, OVERLAY_WIDTH, OVERLAY_HEIGHT))
 }
 
 /// Create the recording overlay window (hidden by default) - Windows/Linux
 #[cfg(not(target_os = "macos"))]
 pub fn create_recording_overlay(app_handle: &AppHandle) {
-    let (x, y) = calculate_overlay_position(app_handle).unwrap_or((100.0, 100.0));
+    let (x, y, width, height) = initial_overlay_bounds(app_handle);
 
     match WebviewWindowBuilder::new(
         app_handle,
@@ -141,13 +327,15 @@ pub fn create_recording_overlay(app_handle: &AppHandle) {
     .title("Recording")
     .position(x, y)
     .resizable(false)
-    .inner_size(OVERLAY_WIDTH, OVERLAY_HEIGHT)
+    .inner_size(width, height)
     .shadow(false)
     .transparent(true)
     .maximizable(false)
     .minimizable(false)
     .closable(false)
     .accept_first_mouse(true)
+    // Clicking it mustn't take focus from the app the transcript goes to
+    .focusable(false)
     .decorations(false)
     .always_on_top(true)
     .skip_taskbar(true)
@@ -173,16 +361,13 @@ pub fn create_recording_overlay(app_handle: &AppHandle) {
 /// Uses NSPanel with proper CollectionBehavior to appear on all spaces and desktops
 #[cfg(target_os = "macos")]
 pub fn create_recording_overlay(app_handle: &AppHandle) {
-    let (x, y) = calculate_overlay_position(app_handle).unwrap_or((100.0, 100.0));
+    let (x, y, width, height) = initial_overlay_bounds(app_handle);
 
     match PanelBuilder::<_, RecordingOverlayPanel>::new(app_handle, "recording_overlay")
         .url(WebviewUrl::App("src/overlay/index.html".into()))
         .title("Recording")
         .position(tauri::Position::Logical(tauri::LogicalPosition { x, y }))
-        .size(tauri::Size::Logical(tauri::LogicalSize {
-            width: OVERLAY_WIDTH,
-            height: OVERLAY_HEIGHT,
-        }))
+        .size(tauri::Size::Logical(tauri::LogicalSize { width, height }))
         .level(PanelLevel::Status)
         .has_shadow(false)
         .transparent(true)
@@ -194,7 +379,7 @@ pub fn create_recording_overlay(app_handle: &AppHandle) {
             CollectionBehavior::new()
                 .can_join_all_spaces()
                 .full_screen_auxiliary()
-                .stationary()
+                .stationary(),
         )
         .no_activate(true)
         .with_window(|w| w.decorations(false).transparent(true))
@@ -210,6 +395,24 @@ pub fn create_recording_overlay(app_handle: &AppHandle) {
     }
 }
 
+/// Create the recording overlay shortly after startup, unless it's been
+/// shown (and so created) by then
+pub fn create_recording_overlay_deferred(app_handle: &AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(OVERLAY_CREATE_DELAY).await;
+        let handle = app_handle.clone();
+        let result = app_handle.run_on_main_thread(move || {
+            if handle.get_webview_window("recording_overlay").is_none() {
+                create_recording_overlay(&handle);
+            }
+        });
+        if let Err(e) = result {
+            log::error!("Failed to create recording overlay window: {}", e);
+        }
+    });
+}
+
 /// Force overlay to be topmost on Windows
 /// Uses raw Win32 API to ensure the window stays above all others
 #[cfg(target_os = "windows")]
@@ -220,6 +423,13 @@ fn force_overlay_topmost(overlay_window: &WebviewWindow) {
 
 /// Show the overlay with a specific state
 pub fn show_overlay(app_handle: &AppHandle, state: OverlayState) {
+    // A recording starting ends positioning, keeping where it was dragged
+    if POSITIONING.swap(false, Ordering::SeqCst) {
+        if let Err(e) = save_overlay_position(app_handle) {
+            log::warn!("{}", e);
+        }
+    }
+
     let settings = settings::get_settings(app_handle);
 
     // Don't show if overlay is disabled
@@ -242,10 +452,10 @@ pub fn show_overlay(app_handle: &AppHandle, state: OverlayState) {
         }
     };
 
-    // Update position in case monitor changed
-    if let Some((x, y)) = calculate_overlay_position(app_handle) {
-        let _ = overlay.set_position(tauri::Position::Logical(tauri::LogicalPosition::new(x, y)));
-    }
+    // Update position and size in case the monitor changed
+    apply_overlay_geometry(app_handle, &overlay);
+
+    OVERLAY_GENERATION.fetch_add(1, Ordering::SeqCst);
 
     // Emit state change to frontend
     let _ = app_handle.emit("overlay-state-change", &state);
@@ -259,8 +469,26 @@ pub fn show_overlay(app_handle: &AppHandle, state: OverlayState) {
     debug!("Overlay shown with state: {:?}", state);
 }
 
-/// Hide the overlay
+/// Hide the overlay, once any flash on it has finished
 pub fn hide_overlay(app_handle: &AppHandle) {
+    let flash_left = FLASH_UNTIL
+        .lock()
+        .unwrap()
+        .and_then(|until| until.checked_duration_since(Instant::now()));
+    if let Some(flash_left) = flash_left {
+        let generation = OVERLAY_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(flash_left).await;
+            if OVERLAY_GENERATION.load(Ordering::SeqCst) == generation {
+                hide_overlay(&app_handle);
+            }
+        });
+        return;
+    }
+
+    OVERLAY_GENERATION.fetch_add(1, Ordering::SeqCst);
+
     if let Some(overlay) = app_handle.get_webview_window("recording_overlay") {
         let _ = overlay.hide();
         let _ = app_handle.emit("overlay-state-change", OverlayState::Hidden);
@@ -268,8 +496,131 @@ pub fn hide_overlay(app_handle: &AppHandle) {
     }
 }
 
+/// Show the finished state for the configured linger time, then hide the
+/// overlay unless it has been shown again in the meantime
+pub fn finish_overlay(app_handle: &AppHandle) {
+    let linger_ms = settings::get_settings(app_handle).overlay_linger_ms;
+    if linger_ms == 0 {
+        hide_overlay(app_handle);
+        return;
+    }
+
+    show_then_hide(app_handle, OverlayState::Done, linger_ms);
+}
+
+/// Briefly show why a transcription failed, then hide the overlay
+pub fn show_overlay_error(app_handle: &AppHandle, code: OverlayErrorCode) {
+    if settings::get_settings(app_handle).overlay_position == OverlayPosition::None {
+        return;
+    }
+
+    show_then_hide(app_handle, OverlayState::Error { code }, ERROR_DISPLAY_MS);
+}
+
+fn show_then_hide(app_handle: &AppHandle, state: OverlayState, duration_ms: u64) {
+    let generation = OVERLAY_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    update_overlay_state(app_handle, state);
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(duration_ms)).await;
+
+        if OVERLAY_GENERATION.load(Ordering::SeqCst) == generation {
+            hide_overlay(&app_handle);
+        } else {
+            debug!("Overlay reused before timeout elapsed, not hiding");
+        }
+    });
+}
+
+/// Flash the overlay in place of a feedback sound (`sound` is the sound's
+/// name, e.g. "start" or "error"). Only a visible overlay can flash.
+pub fn flash_overlay(app_handle: &AppHandle, sound: &str) {
+    let visible = app_handle
+        .get_webview_window("recording_overlay")
+        .and_then(|overlay| overlay.is_visible().ok())
+        .unwrap_or(false);
+    if !visible {
+        debug!("Overlay hidden, not flashing it for {}", sound);
+        return;
+    }
+
+    *FLASH_UNTIL.lock().unwrap() = Some(Instant::now() + Duration::from_millis(FLASH_MS));
+    let _ = app_handle.emit("overlay-flash", sound);
+}
+
 /// Update the overlay state without changing visibility
 pub fn update_overlay_state(app_handle: &AppHandle, state: OverlayState) {
     let _ = app_handle.emit("overlay-state-change", &state);
     debug!("Overlay state updated: {:?}", state);
 }
+
+/// Show the overlay on its own for the user to drag into place, until
+/// [`finish_positioning`] saves where it was left (or the timeout does)
+pub fn start_positioning(app_handle: &AppHandle) -> Result<(), String> {
+    if settings::get_settings(app_handle).overlay_position == OverlayPosition::None {
+        return Err("The overlay is turned off".to_string());
+    }
+    let in_use = app_handle
+        .get_webview_window("recording_overlay")
+        .and_then(|overlay| overlay.is_visible().ok())
+        .unwrap_or(false);
+    if in_use && !POSITIONING.load(Ordering::SeqCst) {
+        return Err("The overlay can't be moved while it's in use".to_string());
+    }
+
+    show_overlay(app_handle, OverlayState::Positioning);
+    POSITIONING.store(true, Ordering::SeqCst);
+    let generation = OVERLAY_GENERATION.load(Ordering::SeqCst);
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(POSITIONING_TIMEOUT).await;
+        if OVERLAY_GENERATION.load(Ordering::SeqCst) == generation {
+            debug!("Overlay positioning timed out");
+            if let Err(e) = finish_positioning(&app_handle) {
+                log::warn!("{}", e);
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Save where the overlay was dragged as its custom position on that
+/// monitor, and hide it
+pub fn finish_positioning(app_handle: &AppHandle) -> Result<(), String> {
+    if !POSITIONING.swap(false, Ordering::SeqCst) {
+        return Ok(());
+    }
+    let result = save_overlay_position(app_handle);
+    hide_overlay(app_handle);
+    result
+}
+
+fn save_overlay_position(app_handle: &AppHandle) -> Result<(), String> {
+    let overlay = app_handle
+        .get_webview_window("recording_overlay")
+        .ok_or_else(|| "Overlay window not found".to_string())?;
+    let position = overlay
+        .outer_position()
+        .map_err(|e| format!("Failed to get the overlay position: {}", e))?;
+    let monitor = overlay
+        .current_monitor()
+        .ok()
+        .flatten()
+        .ok_or_else(|| "The overlay isn't on a monitor".to_string())?;
+
+    let work_area = monitor.work_area();
+    let scale_factor = monitor.scale_factor();
+    let coordinates = OverlayCoordinates {
+        x: (position.x - work_area.position.x) as f64 / scale_factor,
+        y: (position.y - work_area.position.y) as f64 / scale_factor,
+    };
+    let key = monitor_key(&monitor);
+    log::info!("Overlay moved to {:?} on {}", coordinates, key);
+
+    settings::update_setting(app_handle, |s| {
+        s.overlay_position = OverlayPosition::Custom;
+        s.overlay_custom_positions.insert(key, coordinates);
+    })
+}
@@ -0,0 +1,263 @@
+//! OS permissions dictation depends on, for the first-run wizard
+//!
+//! Without microphone access recordings come out silent, and on macOS
+//! pasting needs accessibility access for enigo's simulated keystrokes.
+//! Neither fails loudly, so the wizard checks them up front and sends the
+//! user to the right settings page.
+//!
+//! - macOS asks for the microphone once (the TCC prompt) and after that only
+//!   System Settings can change it. Accessibility is always granted in
+//!   System Settings.
+//! - Windows has a privacy switch for the microphone but never prompts, and
+//!   doesn't gate simulated input.
+//! - Linux has neither.
+
+use std::sync::Arc;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::credentials;
+use crate::models::{EngineType, ModelManager};
+use crate::settings;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionStatus {
+    Granted,
+    Denied,
+    /// Never asked for, so asking will prompt
+    NotDetermined,
+}
+
+/// Whether the selected model can transcribe
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelReadiness {
+    Ready,
+    /// A local model that isn't downloaded
+    NeedsDownload,
+    /// The cloud model with no API key configured, for a provider that
+    /// needs one
+    NeedsApiKey,
+}
+
+/// Everything the first-run wizard walks through
+#[derive(Serialize, Debug, Clone)]
+pub struct OnboardingStatus {
+    pub microphone: PermissionStatus,
+    pub accessibility: PermissionStatus,
+    /// Whether any input device is connected
+    pub has_input_device: bool,
+    pub selected_model: String,
+    pub model: ModelReadiness,
+    /// Whether dictation should work as things are
+    pub complete: bool,
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use std::sync::mpsc;
+
+    use block2::RcBlock;
+    use objc2::runtime::Bool;
+    use objc2_av_foundation::{AVAuthorizationStatus, AVCaptureDevice, AVMediaTypeAudio};
+
+    use super::PermissionStatus;
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXIsProcessTrusted() -> bool;
+    }
+
+    pub const MICROPHONE_SETTINGS: &str =
+        "x-apple.systempreferences:com.apple.preference.security?Privacy_Microphone";
+    pub const ACCESSIBILITY_SETTINGS: &str =
+        "x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility";
+
+    pub fn microphone() -> PermissionStatus {
+        let Some(audio) = (unsafe { AVMediaTypeAudio }) else {
+            return PermissionStatus::NotDetermined;
+        };
+        match unsafe { AVCaptureDevice::authorizationStatusForMediaType(audio) } {
+            AVAuthorizationStatus::Authorized => PermissionStatus::Granted,
+            AVAuthorizationStatus::NotDetermined => PermissionStatus::NotDetermined,
+            _ => PermissionStatus::Denied,
+        }
+    }
+
+    /// Show the TCC prompt and wait for the answer
+    pub fn request_microphone() -> PermissionStatus {
+        let Some(audio) = (unsafe { AVMediaTypeAudio }) else {
+            return PermissionStatus::NotDetermined;
+        };
+        let (sender, receiver) = mpsc::channel();
+        let handler = RcBlock::new(move |granted: Bool| {
+            let _ = sender.send(granted.as_bool());
+        });
+        unsafe { AVCaptureDevice::requestAccessForMediaType_completionHandler(audio, &handler) };
+
+        match receiver.recv() {
+            Ok(true) => PermissionStatus::Granted,
+            Ok(false) => PermissionStatus::Denied,
+            Err(_) => microphone(),
+        }
+    }
+
+    pub fn accessibility() -> PermissionStatus {
+        if unsafe { AXIsProcessTrusted() } {
+            PermissionStatus::Granted
+        } else {
+            PermissionStatus::Denied
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use std::process::Command;
+
+    use super::PermissionStatus;
+    use crate::subprocess;
+
+    /// The microphone privacy switches, for every app and then for desktop
+    /// apps (which we are)
+    const CONSENT_KEYS: [&str; 2] = [
+        r"HKCU\Software\Microsoft\Windows\CurrentVersion\CapabilityAccessManager\ConsentStore\microphone",
+        r"HKCU\Software\Microsoft\Windows\CurrentVersion\CapabilityAccessManager\ConsentStore\microphone\NonPackaged",
+    ];
+
+    pub const MICROPHONE_SETTINGS: &str = "ms-settings:privacy-microphone";
+
+    /// A key's `Value`, "Allow" or "Deny"
+    fn consent(key: &str) -> Option<String> {
+        let mut command = Command::new("reg");
+        command.args(["query", key, "/v", "Value"]);
+        let output = subprocess::hide_window(&mut command).output().ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        stdout
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("Value"))
+            .and_then(|rest| rest.split_whitespace().last())
+            .map(str::to_string)
+    }
+
+    pub fn microphone() -> PermissionStatus {
+        // A missing key means the switch was never touched, and it's on
+        if CONSENT_KEYS
+            .iter()
+            .any(|key| consent(key).as_deref() == Some("Deny"))
+        {
+            PermissionStatus::Denied
+        } else {
+            PermissionStatus::Granted
+        }
+    }
+}
+
+pub fn microphone_status() -> PermissionStatus {
+    #[cfg(target_os = "macos")]
+    return macos::microphone();
+    #[cfg(target_os = "windows")]
+    return windows::microphone();
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    PermissionStatus::Granted
+}
+
+/// Ask for microphone access: the system prompt if it hasn't been asked
+/// before, else the privacy settings page if it was denied. Returns the
+/// status after asking.
+pub fn request_microphone(app: &AppHandle) -> PermissionStatus {
+    let status = microphone_status();
+
+    #[cfg(target_os = "macos")]
+    let status = match status {
+        PermissionStatus::NotDetermined => macos::request_microphone(),
+        PermissionStatus::Denied => {
+            open_settings(app, macos::MICROPHONE_SETTINGS);
+            status
+        }
+        PermissionStatus::Granted => status,
+    };
+    #[cfg(target_os = "windows")]
+    if status == PermissionStatus::Denied {
+        open_settings(app, windows::MICROPHONE_SETTINGS);
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let _ = app;
+
+    status
+}
+
+pub fn accessibility_status() -> PermissionStatus {
+    #[cfg(target_os = "macos")]
+    return macos::accessibility();
+    #[cfg(not(target_os = "macos"))]
+    PermissionStatus::Granted
+}
+
+/// Open the accessibility settings page where the app can be allowed to
+/// paste. Only macOS has one.
+pub fn open_accessibility_settings(app: &AppHandle) {
+    #[cfg(target_os = "macos")]
+    open_settings(app, macos::ACCESSIBILITY_SETTINGS);
+    #[cfg(not(target_os = "macos"))]
+    let _ = app;
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn open_settings(app: &AppHandle, url: &str) {
+    use tauri_plugin_opener::OpenerExt;
+
+    if let Err(e) = app.opener().open_url(url, None::<&str>) {
+        log::warn!("Couldn't open {}: {}", url, e);
+    }
+}
+
+fn model_readiness(app: &AppHandle, model_id: &str) -> ModelReadiness {
+    let Some(manager) = app.try_state::<Arc<ModelManager>>() else {
+        return ModelReadiness::NeedsDownload;
+    };
+    manager.ensure_scanned();
+
+    match manager.get_model_info(model_id) {
+        Some(info) if info.engine_type == EngineType::Cloud => {
+            let settings = settings::get_settings(app);
+            let provider = settings.cloud_provider();
+            if !provider.requires_api_key() || credentials::api_key(&settings, provider).is_some() {
+                ModelReadiness::Ready
+            } else {
+                ModelReadiness::NeedsApiKey
+            }
+        }
+        Some(info) if info.is_downloaded => ModelReadiness::Ready,
+        _ => ModelReadiness::NeedsDownload,
+    }
+}
+
+/// Check everything dictation needs. Blocks while the models folder is
+/// scanned and the devices listed.
+pub fn onboarding_status(app: &AppHandle) -> OnboardingStatus {
+    let microphone = microphone_status();
+    let accessibility = accessibility_status();
+    let has_input_device = iv_core::audio::list_input_devices()
+        .map(|devices| !devices.is_empty())
+        .unwrap_or_else(|e| {
+            log::warn!("Couldn't list input devices: {}", e);
+            false
+        });
+    let selected_model = settings::get_settings(app).selected_model;
+    let model = model_readiness(app, &selected_model);
+
+    OnboardingStatus {
+        complete: microphone == PermissionStatus::Granted
+            && accessibility == PermissionStatus::Granted
+            && has_input_device
+            && model == ModelReadiness::Ready,
+        microphone,
+        accessibility,
+        has_input_device,
+        selected_model,
+        model,
+    }
+}
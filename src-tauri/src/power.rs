@@ -0,0 +1,437 @@
+//! System suspend and resume
+//!
+//! Audio streams don't survive sleep: a recording left running comes back
+//! to a dead stream, and the warm stream and the wake word listener's
+//! microphone go quiet. On Windows global shortcuts are sometimes dropped
+//! too. Before sleep the streams are closed; after resume the selected
+//! device is checked, the listener reopened and the dictation shortcuts
+//! registered again. `system-suspending` and `system-resumed` are emitted so
+//! the UI can catch up.
+//!
+//! Power events come from the platform's own notifications, each watched by
+//! a [`PowerMonitor`] on its own thread:
+//!
+//! - Windows: `WM_POWERBROADCAST` on a hidden window
+//! - macOS: `NSWorkspaceWillSleepNotification` and `NSWorkspaceDidWakeNotification`
+//! - Linux: logind's `PrepareForSleep`, holding a delay inhibitor so the
+//!   streams are closed before the system goes down
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::overlay::OverlayErrorCode;
+use crate::recording_manager::{ManagerState, RecordingManager};
+use crate::settings;
+use crate::shortcut::{self, events};
+use crate::wake_word::{self, WakeWordListener};
+
+/// Whether a recording was cut short going to sleep, for `system-resumed`
+static RECORDING_INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Held while an event is handled, so events from different monitors are
+/// handled one at a time
+static SLEEP: Mutex<SleepTracker> = Mutex::new(SleepTracker { suspended_at: None });
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PowerEvent {
+    /// The system is about to sleep
+    Suspending,
+    /// The system woke up
+    Resumed,
+}
+
+/// Called with each power event. Returns once the event is handled, so a
+/// monitor can hold off sleep until then.
+pub type PowerHandler = Arc<dyn Fn(PowerEvent) + Send + Sync>;
+
+/// A source of power events
+pub trait PowerMonitor: Send + 'static {
+    fn name(&self) -> &'static str;
+
+    /// Call `handler` with events for as long as the app runs. Runs on its
+    /// own thread; returning means the monitor stopped working.
+    fn run(self: Box<Self>, handler: PowerHandler) -> Result<(), anyhow::Error>;
+}
+
+/// Pairs a resume with the suspend before it. Windows can report a resume
+/// twice, and a resume with no suspend seen isn't one we can act on.
+#[derive(Debug)]
+struct SleepTracker {
+    suspended_at: Option<SystemTime>,
+}
+
+impl SleepTracker {
+    /// Whether this is a new suspend, rather than one already seen
+    fn suspend(&mut self, now: SystemTime) -> bool {
+        if self.suspended_at.is_some() {
+            return false;
+        }
+        self.suspended_at = Some(now);
+        true
+    }
+
+    /// How long the system slept, if it was seen going to sleep
+    fn resume(&mut self, now: SystemTime) -> Option<Duration> {
+        let suspended_at = self.suspended_at.take()?;
+        Some(now.duration_since(suspended_at).unwrap_or_default())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use std::cell::RefCell;
+
+    use windows_sys::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+    use windows_sys::Win32::System::LibraryLoader::GetModuleHandleW;
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, RegisterClassW, MSG,
+        PBT_APMRESUMEAUTOMATIC, PBT_APMSUSPEND, WM_POWERBROADCAST, WNDCLASSW, WS_OVERLAPPED,
+    };
+
+    use super::{PowerEvent, PowerHandler, PowerMonitor};
+
+    thread_local! {
+        /// The handler for `window_proc`, which runs on the monitor's thread
+        static HANDLER: RefCell<Option<PowerHandler>> = const { RefCell::new(None) };
+    }
+
+    /// Listens for `WM_POWERBROADCAST` on a window that's never shown.
+    /// Message-only windows don't get broadcasts, so it's a top-level one.
+    pub struct PowerBroadcastMonitor;
+
+    unsafe extern "system" fn window_proc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+    ) -> LRESULT {
+        if msg == WM_POWERBROADCAST {
+            let event = match wparam as u32 {
+                PBT_APMSUSPEND => Some(PowerEvent::Suspending),
+                // Sent on every resume, unlike PBT_APMRESUMESUSPEND which
+                // only follows a wake by the user
+                PBT_APMRESUMEAUTOMATIC => Some(PowerEvent::Resumed),
+                _ => None,
+            };
+            if let Some(event) = event {
+                HANDLER.with_borrow(|handler| {
+                    if let Some(handler) = handler {
+                        handler(event);
+                    }
+                });
+                return 1;
+            }
+        }
+        DefWindowProcW(hwnd, msg, wparam, lparam)
+    }
+
+    impl PowerMonitor for PowerBroadcastMonitor {
+        fn name(&self) -> &'static str {
+            "WM_POWERBROADCAST"
+        }
+
+        fn run(self: Box<Self>, handler: PowerHandler) -> Result<(), anyhow::Error> {
+            HANDLER.set(Some(handler));
+            let class_name: Vec<u16> = "IvPowerMonitor\0".encode_utf16().collect();
+
+            unsafe {
+                let instance = GetModuleHandleW(std::ptr::null());
+                let mut class: WNDCLASSW = std::mem::zeroed();
+                class.lpfnWndProc = Some(window_proc);
+                class.hInstance = instance;
+                class.lpszClassName = class_name.as_ptr();
+                if RegisterClassW(&class) == 0 {
+                    return Err(std::io::Error::last_os_error().into());
+                }
+
+                let hwnd = CreateWindowExW(
+                    0,
+                    class_name.as_ptr(),
+                    class_name.as_ptr(),
+                    WS_OVERLAPPED,
+                    0,
+                    0,
+                    0,
+                    0,
+                    std::ptr::null_mut(),
+                    std::ptr::null_mut(),
+                    instance,
+                    std::ptr::null(),
+                );
+                if hwnd.is_null() {
+                    return Err(std::io::Error::last_os_error().into());
+                }
+
+                let mut msg: MSG = std::mem::zeroed();
+                while GetMessageW(&mut msg, std::ptr::null_mut(), 0, 0) > 0 {
+                    DispatchMessageW(&msg);
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use std::ptr::NonNull;
+
+    use block2::RcBlock;
+    use objc2_app_kit::{
+        NSWorkspace, NSWorkspaceDidWakeNotification, NSWorkspaceWillSleepNotification,
+    };
+    use objc2_foundation::{NSNotification, NSOperationQueue};
+
+    use super::{PowerEvent, PowerHandler, PowerMonitor};
+
+    /// Observes the workspace's sleep and wake notifications
+    pub struct WorkspaceMonitor;
+
+    impl PowerMonitor for WorkspaceMonitor {
+        fn name(&self) -> &'static str {
+            "NSWorkspace"
+        }
+
+        fn run(self: Box<Self>, handler: PowerHandler) -> Result<(), anyhow::Error> {
+            let center = NSWorkspace::sharedWorkspace().notificationCenter();
+            // Handled on a queue of its own rather than the main thread
+            let queue = NSOperationQueue::new();
+            let notifications = unsafe {
+                [
+                    (NSWorkspaceWillSleepNotification, PowerEvent::Suspending),
+                    (NSWorkspaceDidWakeNotification, PowerEvent::Resumed),
+                ]
+            };
+
+            // Observers stay registered while they're kept
+            let _observers: Vec<_> = notifications
+                .into_iter()
+                .map(|(name, event)| {
+                    let handler = handler.clone();
+                    let block = RcBlock::new(move |_: NonNull<NSNotification>| handler(event));
+                    unsafe {
+                        center.addObserverForName_object_queue_usingBlock(
+                            Some(name),
+                            None,
+                            Some(&queue),
+                            &block,
+                        )
+                    }
+                })
+                .collect();
+
+            loop {
+                std::thread::park();
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use zbus::blocking::{Connection, Proxy};
+    use zbus::zvariant::OwnedFd;
+
+    use super::{PowerEvent, PowerHandler, PowerMonitor};
+
+    /// Listens for logind's `PrepareForSleep`
+    pub struct LogindMonitor;
+
+    /// Take a delay inhibitor, which holds off sleep (for a few seconds at
+    /// most) until it's dropped
+    fn inhibit(manager: &Proxy) -> Option<OwnedFd> {
+        manager
+            .call(
+                "Inhibit",
+                &("sleep", "IndexVoice", "Closing audio streams", "delay"),
+            )
+            .map_err(|e| log::warn!("Couldn't take a sleep inhibitor: {}", e))
+            .ok()
+    }
+
+    impl PowerMonitor for LogindMonitor {
+        fn name(&self) -> &'static str {
+            "logind"
+        }
+
+        fn run(self: Box<Self>, handler: PowerHandler) -> Result<(), anyhow::Error> {
+            let connection = Connection::system()?;
+            let manager = Proxy::new(
+                &connection,
+                "org.freedesktop.login1",
+                "/org/freedesktop/login1",
+                "org.freedesktop.login1.Manager",
+            )?;
+            let signals = manager.receive_signal("PrepareForSleep")?;
+
+            let mut inhibitor = inhibit(&manager);
+            for signal in signals {
+                let going_to_sleep: bool = signal.body().deserialize()?;
+                if going_to_sleep {
+                    handler(PowerEvent::Suspending);
+                    drop(inhibitor.take());
+                } else {
+                    inhibitor = inhibit(&manager);
+                    handler(PowerEvent::Resumed);
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Sent with `system-resumed`
+#[derive(Serialize, Debug, Clone)]
+pub struct SystemResumed {
+    pub slept_secs: u64,
+    /// Whether a recording was cut short by the sleep
+    pub recording_interrupted: bool,
+    /// The selected input device, if it's no longer connected
+    pub missing_device: Option<String>,
+    /// Whether the global shortcuts were registered again
+    pub shortcuts_registered: bool,
+}
+
+/// The monitors for this platform
+pub fn default_monitors() -> Vec<Box<dyn PowerMonitor>> {
+    #[cfg(target_os = "windows")]
+    return vec![Box::new(windows::PowerBroadcastMonitor)];
+    #[cfg(target_os = "macos")]
+    return vec![Box::new(macos::WorkspaceMonitor)];
+    #[cfg(target_os = "linux")]
+    return vec![Box::new(linux::LogindMonitor)];
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    Vec::new()
+}
+
+/// Start the monitors, handling their events
+pub fn start(app: &AppHandle, monitors: Vec<Box<dyn PowerMonitor>>) {
+    let app = app.clone();
+    let handler: PowerHandler = Arc::new(move |event| handle_event(&app, event));
+
+    for monitor in monitors {
+        let name = monitor.name();
+        log::debug!("Starting the {} power monitor", name);
+        let handler = handler.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = monitor.run(handler) {
+                log::warn!("The {} power monitor stopped: {}", name, e);
+            }
+        });
+    }
+}
+
+fn handle_event(app: &AppHandle, event: PowerEvent) {
+    let mut sleep = SLEEP.lock().unwrap();
+    match event {
+        PowerEvent::Suspending => {
+            if sleep.suspend(SystemTime::now()) {
+                handle_suspend(app);
+            }
+        }
+        PowerEvent::Resumed => match sleep.resume(SystemTime::now()) {
+            Some(slept) => handle_resume(app, slept),
+            None => log::debug!("Ignoring a resume with no suspend before it"),
+        },
+    }
+}
+
+/// Close every microphone stream, cutting short a recording in progress.
+/// Returns whether there was one.
+fn close_streams(app: &AppHandle) -> bool {
+    let mut interrupted = false;
+    if let Some(manager) = app.try_state::<Arc<RecordingManager>>() {
+        if manager.get_state() == ManagerState::Recording {
+            log::warn!("Cancelling the recording, its stream doesn't survive sleep");
+            manager.cancel();
+            shortcut::abort_active_recording(app, OverlayErrorCode::Interrupted);
+            interrupted = true;
+        }
+        manager.close_warm_stream();
+        manager.stop_mic_test();
+    }
+    if let Some(listener) = app.try_state::<Arc<WakeWordListener>>() {
+        listener.stop();
+    }
+    interrupted
+}
+
+fn handle_suspend(app: &AppHandle) {
+    log::info!("System suspending, closing audio streams");
+    if close_streams(app) {
+        RECORDING_INTERRUPTED.store(true, Ordering::SeqCst);
+    }
+    let _ = app.emit(events::SYSTEM_SUSPENDING, ());
+}
+
+fn handle_resume(app: &AppHandle, slept: Duration) {
+    log::info!("System resumed after about {}s", slept.as_secs());
+    let recording_interrupted = RECORDING_INTERRUPTED.swap(false, Ordering::SeqCst);
+
+    // A USB or Bluetooth microphone may not have come back
+    let missing_device = settings::get_settings(app)
+        .selected_input_device
+        .filter(|selected| match iv_core::audio::list_input_devices() {
+            Ok(devices) => !devices
+                .iter()
+                .any(|device| device.id == *selected || device.name == *selected),
+            Err(e) => {
+                log::warn!("Couldn't list input devices after resume: {}", e);
+                false
+            }
+        });
+    if let Some(device) = &missing_device {
+        log::warn!(
+            "Input device '{}' is gone after resume, recordings will use the default",
+            device
+        );
+    }
+
+    // Windows sometimes drops global shortcuts across sleep
+    let shortcuts_registered = match shortcut::reregister_bindings(app) {
+        Ok(()) => true,
+        Err(e) => {
+            log::error!("Failed to register shortcuts after resume: {}", e);
+            false
+        }
+    };
+
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        wake_word::sync_with_settings(&app_handle).await;
+    });
+
+    let _ = app.emit(
+        events::SYSTEM_RESUMED,
+        SystemResumed {
+            slept_secs: slept.as_secs(),
+            recording_interrupted,
+            missing_device,
+            shortcuts_registered,
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sleep_tracker() {
+        let at = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+        let mut sleep = SleepTracker { suspended_at: None };
+
+        // Nothing to pair a resume with
+        assert_eq!(sleep.resume(at(10)), None);
+
+        assert!(sleep.suspend(at(100)));
+        assert!(!sleep.suspend(at(101)));
+        assert_eq!(sleep.resume(at(3700)), Some(Duration::from_secs(3600)));
+        // Reported twice
+        assert_eq!(sleep.resume(at(3701)), None);
+    }
+}
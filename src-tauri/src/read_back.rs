@@ -0,0 +1,217 @@
+//! Audible read-back of transcripts before pasting
+//!
+//! With read-back on, the final transcript is spoken with the operating
+//! system's text-to-speech and held until the user accepts it with the accept
+//! shortcut (it's pasted) or rejects it with the cancel shortcut (it's
+//! dropped). Both shortcuts are only registered while a transcript is waiting,
+//! so they don't take keys away from other apps the rest of the time. If no
+//! answer comes before the timeout the transcript is dropped, which is the
+//! safe choice when dictating somewhere destructive.
+
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::clipboard;
+use crate::settings::{self, AppSettings};
+use crate::shortcut;
+
+/// A transcript waiting to be accepted after being read back
+struct PendingReadBack {
+    id: u64,
+    text: String,
+    binding_id: String,
+    accept_shortcut: String,
+    cancel_shortcut: String,
+}
+
+#[derive(Default)]
+pub struct ReadBackState {
+    pending: Mutex<Option<PendingReadBack>>,
+    speech: Mutex<Option<Child>>,
+    next_id: Mutex<u64>,
+}
+
+/// Start speaking text with the platform's text-to-speech
+fn speak(text: &str) -> std::io::Result<Child> {
+    #[cfg(target_os = "macos")]
+    {
+        Command::new("say").arg(text).stdin(Stdio::null()).spawn()
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::io::Write;
+
+        use crate::subprocess;
+
+        // The text goes through stdin so it never needs quoting for PowerShell
+        let mut command = Command::new("powershell");
+        command.args([
+            "-NoProfile",
+            "-Command",
+            "Add-Type -AssemblyName System.Speech; \
+             (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak([Console]::In.ReadToEnd())",
+        ]);
+        let mut child = subprocess::hide_window(&mut command)
+            .stdin(Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            let text = text.to_string();
+            std::thread::spawn(move || {
+                let _ = stdin.write_all(text.as_bytes());
+            });
+        }
+        Ok(child)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        // eSpeak exits when killed; speech-dispatcher keeps talking, so it's last
+        let mut last_error = None;
+        for (program, args) in [
+            ("espeak-ng", &[][..]),
+            ("espeak", &[][..]),
+            ("spd-say", &["--wait"][..]),
+        ] {
+            match Command::new(program)
+                .args(args)
+                .arg(text)
+                .stdin(Stdio::null())
+                .spawn()
+            {
+                Ok(child) => return Ok(child),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| std::io::ErrorKind::NotFound.into()))
+    }
+}
+
+fn stop_speaking(state: &ReadBackState) {
+    if let Some(mut child) = state.speech.lock().unwrap().take() {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+/// Read a transcript aloud and hold it until it's accepted or cancelled,
+/// replacing any transcript still waiting
+pub fn request(app_handle: &AppHandle, text: String, binding_id: String) -> Result<(), String> {
+    let state = app_handle
+        .try_state::<ReadBackState>()
+        .ok_or_else(|| "Read-back state not initialized".to_string())?;
+
+    finish(app_handle, false);
+
+    // Without a way to answer, holding the transcript would only lose it
+    let settings = settings::get_settings(app_handle);
+    if let Err(e) = register_shortcuts(app_handle, &settings) {
+        log::warn!(
+            "Can't read back without the accept and cancel shortcuts, pasting: {}",
+            e
+        );
+        return clipboard::paste(text, app_handle, &binding_id);
+    }
+
+    let id = {
+        let mut next_id = state.next_id.lock().unwrap();
+        *next_id += 1;
+        *next_id
+    };
+
+    match speak(&text) {
+        Ok(child) => *state.speech.lock().unwrap() = Some(child),
+        Err(e) => log::warn!("Text-to-speech unavailable, waiting silently: {}", e),
+    }
+
+    log::info!("Reading back transcript, waiting for accept or cancel");
+    let _ = app_handle.emit("read-back-started", &text);
+
+    *state.pending.lock().unwrap() = Some(PendingReadBack {
+        id,
+        text,
+        binding_id,
+        accept_shortcut: settings.read_back_accept_shortcut,
+        cancel_shortcut: settings.read_back_cancel_shortcut,
+    });
+
+    if settings.read_back_timeout_secs > 0 {
+        let app_handle = app_handle.clone();
+        let timeout = Duration::from_secs(settings.read_back_timeout_secs as u64);
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(timeout).await;
+            let timed_out = app_handle
+                .try_state::<ReadBackState>()
+                .is_some_and(|state| {
+                    state.pending.lock().unwrap().as_ref().map(|p| p.id) == Some(id)
+                });
+            if timed_out {
+                log::info!("No answer to the read-back, dropping the transcript");
+                finish(&app_handle, false);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/// Register the accept and cancel shortcuts, both or neither
+fn register_shortcuts(app_handle: &AppHandle, settings: &AppSettings) -> Result<(), String> {
+    shortcut::register_action(
+        app_handle,
+        "read_back_accept",
+        &settings.read_back_accept_shortcut,
+        accept,
+    )?;
+    if let Err(e) = shortcut::register_action(
+        app_handle,
+        "read_back_cancel",
+        &settings.read_back_cancel_shortcut,
+        cancel,
+    ) {
+        shortcut::unregister_action(app_handle, &settings.read_back_accept_shortcut);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Stop the read-back and paste the transcript (or drop it)
+fn finish(app_handle: &AppHandle, accepted: bool) {
+    let Some(state) = app_handle.try_state::<ReadBackState>() else {
+        return;
+    };
+
+    stop_speaking(&state);
+    let Some(pending) = state.pending.lock().unwrap().take() else {
+        return;
+    };
+
+    shortcut::unregister_action(app_handle, &pending.accept_shortcut);
+    shortcut::unregister_action(app_handle, &pending.cancel_shortcut);
+    let _ = app_handle.emit("read-back-finished", accepted);
+
+    if accepted {
+        log::info!("Read-back accepted, pasting");
+        if let Err(e) = clipboard::paste(pending.text, app_handle, &pending.binding_id) {
+            log::error!("Failed to paste transcription: {}", e);
+        }
+    } else {
+        log::info!("Read-back transcript dropped");
+    }
+}
+
+/// Paste the transcript being read back. Runs off the shortcut handler,
+/// since finishing unregisters the shortcut that triggered it.
+pub fn accept(app_handle: &AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn_blocking(move || finish(&app_handle, true));
+}
+
+/// Drop the transcript being read back
+pub fn cancel(app_handle: &AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn_blocking(move || finish(&app_handle, false));
+}
@@ -1,56 +1,420 @@
 //! Recording Manager - Orchestrates audio recording and transcription
 
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
 
-use tauri::{AppHandle, Emitter};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::futures::Notified;
+use tokio::sync::Notify;
 
-use crate::audio::AudioRecorder;
-use crate::cloud_transcribe::CloudTranscriber;
-use crate::local_transcribe::LocalTranscriber;
-use crate::models::{EngineType, ModelManager};
-use crate::shortcut::events;
-use crate::vad::{ensure_vad_model, SileroVad, SmoothedVad, VadFrame, VAD_FRAME_SAMPLES};
+use iv_core::app_capture;
+use iv_core::audio::{self, resample_to_16k, AudioRecorder, DeviceConfigOverride};
+use iv_core::audio_file;
+use iv_core::calibration::{self, DeviceProfile};
+use iv_core::cloud_transcribe::{
+    create_provider, CloudConfig, CloudProviderType, CloudTranscriber, ProviderCapabilities,
+    UploadProgressCallback,
+};
+use iv_core::denoise;
+use iv_core::diarization::{self, SpeakerEmbedder};
+use iv_core::local_transcribe::LocalTranscriber;
+use iv_core::speakers;
+use iv_core::transcript::{TranscriptSegment, TranscriptionResult};
+use iv_core::vad::{
+    analyze_speech, build_detector, filter_and_analyze, AdaptivePrefill, SmoothedVad, StreamingVad,
+    VadAnalysis, VadEngine, VadParams, VadTransition, VoiceActivityDetector,
+};
+
+use crate::credentials;
+use crate::device_profiles;
+use crate::event_throttle;
+use crate::history::{HistoryManager, RecordingSource};
+use crate::models::{EngineType, ModelInfo, ModelManager};
+use crate::overlay::OverlayErrorCode;
+use crate::settings::{self, AppSettings, AudioSource};
+use crate::shortcut::{self, events};
+use crate::transcribe_queue::JobControl;
+use crate::tray;
+use crate::vad::{self, ensure_vad_model};
+
+/// Frames of audio kept after speech ends in fast mode (~90ms, down from ~300ms)
+const FAST_MODE_HANGOVER_FRAMES: usize = 3;
+
+/// Length of the recording used to calibrate an input device
+const CALIBRATION_DURATION: Duration = Duration::from_secs(5);
+
+/// How often `model-loading-progress` is emitted while a model loads
+const MODEL_LOADING_HEARTBEAT: Duration = Duration::from_millis(500);
+
+/// Silenced past the end of the start sound, for output latency and echo
+const START_SOUND_TAIL: Duration = Duration::from_millis(100);
+
+/// How often `recording-tick` is emitted while recording
+const RECORDING_TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often the live preview is transcribed again
+const LIVE_PREVIEW_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Audio transcribed for the live preview, from the end of the recording,
+/// so each pass takes about as long however long the recording gets
+const LIVE_PREVIEW_WINDOW_SECS: usize = 15;
+
+/// Sent with `transcription-slow` when a transcription runs long
+#[derive(Serialize, Debug, Clone)]
+pub struct SlowTranscription {
+    pub elapsed_secs: u32,
+    pub model_id: String,
+    /// A faster downloaded model to suggest switching to, if there is one
+    pub faster_model: Option<ModelInfo>,
+}
+
+/// Returned by `stop_and_transcribe` when the transcription was cancelled
+#[derive(Debug)]
+pub struct TranscriptionCancelled;
+
+impl std::fmt::Display for TranscriptionCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Transcription cancelled")
+    }
+}
+
+impl std::error::Error for TranscriptionCancelled {}
+
+/// Why there was nothing to transcribe, for failures the overlay explains
+#[derive(Debug)]
+pub enum TranscriptionError {
+    /// The model isn't known or isn't downloaded
+    ModelMissing(String),
+    /// No audio was recorded, or there's no speech in it
+    NoSpeech(String),
+}
+
+impl std::fmt::Display for TranscriptionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TranscriptionError::ModelMissing(message) | TranscriptionError::NoSpeech(message) => {
+                write!(f, "{}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TranscriptionError {}
+
+/// Outcome of running the last recording through the VAD settings
+#[derive(Serialize, Debug, Clone)]
+pub struct VadTest {
+    pub recording_id: String,
+    pub params: VadParams,
+    /// Share of the recording the VAD keeps
+    pub retained_percent: f32,
+    pub analysis: VadAnalysis,
+}
+
+/// The cloud transcriber and the config it was built from
+struct CachedCloudTranscriber {
+    config: CloudConfig,
+    transcriber: Arc<CloudTranscriber>,
+}
+
+impl CachedCloudTranscriber {
+    fn new(config: CloudConfig) -> Self {
+        if config.api_key.is_none() && config.provider.requires_api_key() {
+            log::warn!(
+                "No API key for {:?} (keyring, settings or {}). Cloud transcription will fail without it.",
+                config.provider,
+                config.provider.api_key_env_var()
+            );
+        }
+
+        Self {
+            transcriber: Arc::new(CloudTranscriber::new(&config)),
+            config,
+        }
+    }
+}
+
+/// Sent with `recording-tick` every second while recording
+#[derive(Serialize, Debug, Clone)]
+struct RecordingTick {
+    elapsed_secs: u64,
+    /// Transcript of the last few seconds, when the live preview is on
+    partial_text: Option<String>,
+}
+
+/// Sent with `recording-device-lost`
+#[derive(Serialize, Debug, Clone)]
+struct DeviceLost {
+    /// Id of the microphone that was lost, if the recording used one
+    device: Option<String>,
+    reason: String,
+    /// Id of the device recording carried on with, or None when it was aborted
+    fallback: Option<String>,
+}
+
+/// What the open microphone stream was opened for, when it's kept warm
+/// between recordings. A recording wanting anything else reopens it.
+#[derive(Debug, Clone, PartialEq)]
+struct WarmStream {
+    device_id: String,
+    config: DeviceConfigOverride,
+    level_events: bool,
+}
+
+/// A loaded VAD and the engine and model file it was loaded from
+struct CachedVad {
+    engine: VadEngine,
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+    vad: SmoothedVad,
+}
+
+/// The local engine, shared with the blocking threads that load models
+/// into it and transcribe with it
+struct LocalModel {
+    transcriber: LocalTranscriber,
+    /// Held from loading a model until it's done transcribing, so a warm-up
+    /// or a second dictation don't swap the model out from under a job
+    lock: Mutex<()>,
+    model_manager: Arc<ModelManager>,
+    app_handle: AppHandle,
+}
+
+impl LocalModel {
+    /// Load a model if it isn't the one already loaded
+    fn ensure_loaded(&self, model_info: &ModelInfo) -> Result<(), anyhow::Error> {
+        let _lock = self.lock.lock().unwrap();
+        self.load(model_info)
+    }
+
+    /// Load the model and transcribe with it. Blocks, so it's run with
+    /// `spawn_blocking`; a cancelled await leaves it running to the end.
+    fn transcribe(
+        &self,
+        model_info: &ModelInfo,
+        samples_16k: Vec<f32>,
+    ) -> Result<TranscriptionResult, anyhow::Error> {
+        let _lock = self.lock.lock().unwrap();
+        self.load(model_info)?;
+        self.transcriber.transcribe(samples_16k)
+    }
+
+    /// Transcribe with a model if it's the one loaded and it isn't busy
+    fn try_transcribe_loaded(
+        &self,
+        model_id: &str,
+        samples_16k: Vec<f32>,
+    ) -> Option<Result<TranscriptionResult, anyhow::Error>> {
+        let _lock = self.lock.try_lock().ok()?;
+        if self.transcriber.current_model()? != model_id {
+            return None;
+        }
+        Some(self.transcriber.transcribe(samples_16k))
+    }
+
+    fn unload(&self) {
+        let _lock = self.lock.lock().unwrap();
+        self.transcriber.unload_model();
+    }
+
+    /// Called with `lock` held
+    fn load(&self, model_info: &ModelInfo) -> Result<(), anyhow::Error> {
+        let model_id = model_info.id.as_str();
+
+        // If it's a local model, check if it's downloaded
+        if !model_info.is_downloaded {
+            return Err(TranscriptionError::ModelMissing(format!(
+                "Model '{}' is not downloaded. Please download it first.",
+                model_id
+            ))
+            .into());
+        }
+
+        // Check if already loaded
+        if self.transcriber.current_model().as_deref() == Some(model_id) {
+            return Ok(());
+        }
+
+        let model_path = self.model_manager.get_model_path(model_id)?;
+        log::info!("Loading model '{}'...", model_id);
+
+        // Emit loading event
+        let _ = self
+            .app_handle
+            .emit("model-loading", serde_json::json!({ "model_id": model_id }));
+
+        // The engine loads in one call with no stages to report, so a
+        // heartbeat with the elapsed time goes out until it's done
+        let started = Instant::now();
+        let (done_tx, done_rx) = mpsc::channel::<()>();
+        let result = std::thread::scope(|scope| {
+            scope.spawn(move || {
+                while let Err(RecvTimeoutError::Timeout) =
+                    done_rx.recv_timeout(MODEL_LOADING_HEARTBEAT)
+                {
+                    let _ = self.app_handle.emit(
+                        "model-loading-progress",
+                        serde_json::json!({
+                            "model_id": model_id,
+                            "elapsed_ms": started.elapsed().as_millis() as u64,
+                        }),
+                    );
+                }
+            });
+
+            let result = self.transcriber.load_model(model_info, &model_path);
+            drop(done_tx);
+            result
+        });
+        result?;
+        log::info!("Loaded model '{}' in {:?}", model_id, started.elapsed());
+
+        // Emit loaded event
+        let _ = self
+            .app_handle
+            .emit("model-loaded", serde_json::json!({ "model_id": model_id }));
+
+        Ok(())
+    }
+}
+
+/// Called with the draft transcription before the final one is ready
+pub type DraftCallback<'a> = Box<dyn FnOnce(&TranscriptionResult) + Send + 'a>;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum ManagerState {
     Idle,
     Recording,
     Transcribing,
+    /// Recording a device's calibration, which nothing else may interrupt
+    Calibrating,
 }
 
 pub struct RecordingManager {
     state: Mutex<ManagerState>,
+    /// The microphone stream. Kept open between recordings when the stream
+    /// is kept warm, otherwise only open while recording.
     recorder: Mutex<Option<AudioRecorder>>,
-    cloud_transcriber: CloudTranscriber,
-    local_transcriber: LocalTranscriber,
+    /// Set while `recorder` is kept open between recordings
+    warm_stream: Mutex<Option<WarmStream>>,
+    /// Counts recorders created, so a lost stream is only handled while it's
+    /// still the one recording
+    stream_id: AtomicUsize,
+    /// Id of the microphone the current recording uses
+    input_device: Mutex<Option<String>>,
+    /// Audio recorded on devices lost during the current recording, at their
+    /// sample rates, to go in front of what the fallback device records
+    lost_device_audio: Mutex<Vec<(Vec<f32>, u32)>>,
+    /// Microphone opened by `start_mic_test`, outside of any recording
+    mic_test: Mutex<Option<AudioRecorder>>,
+    /// Records the system audio alongside the microphone when they're mixed
+    system_recorder: Mutex<Option<AudioRecorder>>,
+    /// Rebuilt when the cloud settings or API key change
+    cloud_transcriber: Mutex<CachedCloudTranscriber>,
+    /// Capabilities probed so far, by the config they were probed with
+    provider_capabilities: Mutex<HashMap<CloudConfig, ProviderCapabilities>>,
+    local: Arc<LocalModel>,
     model_manager: Arc<ModelManager>,
+    history: Arc<HistoryManager>,
     selected_model: Mutex<String>,
     app_handle: AppHandle,
     vad_enabled: Mutex<bool>,
     vad_model_path: Mutex<Option<PathBuf>>,
+    /// Extra VAD prefill learned from recordings with clipped first words
+    adaptive_prefill: Mutex<AdaptivePrefill>,
+    /// Calibration of the device the current recording uses, if any
+    device_profile: Mutex<Option<DeviceProfile>>,
+    /// Device and processing of the current recording, saved with its history entry
+    recording_source: Mutex<Option<RecordingSource>>,
+    language_override: Mutex<Option<String>>,
+    /// Whether the current recording is translated to English (set by the
+    /// shortcut that started it)
+    translate_recording: Mutex<bool>,
+    /// VAD run on audio as it's captured, while recording
+    live_vad: Arc<Mutex<Option<StreamingVad>>>,
+    /// Seconds of silence after speech that stop the current recording
+    auto_stop_secs: Arc<Mutex<Option<f32>>>,
+    /// When capture of the current recording started
+    capture_started: Mutex<Option<Instant>>,
+    /// Counts recordings, so a recording's ticker stops when it ends
+    recording_id: AtomicUsize,
+    /// Audio captured so far, at the recorder's rate, while the live preview is on
+    preview_audio: Arc<Mutex<Option<Vec<f32>>>>,
+    /// Set while the start sound plays, so the live VAD doesn't hear it
+    start_sound_playing: Arc<AtomicBool>,
+    /// How far into the current recording the start sound ended
+    start_sound_end: Mutex<Option<Duration>>,
+    /// VAD kept loaded between recordings, so stopping doesn't reload the model
+    vad_cache: Mutex<Option<CachedVad>>,
+    /// Loaded on first use when speaker labels are on
+    speaker_embedder: Mutex<Option<SpeakerEmbedder>>,
+    /// Counts transcriptions of recordings, so a cancel only applies to the
+    /// one it was meant for
+    transcription_id: AtomicUsize,
+    /// The recording transcription in progress (0 = none); file and history
+    /// transcriptions can't be cancelled this way
+    active_transcription: AtomicUsize,
+    /// The cancelled transcription, whose result is thrown away
+    cancelled_transcription: AtomicUsize,
+    /// Wakes whoever is waiting on a transcription when it's cancelled
+    transcription_cancel: Notify,
 }
 
 impl RecordingManager {
     pub fn new(
         app_handle: &AppHandle,
         model_manager: Arc<ModelManager>,
+        history: Arc<HistoryManager>,
     ) -> Result<Self, anyhow::Error> {
-        let api_key = std::env::var("OPENAI_API_KEY").ok();
-        if api_key.is_none() {
-            log::warn!("OPENAI_API_KEY not set. Cloud transcription will fail without it.");
-        }
+        let settings = settings::get_settings(app_handle);
+        let cloud_transcriber = CachedCloudTranscriber::new(cloud_config_from_settings(&settings));
 
         Ok(Self {
             state: Mutex::new(ManagerState::Idle),
             recorder: Mutex::new(None),
-            cloud_transcriber: CloudTranscriber::new(api_key),
-            local_transcriber: LocalTranscriber::new(),
+            warm_stream: Mutex::new(None),
+            stream_id: AtomicUsize::new(0),
+            input_device: Mutex::new(None),
+            lost_device_audio: Mutex::new(Vec::new()),
+            mic_test: Mutex::new(None),
+            system_recorder: Mutex::new(None),
+            cloud_transcriber: Mutex::new(cloud_transcriber),
+            provider_capabilities: Mutex::new(HashMap::new()),
+            local: Arc::new(LocalModel {
+                transcriber: LocalTranscriber::new(),
+                lock: Mutex::new(()),
+                model_manager: Arc::clone(&model_manager),
+                app_handle: app_handle.clone(),
+            }),
             model_manager,
-            selected_model: Mutex::new("cloud".to_string()), // Default to cloud
+            history,
+            selected_model: Mutex::new(settings.selected_model.clone()),
             app_handle: app_handle.clone(),
             vad_enabled: Mutex::new(true),
             vad_model_path: Mutex::new(None),
+            adaptive_prefill: Mutex::new(vad::load_adaptive_prefill(app_handle)),
+            device_profile: Mutex::new(None),
+            recording_source: Mutex::new(None),
+            language_override: Mutex::new(None),
+            translate_recording: Mutex::new(false),
+            live_vad: Arc::new(Mutex::new(None)),
+            auto_stop_secs: Arc::new(Mutex::new(None)),
+            capture_started: Mutex::new(None),
+            recording_id: AtomicUsize::new(0),
+            preview_audio: Arc::new(Mutex::new(None)),
+            start_sound_playing: Arc::new(AtomicBool::new(false)),
+            start_sound_end: Mutex::new(None),
+            vad_cache: Mutex::new(None),
+            speaker_embedder: Mutex::new(None),
+            transcription_id: AtomicUsize::new(0),
+            active_transcription: AtomicUsize::new(0),
+            cancelled_transcription: AtomicUsize::new(0),
+            transcription_cancel: Notify::new(),
         })
     }
 
@@ -75,256 +439,1792 @@ impl RecordingManager {
         log::info!("VAD enabled set to {}", enabled);
     }
 
-    /// Ensure VAD model is downloaded
-    pub async fn ensure_vad_model(&self) -> Result<PathBuf, anyhow::Error> {
-        let path = ensure_vad_model(&self.app_handle).await?;
-        *self.vad_model_path.lock().unwrap() = Some(path.clone());
-        Ok(path)
+    /// Get the temporary language override, if any
+    pub fn get_language_override(&self) -> Option<String> {
+        self.language_override.lock().unwrap().clone()
     }
 
-    /// Set the selected model for transcription
-    pub fn set_selected_model(&self, model_id: &str) -> Result<(), anyhow::Error> {
-        // Validate model exists
-        let model_info = self
-            .model_manager
-            .get_model_info(model_id)
-            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+    /// Temporarily override the transcription language without changing settings.
+    /// Pass None to go back to the configured language.
+    pub fn set_language_override(&self, language: Option<String>) {
+        log::info!("Language override set to {:?}", language);
+        *self.language_override.lock().unwrap() = language;
+    }
 
-        // If it's a local model, check if it's downloaded
-        if model_info.engine_type != EngineType::Cloud && !model_info.is_downloaded {
-            return Err(anyhow::anyhow!(
-                "Model '{}' is not downloaded. Please download it first.",
-                model_id
-            ));
+    /// Get the language to transcribe with: the override if set, otherwise the setting
+    fn get_effective_language(&self) -> String {
+        self.get_language_override()
+            .unwrap_or_else(|| settings::get_settings(&self.app_handle).transcription_language)
+    }
+
+    /// Translate the current recording to English instead of transcribing it
+    pub fn set_translate_recording(&self, translate: bool) {
+        *self.translate_recording.lock().unwrap() = translate;
+    }
+
+    /// Whether to translate the current recording to English: always when
+    /// the setting is on, otherwise when the recording asked for it. Files
+    /// and saved recordings have their own option.
+    fn should_translate(&self) -> bool {
+        settings::get_settings(&self.app_handle).translate_to_english
+            || *self.translate_recording.lock().unwrap()
+    }
+
+    /// The cloud transcriber for the current settings and API key. A key
+    /// entered or changed after launch takes effect on the next use.
+    fn cloud_transcriber(&self) -> Arc<CloudTranscriber> {
+        let config = cloud_config_from_settings(&settings::get_settings(&self.app_handle));
+        let mut cached = self.cloud_transcriber.lock().unwrap();
+        if cached.config != config {
+            log::info!("Cloud settings or API key changed, rebuilding the cloud client");
+            *cached = CachedCloudTranscriber::new(config);
         }
+        cached.transcriber.clone()
+    }
 
-        // If switching to a local model, load it
+    /// Emits `upload-progress` while audio is sent to the cloud, so long
+    /// uploads show how far along they are
+    fn upload_progress(&self) -> UploadProgressCallback {
+        let app_handle = self.app_handle.clone();
+        Arc::new(move |sent, total| {
+            let progress = serde_json::json!({
+                "sent": sent,
+                "total": total,
+                "percentage": (sent as f64 / total.max(1) as f64 * 100.0) as u32,
+            });
+            // The last update can't be dropped, or the UI would stop short of 100%
+            if sent >= total {
+                event_throttle::emit_final(
+                    &app_handle,
+                    events::UPLOAD_PROGRESS,
+                    events::UPLOAD_PROGRESS,
+                    progress,
+                );
+            } else {
+                event_throttle::emit(&app_handle, events::UPLOAD_PROGRESS, progress);
+            }
+        })
+    }
+
+    /// What a cloud provider supports with the current settings. Probed once
+    /// per configuration; failed probes (e.g. an unreachable server) aren't
+    /// cached, so they're retried next time.
+    pub async fn provider_capabilities(
+        &self,
+        provider: CloudProviderType,
+    ) -> Result<ProviderCapabilities, anyhow::Error> {
+        let config = cloud_config_for(&settings::get_settings(&self.app_handle), provider);
+        if let Some(capabilities) = self.provider_capabilities.lock().unwrap().get(&config) {
+            return Ok(capabilities.clone());
+        }
+
+        let capabilities = create_provider(&config).capabilities().await?;
+        log::info!("{:?} capabilities: {:?}", provider, capabilities);
+        self.provider_capabilities
+            .lock()
+            .unwrap()
+            .insert(config, capabilities.clone());
+        Ok(capabilities)
+    }
+
+    /// Model to transcribe the next recording with: the selected one, or in
+    /// fast mode the fastest downloaded local model
+    fn transcription_model_id(&self) -> String {
+        let selected = self.get_selected_model();
+        if !settings::get_settings(&self.app_handle).fast_mode {
+            return selected;
+        }
+
+        self.fastest_local_model().map(|m| m.id).unwrap_or(selected)
+    }
+
+    fn fastest_local_model(&self) -> Option<ModelInfo> {
+        self.model_manager
+            .get_available_models()
+            .into_iter()
+            .filter(|m| m.engine_type == EngineType::Parakeet && m.is_downloaded)
+            .max_by(|a, b| a.speed_score.total_cmp(&b.speed_score))
+    }
+
+    /// Local model to draft with before a cloud transcription, when
+    /// draft-then-refine is on. Translations have no local draft.
+    fn draft_model_id(&self, model_id: &str) -> Option<String> {
+        if !settings::get_settings(&self.app_handle).draft_then_refine || self.should_translate() {
+            return None;
+        }
+
+        let model_info = self.model_manager.get_model_info(model_id)?;
         if model_info.engine_type != EngineType::Cloud {
-            let model_path = self.model_manager.get_model_path(model_id)?;
+            return None;
+        }
 
-            // Check if already loaded
-            if self.local_transcriber.current_model().as_deref() != Some(model_id) {
-                log::info!("Loading model '{}'...", model_id);
+        self.fastest_local_model().map(|m| m.id)
+    }
 
-                // Emit loading event
-                let _ = self
-                    .app_handle
-                    .emit("model-loading", serde_json::json!({ "model_id": model_id }));
+    /// Load the model the current recording will be transcribed with, so it's
+    /// ready by the time the key is released (fast mode)
+    pub fn warm_up(&self) {
+        let model_id = self.transcription_model_id();
+        let Some(model_info) = self.model_manager.get_model_info(&model_id) else {
+            return;
+        };
+        if model_info.engine_type == EngineType::Cloud {
+            return;
+        }
 
-                self.local_transcriber
-                    .load_model(&model_info, &model_path)?;
+        if let Err(e) = self.ensure_model_loaded(&model_info) {
+            log::warn!("Failed to warm up model '{}': {}", model_id, e);
+        }
+    }
 
-                // Emit loaded event
-                let _ = self
-                    .app_handle
-                    .emit("model-loaded", serde_json::json!({ "model_id": model_id }));
-            }
-        } else {
-            // Unload local model if switching to cloud
-            if self.local_transcriber.is_loaded() {
-                self.local_transcriber.unload_model();
+    /// VAD parameters for the current settings. `live` is for audio being
+    /// captured from the current input device, which gets its calibration.
+    fn vad_params(&self, live: bool) -> VadParams {
+        let settings = settings::get_settings(&self.app_handle);
+        let mut params = VadParams::from_ms(
+            settings.vad_threshold,
+            settings.vad_prefill_ms,
+            settings.vad_hangover_ms,
+            settings.vad_onset_ms,
+        );
+        params.adaptive = settings.adaptive_vad;
+        if settings.fast_mode {
+            params.hangover_frames = params.hangover_frames.min(FAST_MODE_HANGOVER_FRAMES);
+        }
+        if settings.adaptive_prefill {
+            params = self.adaptive_prefill.lock().unwrap().apply(&params);
+        }
+        if live {
+            if let Some(profile) = *self.device_profile.lock().unwrap() {
+                params.threshold = profile.vad_threshold;
             }
         }
+        params
+    }
 
-        // Update selection
+    /// Record from an input device while the user speaks and save its
+    /// calibration, applied whenever that device records from then on
+    pub fn calibrate_device(
+        &self,
+        device_name: Option<&str>,
+    ) -> Result<DeviceProfile, anyhow::Error> {
         {
-            let mut selected = self.selected_model.lock().unwrap();
-            *selected = model_id.to_string();
+            let mut state = self.state.lock().unwrap();
+            if *state != ManagerState::Idle {
+                return Err(anyhow::anyhow!("Cannot calibrate while {:?}", *state));
+            }
+            *state = ManagerState::Calibrating;
         }
 
-        log::info!("Selected model: {}", model_id);
-        Ok(())
+        let result = self.record_calibration(device_name);
+        *self.state.lock().unwrap() = ManagerState::Idle;
+        result
     }
 
-    /// Start recording audio
-    pub fn start_recording(&self) -> Result<(), anyhow::Error> {
-        let mut state = self.state.lock().unwrap();
+    fn record_calibration(
+        &self,
+        device_name: Option<&str>,
+    ) -> Result<DeviceProfile, anyhow::Error> {
+        let (id, device) = audio::find_input_device(device_name)?;
+        let name = audio::device_name(&device);
+        log::info!("Calibrating input device '{}'", id);
+
+        let mut recorder = AudioRecorder::new()?;
+        recorder.set_config_override(input_device_config(
+            &settings::get_settings(&self.app_handle),
+            &id,
+            &name,
+        ));
+        recorder.open(Some(device))?;
+        recorder.start()?;
+        std::thread::sleep(CALIBRATION_DURATION);
+        let samples = recorder.stop()?;
+        let sample_rate = recorder.sample_rate();
+        recorder.close()?;
+
+        let profile = calibration::calibrate(&samples, sample_rate)?;
+        log::info!("Calibrated '{}': {:?}", name, profile);
+
+        device_profiles::set_profile(&self.app_handle, &name, profile);
+        let _ = self.app_handle.emit(
+            "device-calibrated",
+            serde_json::json!({ "device_name": name, "profile": profile }),
+        );
+
+        Ok(profile)
+    }
 
+    /// Open a device (the default one if None) and stream its level as
+    /// `mic-test-level` without recording, for a meter while the user picks
+    /// a microphone. Replaces any test already running.
+    pub fn start_mic_test(&self, device_name: Option<&str>) -> Result<(), anyhow::Error> {
+        // Held until the test is stored, so a recording starting meanwhile
+        // waits and then stops it instead of sharing the device
+        let state = self.state.lock().unwrap();
         if *state != ManagerState::Idle {
-            let current_state = state.clone();
-            drop(state); // Release lock before returning
             return Err(anyhow::anyhow!(
-                "Cannot start recording: currently {:?}. Please wait for the current operation to complete.",
-                current_state
+                "Cannot test the microphone while {:?}",
+                *state
             ));
         }
+        self.stop_mic_test();
 
-        // Create and open the recorder
-        let mut recorder = AudioRecorder::new()?;
+        let (id, device) = audio::find_input_device(device_name)?;
+        let name = audio::device_name(&device);
 
-        // Set up audio level callback to emit events to the frontend
+        let mut recorder = AudioRecorder::new()?;
+        // Only the level is wanted; nothing is transcribed
+        recorder.set_buffer_samples(false);
         let app_handle = self.app_handle.clone();
         recorder.set_audio_level_callback(move |level| {
-            let _ = app_handle.emit("audio-level", level);
+            event_throttle::emit(&app_handle, events::MIC_TEST_LEVEL, level);
+        });
+        let app_handle = self.app_handle.clone();
+        let lost_name = name.clone();
+        recorder.set_stream_lost_callback(move |reason| {
+            log::warn!("Microphone test on '{}' ended: {}", lost_name, reason);
+            let _ = app_handle.emit(
+                events::MIC_TEST_STOPPED,
+                serde_json::json!({ "device_name": lost_name, "reason": reason }),
+            );
         });
 
-        recorder.open(None)?;
+        recorder.set_config_override(input_device_config(
+            &settings::get_settings(&self.app_handle),
+            &id,
+            &name,
+        ));
+        recorder.open(Some(device))?;
         recorder.start()?;
+        log::info!("Testing input device '{}'", id);
 
-        *self.recorder.lock().unwrap() = Some(recorder);
-        *state = ManagerState::Recording;
-
-        let _ = self.app_handle.emit(events::RECORDING_STARTED, ());
-
-        log::info!("Recording started.");
+        *self.mic_test.lock().unwrap() = Some(recorder);
+        drop(state);
         Ok(())
     }
 
-    /// Stop recording and transcribe
-    pub async fn stop_and_transcribe(&self) -> Result<String, anyhow::Error> {
-        let (samples, sample_rate) = {
-            let mut state = self.state.lock().unwrap();
-            let mut recorder_guard = self.recorder.lock().unwrap();
+    /// Close the device opened by `start_mic_test`, if it's open
+    pub fn stop_mic_test(&self) {
+        if let Some(mut recorder) = self.mic_test.lock().unwrap().take() {
+            let _ = recorder.stop();
+            let _ = recorder.close();
+            log::info!("Stopped microphone test");
+        }
+    }
 
-            if *state != ManagerState::Recording {
-                return Err(anyhow::anyhow!(
-                    "Cannot stop: not currently recording (state: {:?})",
-                    *state
-                ));
+    /// Check a new recording for a clipped first word and adjust the prefill
+    /// used for the next ones
+    fn learn_prefill(&self, samples_16k: &[f32], analysis: &VadAnalysis) {
+        if !settings::get_settings(&self.app_handle).adaptive_prefill {
+            return;
+        }
+
+        let prefill = {
+            let mut prefill = self.adaptive_prefill.lock().unwrap();
+            if !prefill.observe(samples_16k, analysis) {
+                return;
             }
+            *prefill
+        };
 
-            let recorder = recorder_guard
-                .as_mut()
-                .ok_or_else(|| anyhow::anyhow!("Recorder not initialized"))?;
+        log::info!(
+            "Adjusted VAD prefill to +{}ms after checking the speech onset",
+            prefill.extra_ms()
+        );
+        vad::save_adaptive_prefill(&self.app_handle, &prefill);
+        let _ = self.app_handle.emit(
+            "vad-prefill-adjusted",
+            serde_json::json!({ "extra_ms": prefill.extra_ms() }),
+        );
+    }
 
-            let samples = recorder.stop()?;
-            let sample_rate = recorder.sample_rate();
+    /// Ensure VAD model is downloaded
+    pub async fn ensure_vad_model(&self) -> Result<PathBuf, anyhow::Error> {
+        let path = ensure_vad_model(&self.app_handle).await?;
+        *self.vad_model_path.lock().unwrap() = Some(path.clone());
+        Ok(path)
+    }
 
-            recorder.close()?;
-            *recorder_guard = None;
-            *state = ManagerState::Transcribing;
+    /// Set the selected model for transcription
+    pub fn set_selected_model(&self, model_id: &str) -> Result<(), anyhow::Error> {
+        // Validate model exists
+        let model_info = self.model_manager.get_model_info(model_id).ok_or_else(|| {
+            TranscriptionError::ModelMissing(format!("Model not found: {}", model_id))
+        })?;
 
-            let _ = self.app_handle.emit(events::RECORDING_STOPPED, ());
+        if !model_info.is_transcription_model() {
+            return Err(anyhow::anyhow!(
+                "{} isn't a transcription model",
+                model_info.name
+            ));
+        }
 
-            (samples, sample_rate)
-        };
+        // If switching to a local model, load it
+        if model_info.engine_type != EngineType::Cloud {
+            self.ensure_model_loaded(&model_info)?;
+        } else {
+            // Unload local model if switching to cloud
+            if self.local.transcriber.is_loaded() {
+                self.local.unload();
+            }
+        }
 
-        if samples.is_empty() {
-            let mut state = self.state.lock().unwrap();
-            *state = ManagerState::Idle;
-            return Err(anyhow::anyhow!("No audio recorded"));
+        // Update selection
+        {
+            let mut selected = self.selected_model.lock().unwrap();
+            *selected = model_id.to_string();
         }
 
-        log::info!(
-            "Captured {} samples at {} Hz ({:.2}s of audio)",
-            samples.len(),
-            sample_rate,
-            samples.len() as f32 / sample_rate as f32
+        if let Err(e) = settings::update_setting(&self.app_handle, |s| {
+            s.selected_model = model_id.to_string();
+        }) {
+            log::warn!("Failed to persist selected model: {}", e);
+        }
+
+        log::info!("Selected model: {}", model_id);
+        let _ = self.app_handle.emit(
+            "model-selected",
+            serde_json::json!({ "model_id": model_id }),
         );
+        tray::refresh_tray_menu(&self.app_handle);
+
+        if let Some(warning) = model_info.language_warning(&self.get_effective_language()) {
+            log::warn!("{}", warning);
+            let _ = self.app_handle.emit(
+                "model-language-warning",
+                serde_json::json!({ "model_id": model_id, "message": warning }),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Switch between the cloud and the most recently used downloaded local
+    /// model, returning the id of the model now selected
+    pub fn toggle_cloud_model(&self) -> Result<String, anyhow::Error> {
+        let model_id = if self.get_selected_model() == ModelInfo::cloud().id {
+            self.model_manager
+                .most_recent_local_model()
+                .ok_or_else(|| anyhow::anyhow!("No local model downloaded"))?
+                .id
+        } else {
+            ModelInfo::cloud().id
+        };
+        self.set_selected_model(&model_id)?;
+        Ok(model_id)
+    }
 
-        // Get selected model
+    /// Load the selected local model ahead of the first dictation
+    pub fn preload_selected_model(&self) -> Result<(), anyhow::Error> {
         let model_id = self.get_selected_model();
         let model_info = self
             .model_manager
             .get_model_info(&model_id)
-            .ok_or_else(|| anyhow::anyhow!("Selected model not found"))?;
+            .ok_or_else(|| {
+                TranscriptionError::ModelMissing(format!("Model not found: {}", model_id))
+            })?;
 
-        // Resample to 16kHz if needed (required for all models and VAD)
-        let samples_16k = if sample_rate != 16000 {
-            let resampled = resample_to_16k(&samples, sample_rate);
-            log::info!(
-                "Resampled audio: {} Hz → 16000 Hz ({} → {} samples)",
-                sample_rate,
-                samples.len(),
-                resampled.len()
-            );
-            resampled
-        } else {
-            samples
-        };
+        if model_info.engine_type == EngineType::Cloud {
+            log::debug!("Selected model is cloud-based, nothing to preload");
+            return Ok(());
+        }
 
-        // Apply VAD if enabled
-        let samples_filtered = if self.is_vad_enabled() {
-            let vad_path = self.vad_model_path.lock().unwrap().clone();
-            if let Some(path) = vad_path {
-                match self.filter_with_vad(&samples_16k, &path) {
-                    Ok(filtered) => {
-                        let original_duration = samples_16k.len() as f32 / 16000.0;
-                        let filtered_duration = filtered.len() as f32 / 16000.0;
-                        log::info!(
-                            "VAD applied: original {:.2}s, filtered {:.2}s. ({:.1}% retained)",
-                            original_duration,
-                            filtered_duration,
-                            (filtered_duration / original_duration) * 100.0,
-                        );
-                        filtered
-                    }
-                    Err(e) => {
-                        log::error!("VAD processing failed: {}. Proceeding without VAD.", e);
-                        samples_16k
-                    }
-                }
-            } else {
-                log::debug!("VAD model path not set. Skipping VAD.");
-                samples_16k
+        log::info!("Preloading model '{}' at startup", model_id);
+        self.ensure_model_loaded(&model_info)
+    }
+
+    /// Load a local model into the transcriber if it isn't already loaded
+    fn ensure_model_loaded(&self, model_info: &ModelInfo) -> Result<(), anyhow::Error> {
+        self.local.ensure_loaded(model_info)
+    }
+
+    /// Transcribe with a local model on a blocking thread, loading it first.
+    /// The model stays locked until the job finishes, even when the
+    /// transcription is cancelled and nothing waits for it any more.
+    async fn transcribe_locally(
+        &self,
+        model_info: &ModelInfo,
+        samples_16k: Vec<f32>,
+    ) -> Result<TranscriptionResult, anyhow::Error> {
+        let local = Arc::clone(&self.local);
+        let model_info = model_info.clone();
+        tauri::async_runtime::spawn_blocking(move || local.transcribe(&model_info, samples_16k))
+            .await?
+    }
+
+    /// Transcribe a short clip with a local model only, never the cloud
+    /// (used to listen for the wake phrase)
+    pub fn transcribe_snippet_locally(
+        &self,
+        samples_16k: Vec<f32>,
+    ) -> Result<String, anyhow::Error> {
+        let selected = self.get_selected_model();
+        let model_info = self
+            .model_manager
+            .get_available_models()
+            .into_iter()
+            .filter(|m| m.engine_type == EngineType::Parakeet && m.is_downloaded)
+            .max_by_key(|m| m.id == selected)
+            .ok_or_else(|| anyhow::anyhow!("No local model downloaded"))?;
+
+        Ok(self.local.transcribe(&model_info, samples_16k)?.text)
+    }
+
+    /// Start recording audio
+    pub fn start_recording(&self) -> Result<(), anyhow::Error> {
+        self.start(false)
+    }
+
+    /// Start a recording that ends by itself after a pause in speech, for
+    /// sessions with no key release to stop them (e.g. wake word)
+    pub fn start_hands_free_recording(&self) -> Result<(), anyhow::Error> {
+        self.start(true)
+    }
+
+    fn start(&self, hands_free: bool) -> Result<(), anyhow::Error> {
+        let mut state = self.state.lock().unwrap();
+
+        if *state != ManagerState::Idle {
+            let current_state = state.clone();
+            drop(state); // Release lock before returning
+            return Err(anyhow::anyhow!(
+                "Cannot start recording: currently {:?}. Please wait for the current operation to complete.",
+                current_state
+            ));
+        }
+
+        // A running microphone test would hold on to the device
+        self.stop_mic_test();
+
+        let settings = settings::get_settings(&self.app_handle);
+
+        // Pick the device first, so its calibration applies to the live VAD too
+        let audio_source = match settings.capture_app {
+            Some(_) => None,
+            None => Some(settings.audio_source),
+        };
+        let device = match audio_source {
+            Some(AudioSource::Microphone | AudioSource::Mixed) => Some(audio::find_input_device(
+                settings.selected_input_device.as_deref(),
+            )?),
+            _ => None,
+        };
+        let (device_id, device) = device.unzip();
+        let device_name = device.as_ref().map(audio::device_name);
+        let profile = device_name
+            .as_ref()
+            .and_then(|name| device_profiles::get_profile(&self.app_handle, name));
+        if let Some(profile) = &profile {
+            log::debug!("Applying device calibration {:?}", profile);
+        }
+        *self.device_profile.lock().unwrap() = profile;
+        *self.input_device.lock().unwrap() = device_id.clone();
+        self.lost_device_audio.lock().unwrap().clear();
+        *self.translate_recording.lock().unwrap() = false;
+        *self.start_sound_end.lock().unwrap() = None;
+        self.start_sound_playing.store(false, Ordering::Relaxed);
+
+        // Run the VAD on captured audio so the overlay can show whether speech is
+        // heard, and to stop automatically after a pause when auto-stop is on
+        *self.auto_stop_secs.lock().unwrap() = (settings.auto_stop_on_silence || hands_free)
+            .then_some(settings.auto_stop_silence_secs);
+        let live_vad = self.build_live_vad(&settings, hands_free);
+
+        // A warm microphone stream is reused as long as it was opened for the
+        // same device and is still running
+        let device_config = device_id
+            .as_ref()
+            .zip(device_name.as_ref())
+            .map(|(id, name)| input_device_config(&settings, id, name));
+        let warm_stream = match (&settings.capture_app, audio_source, &device_id) {
+            (None, Some(AudioSource::Microphone | AudioSource::Mixed), Some(id))
+                if settings.keep_stream_warm =>
+            {
+                Some(WarmStream {
+                    device_id: id.clone(),
+                    config: device_config.clone().unwrap_or_default(),
+                    level_events: settings.audio_level_events,
+                })
             }
-        } else {
-            samples_16k
+            _ => None,
+        };
+        let reused = {
+            let mut recorder_guard = self.recorder.lock().unwrap();
+            let mut warm_guard = self.warm_stream.lock().unwrap();
+            let reusable = warm_stream.is_some()
+                && *warm_guard == warm_stream
+                && recorder_guard.as_ref().is_some_and(AudioRecorder::is_open);
+            if !reusable {
+                if let Some(mut stale) = recorder_guard.take() {
+                    log::info!("Closing the warm audio stream");
+                    let _ = stale.close();
+                }
+            }
+            *warm_guard = warm_stream.clone();
+            recorder_guard.take()
         };
 
-        if samples_filtered.is_empty() {
+        let recorder = match reused {
+            Some(recorder) => {
+                log::debug!("Reusing the warm audio stream");
+                recorder
+            }
+            None => {
+                let mut recorder =
+                    self.new_recorder(&settings, live_vad.is_some() || warm_stream.is_some())?;
+                match &settings.capture_app {
+                    Some(app_name) => {
+                        let target = app_capture::find_capture_target(app_name)?;
+                        recorder.open_app(&target.id)?;
+                    }
+                    None if audio_source == Some(AudioSource::System) => recorder.open_system()?,
+                    None => {
+                        if let Some(config) = device_config {
+                            recorder.set_config_override(config);
+                        }
+                        recorder.open(device)?
+                    }
+                }
+                recorder
+            }
+        };
+
+        // When mixing, the system audio is recorded separately and mixed in at
+        // the end; the levels and live VAD follow the microphone
+        let system_recorder = match audio_source {
+            Some(AudioSource::Mixed) => {
+                let mut system_recorder = AudioRecorder::new()?;
+                system_recorder.open_system()?;
+                Some(system_recorder)
+            }
+            _ => None,
+        };
+
+        if let Some(vad) = live_vad {
+            *self.live_vad.lock().unwrap() =
+                Some(StreamingVad::new(Box::new(vad), recorder.sample_rate()));
+        }
+
+        recorder.start()?;
+        if let Some(system_recorder) = &system_recorder {
+            system_recorder.start()?;
+        }
+        *self.capture_started.lock().unwrap() = Some(Instant::now());
+        *self.preview_audio.lock().unwrap() = settings.live_preview.then(Vec::new);
+
+        let source_device = match (&settings.capture_app, device_name) {
+            (Some(app_name), _) => format!("App: {}", app_name),
+            (None, Some(name)) if system_recorder.is_some() => format!("{} + system audio", name),
+            (None, Some(name)) => name,
+            (None, None) => "System audio".to_string(),
+        };
+        *self.recording_source.lock().unwrap() = Some(RecordingSource {
+            device: source_device,
+            sample_rate: recorder.sample_rate(),
+            stages: profile
+                .map(|_| "device_gain".to_string())
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        });
+
+        *self.recorder.lock().unwrap() = Some(recorder);
+        *self.system_recorder.lock().unwrap() = system_recorder;
+        *state = ManagerState::Recording;
+        self.spawn_recording_ticker(settings.live_preview);
+
+        let _ = self.app_handle.emit(events::RECORDING_STARTED, ());
+
+        log::info!("Recording started.");
+        Ok(())
+    }
+
+    /// Create a recorder reporting levels to the frontend (~30 fps, unless
+    /// disabled to save power) and, with `live_vad`, feeding the live VAD.
+    /// With the live preview on, captured audio is also kept for it.
+    fn new_recorder(
+        &self,
+        settings: &AppSettings,
+        live_vad: bool,
+    ) -> Result<AudioRecorder, anyhow::Error> {
+        let mut recorder = AudioRecorder::new()?;
+
+        // Handled on another thread, since failing over closes the lost
+        // recorder, which joins the thread reporting it
+        let stream_id = self.stream_id.fetch_add(1, Ordering::SeqCst) + 1;
+        let app_handle = self.app_handle.clone();
+        recorder.set_stream_lost_callback(move |reason| {
+            let app_handle = app_handle.clone();
+            let reason = reason.to_string();
+            tauri::async_runtime::spawn_blocking(move || {
+                if let Some(manager) = app_handle.try_state::<Arc<RecordingManager>>() {
+                    manager.handle_stream_lost(stream_id, &reason);
+                }
+            });
+        });
+
+        if settings.audio_level_events || settings.tray_animation {
+            let app_handle = self.app_handle.clone();
+            let emit_levels = settings.audio_level_events;
+            recorder.set_audio_level_callback(move |level| {
+                tray::set_audio_level(level);
+                if emit_levels {
+                    event_throttle::emit(&app_handle, events::AUDIO_LEVEL, level);
+                }
+            });
+        }
+
+        if live_vad || settings.live_preview {
+            let slot = Arc::clone(&self.live_vad);
+            let auto_stop_secs = Arc::clone(&self.auto_stop_secs);
+            let start_sound_playing = Arc::clone(&self.start_sound_playing);
+            let preview_audio = Arc::clone(&self.preview_audio);
+            let app_handle = self.app_handle.clone();
+            recorder.set_sample_callback(move |samples| {
+                // The start sound would count as speech
+                if start_sound_playing.load(Ordering::Relaxed) {
+                    return;
+                }
+
+                if let Some(preview) = preview_audio.lock().unwrap().as_mut() {
+                    preview.extend_from_slice(samples);
+                }
+
+                let mut guard = slot.lock().unwrap();
+                let Some(vad) = guard.as_mut() else {
+                    return;
+                };
+
+                match vad.push_samples(samples) {
+                    Ok(Some(VadTransition::SpeechStarted)) => {
+                        let _ = app_handle.emit(events::SPEECH_DETECTED, ());
+                    }
+                    Ok(Some(VadTransition::SilenceStarted)) => {
+                        let _ = app_handle.emit(events::SILENCE_DETECTED, ());
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        log::warn!("Live VAD failed, disabling it for this recording: {}", e);
+                        *guard = None;
+                        return;
+                    }
+                }
+
+                if let Some(limit) = *auto_stop_secs.lock().unwrap() {
+                    if vad.has_heard_speech() && vad.trailing_silence_secs() >= limit {
+                        log::info!("{:.1}s of silence, stopping recording automatically", limit);
+                        *guard = None;
+                        // Stops on an async task, since closing the recorder joins this thread
+                        shortcut::stop_active_recording(&app_handle);
+                    }
+                }
+            });
+        }
+
+        Ok(recorder)
+    }
+
+    /// Emit `recording-tick` every second until the recording ends, with a
+    /// preview of what's been said when `live_preview` is on
+    fn spawn_recording_ticker(&self, live_preview: bool) {
+        let recording_id = self.recording_id.fetch_add(1, Ordering::SeqCst) + 1;
+        let app_handle = self.app_handle.clone();
+        let started = Instant::now();
+
+        tauri::async_runtime::spawn(async move {
+            let partial_text: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+            let preview_running = Arc::new(AtomicBool::new(false));
+            let mut last_preview = started;
+
+            loop {
+                tokio::time::sleep(RECORDING_TICK_INTERVAL).await;
+                let Some(manager) = app_handle.try_state::<Arc<RecordingManager>>() else {
+                    return;
+                };
+                let manager = Arc::clone(&manager);
+                if !manager.is_recording(recording_id) {
+                    return;
+                }
+
+                // One preview at a time, so a slow model doesn't pile them up
+                if live_preview
+                    && last_preview.elapsed() >= LIVE_PREVIEW_INTERVAL
+                    && !preview_running.swap(true, Ordering::SeqCst)
+                {
+                    last_preview = Instant::now();
+                    let partial_text = Arc::clone(&partial_text);
+                    let preview_running = Arc::clone(&preview_running);
+                    tauri::async_runtime::spawn_blocking(move || {
+                        if let Some(text) = manager.transcribe_preview(recording_id) {
+                            *partial_text.lock().unwrap() = Some(text);
+                        }
+                        preview_running.store(false, Ordering::SeqCst);
+                    });
+                }
+
+                let _ = app_handle.emit(
+                    events::RECORDING_TICK,
+                    RecordingTick {
+                        elapsed_secs: started.elapsed().as_secs(),
+                        partial_text: partial_text.lock().unwrap().clone(),
+                    },
+                );
+            }
+        });
+    }
+
+    /// Whether recording `recording_id` is still going
+    fn is_recording(&self, recording_id: usize) -> bool {
+        self.get_state() == ManagerState::Recording
+            && self.recording_id.load(Ordering::SeqCst) == recording_id
+    }
+
+    /// Transcribe the end of the recording so far with the local model it
+    /// will be transcribed (or drafted) with, if that's the one loaded.
+    /// Models aren't loaded for this, to keep it cheap. Skipped once the
+    /// recording has stopped, since the final transcription needs the model.
+    fn transcribe_preview(&self, recording_id: usize) -> Option<String> {
+        if !self.is_recording(recording_id) {
+            return None;
+        }
+        let model_id = self.transcription_model_id();
+        let model_id = self.draft_model_id(&model_id).unwrap_or(model_id);
+
+        let sample_rate = self.recorder.lock().unwrap().as_ref()?.sample_rate();
+        let samples = {
+            let mut guard = self.preview_audio.lock().unwrap();
+            let preview = guard.as_mut()?;
+            // Only the window is ever transcribed, so the rest is let go
+            let window = LIVE_PREVIEW_WINDOW_SECS * sample_rate as usize;
+            preview.drain(..preview.len().saturating_sub(window));
+            preview.clone()
+        };
+        if samples.is_empty() {
+            return None;
+        }
+
+        // Skipped while a model loads or a transcription runs
+        let result = self
+            .local
+            .try_transcribe_loaded(&model_id, resample_to_16k(&samples, sample_rate))?;
+        if !self.is_recording(recording_id) {
+            return None;
+        }
+        match result {
+            Ok(result) => Some(result.text.trim().to_string()).filter(|text| !text.is_empty()),
+            Err(e) => {
+                log::debug!("Live preview transcription failed: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Called by the feedback sound player when the start sound starts and
+    /// stops playing, so it can be kept out of the recording
+    pub fn set_start_sound_playing(&self, playing: bool) {
+        self.start_sound_playing.store(playing, Ordering::Relaxed);
+        if playing {
+            return;
+        }
+        if let Some(started) = *self.capture_started.lock().unwrap() {
+            *self.start_sound_end.lock().unwrap() = Some(started.elapsed());
+        }
+    }
+
+    /// How much of the start of the recording to silence for the start
+    /// sound, if it played during it
+    fn take_start_sound_gate(&self) -> Option<Duration> {
+        let started = self.capture_started.lock().unwrap().take()?;
+        let end = self.start_sound_end.lock().unwrap().take().or_else(|| {
+            // Stopped while the sound was still playing
+            self.start_sound_playing
+                .swap(false, Ordering::Relaxed)
+                .then(|| started.elapsed())
+        })?;
+        Some(end + START_SOUND_TAIL)
+    }
+
+    /// Stop recording and transcribe. With draft-then-refine on and a cloud
+    /// model selected, `on_draft` first gets a local transcription to paste
+    /// while the cloud one is pending; if the cloud fails, the draft is returned.
+    /// Returned along with the ID of its history entry, if one was saved.
+    pub async fn stop_and_transcribe(
+        &self,
+        on_draft: Option<DraftCallback<'_>>,
+    ) -> Result<(TranscriptionResult, Option<String>), anyhow::Error> {
+        let (mut samples, sample_rate, system_audio, mut source, transcription_id) = {
+            let mut state = self.state.lock().unwrap();
+            let mut recorder_guard = self.recorder.lock().unwrap();
+
+            if *state != ManagerState::Recording {
+                return Err(anyhow::anyhow!(
+                    "Cannot stop: not currently recording (state: {:?})",
+                    *state
+                ));
+            }
+
+            let recorder = recorder_guard
+                .as_mut()
+                .ok_or_else(|| anyhow::anyhow!("Recorder not initialized"))?;
+
+            let mut samples = recorder.stop()?;
+            let sample_rate = recorder.sample_rate();
+            let capture_stats = recorder.capture_stats();
+
+            if let Some(profile) = *self.device_profile.lock().unwrap() {
+                profile.apply_gain(&mut samples);
+            }
+
+            // Put back what was recorded before failing over to this device
+            let lost_device_audio = std::mem::take(&mut *self.lost_device_audio.lock().unwrap());
+            if !lost_device_audio.is_empty() {
+                let mut joined: Vec<f32> = lost_device_audio
+                    .into_iter()
+                    .flat_map(|(lost, rate)| audio::resample(&lost, rate, sample_rate))
+                    .collect();
+                joined.extend(samples);
+                samples = joined;
+            }
+
+            let start_sound_gate = self.take_start_sound_gate();
+            if let Some(gate) = start_sound_gate {
+                log::debug!("Gating the start sound out of the first {:?}", gate);
+                audio::gate_start(&mut samples, sample_rate, gate);
+            }
+
+            // A warm stream stays open, idle, for the next recording
+            if self.warm_stream.lock().unwrap().is_none() {
+                recorder.close()?;
+                *recorder_guard = None;
+            }
+
+            // Losing the system audio still leaves the microphone to transcribe
+            let system_audio =
+                self.system_recorder
+                    .lock()
+                    .unwrap()
+                    .take()
+                    .and_then(|mut system_recorder| {
+                        let mut system_samples = system_recorder
+                            .stop()
+                            .map_err(|e| log::warn!("Failed to stop system audio capture: {}", e))
+                            .ok()?;
+                        let system_rate = system_recorder.sample_rate();
+                        let _ = system_recorder.close();
+                        // The loopback hears the start sound at full volume
+                        if let Some(gate) = start_sound_gate {
+                            audio::gate_start(&mut system_samples, system_rate, gate);
+                        }
+                        Some((system_samples, system_rate))
+                    });
+
+            *self.live_vad.lock().unwrap() = None;
+            *self.preview_audio.lock().unwrap() = None;
+            *state = ManagerState::Transcribing;
+            let transcription_id = self.transcription_id.fetch_add(1, Ordering::SeqCst) + 1;
+            self.active_transcription
+                .store(transcription_id, Ordering::SeqCst);
+
+            let _ = self.app_handle.emit(events::RECORDING_STOPPED, ());
+
+            let mut source = self
+                .recording_source
+                .lock()
+                .unwrap()
+                .take()
+                .unwrap_or_default();
+            if start_sound_gate.is_some() {
+                source.stages.push("gate_start_sound".to_string());
+            }
+            source.add_capture_stats(&capture_stats);
+            self.warn_dropped_buffers(source.dropped_buffers);
+            (samples, sample_rate, system_audio, source, transcription_id)
+        };
+
+        if samples.is_empty() {
             let mut state = self.state.lock().unwrap();
             *state = ManagerState::Idle;
-            return Err(anyhow::anyhow!("No speech detected in the recording"));
+            self.active_transcription.store(0, Ordering::SeqCst);
+            return Err(TranscriptionError::NoSpeech("No audio recorded".to_string()).into());
         }
 
-        // Transcribe based on engine type
-        let result = match model_info.engine_type {
-            EngineType::Cloud => {
-                log::info!("Using cloud transcription (OpenAI)");
-                self.cloud_transcriber
-                    .transcribe(samples_filtered, 16000, None)
+        log::info!(
+            "Captured {} samples at {} Hz ({:.2}s of audio)",
+            samples.len(),
+            sample_rate,
+            samples.len() as f32 / sample_rate as f32
+        );
+
+        // Take out fan noise and hum before the VAD and the model hear it
+        let settings = settings::get_settings(&self.app_handle);
+        if settings.noise_suppression && !settings.fast_mode {
+            let started = Instant::now();
+            let denoised = tauri::async_runtime::spawn_blocking(move || {
+                denoise::denoise(&samples, sample_rate)
+            })
+            .await;
+            samples = match denoised {
+                Ok(denoised) => denoised,
+                Err(e) => {
+                    let mut state = self.state.lock().unwrap();
+                    *state = ManagerState::Idle;
+                    self.active_transcription.store(0, Ordering::SeqCst);
+                    return Err(anyhow::anyhow!("Noise suppression failed: {}", e));
+                }
+            };
+            log::info!("Suppressed noise in {:?}", started.elapsed());
+            source.stages.push("denoise".to_string());
+        }
+
+        // Bring quiet microphones up to a level the models expect
+        if settings.normalize_audio {
+            let gain = audio::normalize(&mut samples, settings.normalize_target_peak);
+            if gain > 1.0 {
+                log::info!("Normalized audio with {:.2}x gain", gain);
+                source.stages.push("normalize".to_string());
+            }
+        }
+
+        // Resample to 16kHz if needed (required for all models and VAD)
+        let samples_16k = if sample_rate != 16000 {
+            let resampled = resample_to_16k(&samples, sample_rate);
+            log::info!(
+                "Resampled audio: {} Hz → 16000 Hz ({} → {} samples)",
+                sample_rate,
+                samples.len(),
+                resampled.len()
+            );
+            source.stages.push("resample".to_string());
+            resampled
+        } else {
+            samples
+        };
+
+        // With speaker tagging each side is transcribed on its own; the mix is
+        // what gets drafted and saved either way
+        let (samples_16k, sides) = match system_audio {
+            Some((system_samples, system_rate)) => {
+                let system_16k = if system_rate != 16000 {
+                    resample_to_16k(&system_samples, system_rate)
+                } else {
+                    system_samples
+                };
+                log::info!("Mixing in {} samples of system audio", system_16k.len());
+                let mixed = audio::mix(&samples_16k, &system_16k);
+                source.stages.push("mix_system_audio".to_string());
+                let sides = settings.tag_speakers.then_some((samples_16k, system_16k));
+                if sides.is_some() {
+                    source.stages.push("speaker_turns".to_string());
+                }
+                (mixed, sides)
+            }
+            None => (samples_16k, None),
+        };
+
+        if self.is_vad_enabled() {
+            source.stages.push("vad".to_string());
+        }
+
+        let mut model_id = self.transcription_model_id();
+
+        let mut draft = None;
+        if let Some(on_draft) = on_draft {
+            if let Some(draft_model_id) = self.draft_model_id(&model_id) {
+                match self
+                    .transcribe_samples(&samples_16k, &draft_model_id, false, false)
                     .await
+                {
+                    Ok(transcript) => {
+                        on_draft(&transcript);
+                        draft = Some((transcript, draft_model_id));
+                    }
+                    Err(e) => log::warn!("Draft transcription failed: {}", e),
+                }
             }
-            EngineType::Parakeet => {
-                log::info!("Using local transcription ({})", model_info.name);
-                // Local transcription is sync
-                self.local_transcriber.transcribe(samples_filtered)
+        }
+
+        let mut result = match &sides {
+            Some((me, them)) => {
+                self.transcribe_speakers(me, them, &model_id, self.should_translate())
+                    .await
+            }
+            None => {
+                self.transcribe_samples(&samples_16k, &model_id, true, self.should_translate())
+                    .await
             }
         };
 
+        if let (Err(e), Some((transcript, draft_model_id))) = (&result, draft) {
+            log::warn!("Refining the draft failed, keeping it: {}", e);
+            result = Ok(transcript);
+            model_id = draft_model_id;
+        }
+
+        // Cancelling already went back to idle, and a new recording may have
+        // started since
+        if self.cancelled_transcription.load(Ordering::SeqCst) == transcription_id {
+            log::info!("Discarding the result of a cancelled transcription");
+            return Err(TranscriptionCancelled.into());
+        }
+
         // Reset state
         {
             let mut state = self.state.lock().unwrap();
             *state = ManagerState::Idle;
+            self.active_transcription.store(0, Ordering::SeqCst);
+        }
+
+        let history_entry = match &result {
+            Ok(transcript) => match self.history.add_entry(
+                &transcript.text,
+                &transcript.segments,
+                &model_id,
+                &samples_16k,
+                source,
+            ) {
+                Ok(entry) => Some(entry.id),
+                Err(e) => {
+                    log::warn!("Failed to save transcription to history: {}", e);
+                    None
+                }
+            },
+            Err(_) => None,
+        };
+
+        result.map(|transcript| (transcript, history_entry))
+    }
+
+    /// Re-run a saved recording from the history through a (possibly different) model
+    pub async fn retranscribe(
+        &self,
+        recording_id: &str,
+        model_id: &str,
+    ) -> Result<TranscriptionResult, anyhow::Error> {
+        let samples = self.history.load_recording(recording_id)?;
+
+        {
+            let mut state = self.state.lock().unwrap();
+            if *state != ManagerState::Idle {
+                return Err(anyhow::anyhow!(
+                    "Cannot retranscribe: currently {:?}. Please wait for the current operation to complete.",
+                    *state
+                ));
+            }
+            *state = ManagerState::Transcribing;
+        }
+
+        log::info!(
+            "Retranscribing recording '{}' with model '{}'",
+            recording_id,
+            model_id
+        );
+        let translate = settings::get_settings(&self.app_handle).translate_to_english;
+        let result = self
+            .transcribe_samples(&samples, model_id, false, translate)
+            .await;
+
+        {
+            let mut state = self.state.lock().unwrap();
+            *state = ManagerState::Idle;
+        }
+
+        result
+    }
+
+    /// Transcribe an audio or video file with the given model. Long files are
+    /// transcribed in chunks, with progress reported after each.
+    pub async fn transcribe_file(
+        &self,
+        path: &Path,
+        model_id: &str,
+        translate: bool,
+    ) -> Result<TranscriptionResult, anyhow::Error> {
+        let model_info = self.model_manager.get_model_info(model_id).ok_or_else(|| {
+            TranscriptionError::ModelMissing(format!("Model not found: {}", model_id))
+        })?;
+
+        {
+            let mut state = self.state.lock().unwrap();
+            if *state != ManagerState::Idle {
+                return Err(anyhow::anyhow!(
+                    "Cannot transcribe file: currently {:?}. Please wait for the current operation to complete.",
+                    *state
+                ));
+            }
+            *state = ManagerState::Transcribing;
+        }
+
+        log::info!("Transcribing file {:?} with model '{}'", path, model_id);
+        let result = self
+            .transcribe_file_chunks(path, &model_info, translate, None)
+            .await;
+
+        {
+            let mut state = self.state.lock().unwrap();
+            *state = ManagerState::Idle;
+        }
+
+        result
+    }
+
+    /// Transcribe a file for the batch queue. Unlike `transcribe_file` this
+    /// leaves the recording state alone, so dictation works in the meantime.
+    /// Each chunk loads its model and transcribes under the model lock, so a
+    /// dictation with another model waits for the chunk rather than swapping
+    /// the model out in the middle of it.
+    pub async fn transcribe_queued_file(
+        &self,
+        path: &Path,
+        model_id: &str,
+        translate: bool,
+        control: &JobControl,
+    ) -> Result<TranscriptionResult, anyhow::Error> {
+        let model_info = self.model_manager.get_model_info(model_id).ok_or_else(|| {
+            TranscriptionError::ModelMissing(format!("Model not found: {}", model_id))
+        })?;
+
+        self.transcribe_file_chunks(path, &model_info, translate, Some(control))
+            .await
+    }
+
+    /// Transcribe a recording pushed from a phone (see [`crate::companion`])
+    /// with the selected model, saving it to the history like a dictation
+    pub async fn transcribe_companion_audio(
+        &self,
+        path: &Path,
+    ) -> Result<TranscriptionResult, anyhow::Error> {
+        let file = path.to_path_buf();
+        let samples_16k =
+            tauri::async_runtime::spawn_blocking(move || audio_file::load_16k_mono(&file))
+                .await??;
+
+        {
+            let mut state = self.state.lock().unwrap();
+            if *state != ManagerState::Idle {
+                return Err(anyhow::anyhow!(
+                    "Cannot transcribe phone recording: currently {:?}",
+                    *state
+                ));
+            }
+            *state = ManagerState::Transcribing;
+        }
+
+        let model_id = self.get_selected_model();
+        log::info!("Transcribing phone recording with model '{}'", model_id);
+        let translate = settings::get_settings(&self.app_handle).translate_to_english;
+        let result = self
+            .transcribe_samples(&samples_16k, &model_id, false, translate)
+            .await;
+
+        {
+            let mut state = self.state.lock().unwrap();
+            *state = ManagerState::Idle;
+        }
+
+        if let Ok(transcript) = &result {
+            let source = RecordingSource {
+                device: "Phone".to_string(),
+                sample_rate: 16000,
+                ..Default::default()
+            };
+            if let Err(e) = self.history.add_entry(
+                &transcript.text,
+                &transcript.segments,
+                &model_id,
+                &samples_16k,
+                source,
+            ) {
+                log::warn!("Failed to save transcription to history: {}", e);
+            }
         }
 
         result
     }
 
+    async fn transcribe_file_chunks(
+        &self,
+        path: &Path,
+        model_info: &ModelInfo,
+        translate: bool,
+        control: Option<&JobControl>,
+    ) -> Result<TranscriptionResult, anyhow::Error> {
+        let file = path.to_path_buf();
+        let samples_16k =
+            tauri::async_runtime::spawn_blocking(move || audio_file::load_16k_mono(&file))
+                .await??;
+
+        // A long file takes a while through the VAD, too long to hold up
+        // the async runtime
+        let (speech, vad) = tokio::task::block_in_place(|| self.apply_vad(&samples_16k, false));
+        if speech.is_empty() {
+            return Err(
+                TranscriptionError::NoSpeech("No speech detected in the file".to_string()).into(),
+            );
+        }
+
+        let chunks = audio_file::chunk_at_pauses(&speech);
+        let total_chunks = chunks.len();
+        let mut texts = Vec::new();
+        let mut segments = Vec::new();
+
+        for (index, range) in chunks.into_iter().enumerate() {
+            if let Some(control) = control {
+                control.wait_while_paused().await;
+                if control.is_cancelled() {
+                    return Err(anyhow::anyhow!("File transcription cancelled"));
+                }
+            }
+
+            let offset = range.start as f32 / 16000.0;
+            let chunk = self
+                .transcribe_speech(speech[range].to_vec(), model_info, translate)
+                .await?;
+
+            if !chunk.text.is_empty() {
+                texts.push(chunk.text);
+            }
+            segments.extend(chunk.segments.into_iter().map(|segment| TranscriptSegment {
+                start: segment.start + offset,
+                end: segment.end + offset,
+                ..segment
+            }));
+
+            let _ = self.app_handle.emit(
+                "file-transcription-progress",
+                serde_json::json!({
+                    "path": path,
+                    "chunk": index + 1,
+                    "total_chunks": total_chunks,
+                    "progress": (index + 1) as f32 / total_chunks as f32,
+                }),
+            );
+            if let Some(control) = control {
+                control.set_progress((index + 1) as f32 / total_chunks as f32);
+            }
+        }
+
+        let mut result = TranscriptionResult {
+            text: texts.join(" "),
+            segments,
+        };
+        tokio::task::block_in_place(|| self.diarize(&speech, &mut result));
+        if let Some(vad) = vad {
+            vad.restore_timeline(&mut result.segments);
+        }
+        Ok(result)
+    }
+
+    /// Run pre-captured 16kHz samples through VAD and the given model.
+    /// Segment timestamps are relative to `samples_16k`, silence included.
+    async fn transcribe_samples(
+        &self,
+        samples_16k: &[f32],
+        model_id: &str,
+        new_recording: bool,
+        translate: bool,
+    ) -> Result<TranscriptionResult, anyhow::Error> {
+        let model_info = self.model_manager.get_model_info(model_id).ok_or_else(|| {
+            TranscriptionError::ModelMissing(format!("Model not found: {}", model_id))
+        })?;
+
+        // The VAD and speaker models run ONNX inference, too slow to hold up
+        // the async runtime
+        let (samples_filtered, vad) =
+            tokio::task::block_in_place(|| self.apply_vad(samples_16k, new_recording));
+
+        if samples_filtered.is_empty() {
+            return Err(TranscriptionError::NoSpeech(
+                "No speech detected in the recording".to_string(),
+            )
+            .into());
+        }
+
+        let mut result = if settings::get_settings(&self.app_handle).diarization {
+            let mut result = self
+                .transcribe_speech(samples_filtered.clone(), &model_info, translate)
+                .await?;
+            tokio::task::block_in_place(|| self.diarize(&samples_filtered, &mut result));
+            result
+        } else {
+            self.transcribe_speech(samples_filtered, &model_info, translate)
+                .await?
+        };
+        if let Some(vad) = vad {
+            vad.restore_timeline(&mut result.segments);
+        }
+        Ok(result)
+    }
+
+    /// Label the segments of a transcript with speakers, when diarization is
+    /// on and its model is downloaded. `speech` is the audio the segment
+    /// times refer to.
+    fn diarize(&self, speech: &[f32], result: &mut TranscriptionResult) {
+        if result.segments.len() < 2 || !settings::get_settings(&self.app_handle).diarization {
+            return;
+        }
+
+        let model_id = ModelInfo::speaker_embedding().id;
+        if !self.model_manager.is_model_downloaded(&model_id) {
+            log::debug!("Speaker labels are on but the model isn't downloaded");
+            return;
+        }
+
+        let mut embedder = self.speaker_embedder.lock().unwrap();
+        if embedder.is_none() {
+            let loaded = self
+                .model_manager
+                .get_model_path(&model_id)
+                .and_then(|path| SpeakerEmbedder::new(&path));
+            match loaded {
+                Ok(loaded) => *embedder = Some(loaded),
+                Err(e) => {
+                    log::warn!("Failed to load the speaker embedding model: {}", e);
+                    return;
+                }
+            }
+        }
+        let Some(embedder) = embedder.as_mut() else {
+            return;
+        };
+
+        match diarization::label_speakers(embedder, speech, &mut result.segments) {
+            Ok(speakers) => {
+                log::info!("Found {} speakers", speakers);
+                self.model_manager.mark_used(&model_id);
+            }
+            Err(e) => log::warn!("Speaker diarization failed: {}", e),
+        }
+    }
+
+    /// Transcribe separate microphone and system audio turn by turn, tagging
+    /// each turn as "Me" or "Them"
+    async fn transcribe_speakers(
+        &self,
+        me: &[f32],
+        them: &[f32],
+        model_id: &str,
+        translate: bool,
+    ) -> Result<TranscriptionResult, anyhow::Error> {
+        let model_info = self.model_manager.get_model_info(model_id).ok_or_else(|| {
+            TranscriptionError::ModelMissing(format!("Model not found: {}", model_id))
+        })?;
+
+        let turns = speakers::speaker_turns(me, them);
+        log::info!("Transcribing {} speaker turns", turns.len());
+
+        let mut transcribed = Vec::with_capacity(turns.len());
+        for turn in turns {
+            let (speech, vad) =
+                tokio::task::block_in_place(|| self.apply_vad(turn.samples(me, them), false));
+            if speech.is_empty() {
+                continue;
+            }
+            let mut result = self
+                .transcribe_speech(speech, &model_info, translate)
+                .await?;
+            if let Some(vad) = vad {
+                vad.restore_timeline(&mut result.segments);
+            }
+            transcribed.push((turn, result));
+        }
+
+        let merged = speakers::merge_turns(transcribed);
+        if merged.text.is_empty() {
+            return Err(TranscriptionError::NoSpeech(
+                "No speech detected in the recording".to_string(),
+            )
+            .into());
+        }
+        Ok(merged)
+    }
+
+    /// Transcribe speech (already through the VAD) with a model, or
+    /// translate it to English, and apply the dictionary
+    async fn transcribe_speech(
+        &self,
+        samples_filtered: Vec<f32>,
+        model_info: &ModelInfo,
+        translate: bool,
+    ) -> Result<TranscriptionResult, anyhow::Error> {
+        let language = self.get_effective_language();
+
+        // Transcribe based on engine type
+        let (transcript, hints_used) = match model_info.engine_type {
+            // Only the cloud translates, whichever model is selected
+            _ if translate => {
+                let cloud_transcriber = self.cloud_transcriber();
+                log::info!(
+                    "Translating to English with cloud transcription ({})",
+                    cloud_transcriber.provider_name()
+                );
+                let transcript = cloud_transcriber
+                    .translate(samples_filtered, 16000, Some(&self.upload_progress()))
+                    .await?;
+                (transcript, false)
+            }
+            EngineType::Cloud => {
+                let cloud_transcriber = self.cloud_transcriber();
+                log::info!(
+                    "Using cloud transcription ({}), language: {}",
+                    cloud_transcriber.provider_name(),
+                    language
+                );
+                let transcript = cloud_transcriber
+                    .transcribe(
+                        samples_filtered,
+                        16000,
+                        language_param(&language),
+                        Some(&self.upload_progress()),
+                    )
+                    .await?;
+                (transcript, false)
+            }
+            EngineType::Parakeet => {
+                log::info!("Using local transcription ({})", model_info.name);
+
+                let transcript = self
+                    .transcribe_locally(model_info, samples_filtered)
+                    .await?;
+
+                self.model_manager.mark_used(&model_info.id);
+                (transcript, self.local.transcriber.supports_phrase_hints())
+            }
+            EngineType::Diarization => {
+                return Err(anyhow::anyhow!(
+                    "{} isn't a transcription model",
+                    model_info.name
+                ));
+            }
+        };
+
+        let dictionary = settings::get_settings(&self.app_handle).dictionary;
+        Ok(TranscriptionResult {
+            text: dictionary.apply(&transcript.text, hints_used),
+            segments: transcript
+                .segments
+                .into_iter()
+                .map(|segment| TranscriptSegment {
+                    text: dictionary.apply(&segment.text, hints_used),
+                    ..segment
+                })
+                .collect(),
+        })
+    }
+
+    /// Apply VAD if enabled, returning the speech-only samples and, when
+    /// silence was cut, the analysis that maps their times back. New
+    /// recordings get the input device's calibration and feed the adaptive
+    /// prefill.
+    fn apply_vad(
+        &self,
+        samples_16k: &[f32],
+        new_recording: bool,
+    ) -> (Vec<f32>, Option<VadAnalysis>) {
+        if !self.is_vad_enabled() {
+            return (samples_16k.to_vec(), None);
+        }
+
+        let vad_path = self.vad_model_path.lock().unwrap().clone();
+        match self.filter_with_vad(samples_16k, vad_path.as_deref(), new_recording) {
+            Ok((filtered, analysis)) => {
+                if new_recording {
+                    self.learn_prefill(samples_16k, &analysis);
+                }
+
+                let original_duration = samples_16k.len() as f32 / 16000.0;
+                let filtered_duration = filtered.len() as f32 / 16000.0;
+                log::info!(
+                    "VAD applied: original {:.2}s, filtered {:.2}s. ({:.1}% retained)",
+                    original_duration,
+                    filtered_duration,
+                    (filtered_duration / original_duration) * 100.0,
+                );
+                (filtered, Some(analysis))
+            }
+            Err(e) => {
+                log::error!("VAD processing failed: {}. Proceeding without VAD.", e);
+                (samples_16k.to_vec(), None)
+            }
+        }
+    }
+
     /// Filter audio using VAD to remove silence
     fn filter_with_vad(
         &self,
         samples: &[f32],
-        vad_path: &PathBuf,
-    ) -> Result<Vec<f32>, anyhow::Error> {
-        use crate::vad::VoiceActivityDetector;
+        vad_path: Option<&Path>,
+        live: bool,
+    ) -> Result<(Vec<f32>, VadAnalysis), anyhow::Error> {
+        self.with_cached_vad(vad_path, &self.vad_params(live), |vad| {
+            filter_and_analyze(vad, samples)
+        })
+    }
 
-        let silero = SileroVad::new(vad_path, 0.5)?;
-        let mut smoothed_vad = SmoothedVad::with_defaults(Box::new(silero));
+    /// Run the VAD kept loaded between recordings, reset and set to `params`.
+    /// It's loaded on first use and reloaded when the engine or the model
+    /// file changes.
+    fn with_cached_vad<T>(
+        &self,
+        vad_path: Option<&Path>,
+        params: &VadParams,
+        run: impl FnOnce(&mut SmoothedVad) -> Result<T, anyhow::Error>,
+    ) -> Result<T, anyhow::Error> {
+        let engine = settings::get_settings(&self.app_handle).vad_engine;
+        let modified = vad_path
+            .and_then(|path| std::fs::metadata(path).ok())
+            .and_then(|metadata| metadata.modified().ok());
 
-        let mut speech_samples = Vec::new();
+        let mut cache = self.vad_cache.lock().unwrap();
+        let cached = match cache.take() {
+            Some(cached)
+                if cached.engine == engine
+                    && cached.path.as_deref() == vad_path
+                    && cached.modified == modified =>
+            {
+                cached
+            }
+            stale => {
+                if stale.is_some() {
+                    log::info!("VAD engine or model changed, reloading it");
+                }
+                CachedVad {
+                    engine,
+                    path: vad_path.map(Path::to_path_buf),
+                    modified,
+                    vad: build_vad(engine, vad_path, params)?,
+                }
+            }
+        };
 
-        for chunk in samples.chunks(VAD_FRAME_SAMPLES) {
-            let frame: Vec<f32> = if chunk.len() < VAD_FRAME_SAMPLES {
-                let mut padded = chunk.to_vec();
-                padded.resize(VAD_FRAME_SAMPLES, 0.0);
-                padded
-            } else {
-                chunk.to_vec()
-            };
+        let cached = cache.insert(cached);
+        cached.vad.set_params(params);
+        cached.vad.reset();
+        run(&mut cached.vad)
+    }
+
+    /// Build the detector used for live speech indication and auto-stop, if
+    /// either is enabled and a VAD engine is available
+    fn build_live_vad(&self, settings: &AppSettings, hands_free: bool) -> Option<SmoothedVad> {
+        if !settings.live_vad_indicator && !settings.auto_stop_on_silence && !hands_free {
+            return None;
+        }
+
+        let vad_path = self.vad_model_path.lock().unwrap().clone();
+        build_vad(
+            settings.vad_engine,
+            vad_path.as_deref(),
+            &self.vad_params(true),
+        )
+        .map_err(|e| log::warn!("Failed to create live VAD: {}", e))
+        .ok()
+    }
+
+    /// Re-run a saved recording through the VAD with the given parameters,
+    /// reporting what would have been kept
+    pub async fn replay_vad(
+        &self,
+        recording_id: &str,
+        params: &VadParams,
+    ) -> Result<VadAnalysis, anyhow::Error> {
+        let samples = self.history.load_recording(recording_id)?;
+        let vad_path = match settings::get_settings(&self.app_handle).vad_engine {
+            VadEngine::Energy => None,
+            VadEngine::Silero => Some(self.ensure_vad_model().await?),
+            VadEngine::Auto => self
+                .ensure_vad_model()
+                .await
+                .map_err(|e| log::warn!("Failed to get the Silero VAD model: {}", e))
+                .ok(),
+        };
+
+        let analysis = self.with_cached_vad(vad_path.as_deref(), params, |vad| {
+            analyze_speech(vad, &samples)
+        })?;
+
+        log::debug!(
+            "VAD replay of '{}' with {:?}: {:.2}s of {:.2}s retained in {} segments",
+            recording_id,
+            params,
+            analysis.retained_secs,
+            analysis.total_secs,
+            analysis.segments.len()
+        );
+
+        Ok(analysis)
+    }
+
+    /// Run the most recent saved recording through the VAD with the current
+    /// settings, reporting how much of it would be kept
+    pub async fn test_vad_on_last_recording(&self) -> Result<VadTest, anyhow::Error> {
+        let recording_id = self
+            .history
+            .get_entries()
+            .into_iter()
+            .find(|entry| entry.audio_file.is_some())
+            .map(|entry| entry.id)
+            .ok_or_else(|| anyhow::anyhow!("No saved recording to test the VAD on"))?;
+
+        let params = self.vad_params(false);
+        let analysis = self.replay_vad(&recording_id, &params).await?;
+        Ok(VadTest {
+            recording_id,
+            params,
+            retained_percent: analysis.retained_percent(),
+            analysis,
+        })
+    }
+
+    /// Warn, and tell the frontend, when a recording dropped more buffers
+    /// than `dropped_buffer_warning`: its transcript may be garbled because
+    /// the system couldn't keep up
+    fn warn_dropped_buffers(&self, dropped_buffers: u64) {
+        let threshold = settings::get_settings(&self.app_handle).dropped_buffer_warning;
+        if threshold == 0 || dropped_buffers <= threshold {
+            return;
+        }
+
+        log::warn!(
+            "The recording dropped {} audio buffers, the system may be overloaded",
+            dropped_buffers
+        );
+        let _ = self.app_handle.emit(
+            events::RECORDING_BUFFERS_DROPPED,
+            serde_json::json!({ "dropped_buffers": dropped_buffers, "threshold": threshold }),
+        );
+    }
+
+    /// Fail over to the default microphone when the recording's stream is
+    /// lost (e.g. a USB microphone was unplugged), or abort the recording
+    /// when there's nothing to fail over to
+    fn handle_stream_lost(&self, stream_id: usize, reason: &str) {
+        let mut state = self.state.lock().unwrap();
+        if *state != ManagerState::Recording || self.stream_id.load(Ordering::SeqCst) != stream_id {
+            return;
+        }
+        let mut recorder_guard = self.recorder.lock().unwrap();
+        let Some(mut lost) = recorder_guard.take() else {
+            return;
+        };
+
+        let lost_device = self.input_device.lock().unwrap().clone();
+        log::warn!(
+            "Lost the audio stream from {}: {}",
+            lost_device.as_deref().unwrap_or("the capture source"),
+            reason
+        );
+
+        let mut samples = lost.stop().unwrap_or_default();
+        let sample_rate = lost.sample_rate();
+        if let Some(source) = self.recording_source.lock().unwrap().as_mut() {
+            source.add_capture_stats(&lost.capture_stats());
+        }
+        let _ = lost.close();
+        *self.warm_stream.lock().unwrap() = None;
+        if let Some(profile) = *self.device_profile.lock().unwrap() {
+            profile.apply_gain(&mut samples);
+        }
+
+        // Only a microphone can fall back, and only to a different device
+        let settings = settings::get_settings(&self.app_handle);
+        let fallback = lost_device.as_ref().and_then(|lost_id| {
+            audio::find_input_device(None)
+                .ok()
+                .filter(|(id, _)| id != lost_id)
+        });
+        let resumed = fallback.map(|(id, device)| {
+            let name = audio::device_name(&device);
+            *self.device_profile.lock().unwrap() =
+                device_profiles::get_profile(&self.app_handle, &name);
+            let hands_free = self.auto_stop_secs.lock().unwrap().is_some();
+            let live_vad = self.build_live_vad(&settings, hands_free);
+
+            let recorder =
+                self.new_recorder(&settings, live_vad.is_some())
+                    .and_then(|mut recorder| {
+                        recorder.set_config_override(input_device_config(&settings, &id, &name));
+                        recorder.open(Some(device))?;
+                        recorder.start()?;
+                        Ok(recorder)
+                    });
+            (id, name, live_vad, recorder)
+        });
 
-            match smoothed_vad.push_frame(&frame)? {
-                VadFrame::Speech(speech) => {
-                    speech_samples.extend_from_slice(speech);
+        match resumed {
+            Some((id, name, live_vad, Ok(recorder))) => {
+                log::info!("Recording continues on {}", name);
+                *self.live_vad.lock().unwrap() =
+                    live_vad.map(|vad| StreamingVad::new(Box::new(vad), recorder.sample_rate()));
+                // The preview starts over, as the new device may run at another rate
+                if let Some(preview) = self.preview_audio.lock().unwrap().as_mut() {
+                    preview.clear();
+                }
+                self.lost_device_audio
+                    .lock()
+                    .unwrap()
+                    .push((samples, sample_rate));
+                if let Some(source) = self.recording_source.lock().unwrap().as_mut() {
+                    source.device = name.clone();
+                    source.sample_rate = recorder.sample_rate();
+                    source.stages.push("device_failover".to_string());
                 }
-                VadFrame::Noise => {
-                    // Skip Silence
+                *self.input_device.lock().unwrap() = Some(id.clone());
+                *recorder_guard = Some(recorder);
+
+                let _ = self.app_handle.emit(
+                    events::RECORDING_DEVICE_LOST,
+                    DeviceLost {
+                        device: lost_device,
+                        reason: reason.to_string(),
+                        fallback: Some(id),
+                    },
+                );
+            }
+            resumed => {
+                if let Some((_, name, _, Err(e))) = resumed {
+                    log::error!("Failed to continue recording on {}: {}", name, e);
                 }
+                log::warn!("No device to continue recording on, aborting the recording");
+
+                if let Some(mut system_recorder) = self.system_recorder.lock().unwrap().take() {
+                    let _ = system_recorder.stop();
+                    let _ = system_recorder.close();
+                }
+                *self.recording_source.lock().unwrap() = None;
+                *self.live_vad.lock().unwrap() = None;
+                *self.preview_audio.lock().unwrap() = None;
+                *self.capture_started.lock().unwrap() = None;
+                self.lost_device_audio.lock().unwrap().clear();
+                *state = ManagerState::Idle;
+                drop(recorder_guard);
+                drop(state);
+
+                let _ = self.app_handle.emit(
+                    events::RECORDING_DEVICE_LOST,
+                    DeviceLost {
+                        device: lost_device,
+                        reason: reason.to_string(),
+                        fallback: None,
+                    },
+                );
+                shortcut::abort_active_recording(&self.app_handle, OverlayErrorCode::DeviceLost);
             }
         }
+    }
 
-        Ok(speech_samples)
+    /// Close the microphone stream kept open between recordings, e.g. once
+    /// keeping it warm is turned off. A recording in progress closes it when
+    /// it ends.
+    pub fn close_warm_stream(&self) {
+        let state = self.state.lock().unwrap();
+        if self.warm_stream.lock().unwrap().take().is_none() {
+            return;
+        }
+        if *state == ManagerState::Idle {
+            if let Some(mut recorder) = self.recorder.lock().unwrap().take() {
+                log::info!("Closing the warm audio stream");
+                let _ = recorder.close();
+            }
+        }
     }
 
     pub fn cancel(&self) {
@@ -333,40 +2233,139 @@ impl RecordingManager {
 
         if let Some(recorder) = recorder_guard.as_mut() {
             let _ = recorder.stop();
-            let _ = recorder.close();
+            if self.warm_stream.lock().unwrap().is_none() {
+                let _ = recorder.close();
+                *recorder_guard = None;
+            }
+        }
+        *self.recording_source.lock().unwrap() = None;
+        if let Some(mut system_recorder) = self.system_recorder.lock().unwrap().take() {
+            let _ = system_recorder.stop();
+            let _ = system_recorder.close();
         }
-        *recorder_guard = None;
+        *self.live_vad.lock().unwrap() = None;
+        *self.preview_audio.lock().unwrap() = None;
+        *self.capture_started.lock().unwrap() = None;
+        self.lost_device_audio.lock().unwrap().clear();
         *state = ManagerState::Idle;
 
         log::info!("Recording cancelled.");
     }
 
+    /// Give up on the transcription in progress: its result is thrown away
+    /// and a new recording can start straight away. Returns whether there
+    /// was one to cancel.
+    pub fn cancel_transcription(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let active = self.active_transcription.swap(0, Ordering::SeqCst);
+        if *state != ManagerState::Transcribing || active == 0 {
+            return false;
+        }
+
+        self.cancelled_transcription.store(active, Ordering::SeqCst);
+        *state = ManagerState::Idle;
+        self.transcription_cancel.notify_waiters();
+        log::info!("Transcription cancelled.");
+        true
+    }
+
+    /// Completes when the transcription in progress is cancelled, to stop
+    /// waiting on it (e.g. on a slow upload) right away
+    pub fn transcription_cancelled(&self) -> Notified<'_> {
+        self.transcription_cancel.notified()
+    }
+
+    /// What to tell the user about a transcription that's running long
+    pub fn slow_transcription(&self, elapsed_secs: u32) -> SlowTranscription {
+        let model_id = self.transcription_model_id();
+        SlowTranscription {
+            elapsed_secs,
+            faster_model: self
+                .fastest_local_model()
+                .filter(|model| model.id != model_id),
+            model_id,
+        }
+    }
+
     pub fn unload_local_model(&self) {
-        self.local_transcriber.unload_model();
+        self.local.unload();
     }
 }
 
 impl Drop for RecordingManager {
     fn drop(&mut self) {
         self.cancel();
-        self.local_transcriber.unload_model();
+        self.local.transcriber.unload_model();
     }
 }
 
-fn resample_to_16k(samples: &[f32], from_rate: u32) -> Vec<f32> {
-    let ratio = 16000.0 / from_rate as f64;
-    let new_len = (samples.len() as f64 * ratio) as usize;
-    let mut output = Vec::with_capacity(new_len);
+/// Build the detector + smoothing pipeline for a parameter set
+fn build_vad(
+    engine: VadEngine,
+    vad_path: Option<&Path>,
+    params: &VadParams,
+) -> Result<SmoothedVad, anyhow::Error> {
+    let detector = build_detector(engine, vad_path, params.threshold)?;
+    Ok(SmoothedVad::from_params(detector, params))
+}
+
+/// Build the cloud provider config from settings, with the API key from
+/// the keyring, settings or environment (see `credentials`)
+fn cloud_config_from_settings(settings: &AppSettings) -> CloudConfig {
+    cloud_config_for(settings, settings.cloud_provider())
+}
 
-    for i in 0..new_len {
-        let src_idx = i as f64 / ratio;
-        let idx_floor = src_idx.floor() as usize;
-        let idx_ceil = (idx_floor + 1).min(samples.len() - 1);
-        let frac = src_idx - idx_floor as f64;
+/// The config a provider would be built with under these settings. A team
+/// endpoint only gets the team's own key, and its URL is only used for the
+/// team's provider.
+fn cloud_config_for(settings: &AppSettings, provider: CloudProviderType) -> CloudConfig {
+    let (api_key, base_url) = match &settings.team_endpoint {
+        Some(endpoint) if endpoint.provider == provider => {
+            (endpoint.api_key.clone(), non_empty(&endpoint.base_url))
+        }
+        Some(_) => (credentials::api_key(settings, provider), None),
+        None => (
+            credentials::api_key(settings, provider),
+            non_empty(&settings.cloud_base_url),
+        ),
+    };
 
-        let sample = samples[idx_floor] as f64 * (1.0 - frac) + samples[idx_ceil] as f64 * frac;
-        output.push(sample as f32);
+    CloudConfig {
+        provider,
+        api_key,
+        base_url,
+        model: non_empty(&settings.cloud_model),
+        azure_region: settings.azure_region.clone(),
     }
+}
+
+/// The stream config override for an input device from settings, by its id
+/// or else its name (empty, so chosen automatically, if it has neither)
+pub fn input_device_config(
+    settings: &AppSettings,
+    device_id: &str,
+    device_name: &str,
+) -> DeviceConfigOverride {
+    settings
+        .input_device_configs
+        .get(device_id)
+        .or_else(|| settings.input_device_configs.get(device_name))
+        .cloned()
+        .unwrap_or_default()
+}
 
-    output
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value
+        .as_ref()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Convert a language setting into an engine parameter ("auto" means let the engine detect)
+fn language_param(language: &str) -> Option<&str> {
+    if language.is_empty() || language == "auto" {
+        None
+    } else {
+        Some(language)
+    }
 }
@@ -0,0 +1,123 @@
+//! Review-before-paste window
+//!
+//! When review is on, or the engine is unsure of a transcription, the text is
+//! held in a small editable popup instead of being pasted. Accepting pastes the
+//! (possibly corrected) text into the app that was focused before; discarding,
+//! closing the popup or letting its countdown run out drops it. The original
+//! transcription stays in the history either way.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use iv_core::transcript::TranscriptionResult;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::clipboard;
+
+const REVIEW_WINDOW_LABEL: &str = "review";
+const REVIEW_WIDTH: f64 = 420.0;
+const REVIEW_HEIGHT: f64 = 240.0;
+
+/// Time for the previously focused app to get focus back after the review
+/// window hides, before pasting into it
+const REFOCUS_DELAY_MS: u64 = 250;
+
+/// A transcription waiting for the user to accept or discard it
+#[derive(Serialize, Debug, Clone)]
+pub struct PendingReview {
+    pub result: TranscriptionResult,
+    /// Binding that recorded it, used for the paste template
+    pub binding_id: String,
+    /// Seconds before the review is discarded (0 = wait indefinitely)
+    pub timeout_secs: u32,
+    /// Confidence below which segments are highlighted, when the engine
+    /// reports confidence
+    pub confidence_threshold: f32,
+}
+
+#[derive(Default)]
+pub struct ReviewState {
+    pending: Mutex<Option<PendingReview>>,
+}
+
+/// Hold a transcription and show it in the review window, replacing any
+/// review still open
+pub fn request_review(app_handle: &AppHandle, review: PendingReview) -> Result<(), String> {
+    let state = app_handle
+        .try_state::<ReviewState>()
+        .ok_or_else(|| "Review state not initialized".to_string())?;
+    *state.pending.lock().unwrap() = Some(review.clone());
+
+    if let Some(window) = app_handle.get_webview_window(REVIEW_WINDOW_LABEL) {
+        let _ = app_handle.emit_to(REVIEW_WINDOW_LABEL, "review-requested", &review);
+        let _ = window.show();
+        let _ = window.set_focus();
+        return Ok(());
+    }
+
+    // A new window fetches the pending review when it loads
+    WebviewWindowBuilder::new(
+        app_handle,
+        REVIEW_WINDOW_LABEL,
+        WebviewUrl::App("src/review/index.html".into()),
+    )
+    .title("Review transcription")
+    .inner_size(REVIEW_WIDTH, REVIEW_HEIGHT)
+    .resizable(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .center()
+    .focused(true)
+    .build()
+    .map_err(|e| format!("Failed to create review window: {}", e))?;
+
+    log::debug!("Review window created");
+    Ok(())
+}
+
+/// The transcription currently waiting for review
+pub fn pending_review(app_handle: &AppHandle) -> Option<PendingReview> {
+    let state = app_handle.try_state::<ReviewState>()?;
+    let pending = state.pending.lock().unwrap().clone();
+    pending
+}
+
+fn take_pending(app_handle: &AppHandle) -> Option<PendingReview> {
+    let state = app_handle.try_state::<ReviewState>()?;
+    let pending = state.pending.lock().unwrap().take();
+    pending
+}
+
+fn hide_window(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_webview_window(REVIEW_WINDOW_LABEL) {
+        let _ = window.hide();
+    }
+}
+
+/// Paste the reviewed text into the app that was focused before the review
+pub async fn accept(app_handle: &AppHandle, text: String) -> Result<(), String> {
+    let review =
+        take_pending(app_handle).ok_or_else(|| "No transcription to review".to_string())?;
+
+    hide_window(app_handle);
+    tokio::time::sleep(Duration::from_millis(REFOCUS_DELAY_MS)).await;
+
+    log::info!("Review accepted, pasting {} chars", text.len());
+    clipboard::paste(text, app_handle, &review.binding_id)
+}
+
+/// Drop the transcription under review without pasting it
+pub fn discard(app_handle: &AppHandle) {
+    if take_pending(app_handle).is_some() {
+        log::info!("Review discarded");
+    }
+    hide_window(app_handle);
+}
+
+/// Closing the review window discards the review; the window is hidden, not
+/// destroyed, so the next review opens instantly
+pub fn handle_close_requested(window: &tauri::Window, api: &tauri::CloseRequestApi) {
+    api.prevent_close();
+    discard(window.app_handle());
+}
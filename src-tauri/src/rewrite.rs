@@ -0,0 +1,120 @@
+//! LLM rewrite step after transcription
+//!
+//! With rewriting on, the transcript is sent through the selected preset (or
+//! custom prompt) before formatting and pasting. Both versions are emitted as
+//! `transcription-rewritten`, so the original stays available, and history
+//! keeps the original. A rewrite that fails or runs past its timeout is
+//! skipped and the original is pasted.
+//!
+//! A shortcut binding can carry its own rewrite prompt, used instead of the
+//! global preset whether or not rewriting is on, so one hotkey can paste raw
+//! dictation while another writes an email reply.
+
+use std::time::Duration;
+
+use iv_core::cloud_transcribe::CloudProviderType;
+use iv_core::rewrite::{self, RewriteConfig, RewritePreset};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::credentials;
+use crate::settings::AppSettings;
+use crate::shortcut::events;
+
+#[derive(Serialize, Debug, Clone)]
+struct RewrittenTranscript<'a> {
+    original: &'a str,
+    text: &'a str,
+    preset: RewritePreset,
+}
+
+fn rewrite_config(settings: &AppSettings) -> RewriteConfig {
+    let base_url = settings
+        .rewrite_base_url
+        .as_ref()
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty());
+    // Local servers don't need the OpenAI key
+    let api_key = match base_url {
+        Some(_) => None,
+        None => credentials::api_key(settings, CloudProviderType::OpenAi),
+    };
+
+    RewriteConfig {
+        api_key,
+        base_url,
+        model: settings.rewrite_model.clone(),
+    }
+}
+
+/// The preset and instructions to rewrite a binding's transcripts with, if any
+fn rewrite_instructions(
+    settings: &AppSettings,
+    binding_id: &str,
+) -> Option<(RewritePreset, String)> {
+    let binding_prompt = settings
+        .bindings
+        .get(binding_id)
+        .and_then(|binding| binding.rewrite_prompt.as_deref());
+
+    match binding_prompt {
+        // An empty prompt keeps the binding's dictation raw
+        Some(prompt) => RewritePreset::Custom
+            .instructions(prompt)
+            .map(|instructions| (RewritePreset::Custom, instructions)),
+        None if settings.rewrite_enabled => {
+            let instructions = settings
+                .rewrite_preset
+                .instructions(&settings.rewrite_custom_prompt);
+            if instructions.is_none() {
+                log::warn!("Rewrite is on with a custom preset but no prompt, skipping it");
+            }
+            instructions.map(|instructions| (settings.rewrite_preset, instructions))
+        }
+        None => None,
+    }
+}
+
+/// Rewrite a transcript if rewriting is on, or the binding has its own
+/// prompt. Returns None when there's nothing to do, or when the rewrite
+/// failed and the original should be used.
+pub async fn rewrite_transcript(
+    app_handle: &AppHandle,
+    settings: &AppSettings,
+    binding_id: &str,
+    text: &str,
+) -> Option<String> {
+    if text.trim().is_empty() {
+        return None;
+    }
+    let (preset, instructions) = rewrite_instructions(settings, binding_id)?;
+
+    let timeout = Duration::from_millis(settings.rewrite_timeout_ms);
+    let config = rewrite_config(settings);
+    let rewritten =
+        match tokio::time::timeout(timeout, rewrite::rewrite(&config, &instructions, text)).await {
+            Ok(Ok(rewritten)) => rewritten,
+            Ok(Err(e)) => {
+                log::warn!("{}, keeping the original transcript", e);
+                return None;
+            }
+            Err(_) => {
+                log::warn!(
+                    "Rewrite took longer than {}ms, keeping the original transcript",
+                    settings.rewrite_timeout_ms
+                );
+                return None;
+            }
+        };
+
+    log::info!("Rewrote transcript with {:?}: {}", preset, rewritten);
+    let _ = app_handle.emit(
+        events::TRANSCRIPTION_REWRITTEN,
+        RewrittenTranscript {
+            original: text,
+            text: &rewritten,
+            preset,
+        },
+    );
+    Some(rewritten)
+}
@@ -1,5 +1,17 @@
 //! Application settings management
 
+use iv_core::audio::DeviceConfigOverride;
+use iv_core::cloud_transcribe::CloudProviderType;
+use iv_core::rewrite::RewritePreset;
+use iv_core::vad::VadEngine;
+
+use crate::commands::{self, VoiceCommand};
+use crate::dictionary::Dictionary;
+use crate::event_throttle::DEFAULT_MAX_EVENT_RATE_HZ;
+use crate::formatting::{CasingStyle, CodeMode, PostProcessPlugin};
+use crate::hooks::RecordingHook;
+use crate::team_config::{self, TeamEndpoint};
+
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tauri::AppHandle;
@@ -15,6 +27,24 @@ pub struct ShortcutBinding {
     pub description: String,
     pub default_binding: String,
     pub current_binding: String,
+    /// Casing override for transcripts from this binding (None = use the global style)
+    #[serde(default)]
+    pub casing: Option<CasingStyle>,
+    /// Name of the output template from `AppSettings::templates` to wrap transcripts in
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Translate speech in any language to English (cloud transcription only)
+    #[serde(default)]
+    pub translate: bool,
+    /// Terminator for transcripts from this binding, over the app and global
+    /// ones (None = don't override)
+    #[serde(default)]
+    pub terminator: Option<Terminator>,
+    /// Rewrite prompt for transcripts from this binding, used instead of the
+    /// global rewrite preset (None = use the global setting, empty = never
+    /// rewrite)
+    #[serde(default)]
+    pub rewrite_prompt: Option<String>,
 }
 
 /// Overlay position options
@@ -25,6 +55,54 @@ pub enum OverlayPosition {
     Top,
     #[default]
     Bottom,
+    /// Where the user dragged it on each monitor, or the bottom on monitors
+    /// it hasn't been dragged on
+    Custom,
+}
+
+/// Which monitor the overlay appears on
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayMonitor {
+    /// The one with the mouse cursor
+    #[default]
+    Cursor,
+    /// The one with the window that has keyboard focus, where the text goes
+    FocusedWindow,
+    /// Always `overlay_fixed_monitor`
+    Fixed,
+}
+
+/// Where the overlay was dragged to on a monitor, in logical pixels from the
+/// top-left corner of the monitor's work area
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct OverlayCoordinates {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Where recordings take their audio from
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioSource {
+    #[default]
+    Microphone,
+    /// Everything the system is playing (loopback), e.g. the other side of a call
+    System,
+    /// The microphone and the system audio mixed together
+    Mixed,
+}
+
+/// What to do with downloaded models that haven't been used in a while
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelCleanupPolicy {
+    #[default]
+    Off,
+    /// Emit an event listing them so the user can confirm removal
+    Prompt,
+    /// Remove them at startup
+    Automatic,
 }
 
 /// Paste method options
@@ -39,6 +117,19 @@ pub enum PasteMethod {
     CtrlShiftV,
 }
 
+/// How previous clipboard contents above `large_clipboard_threshold_kb` are restored
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LargeClipboardRestore {
+    /// Restore on a background thread, so pasting returns right away
+    #[default]
+    Background,
+    /// Leave the transcription on the clipboard
+    Skip,
+    /// Restore before pasting returns, like small contents
+    Blocking,
+}
+
 /// Clipboard handling options
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
 #[serde(rename_all = "snake_case")]
@@ -48,44 +139,544 @@ pub enum ClipboardHandling {
     CopyToClipboard,
 }
 
+/// What ends a pasted transcript
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Terminator {
+    /// A trailing space, so the next dictation starts a new word (editors)
+    #[default]
+    Space,
+    /// A line break in the text
+    Newline,
+    /// An Enter keypress after pasting, e.g. to send a chat message
+    Enter,
+    None,
+}
+
+impl Terminator {
+    /// End text with the terminator's characters, unless it already ends in
+    /// whitespace that does the same job (e.g. a template ending in a newline)
+    pub fn append_to(self, text: String) -> String {
+        match self {
+            Terminator::Space if !text.ends_with(char::is_whitespace) => format!("{} ", text),
+            Terminator::Newline if !text.ends_with('\n') => format!("{}\n", text),
+            _ => text,
+        }
+    }
+}
+
+/// How a recording session was activated
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivationMode {
+    /// Hold the shortcut to record, release to transcribe
+    #[default]
+    PushToTalk,
+    /// Hands-free recording that ends on its own (e.g. after silence)
+    Continuous,
+    /// Recording started by a spoken wake phrase
+    WakeWord,
+}
+
+/// Feedback sounds for a single activation mode
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModeFeedback {
+    /// Play the start sound when recording begins
+    pub start_sound: bool,
+    /// Play the stop sound when transcription completes
+    pub stop_sound: bool,
+}
+
+/// Per-mode feedback sound preferences
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct FeedbackSettings {
+    pub push_to_talk: ModeFeedback,
+    pub continuous: ModeFeedback,
+    pub wake_word: ModeFeedback,
+}
+
+impl Default for FeedbackSettings {
+    fn default() -> Self {
+        Self {
+            push_to_talk: ModeFeedback {
+                start_sound: true,
+                stop_sound: true,
+            },
+            // A beep on every segment is maddening in hands-free modes
+            continuous: ModeFeedback {
+                start_sound: false,
+                stop_sound: true,
+            },
+            wake_word: ModeFeedback {
+                start_sound: false,
+                stop_sound: true,
+            },
+        }
+    }
+}
+
+impl FeedbackSettings {
+    /// Get the feedback preferences for an activation mode
+    pub fn for_mode(&self, mode: ActivationMode) -> ModeFeedback {
+        match mode {
+            ActivationMode::PushToTalk => self.push_to_talk,
+            ActivationMode::Continuous => self.continuous,
+            ActivationMode::WakeWord => self.wake_word,
+        }
+    }
+}
+
+/// How feedback events (recording starting and stopping, transcriptions
+/// completing, failing or being cancelled) are signalled
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedbackMode {
+    /// Play a sound
+    #[default]
+    Sound,
+    /// Flash the overlay instead, for meetings and other silent places
+    OverlayFlash,
+    /// Blink the tray icon instead
+    TrayBlink,
+    /// Flash the overlay and blink the tray icon
+    Visual,
+}
+
+/// Feedback sounds for what happens after recording, in every activation mode
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct SoundEvents {
+    /// Play the complete sound when the transcription is ready, in place of
+    /// the stop sound
+    pub complete: bool,
+    /// Play a sound when recording or transcription fails
+    pub error: bool,
+    /// Play a sound when a transcription is cancelled
+    pub cancelled: bool,
+}
+
+impl Default for SoundEvents {
+    fn default() -> Self {
+        Self {
+            complete: false,
+            error: true,
+            cancelled: false,
+        }
+    }
+}
+
+/// Audio files played instead of the built-in feedback sounds (None = built-in)
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct CustomSounds {
+    pub start: Option<String>,
+    pub stop: Option<String>,
+    pub complete: Option<String>,
+    pub error: Option<String>,
+    pub cancelled: Option<String>,
+}
+
+/// API keys for each cloud provider kept in the settings store. A key in the
+/// OS keyring wins over these, and these over the environment.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct CloudApiKeys {
+    pub openai: Option<String>,
+    pub groq: Option<String>,
+    pub deepgram: Option<String>,
+    pub azure: Option<String>,
+    /// Optional bearer token for a self-hosted whisper server
+    pub self_hosted: Option<String>,
+}
+
+impl CloudApiKeys {
+    /// Get the stored (non-empty) key for a provider
+    pub fn get(&self, provider: CloudProviderType) -> Option<String> {
+        let key = match provider {
+            CloudProviderType::OpenAi => &self.openai,
+            CloudProviderType::Groq => &self.groq,
+            CloudProviderType::Deepgram => &self.deepgram,
+            CloudProviderType::Azure => &self.azure,
+            CloudProviderType::SelfHosted => &self.self_hosted,
+            CloudProviderType::Mock => return None,
+        };
+        key.clone().filter(|k| !k.trim().is_empty())
+    }
+}
+
 /// Main application settings
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
 pub struct AppSettings {
     /// Keyboard shortcut bindings
     pub bindings: HashMap<String, ShortcutBinding>,
+    /// Built-in bindings these settings have been given, so one added in a
+    /// newer version shows up once and one the user removed stays removed.
+    /// Empty in settings saved before it existed.
+    #[serde(default)]
+    pub known_bindings: Vec<String>,
 
     /// Selected transcription model ID
     pub selected_model: String,
 
-    /// Selected microphone device name (None = default)
+    /// Load the selected local model at launch instead of on first use
+    pub preload_model_on_startup: bool,
+
+    /// Cleanup policy for models that haven't been used recently
+    pub model_cleanup: ModelCleanupPolicy,
+
+    /// Days without use before a model counts as unused
+    pub model_cleanup_days: u32,
+
+    /// Use models from a machine-wide folder shared by every account, and
+    /// download into it when this account can write to it
+    pub shared_models: bool,
+
+    /// The machine-wide models folder (None = the platform's: ProgramData on
+    /// Windows, /Library/Application Support on macOS, /usr/share elsewhere)
+    pub shared_models_dir: Option<String>,
+
+    /// Trade accuracy for latency: transcribe with the fastest downloaded
+    /// model, cut the VAD hangover short, skip noise suppression and load
+    /// the model while the key is still held
+    pub fast_mode: bool,
+
+    /// With a cloud model selected, paste a draft from the fastest downloaded
+    /// local model right away and replace it once the cloud result arrives
+    pub draft_then_refine: bool,
+
+    /// Files the batch transcription queue works on at once
+    pub queue_parallelism: usize,
+
+    /// Selected microphone, by the id from `list_audio_devices` or (as saved
+    /// by older versions) its name (None = default)
     pub selected_input_device: Option<String>,
 
+    /// Sample rate, channel and format for specific input devices, by device
+    /// id or name, used instead of the automatically chosen config
+    pub input_device_configs: HashMap<String, DeviceConfigOverride>,
+
+    /// Warn when a recording drops more than this many audio buffers, which
+    /// happens when the system is too busy to keep up (0 = never)
+    pub dropped_buffer_warning: u64,
+
     /// Selected output device for audio feedback (None = default)
     pub selected_output_device: Option<String>,
 
+    /// Suppress steady background noise (fans, hum) in microphone audio
+    /// before VAD and transcription. Skipped in fast mode.
+    pub noise_suppression: bool,
+
+    /// Boost quiet recordings so they peak at `normalize_target_peak`
+    /// before VAD and transcription
+    pub normalize_audio: bool,
+
+    /// Peak level (0.0 - 1.0) quiet recordings are boosted to
+    pub normalize_target_peak: f32,
+
     /// Whether VAD is enabled
     pub vad_enabled: bool,
 
-    /// Whether audio feedback is enabled
+    /// Which detector the VAD uses. Auto uses Silero and falls back to the
+    /// energy detector when the model isn't available.
+    pub vad_engine: VadEngine,
+
+    /// Speech probability above which a frame counts as voice (0.0 - 1.0).
+    /// A calibrated input device uses its own threshold instead.
+    pub vad_threshold: f32,
+
+    /// Audio kept before detected speech, in milliseconds
+    pub vad_prefill_ms: u32,
+
+    /// Audio kept after speech ends, in milliseconds
+    pub vad_hangover_ms: u32,
+
+    /// Speech needed before it counts as speech, in milliseconds
+    pub vad_onset_ms: u32,
+
+    /// Add VAD prefill automatically when recordings show the first word
+    /// being cut off
+    pub adaptive_prefill: bool,
+
+    /// Raise the VAD threshold while background noise is high (a café, a
+    /// fan), so noise isn't taken for speech, and lower it again after
+    pub adaptive_vad: bool,
+
+    /// Run the VAD while recording to show whether speech is being picked up
+    pub live_vad_indicator: bool,
+
+    /// Transcribe the last few seconds with the loaded local model every
+    /// couple of seconds while recording, and show the text on the overlay.
+    /// Only runs when a local model is already loaded.
+    pub live_preview: bool,
+
+    /// Stop recording automatically after a pause in speech (hands-free dictation)
+    pub auto_stop_on_silence: bool,
+
+    /// Seconds of trailing silence before auto-stop kicks in
+    pub auto_stop_silence_secs: f32,
+
+    /// Listen for the wake phrase and start a hands-free recording when it's heard
+    pub wake_word_enabled: bool,
+
+    /// Phrase that starts a recording in wake word mode
+    pub wake_phrase: String,
+
+    /// Accept recordings pushed from a paired phone over the local network
+    pub companion_enabled: bool,
+
+    /// Port the phone companion endpoint listens on
+    pub companion_port: u16,
+
+    /// Token a phone needs to send recordings, only kept here when the OS
+    /// keyring isn't available (see [`crate::companion`])
+    pub companion_token: String,
+
+    /// Paste transcripts of phone recordings into the focused app, rather
+    /// than only saving them to the history
+    pub companion_paste: bool,
+
+    /// Whether audio feedback is enabled (master switch)
     pub audio_feedback: bool,
 
+    /// Per-activation-mode feedback sound preferences
+    pub feedback: FeedbackSettings,
+
+    /// Which sounds play for completed, failed and cancelled transcriptions
+    pub sound_events: SoundEvents,
+
+    /// Sound files chosen by the user in place of the built-in ones
+    pub custom_sounds: CustomSounds,
+
+    /// Play feedback sounds, or show something instead. The toggles above
+    /// choose which events are signalled either way.
+    pub feedback_mode: FeedbackMode,
+
     /// Audio feedback volume (0.0 - 1.0)
     pub audio_feedback_volume: f32,
 
+    /// Silence the start of recordings while the start sound plays, so the
+    /// beep isn't transcribed or mistaken for speech. Only worth it when the
+    /// sound comes out of speakers the microphone hears; with headphones it
+    /// would just cut off the first words.
+    pub gate_start_sound: bool,
+
     /// Overlay position
     pub overlay_position: OverlayPosition,
 
+    /// Dragged overlay positions for the custom position, by monitor name
+    pub overlay_custom_positions: HashMap<String, OverlayCoordinates>,
+
+    /// Which monitor the overlay appears on
+    pub overlay_monitor: OverlayMonitor,
+
+    /// Monitor name for the fixed overlay monitor (None = primary monitor)
+    pub overlay_fixed_monitor: Option<String>,
+
+    /// Record this application's audio instead of the microphone (None = microphone)
+    pub capture_app: Option<String>,
+
+    /// Record the microphone, the system audio or both (ignored while `capture_app` is set)
+    pub audio_source: AudioSource,
+
+    /// With the microphone and system audio mixed, transcribe each side on its own
+    /// and tag the transcript as "Me" and "Them"
+    pub tag_speakers: bool,
+
+    /// Label speakers ("Speaker 1", "Speaker 2") in the segments of recordings and
+    /// transcribed files (needs the speaker embedding model)
+    pub diarization: bool,
+
+    /// Emit live audio level events for the overlay waveform (disable on low-power machines)
+    pub audio_level_events: bool,
+
+    /// Animate the tray icon while recording: a level meter on the icon and
+    /// the elapsed time beside it (in its tooltip where trays have no title)
+    pub tray_animation: bool,
+
+    /// Keep the microphone stream open between recordings, so recording starts
+    /// without the delay of opening the device (the OS shows the microphone
+    /// as in use the whole time)
+    pub keep_stream_warm: bool,
+
+    /// Maximum rate for high-frequency events like progress and audio levels (0 = unlimited)
+    pub max_event_rate_hz: u32,
+
+    /// How long the overlay keeps showing the finished state before hiding (0 = hide immediately)
+    pub overlay_linger_ms: u64,
+
+    /// Seconds a transcription can run before the overlay and tray say it's
+    /// still working and offer to cancel (0 = never)
+    pub slow_transcription_secs: u32,
+
+    /// Overlay size relative to the default, on top of display scaling (0.5-2.0)
+    pub overlay_scale: f64,
+
+    /// Announce completions and errors to screen readers
+    pub accessibility_announcements: bool,
+
     /// Paste method to use
     pub paste_method: PasteMethod,
 
-    /// Whether to append trailing space after transcription
-    pub append_trailing_space: bool,
+    /// Don't paste a transcription identical to the one pasted less than this
+    /// many seconds before (a bouncing key, the same sentence said twice).
+    /// It's still kept in the history, marked as not pasted. 0 = always paste.
+    pub duplicate_paste_secs: u32,
+
+    /// Show every transcription in the review window and paste it only
+    /// once accepted
+    pub review_before_paste: bool,
+
+    /// Seconds the review window waits before discarding (0 = no countdown)
+    pub review_timeout_secs: u32,
+
+    /// Hold transcriptions the engine is unsure of in the review window,
+    /// even when `review_before_paste` is off
+    pub review_low_confidence: bool,
+
+    /// Segment confidence (0.0 - 1.0) below which a transcription is held
+    pub review_confidence_threshold: f32,
+
+    /// Speak each transcription with text-to-speech and paste it only once
+    /// accepted with the accept shortcut
+    pub read_back_before_paste: bool,
+
+    /// Accepts a transcription being read back (only registered meanwhile)
+    pub read_back_accept_shortcut: String,
+
+    /// Drops a transcription being read back (only registered meanwhile)
+    pub read_back_cancel_shortcut: String,
+
+    /// Seconds to wait for an answer before dropping the transcription
+    /// (0 = wait indefinitely)
+    pub read_back_timeout_secs: u32,
+
+    /// Apps (name or executable) that get Ctrl+Shift+V instead of Ctrl+V.
+    /// Not used on macOS, where Cmd+V already pastes in terminals.
+    pub terminal_apps: Vec<String>,
+
+    /// Casing applied to transcripts (bindings can override it)
+    pub output_casing: CasingStyle,
+
+    /// Use the output language's quotes, spacing and decimal separator
+    /// instead of the engine's English-style punctuation
+    pub locale_typography: bool,
+
+    /// Locale whose typography applies, e.g. "fr" or "de-CH" (None = the
+    /// transcription language)
+    pub typography_locale: Option<String>,
+
+    /// When to format transcripts as code instead of prose
+    pub code_mode: CodeMode,
+
+    /// Apps (name or executable) that count as code editors for automatic code mode
+    pub code_mode_apps: Vec<String>,
+
+    /// External programs run on transcripts after formatting, in order
+    pub post_process_plugins: Vec<PostProcessPlugin>,
+
+    /// External programs run as recordings start and once they're over
+    pub recording_hooks: Vec<RecordingHook>,
+
+    /// Phrases recognized by the command mode binding and the actions they run
+    pub voice_commands: Vec<VoiceCommand>,
+
+    /// Treat "scratch that" and "cap that", said on their own with any
+    /// dictation binding, as edits to the text pasted last
+    pub voice_edit_commands: bool,
+
+    /// Output templates by name, e.g. "todo" => "- [ ] {text}", selected per binding
+    pub templates: HashMap<String, String>,
+
+    /// Optional global shortcut that removes the last pasted transcription
+    pub undo_paste_shortcut: Option<String>,
+
+    /// Global shortcut that cancels the recording in progress. It's only
+    /// registered while recording, so the key works normally otherwise.
+    pub cancel_recording_shortcut: Option<String>,
+
+    /// How long to wait after pasting before restoring the previous clipboard
+    /// contents (slow apps may otherwise paste the restored contents)
+    pub clipboard_restore_delay_ms: u64,
+
+    /// Previous clipboard contents larger than this (e.g. big images) are restored
+    /// according to `large_clipboard_restore`
+    pub large_clipboard_threshold_kb: u64,
+
+    /// What to do with previous clipboard contents over the threshold
+    pub large_clipboard_restore: LargeClipboardRestore,
+
+    /// Replacements and phrase hints applied to transcripts
+    pub dictionary: Dictionary,
+
+    /// What ends a pasted transcript, unless the app or binding overrides it
+    pub terminator: Terminator,
+
+    /// Terminators for specific apps, by app or process name (e.g. Enter
+    /// for chat apps)
+    pub app_terminators: HashMap<String, Terminator>,
+
+    /// Replaced by `terminator`, only read to migrate older settings
+    #[serde(skip_serializing)]
+    pub append_trailing_space: Option<bool>,
 
     /// Clipboard handling behavior
     pub clipboard_handling: ClipboardHandling,
 
     /// Transcription language (e.g., "en", "auto")
     pub transcription_language: String,
+
+    /// Translate every recording to English with the cloud provider's
+    /// translations endpoint, whatever language is spoken
+    pub translate_to_english: bool,
+
+    /// Keep the main window hidden on launch (tray only)
+    pub start_minimized: bool,
+
+    /// HTTPS URL of a team baseline configuration to load at startup
+    pub team_config_url: Option<String>,
+
+    /// The team's transcription endpoint, while it's the one in use. Set by
+    /// the baseline at runtime, never stored.
+    #[serde(skip)]
+    pub team_endpoint: Option<TeamEndpoint>,
+
+    /// Cloud transcription provider (None = not chosen, so OpenAI unless
+    /// the team baseline names one)
+    pub cloud_provider: Option<CloudProviderType>,
+
+    /// Per-provider API keys
+    pub cloud_api_keys: CloudApiKeys,
+
+    /// Azure Speech resource region (e.g., "eastus")
+    pub azure_region: String,
+
+    /// Base URL for an OpenAI-compatible server (None = api.openai.com), or
+    /// the address of the self-hosted whisper server
+    pub cloud_base_url: Option<String>,
+
+    /// Cloud model name override (None = provider default)
+    pub cloud_model: Option<String>,
+
+    /// Rewrite transcripts with an LLM before they're pasted
+    pub rewrite_enabled: bool,
+
+    /// What the rewrite does
+    pub rewrite_preset: RewritePreset,
+
+    /// Instructions for the custom rewrite preset
+    pub rewrite_custom_prompt: String,
+
+    /// Chat model the rewrite uses
+    pub rewrite_model: String,
+
+    /// OpenAI-compatible server to rewrite with, e.g. a local Ollama
+    /// (None = api.openai.com with the OpenAI key)
+    pub rewrite_base_url: Option<String>,
+
+    /// How long to wait for a rewrite before pasting the original
+    pub rewrite_timeout_ms: u64,
 }
 
 impl Default for AppSettings {
@@ -107,28 +698,286 @@ impl Default for AppSettings {
                 description: "Hold to record, release to transcribe".to_string(),
                 default_binding: default_shortcut.to_string(),
                 current_binding: default_shortcut.to_string(),
+                casing: None,
+                template: None,
+                translate: false,
+                terminator: None,
+                rewrite_prompt: None,
+            },
+        );
+
+        // Same as push-to-talk with Shift held, for chat-style output
+        let chat_shortcut = if cfg!(target_os = "macos") {
+            "Alt+Shift+Space"
+        } else {
+            "Ctrl+Shift+Space"
+        };
+
+        bindings.insert(
+            "transcribe_chat".to_string(),
+            ShortcutBinding {
+                id: "transcribe_chat".to_string(),
+                name: "Push to Talk (Chat Style)".to_string(),
+                description: "Like push to talk, but all lowercase with no trailing period"
+                    .to_string(),
+                default_binding: chat_shortcut.to_string(),
+                current_binding: chat_shortcut.to_string(),
+                casing: Some(CasingStyle::Lowercase),
+                template: None,
+                translate: false,
+                terminator: None,
+                rewrite_prompt: None,
+            },
+        );
+
+        // Same as push-to-talk, but only copies, for apps that reject
+        // simulated keystrokes
+        let clipboard_shortcut = if cfg!(target_os = "macos") {
+            "Alt+Shift+C"
+        } else {
+            "Ctrl+Alt+Shift+C"
+        };
+
+        bindings.insert(
+            "transcribe_clipboard".to_string(),
+            ShortcutBinding {
+                id: "transcribe_clipboard".to_string(),
+                name: "Transcribe to Clipboard".to_string(),
+                description: "Like push to talk, but copies the text instead of pasting it"
+                    .to_string(),
+                default_binding: clipboard_shortcut.to_string(),
+                current_binding: clipboard_shortcut.to_string(),
+                casing: None,
+                template: None,
+                translate: false,
+                terminator: None,
+                rewrite_prompt: None,
+            },
+        );
+
+        // Same as push-to-talk, but speech in any language comes out in English
+        let translate_shortcut = if cfg!(target_os = "macos") {
+            "Alt+Shift+T"
+        } else {
+            "Ctrl+Alt+Shift+T"
+        };
+
+        bindings.insert(
+            "transcribe_translate".to_string(),
+            ShortcutBinding {
+                id: "transcribe_translate".to_string(),
+                name: "Translate to English".to_string(),
+                description: "Like push to talk, but translates what you say to English"
+                    .to_string(),
+                default_binding: translate_shortcut.to_string(),
+                current_binding: translate_shortcut.to_string(),
+                casing: None,
+                template: None,
+                translate: true,
+                terminator: None,
+                rewrite_prompt: None,
+            },
+        );
+
+        // Hold and speak a command ("select all") to run it instead of
+        // pasting. Ctrl+Option+Space switches input sources on macOS.
+        let command_shortcut = if cfg!(target_os = "macos") {
+            "Alt+Shift+M"
+        } else {
+            "Ctrl+Alt+Space"
+        };
+
+        bindings.insert(
+            "command_mode".to_string(),
+            ShortcutBinding {
+                id: "command_mode".to_string(),
+                name: "Voice Command".to_string(),
+                description: "Hold to speak a command, release to run it".to_string(),
+                default_binding: command_shortcut.to_string(),
+                current_binding: command_shortcut.to_string(),
+                casing: None,
+                template: None,
+                translate: false,
+                terminator: None,
+                rewrite_prompt: None,
             },
         );
 
+        let mut known_bindings: Vec<String> = bindings.keys().cloned().collect();
+        known_bindings.sort();
+
         Self {
             bindings,
+            known_bindings,
             selected_model: "cloud".to_string(),
+            preload_model_on_startup: false,
+            model_cleanup: ModelCleanupPolicy::Off,
+            model_cleanup_days: 30,
+            shared_models: false,
+            shared_models_dir: None,
+            fast_mode: false,
+            draft_then_refine: false,
+            queue_parallelism: 1,
             selected_input_device: None,
+            input_device_configs: HashMap::new(),
+            dropped_buffer_warning: 3,
             selected_output_device: None,
+            noise_suppression: false,
+            normalize_audio: false,
+            normalize_target_peak: 0.7,
             vad_enabled: true,
+            vad_engine: VadEngine::default(),
+            vad_threshold: 0.5,
+            vad_prefill_ms: 90,
+            vad_hangover_ms: 300,
+            vad_onset_ms: 60,
+            adaptive_prefill: true,
+            adaptive_vad: false,
+            live_vad_indicator: true,
+            live_preview: false,
+            auto_stop_on_silence: false,
+            auto_stop_silence_secs: 2.0,
+            wake_word_enabled: false,
+            wake_phrase: "start dictation".to_string(),
+            companion_enabled: false,
+            companion_port: 47813,
+            companion_token: String::new(),
+            companion_paste: true,
             audio_feedback: true,
+            feedback: FeedbackSettings::default(),
+            sound_events: SoundEvents::default(),
+            custom_sounds: CustomSounds::default(),
+            feedback_mode: FeedbackMode::default(),
             audio_feedback_volume: 0.5,
+            gate_start_sound: false,
             overlay_position: OverlayPosition::Bottom,
+            overlay_custom_positions: HashMap::new(),
+            overlay_monitor: OverlayMonitor::default(),
+            overlay_fixed_monitor: None,
+            capture_app: None,
+            audio_source: AudioSource::Microphone,
+            tag_speakers: false,
+            diarization: false,
+            audio_level_events: true,
+            tray_animation: true,
+            keep_stream_warm: false,
+            max_event_rate_hz: DEFAULT_MAX_EVENT_RATE_HZ,
+            overlay_linger_ms: 0,
+            slow_transcription_secs: 10,
+            overlay_scale: 1.0,
+            accessibility_announcements: false,
             paste_method: PasteMethod::CtrlV,
-            append_trailing_space: true,
+            duplicate_paste_secs: 3,
+            review_before_paste: false,
+            review_timeout_secs: 15,
+            review_low_confidence: false,
+            review_confidence_threshold: 0.6,
+            read_back_before_paste: false,
+            read_back_accept_shortcut: "Enter".to_string(),
+            read_back_cancel_shortcut: "Escape".to_string(),
+            read_back_timeout_secs: 20,
+            terminal_apps: default_terminal_apps(),
+            output_casing: CasingStyle::Original,
+            locale_typography: false,
+            typography_locale: None,
+            code_mode: CodeMode::Off,
+            code_mode_apps: default_code_mode_apps(),
+            post_process_plugins: Vec::new(),
+            recording_hooks: Vec::new(),
+            templates: HashMap::new(),
+            voice_commands: commands::default_voice_commands(),
+            voice_edit_commands: true,
+            undo_paste_shortcut: None,
+            cancel_recording_shortcut: Some("Escape".to_string()),
+            clipboard_restore_delay_ms: 50,
+            large_clipboard_threshold_kb: 1024,
+            large_clipboard_restore: LargeClipboardRestore::Background,
+            dictionary: Dictionary::default(),
+            terminator: Terminator::Space,
+            app_terminators: HashMap::new(),
+            append_trailing_space: None,
             clipboard_handling: ClipboardHandling::DontModify,
             transcription_language: "en".to_string(),
+            translate_to_english: false,
+            start_minimized: false,
+            team_config_url: None,
+            team_endpoint: None,
+            cloud_provider: None,
+            cloud_api_keys: CloudApiKeys::default(),
+            azure_region: "eastus".to_string(),
+            cloud_base_url: None,
+            cloud_model: None,
+            rewrite_enabled: false,
+            rewrite_preset: RewritePreset::FixGrammar,
+            rewrite_custom_prompt: String::new(),
+            rewrite_model: "gpt-4o-mini".to_string(),
+            rewrite_base_url: None,
+            rewrite_timeout_ms: 5000,
         }
     }
 }
 
-/// Get current settings from the store, or defaults if not set
+fn default_code_mode_apps() -> Vec<String> {
+    [
+        "code",
+        "cursor",
+        "zed",
+        "sublime_text",
+        "idea",
+        "idea64",
+        "pycharm",
+        "pycharm64",
+        "webstorm",
+        "webstorm64",
+        "rustrover",
+        "Xcode",
+        "studio64",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+fn default_terminal_apps() -> Vec<String> {
+    [
+        "WindowsTerminal",
+        "wezterm-gui",
+        "alacritty",
+        "kitty",
+        "ghostty",
+        "gnome-terminal-server",
+        "konsole",
+        "xterm",
+        "tilix",
+        "terminator",
+        "xfce4-terminal",
+        "foot",
+        "mintty",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+impl AppSettings {
+    /// The cloud provider to transcribe with
+    pub fn cloud_provider(&self) -> CloudProviderType {
+        self.cloud_provider.unwrap_or_default()
+    }
+}
+
+/// Get the effective settings: the user's own settings with the team
+/// baseline (if any) filling in what they haven't set
 pub fn get_settings(app: &AppHandle) -> AppSettings {
+    let mut settings = get_local_settings(app);
+    if let Some(baseline) = team_config::cached(app) {
+        baseline.apply_under(&mut settings);
+    }
+    settings
+}
+
+/// Get the user's own settings from the store, or defaults if not set
+pub fn get_local_settings(app: &AppHandle) -> AppSettings {
     let store = match app.store(SETTINGS_STORE_PATH) {
         Ok(s) => s,
         Err(e) => {
@@ -139,7 +988,13 @@ pub fn get_settings(app: &AppHandle) -> AppSettings {
 
     match store.get("settings") {
         Some(value) => match serde_json::from_value::<AppSettings>(value.clone()) {
-            Ok(settings) => settings,
+            Ok(mut settings) => {
+                if settings.append_trailing_space.take() == Some(false) {
+                    settings.terminator = Terminator::None;
+                }
+                add_new_bindings(&mut settings);
+                settings
+            }
             Err(e) => {
                 log::warn!("Failed to deserialize settings, using defaults: {}", e);
                 AppSettings::default()
@@ -152,6 +1007,21 @@ pub fn get_settings(app: &AppHandle) -> AppSettings {
     }
 }
 
+/// Give existing settings the built-in bindings added since they were saved,
+/// once each
+fn add_new_bindings(settings: &mut AppSettings) {
+    let mut defaults: Vec<(String, ShortcutBinding)> =
+        AppSettings::default().bindings.into_iter().collect();
+    defaults.sort_by(|a, b| a.0.cmp(&b.0));
+
+    for (id, binding) in defaults {
+        if !settings.known_bindings.contains(&id) {
+            settings.bindings.entry(id.clone()).or_insert(binding);
+            settings.known_bindings.push(id);
+        }
+    }
+}
+
 /// Write settings to the store
 pub fn write_settings(app: &AppHandle, settings: &AppSettings) -> Result<(), String> {
     let store = app
@@ -175,7 +1045,8 @@ pub fn update_setting<F>(app: &AppHandle, updater: F) -> Result<(), String>
 where
     F: FnOnce(&mut AppSettings),
 {
-    let mut settings = get_settings(app);
+    // Only the user's own settings are written back, never the team baseline
+    let mut settings = get_local_settings(app);
     updater(&mut settings);
     write_settings(app, &settings)
 }
@@ -1,29 +1,174 @@
 //! Keyboard shortcut handling with full UX integration
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
+use iv_core::transcript::TranscriptionResult;
 use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 
+use crate::accessibility::{self, Politeness};
 use crate::audio_feedback::{self, SoundType};
 use crate::clipboard;
-use crate::overlay::{self, OverlayState};
-use crate::recording_manager::RecordingManager;
+use crate::commands;
+use crate::draft;
+use crate::formatting;
+use crate::history::HistoryManager;
+use crate::hooks::{self, HookEvent};
+use crate::overlay::{self, OverlayErrorCode, OverlayState};
+use crate::read_back;
+use crate::recording_manager::{
+    DraftCallback, ManagerState, RecordingManager, TranscriptionCancelled,
+};
+use crate::review_window::{self, PendingReview};
+use crate::rewrite;
+use crate::settings::{self, ActivationMode, AppSettings, Terminator};
 use crate::tray::{self, TrayIconState};
 
-pub const DEFAULT_SHORTCUT: &str = "ctrl+space";
+/// Binding that started the current recording, so its release stops it
+static ACTIVE_BINDING: Mutex<Option<String>> = Mutex::new(None);
+
+/// The cancel shortcut while it's registered
+static CANCEL_SHORTCUT: Mutex<Option<String>> = Mutex::new(None);
+
+/// Pseudo binding id for recordings started by the wake phrase
+pub const WAKE_WORD_BINDING: &str = "wake_word";
+
+/// Binding whose transcripts run voice commands instead of being pasted
+pub const COMMAND_MODE_BINDING: &str = "command_mode";
+
+/// Binding whose transcripts are copied to the clipboard instead of pasted
+pub const CLIPBOARD_BINDING: &str = "transcribe_clipboard";
+
+/// Pseudo binding id for recordings pushed from a phone
+pub const COMPANION_BINDING: &str = "companion";
 
 pub mod events {
     pub const RECORDING_STARTED: &str = "recording-started";
     pub const RECORDING_STOPPED: &str = "recording-stopped";
+    pub const RECORDING_CANCELLED: &str = "recording-cancelled";
+    pub const RECORDING_TICK: &str = "recording-tick";
+    pub const RECORDING_DEVICE_LOST: &str = "recording-device-lost";
+    pub const RECORDING_BUFFERS_DROPPED: &str = "recording-buffers-dropped";
     pub const TRANSCRIPTION_STARTED: &str = "transcription-started";
+    pub const UPLOAD_PROGRESS: &str = "upload-progress";
+    pub const TRANSCRIPTION_DRAFT: &str = "transcription-draft";
     pub const TRANSCRIPTION_COMPLETED: &str = "transcription-completed";
+    pub const TRANSCRIPTION_REWRITTEN: &str = "transcription-rewritten";
     pub const TRANSCRIPTION_ERROR: &str = "transcription-error";
+    pub const TRANSCRIPTION_SLOW: &str = "transcription-slow";
+    pub const TRANSCRIPTION_CANCELLED: &str = "transcription-cancelled";
+    pub const TRANSCRIPTION_NEEDS_REVIEW: &str = "transcription-needs-review";
+    pub const VOICE_COMMAND_EXECUTED: &str = "voice-command-executed";
+    pub const AUDIO_LEVEL: &str = "audio-level";
+    pub const MIC_TEST_LEVEL: &str = "mic-test-level";
+    pub const MIC_TEST_STOPPED: &str = "mic-test-stopped";
+    pub const SPEECH_DETECTED: &str = "speech-detected";
+    pub const SILENCE_DETECTED: &str = "silence-detected";
+    pub const SYSTEM_SUSPENDING: &str = "system-suspending";
+    pub const SYSTEM_RESUMED: &str = "system-resumed";
 }
 
+/// Register a global shortcut for every binding in the settings
 pub fn init_shortcut(app: &AppHandle) -> Result<(), String> {
-    let shortcut_str = DEFAULT_SHORTCUT;
+    let settings = settings::get_settings(app);
+    let registered = register_bindings(app, &settings);
+
+    if let Some(shortcut_str) = settings
+        .undo_paste_shortcut
+        .as_deref()
+        .filter(|s| !s.is_empty())
+    {
+        if let Err(e) = register_action(app, "undo_last_paste", shortcut_str, |app| {
+            if let Err(e) = clipboard::undo_last_paste(app) {
+                log::warn!("Cannot undo last paste: {}", e);
+            }
+        }) {
+            log::error!("{}", e);
+        }
+    }
 
+    if registered == 0 {
+        return Err("No shortcuts could be registered".to_string());
+    }
+
+    Ok(())
+}
+
+/// Register the dictation shortcuts again, after Windows dropped them across
+/// sleep. Other shortcuts (undo, cancel, read back) are left registered.
+pub fn reregister_bindings(app: &AppHandle) -> Result<(), String> {
+    let settings = settings::get_settings(app);
+    for binding in settings.bindings.values() {
+        if let Ok(shortcut) = binding.current_binding.parse::<Shortcut>() {
+            let _ = app.global_shortcut().unregister(shortcut);
+        }
+    }
+
+    if register_bindings(app, &settings) == 0 {
+        return Err("No shortcuts could be registered".to_string());
+    }
+    Ok(())
+}
+
+/// Register every binding's shortcut, returning how many were registered
+fn register_bindings(app: &AppHandle, settings: &AppSettings) -> usize {
+    let mut registered = 0;
+    for binding in settings.bindings.values() {
+        match register_binding(app, &binding.id, &binding.current_binding) {
+            Ok(()) => registered += 1,
+            Err(e) => log::error!("{}", e),
+        }
+    }
+    registered
+}
+
+/// Register a shortcut that runs `action` once the keys are released, so any
+/// keystrokes it sends aren't combined with the user's held modifiers
+pub fn register_action(
+    app: &AppHandle,
+    name: &str,
+    shortcut_str: &str,
+    action: fn(&AppHandle),
+) -> Result<(), String> {
+    let shortcut: Shortcut = shortcut_str
+        .parse()
+        .map_err(|e| format!("Failed to parse shortcut '{}': {}", shortcut_str, e))?;
+
+    if app.global_shortcut().is_registered(shortcut) {
+        return Err(format!(
+            "Shortcut '{}' for '{}' is already in use",
+            shortcut_str, name
+        ));
+    }
+
+    app.global_shortcut()
+        .on_shortcut(shortcut, move |app_handle, _shortcut, event| {
+            if event.state == ShortcutState::Released {
+                action(app_handle);
+            }
+        })
+        .map_err(|e| format!("Failed to register shortcut '{}': {}", shortcut_str, e))?;
+
+    log::info!(
+        "Registered global shortcut '{}' for '{}'",
+        shortcut_str,
+        name
+    );
+    Ok(())
+}
+
+/// Remove a shortcut registered with [`register_action`]
+pub fn unregister_action(app: &AppHandle, shortcut_str: &str) {
+    let Ok(shortcut) = shortcut_str.parse::<Shortcut>() else {
+        return;
+    };
+    if let Err(e) = app.global_shortcut().unregister(shortcut) {
+        log::warn!("Failed to unregister shortcut '{}': {}", shortcut_str, e);
+    }
+}
+
+fn register_binding(app: &AppHandle, binding_id: &str, shortcut_str: &str) -> Result<(), String> {
     let shortcut: Shortcut = shortcut_str
         .parse()
         .map_err(|e| format!("Failed to parse shortcut '{}': {}", shortcut_str, e))?;
@@ -33,17 +178,22 @@ pub fn init_shortcut(app: &AppHandle) -> Result<(), String> {
         return Ok(());
     }
 
+    let id = binding_id.to_string();
     app.global_shortcut()
         .on_shortcut(shortcut, move |app_handle, _shortcut, event| {
-            handle_shortcut_event(app_handle, event.state);
+            handle_shortcut_event(app_handle, &id, event.state);
         })
         .map_err(|e| format!("Failed to register shortcut '{}': {}", shortcut_str, e))?;
 
-    log::info!("Registered global shortcut: '{}'", shortcut_str);
+    log::info!(
+        "Registered global shortcut '{}' for '{}'",
+        shortcut_str,
+        binding_id
+    );
     Ok(())
 }
 
-fn handle_shortcut_event(app: &AppHandle, state: ShortcutState) {
+fn handle_shortcut_event(app: &AppHandle, binding_id: &str, state: ShortcutState) {
     let manager = match app.try_state::<Arc<RecordingManager>>() {
         Some(m) => m,
         None => {
@@ -63,57 +213,497 @@ fn handle_shortcut_event(app: &AppHandle, state: ShortcutState) {
                 return;
             }
 
+            *ACTIVE_BINDING.lock().unwrap() = Some(binding_id.to_string());
+            sync_cancel_shortcut(app);
+            hooks::run(app, HookEvent::PreRecord, binding_id, None, false);
+            let settings = settings::get_settings(app);
+            manager.set_translate_recording(
+                settings
+                    .bindings
+                    .get(binding_id)
+                    .is_some_and(|binding| binding.translate),
+            );
+
+            // Load the model while the user is still talking
+            if settings.fast_mode {
+                let manager = Arc::clone(&manager);
+                tauri::async_runtime::spawn_blocking(move || manager.warm_up());
+            }
+
             // Only update UI after recording has successfully started
             tray::change_tray_icon(app, TrayIconState::Recording);
             overlay::show_overlay(app, OverlayState::Recording);
-            audio_feedback::play_feedback_sound(app, SoundType::Start);
+            audio_feedback::play_feedback_sound(app, SoundType::Start, ActivationMode::PushToTalk);
         }
         ShortcutState::Released => {
+            {
+                let mut active = ACTIVE_BINDING.lock().unwrap();
+                if active.as_deref() != Some(binding_id) {
+                    // Released a shortcut that didn't start the current recording
+                    return;
+                }
+                *active = None;
+            }
+            sync_cancel_shortcut(app);
+
             log::debug!("Shortcut released - stopping recording");
+            spawn_transcription(app, Arc::clone(&manager), binding_id.to_string());
+        }
+    }
+}
 
-            let manager = Arc::clone(&manager);
-            let app_handle = app.clone();
+/// Start a hands-free recording after the wake phrase was heard
+pub fn start_wake_word_recording(app: &AppHandle) {
+    let Some(manager) = app.try_state::<Arc<RecordingManager>>() else {
+        log::error!("RecordingManager not found in app state");
+        return;
+    };
 
-            tauri::async_runtime::spawn(async move {
-                // Update UI to transcribing state
-                tray::change_tray_icon(&app_handle, TrayIconState::Transcribing);
-                overlay::update_overlay_state(&app_handle, OverlayState::Transcribing);
+    if let Err(e) = manager.start_hands_free_recording() {
+        log::warn!("Cannot start wake word recording: {}", e);
+        return;
+    }
 
-                let _ = app_handle.emit(events::TRANSCRIPTION_STARTED, ());
+    *ACTIVE_BINDING.lock().unwrap() = Some(WAKE_WORD_BINDING.to_string());
+    sync_cancel_shortcut(app);
+    hooks::run(app, HookEvent::PreRecord, WAKE_WORD_BINDING, None, false);
 
-                match manager.stop_and_transcribe().await {
-                    Ok(text) => {
-                        log::info!("Transcription complete: {}", text);
+    tray::change_tray_icon(app, TrayIconState::Recording);
+    overlay::show_overlay(app, OverlayState::Recording);
+    audio_feedback::play_feedback_sound(app, SoundType::Start, ActivationMode::WakeWord);
+}
 
-                        // Play stop sound
-                        audio_feedback::play_feedback_sound(&app_handle, SoundType::Stop);
+fn activation_mode(binding_id: &str) -> ActivationMode {
+    if binding_id == WAKE_WORD_BINDING {
+        ActivationMode::WakeWord
+    } else {
+        ActivationMode::PushToTalk
+    }
+}
 
-                        // Emit completion event to frontend
-                        let _ = app_handle.emit(events::TRANSCRIPTION_COMPLETED, &text);
+/// Reset the shortcut and UI after the recording ended without a
+/// transcription, e.g. because its device was lost
+pub fn abort_active_recording(app: &AppHandle, code: OverlayErrorCode) {
+    if let Some(binding_id) = ACTIVE_BINDING.lock().unwrap().take() {
+        sync_cancel_shortcut(app);
+        hooks::run(app, HookEvent::PostPaste, &binding_id, None, false);
+    }
+    tray::change_tray_icon(app, TrayIconState::Idle);
+    overlay::show_overlay_error(app, code);
+}
 
-                        // Paste the transcribed text
-                        if let Err(e) = clipboard::paste(text, &app_handle) {
-                            log::error!("Failed to paste transcription: {}", e);
-                        }
+/// Stop the current recording as if the shortcut that started it was
+/// released (used when recording ends without a key release, e.g. auto-stop)
+pub fn stop_active_recording(app: &AppHandle) {
+    let Some(binding_id) = ACTIVE_BINDING.lock().unwrap().take() else {
+        log::debug!("No active recording to stop");
+        return;
+    };
+    sync_cancel_shortcut(app);
+
+    let Some(manager) = app.try_state::<Arc<RecordingManager>>() else {
+        log::error!("RecordingManager not found in app state");
+        return;
+    };
+
+    spawn_transcription(app, Arc::clone(&manager), binding_id);
+}
+
+/// Throw away the current recording without transcribing it
+pub fn cancel_active_recording(app: &AppHandle) {
+    let Some(binding_id) = ACTIVE_BINDING.lock().unwrap().take() else {
+        log::debug!("No active recording to cancel");
+        return;
+    };
+    sync_cancel_shortcut(app);
+
+    if let Some(manager) = app.try_state::<Arc<RecordingManager>>() {
+        manager.cancel();
+    }
+    hooks::run(app, HookEvent::PostPaste, &binding_id, None, false);
+    audio_feedback::play_feedback_sound(app, SoundType::Cancelled, activation_mode(&binding_id));
+    accessibility::announce(app, "Recording cancelled", Politeness::Polite);
+    tray::change_tray_icon(app, TrayIconState::Idle);
+    overlay::hide_overlay(app);
+    let _ = app.emit(events::RECORDING_CANCELLED, ());
+}
+
+/// Register `cancel_recording_shortcut` while a recording is active and
+/// release it otherwise, so the key (Escape by default) keeps working in
+/// other apps. Call after changing `ACTIVE_BINDING`. The work happens on
+/// another thread, as shortcuts can't be changed from inside a shortcut
+/// handler.
+fn sync_cancel_shortcut(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut registered = CANCEL_SHORTCUT.lock().unwrap();
+        let wanted = if ACTIVE_BINDING.lock().unwrap().is_some() {
+            settings::get_settings(&app)
+                .cancel_recording_shortcut
+                .filter(|s| !s.is_empty())
+        } else {
+            None
+        };
+        if *registered == wanted {
+            return;
+        }
+
+        if let Some(shortcut_str) = registered.take() {
+            unregister_action(&app, &shortcut_str);
+        }
+        if let Some(shortcut_str) = wanted {
+            match register_action(
+                &app,
+                "cancel_recording",
+                &shortcut_str,
+                cancel_active_recording,
+            ) {
+                Ok(()) => *registered = Some(shortcut_str),
+                Err(e) => log::warn!("{}", e),
+            }
+        }
+    });
+}
+
+/// Cancel the transcription in progress, if there is one. Its result is
+/// thrown away and the UI resets right away.
+pub fn cancel_transcription(app: &AppHandle) {
+    if let Some(manager) = app.try_state::<Arc<RecordingManager>>() {
+        manager.cancel_transcription();
+    }
+}
+
+/// After `slow_transcription_secs`, tell the overlay and tray that the
+/// transcription is still running, so they can offer to cancel it or
+/// suggest a faster model. Abort the task once the transcription ends.
+fn spawn_slow_transcription_hint(
+    app: &AppHandle,
+    manager: &Arc<RecordingManager>,
+) -> tauri::async_runtime::JoinHandle<()> {
+    let app_handle = app.clone();
+    let manager = Arc::clone(manager);
+    let secs = settings::get_settings(app).slow_transcription_secs;
+
+    tauri::async_runtime::spawn(async move {
+        if secs == 0 {
+            return;
+        }
+        tokio::time::sleep(Duration::from_secs(secs as u64)).await;
+        if manager.get_state() != ManagerState::Transcribing {
+            return;
+        }
+
+        log::info!("Transcription still running after {}s", secs);
+        tray::set_transcription_slow(&app_handle, true);
+        let _ = app_handle.emit(events::TRANSCRIPTION_SLOW, manager.slow_transcription(secs));
+    })
+}
+
+/// Stop recording, then transcribe and paste in the background
+fn spawn_transcription(app: &AppHandle, manager: Arc<RecordingManager>, binding_id: String) {
+    let app_handle = app.clone();
+
+    tauri::async_runtime::spawn(async move {
+        // Update UI to transcribing state
+        tray::change_tray_icon(&app_handle, TrayIconState::Transcribing);
+        overlay::update_overlay_state(&app_handle, OverlayState::Transcribing);
+
+        let _ = app_handle.emit(events::TRANSCRIPTION_STARTED, ());
+
+        // Drafts are pasted straight away, so only for text that gets pasted
+        // without review, and not where Enter would send the draft before
+        // the final transcript replaces it
+        let settings = settings::get_settings(&app_handle);
+        let mut pasted_draft: Option<String> = None;
+        let drafts_allowed = binding_id != COMMAND_MODE_BINDING
+            && binding_id != CLIPBOARD_BINDING
+            && !settings.review_before_paste
+            && !settings.read_back_before_paste
+            && clipboard::resolve_terminator(&settings, &binding_id) != Terminator::Enter;
+        let on_draft: Option<DraftCallback> = drafts_allowed.then(|| {
+            let app_handle = &app_handle;
+            let binding_id = &binding_id;
+            let pasted_draft = &mut pasted_draft;
+            Box::new(move |draft: &TranscriptionResult| {
+                // Called from inside the transcription future
+                let text = tokio::task::block_in_place(|| {
+                    formatting::process_transcript(draft, &settings, binding_id)
+                });
+                let _ = app_handle.emit(events::TRANSCRIPTION_DRAFT, draft);
+                // Left to the final transcription, which is checked again
+                if is_duplicate_paste(app_handle, &settings, &text, binding_id) {
+                    log::info!("Not pasting draft, it repeats the last paste");
+                    return;
+                }
+                log::info!("Pasting draft transcription: {}", text);
+                match clipboard::paste(text.clone(), app_handle, binding_id) {
+                    Ok(()) => *pasted_draft = Some(text),
+                    Err(e) => log::error!("Failed to paste draft transcription: {}", e),
+                }
+            }) as DraftCallback
+        });
+
+        // The final transcript and whether it was pasted, for the post_paste hooks
+        let mut final_text: Option<String> = None;
+        let mut pasted = false;
+
+        let slow_hint = spawn_slow_transcription_hint(&app_handle, &manager);
+        let outcome = tokio::select! {
+            result = manager.stop_and_transcribe(on_draft) => result,
+            _ = manager.transcription_cancelled() => Err(TranscriptionCancelled.into()),
+        };
+        let history_entry = outcome.as_ref().ok().and_then(|(_, id)| id.clone());
+        let outcome = outcome.map(|(result, _)| result);
+        slow_hint.abort();
+        tray::set_transcription_slow(&app_handle, false);
+
+        let edit_command = match &outcome {
+            Ok(result)
+                if settings::get_settings(&app_handle).voice_edit_commands
+                    && binding_id != COMMAND_MODE_BINDING
+                    && binding_id != CLIPBOARD_BINDING =>
+            {
+                commands::EditCommand::parse(&result.text)
+            }
+            _ => None,
+        };
+
+        let error_code = match outcome {
+            Ok(_) if edit_command.is_some() => edit_command.and_then(|command| {
+                run_edit_command(&app_handle, command, pasted_draft.is_some(), &binding_id)
+            }),
+            Ok(result) if binding_id == COMMAND_MODE_BINDING => {
+                match commands::execute(&app_handle, &result.text) {
+                    Ok(phrase) => {
+                        audio_feedback::play_completion_sound(
+                            &app_handle,
+                            ActivationMode::PushToTalk,
+                        );
+                        accessibility::announce(
+                            &app_handle,
+                            &format!("Ran command {}", phrase),
+                            Politeness::Polite,
+                        );
+                        let _ = app_handle.emit(events::VOICE_COMMAND_EXECUTED, phrase);
+                        None
                     }
                     Err(e) => {
-                        log::error!("Transcription error: {}", e);
-                        let _ = app_handle.emit(events::TRANSCRIPTION_ERROR, e.to_string());
+                        log::warn!("{}", e);
+                        audio_feedback::play_feedback_sound(
+                            &app_handle,
+                            SoundType::Error,
+                            ActivationMode::PushToTalk,
+                        );
+                        accessibility::announce(&app_handle, &e, Politeness::Assertive);
+                        let _ = app_handle.emit(events::TRANSCRIPTION_ERROR, e);
+                        Some(OverlayErrorCode::UnknownCommand)
                     }
                 }
+            }
+            Ok(mut result) => {
+                log::info!("Transcription complete: {}", result.text);
+
+                let settings = settings::get_settings(&app_handle);
+                if let Some(rewritten) =
+                    rewrite::rewrite_transcript(&app_handle, &settings, &binding_id, &result.text)
+                        .await
+                {
+                    result.text = rewritten;
+                }
+                result.text =
+                    formatting::process_transcript_blocking(&result, &settings, &binding_id).await;
+                final_text = Some(result.text.clone());
+
+                audio_feedback::play_completion_sound(&app_handle, activation_mode(&binding_id));
 
-                // Reset UI
+                // Emit completion event (text, segments and confidence) to frontend
+                let _ = app_handle.emit(events::TRANSCRIPTION_COMPLETED, &result);
+                accessibility::announce(
+                    &app_handle,
+                    &completion_announcement(&settings, &binding_id, &result.text),
+                    Politeness::Polite,
+                );
+
+                let low_confidence = settings.review_low_confidence
+                    && result.needs_review(settings.review_confidence_threshold);
+                if low_confidence {
+                    log::info!(
+                        "Transcription confidence {:?} is below {}, holding it for review",
+                        result.confidence(),
+                        settings.review_confidence_threshold
+                    );
+                    let _ = app_handle.emit(events::TRANSCRIPTION_NEEDS_REVIEW, &result);
+                }
+
+                if let Some(draft_text) = pasted_draft {
+                    pasted = true;
+                    if draft::differs_materially(&draft_text, &result.text) {
+                        log::info!("Replacing draft with refined transcription");
+                        if let Err(e) = draft::replace_draft(&app_handle, result.text, &binding_id)
+                        {
+                            log::error!("Failed to replace draft transcription: {}", e);
+                        }
+                    }
+                } else if binding_id == CLIPBOARD_BINDING {
+                    if let Err(e) = clipboard::copy(result.text, &app_handle, &binding_id) {
+                        log::error!("Failed to copy transcription: {}", e);
+                    }
+                } else if settings.review_before_paste || low_confidence {
+                    // Hold the text until the user accepts or corrects it
+                    let review = PendingReview {
+                        result,
+                        binding_id: binding_id.clone(),
+                        timeout_secs: settings.review_timeout_secs,
+                        confidence_threshold: settings.review_confidence_threshold,
+                    };
+                    if let Err(e) = review_window::request_review(&app_handle, review) {
+                        log::error!("Failed to show review window: {}", e);
+                    }
+                } else if settings.read_back_before_paste {
+                    if let Err(e) = read_back::request(&app_handle, result.text, binding_id.clone())
+                    {
+                        log::error!("Failed to read back transcription: {}", e);
+                    }
+                } else if is_duplicate_paste(&app_handle, &settings, &result.text, &binding_id) {
+                    log::info!("Not pasting transcription, it repeats the last paste");
+                    let history = app_handle.try_state::<Arc<HistoryManager>>();
+                    if let (Some(history), Some(id)) = (history, &history_entry) {
+                        if let Err(e) = history.mark_paste_suppressed(id) {
+                            log::warn!("Failed to note the skipped paste in history: {}", e);
+                        }
+                    }
+                } else {
+                    match clipboard::paste(result.text, &app_handle, &binding_id) {
+                        Ok(()) => pasted = true,
+                        Err(e) => log::error!("Failed to paste transcription: {}", e),
+                    }
+                }
+                None
+            }
+            Err(e) if e.is::<TranscriptionCancelled>() => {
+                let _ = app_handle.emit(events::TRANSCRIPTION_CANCELLED, ());
+                audio_feedback::play_feedback_sound(
+                    &app_handle,
+                    SoundType::Cancelled,
+                    activation_mode(&binding_id),
+                );
+                accessibility::announce(&app_handle, "Transcription cancelled", Politeness::Polite);
                 tray::change_tray_icon(&app_handle, TrayIconState::Idle);
                 overlay::hide_overlay(&app_handle);
-            });
+                hooks::run(&app_handle, HookEvent::PostPaste, &binding_id, None, false);
+                return;
+            }
+            Err(e) => {
+                log::error!("Transcription error: {}", e);
+                audio_feedback::play_feedback_sound(
+                    &app_handle,
+                    SoundType::Error,
+                    activation_mode(&binding_id),
+                );
+                accessibility::announce(
+                    &app_handle,
+                    &format!("Transcription failed: {}", e),
+                    Politeness::Assertive,
+                );
+                let _ = app_handle.emit(events::TRANSCRIPTION_ERROR, e.to_string());
+                Some(OverlayErrorCode::from_error(&e))
+            }
+        };
+
+        // Reset UI
+        tray::change_tray_icon(&app_handle, TrayIconState::Idle);
+        match error_code {
+            None => overlay::finish_overlay(&app_handle),
+            Some(code) => overlay::show_overlay_error(&app_handle, code),
+        }
+        hooks::run(
+            &app_handle,
+            HookEvent::PostPaste,
+            &binding_id,
+            final_text.as_deref(),
+            pasted,
+        );
+    });
+}
+
+/// Run an edit command on the text pasted before it, first taking back the
+/// draft of the command itself if one was pasted
+fn run_edit_command(
+    app_handle: &AppHandle,
+    command: commands::EditCommand,
+    draft_pasted: bool,
+    binding_id: &str,
+) -> Option<OverlayErrorCode> {
+    if draft_pasted {
+        if let Err(e) = clipboard::undo_last_paste(app_handle) {
+            log::error!("Failed to undo the draft of '{}': {}", command.phrase(), e);
+        }
+    }
+
+    let result = if clipboard::has_pasted(app_handle) {
+        command.execute(app_handle)
+    } else {
+        Err(format!("Nothing to {}", command.phrase()))
+    };
+    match result {
+        Ok(()) => {
+            audio_feedback::play_completion_sound(app_handle, activation_mode(binding_id));
+            accessibility::announce(
+                app_handle,
+                &format!("Ran command {}", command.phrase()),
+                Politeness::Polite,
+            );
+            let _ = app_handle.emit(events::VOICE_COMMAND_EXECUTED, command.phrase());
+            None
+        }
+        Err(e) => {
+            log::warn!("Edit command '{}' failed: {}", command.phrase(), e);
+            audio_feedback::play_feedback_sound(
+                app_handle,
+                SoundType::Error,
+                activation_mode(binding_id),
+            );
+            accessibility::announce(app_handle, &e, Politeness::Assertive);
+            let _ = app_handle.emit(events::TRANSCRIPTION_ERROR, e);
+            Some(OverlayErrorCode::NothingToEdit)
         }
     }
 }
 
-pub fn cleanup_shortcut(app: &AppHandle) {
-    let shortcut: Result<Shortcut, _> = DEFAULT_SHORTCUT.parse();
-    if let Ok(s) = shortcut {
-        let _ = app.global_shortcut().unregister(s);
-        log::debug!("Unregistered global shortcut: '{}'", DEFAULT_SHORTCUT);
+/// Whether pasting `text` would repeat the last paste within
+/// `duplicate_paste_secs`, as when a key bounces
+fn is_duplicate_paste(
+    app_handle: &AppHandle,
+    settings: &AppSettings,
+    text: &str,
+    binding_id: &str,
+) -> bool {
+    settings.duplicate_paste_secs > 0
+        && clipboard::is_repeat_paste(
+            app_handle,
+            text,
+            binding_id,
+            Duration::from_secs(settings.duplicate_paste_secs as u64),
+        )
+}
+
+/// What a screen reader says when a transcription is done: the text, and
+/// where it went when it wasn't pasted straight away
+fn completion_announcement(settings: &AppSettings, binding_id: &str, text: &str) -> String {
+    if binding_id == CLIPBOARD_BINDING {
+        format!("Copied: {}", text)
+    } else if settings.review_before_paste {
+        format!("Ready for review: {}", text)
+    } else if settings.read_back_before_paste {
+        "Transcription ready, accept or cancel after the read-back".to_string()
+    } else {
+        format!("Transcribed: {}", text)
     }
 }
+
+pub fn cleanup_shortcut(app: &AppHandle) {
+    let _ = app.global_shortcut().unregister_all();
+    *ACTIVE_BINDING.lock().unwrap() = None;
+    *CANCEL_SHORTCUT.lock().unwrap() = None;
+    log::debug!("Unregistered global shortcuts");
+}
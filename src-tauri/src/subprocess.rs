@@ -0,0 +1,161 @@
+//! Running external programs: user plugins and hooks, and the system tools
+//! some features shell out to
+
+use std::io::{Read, Write};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// How often a running program is checked for exit
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// A program the user registered in the settings, as plugins and hooks are
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ExternalCommand {
+    /// Executable to run
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Bindings it runs for (empty = all)
+    #[serde(default)]
+    pub bindings: Vec<String>,
+    /// How long it may run before it's killed (None = the caller's default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+impl ExternalCommand {
+    /// Whether it's enabled for a binding's recordings
+    pub fn applies_to(&self, binding_id: &str) -> bool {
+        self.enabled && (self.bindings.is_empty() || self.bindings.iter().any(|b| b == binding_id))
+    }
+
+    /// Run it with `input` as JSON on stdin (see `run_with_timeout`),
+    /// returning its stdout if it's piped. Exiting non-zero is an error.
+    pub fn run(
+        &self,
+        input: &impl Serialize,
+        stdout: Stdio,
+        default_timeout: Duration,
+    ) -> Result<String> {
+        let timeout = self
+            .timeout_ms
+            .map(Duration::from_millis)
+            .unwrap_or(default_timeout);
+        let (status, output) = run_with_timeout(
+            Command::new(&self.command)
+                .args(&self.args)
+                .stdout(stdout)
+                .stderr(Stdio::inherit()),
+            serde_json::to_vec(input)?,
+            timeout,
+        )?;
+
+        if !status.success() {
+            return Err(anyhow::anyhow!("exited with {}", status));
+        }
+        Ok(output)
+    }
+}
+
+/// Keep a console program from flashing up a console window on Windows
+pub fn hide_window(command: &mut Command) -> &mut Command {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    command
+}
+
+/// Run a program with `input` on its stdin, returning its exit status and
+/// what it wrote to stdout (empty unless `stdout` is piped). It's killed
+/// once `timeout` has passed since it started, whatever it's doing: stdin
+/// is written and stdout read on threads of their own, so a program that
+/// doesn't read its input or fills its output pipe can't hold things up.
+/// Nor can one that leaves a background process holding its stdout open:
+/// the output is only waited for until the same deadline.
+pub fn run_with_timeout(
+    command: &mut Command,
+    input: Vec<u8>,
+    timeout: Duration,
+) -> Result<(ExitStatus, String)> {
+    let deadline = Instant::now() + timeout;
+    let mut child = hide_window(command).stdin(Stdio::piped()).spawn()?;
+
+    // Dropping stdin after writing closes it, so the program sees EOF. One
+    // that exits without reading it breaks the pipe, which is fine.
+    if let Some(mut stdin) = child.stdin.take() {
+        std::thread::spawn(move || {
+            let _ = stdin.write_all(&input);
+        });
+    }
+    let reader = child.stdout.take().map(|mut stdout| {
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut output = String::new();
+            let _ = sender.send(stdout.read_to_string(&mut output).map(|_| output));
+        });
+        receiver
+    });
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(anyhow::anyhow!("timed out after {}ms", timeout.as_millis()));
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    };
+
+    let output = match reader {
+        Some(reader) => {
+            match reader.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(output) => output?,
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(anyhow::anyhow!(
+                        "output still open after {}ms",
+                        timeout.as_millis()
+                    ))
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(anyhow::anyhow!("failed to read output"))
+                }
+            }
+        }
+        None => String::new(),
+    };
+    Ok((status, output))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_applies_to() {
+        let mut command: ExternalCommand = serde_json::from_str(r#"{ "command": "cat" }"#).unwrap();
+        assert!(command.applies_to("transcribe"));
+
+        command.bindings = vec!["transcribe_chat".to_string()];
+        assert!(!command.applies_to("transcribe"));
+        assert!(command.applies_to("transcribe_chat"));
+
+        command.enabled = false;
+        assert!(!command.applies_to("transcribe_chat"));
+    }
+}
@@ -0,0 +1,257 @@
+//! Team baseline configuration
+//!
+//! An admin can publish a JSON file over HTTPS with shared vocabulary,
+//! recommended models and a transcription endpoint. When a URL is set, the
+//! file is fetched at startup and cached, and its values fill in whatever the
+//! user hasn't set locally. The baseline is never written into the user's own
+//! settings, so updates to it keep flowing and local changes always win.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use iv_core::cloud_transcribe::CloudProviderType;
+use iv_core::models::ModelInfo;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_store::StoreExt;
+
+use crate::dictionary::Dictionary;
+use crate::settings::{self, AppSettings, SETTINGS_STORE_PATH};
+
+const TEAM_CONFIG_KEY: &str = "team_config";
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Transcription endpoint shared by the team (e.g. a self-hosted Whisper server)
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct TeamEndpoint {
+    pub provider: CloudProviderType,
+    pub base_url: Option<String>,
+    pub model: Option<String>,
+    /// Credential for the endpoint. Without one requests to it carry no key,
+    /// as the user's own keys are never sent to a server the team chose.
+    pub api_key: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct TeamConfig {
+    pub dictionary: Dictionary,
+    /// Output templates by name
+    pub templates: HashMap<String, String>,
+    /// Model ids the team recommends
+    pub recommended_models: Vec<String>,
+    pub endpoint: Option<TeamEndpoint>,
+}
+
+/// Flag the models the cached baseline recommends
+pub fn mark_recommended(app: &AppHandle, models: &mut [ModelInfo]) {
+    let Some(config) = cached(app) else {
+        return;
+    };
+    for model in models {
+        model.is_recommended = config.recommended_models.contains(&model.id);
+    }
+}
+
+impl TeamConfig {
+    /// Fill in settings from the baseline wherever the user has no value of
+    /// their own. The endpoint only applies while the user hasn't picked a
+    /// provider or set a base URL, and only when it has a URL, so the
+    /// provider and URL always come as a pair.
+    pub fn apply_under(&self, settings: &mut AppSettings) {
+        settings.dictionary.merge_under(&self.dictionary);
+
+        for (name, template) in &self.templates {
+            settings
+                .templates
+                .entry(name.clone())
+                .or_insert_with(|| template.clone());
+        }
+
+        let is_set = |url: &Option<String>| url.as_deref().is_some_and(|u| !u.trim().is_empty());
+        if let Some(endpoint) = &self.endpoint {
+            if is_set(&endpoint.base_url)
+                && settings.cloud_provider.is_none()
+                && !is_set(&settings.cloud_base_url)
+            {
+                settings.cloud_provider = Some(endpoint.provider);
+                settings.cloud_base_url = endpoint.base_url.clone();
+                if settings.cloud_model.is_none() {
+                    settings.cloud_model = endpoint.model.clone();
+                }
+                settings.team_endpoint = Some(endpoint.clone());
+            }
+        }
+    }
+}
+
+/// The last successfully fetched baseline
+pub fn cached(app: &AppHandle) -> Option<TeamConfig> {
+    let store = app.store(SETTINGS_STORE_PATH).ok()?;
+    let value = store.get(TEAM_CONFIG_KEY)?;
+    serde_json::from_value(value).ok()
+}
+
+fn save_cache(app: &AppHandle, config: Option<&TeamConfig>) {
+    let Ok(store) = app.store(SETTINGS_STORE_PATH) else {
+        return;
+    };
+
+    match config.map(serde_json::to_value) {
+        Some(Ok(value)) => store.set(TEAM_CONFIG_KEY, value),
+        Some(Err(e)) => {
+            log::warn!("Failed to serialize team config: {}", e);
+            return;
+        }
+        None => {
+            store.delete(TEAM_CONFIG_KEY);
+        }
+    }
+
+    if let Err(e) = store.save() {
+        log::warn!("Failed to save team config: {}", e);
+    }
+}
+
+async fn fetch(url: &str) -> Result<TeamConfig, String> {
+    if !url.starts_with("https://") {
+        return Err("Team configuration URL must use HTTPS".to_string());
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch team configuration: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("Failed to fetch team configuration: {}", e))?;
+
+    response
+        .json::<TeamConfig>()
+        .await
+        .map_err(|e| format!("Invalid team configuration: {}", e))
+}
+
+/// Fetch the baseline from the configured URL and cache it. Without a URL the
+/// cache is cleared; if the fetch fails the previous cache stays in use.
+pub async fn refresh(app: &AppHandle) -> Result<Option<TeamConfig>, String> {
+    let Some(url) = settings::get_local_settings(app)
+        .team_config_url
+        .filter(|u| !u.trim().is_empty())
+    else {
+        save_cache(app, None);
+        return Ok(None);
+    };
+
+    let config = fetch(url.trim()).await?;
+    log::info!("Loaded team configuration from {}", url);
+
+    save_cache(app, Some(&config));
+    let _ = app.emit("team-config-updated", &config);
+    Ok(Some(config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::Replacement;
+
+    #[test]
+    fn test_local_settings_win() {
+        let mut settings = AppSettings::default();
+        settings.dictionary.replacements = vec![Replacement {
+            from: "eye vee".to_string(),
+            to: "IV".to_string(),
+        }];
+        settings.cloud_base_url = Some("http://localhost:8000".to_string());
+
+        let team = TeamConfig {
+            dictionary: Dictionary {
+                replacements: vec![
+                    Replacement {
+                        from: "Eye Vee".to_string(),
+                        to: "iv".to_string(),
+                    },
+                    Replacement {
+                        from: "k8s".to_string(),
+                        to: "Kubernetes".to_string(),
+                    },
+                ],
+                ..Default::default()
+            },
+            endpoint: Some(TeamEndpoint {
+                provider: CloudProviderType::OpenAi,
+                base_url: Some("https://whisper.example.com/v1".to_string()),
+                model: None,
+                api_key: None,
+            }),
+            ..Default::default()
+        };
+
+        team.apply_under(&mut settings);
+
+        let replacements: Vec<&str> = settings
+            .dictionary
+            .replacements
+            .iter()
+            .map(|r| r.to.as_str())
+            .collect();
+        assert_eq!(replacements, vec!["IV", "Kubernetes"]);
+        assert_eq!(
+            settings.cloud_base_url.as_deref(),
+            Some("http://localhost:8000")
+        );
+        assert_eq!(settings.team_endpoint, None);
+    }
+
+    #[test]
+    fn test_endpoint_applies_as_a_pair() {
+        let mut settings = AppSettings::default();
+        let mut team = TeamConfig {
+            endpoint: Some(TeamEndpoint {
+                provider: CloudProviderType::SelfHosted,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        // No URL, so the default provider stays
+        team.apply_under(&mut settings);
+        assert_eq!(settings.cloud_provider(), CloudProviderType::OpenAi);
+        assert_eq!(settings.team_endpoint, None);
+
+        let endpoint = team.endpoint.as_mut().unwrap();
+        endpoint.base_url = Some("https://whisper.example.com".to_string());
+        team.apply_under(&mut settings);
+        assert_eq!(settings.cloud_provider(), CloudProviderType::SelfHosted);
+        assert_eq!(settings.team_endpoint, team.endpoint);
+    }
+
+    #[test]
+    fn test_chosen_provider_wins() {
+        let mut settings = AppSettings {
+            cloud_provider: Some(CloudProviderType::Groq),
+            ..Default::default()
+        };
+        let team = TeamConfig {
+            endpoint: Some(TeamEndpoint {
+                provider: CloudProviderType::SelfHosted,
+                base_url: Some("https://whisper.example.com".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        team.apply_under(&mut settings);
+        assert_eq!(settings.cloud_provider(), CloudProviderType::Groq);
+        assert_eq!(settings.cloud_base_url, None);
+        assert_eq!(settings.team_endpoint, None);
+    }
+}
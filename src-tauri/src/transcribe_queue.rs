@@ -0,0 +1,330 @@
+//! Batch file transcription queue
+//!
+//! Files are queued as jobs and transcribed in the background, one at a time
+//! or a few at once (`queue_parallelism`). Jobs can be paused and cancelled
+//! individually: a running job stops between chunks. Queued files don't hold
+//! the recording state machine, so dictation keeps working while a folder of
+//! recordings is processed. Every change to a job is emitted as
+//! `queue-progress` with the job.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use iv_core::transcript::TranscriptionResult;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::recording_manager::RecordingManager;
+use crate::settings;
+
+/// How often a paused job checks whether it was resumed
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Paused,
+    Completed,
+    Failed { error: String },
+    Cancelled,
+}
+
+impl JobStatus {
+    fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            JobStatus::Completed | JobStatus::Failed { .. } | JobStatus::Cancelled
+        )
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct QueueJob {
+    pub id: u64,
+    pub path: String,
+    pub model_id: String,
+    pub status: JobStatus,
+    /// Fraction of the file transcribed (0.0 - 1.0)
+    pub progress: f32,
+    /// Write the transcript next to the file when done
+    pub save_transcript: bool,
+    /// Translate to English rather than transcribe
+    pub translate: bool,
+    pub output_path: Option<String>,
+    pub result: Option<TranscriptionResult>,
+}
+
+/// Lets a running job be paused, cancelled and report progress
+pub struct JobControl {
+    job_id: u64,
+    app_handle: AppHandle,
+    paused: AtomicBool,
+    cancelled: AtomicBool,
+}
+
+impl JobControl {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Wait until the job is resumed (or cancelled)
+    pub async fn wait_while_paused(&self) {
+        while self.paused.load(Ordering::Relaxed) && !self.is_cancelled() {
+            tokio::time::sleep(PAUSE_POLL_INTERVAL).await;
+        }
+    }
+
+    pub fn set_progress(&self, progress: f32) {
+        if let Some(queue) = self.app_handle.try_state::<TranscribeQueue>() {
+            queue.update(&self.app_handle, self.job_id, |job| job.progress = progress);
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TranscribeQueue {
+    jobs: Mutex<Vec<QueueJob>>,
+    /// Controls of the jobs that have started and not finished
+    controls: Mutex<HashMap<u64, Arc<JobControl>>>,
+    next_id: Mutex<u64>,
+}
+
+impl TranscribeQueue {
+    pub fn jobs(&self) -> Vec<QueueJob> {
+        self.jobs.lock().unwrap().clone()
+    }
+
+    /// Change a job and emit it
+    fn update(&self, app_handle: &AppHandle, id: u64, change: impl FnOnce(&mut QueueJob)) {
+        let job = {
+            let mut jobs = self.jobs.lock().unwrap();
+            let Some(job) = jobs.iter_mut().find(|j| j.id == id) else {
+                return;
+            };
+            change(job);
+            job.clone()
+        };
+        let _ = app_handle.emit("queue-progress", &job);
+    }
+
+    /// Add files to the queue, transcribed with `model_id` (or the selected model)
+    pub fn enqueue(
+        &self,
+        app_handle: &AppHandle,
+        paths: Vec<String>,
+        model_id: String,
+        save_transcripts: bool,
+        translate: bool,
+    ) -> Vec<QueueJob> {
+        let added: Vec<QueueJob> = paths
+            .into_iter()
+            .map(|path| {
+                let mut next_id = self.next_id.lock().unwrap();
+                *next_id += 1;
+                QueueJob {
+                    id: *next_id,
+                    path,
+                    model_id: model_id.clone(),
+                    status: JobStatus::Queued,
+                    progress: 0.0,
+                    save_transcript: save_transcripts,
+                    translate,
+                    output_path: None,
+                    result: None,
+                }
+            })
+            .collect();
+
+        log::info!("Queued {} files for transcription", added.len());
+        self.jobs.lock().unwrap().extend(added.iter().cloned());
+        for job in &added {
+            let _ = app_handle.emit("queue-progress", job);
+        }
+
+        self.pump(app_handle);
+        added
+    }
+
+    pub fn pause(&self, app_handle: &AppHandle, id: u64) -> Result<(), String> {
+        if let Some(control) = self.controls.lock().unwrap().get(&id) {
+            control.paused.store(true, Ordering::Relaxed);
+        }
+        self.set_status(app_handle, id, |status| {
+            matches!(status, JobStatus::Queued | JobStatus::Running).then_some(JobStatus::Paused)
+        })
+    }
+
+    pub fn resume(&self, app_handle: &AppHandle, id: u64) -> Result<(), String> {
+        let started = match self.controls.lock().unwrap().get(&id) {
+            Some(control) => {
+                control.paused.store(false, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        };
+        self.set_status(app_handle, id, |status| {
+            (*status == JobStatus::Paused).then_some(if started {
+                JobStatus::Running
+            } else {
+                JobStatus::Queued
+            })
+        })?;
+        self.pump(app_handle);
+        Ok(())
+    }
+
+    pub fn cancel(&self, app_handle: &AppHandle, id: u64) -> Result<(), String> {
+        // A running job is marked cancelled when it stops, between chunks
+        if let Some(control) = self.controls.lock().unwrap().get(&id) {
+            control.cancelled.store(true, Ordering::Relaxed);
+            return Ok(());
+        }
+        self.set_status(app_handle, id, |status| {
+            (!status.is_finished()).then_some(JobStatus::Cancelled)
+        })
+    }
+
+    /// Remove completed, failed and cancelled jobs
+    pub fn clear_finished(&self) {
+        self.jobs
+            .lock()
+            .unwrap()
+            .retain(|job| !job.status.is_finished());
+    }
+
+    fn set_status(
+        &self,
+        app_handle: &AppHandle,
+        id: u64,
+        next: impl FnOnce(&JobStatus) -> Option<JobStatus>,
+    ) -> Result<(), String> {
+        let status = self
+            .jobs
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|j| j.id == id)
+            .map(|j| j.status.clone())
+            .ok_or_else(|| format!("No queued job {}", id))?;
+
+        let next =
+            next(&status).ok_or_else(|| format!("Job {} can't change from {:?}", id, status))?;
+        self.update(app_handle, id, |job| job.status = next);
+        Ok(())
+    }
+
+    /// Start queued jobs while fewer than `queue_parallelism` are running
+    fn pump(&self, app_handle: &AppHandle) {
+        let parallelism = settings::get_settings(app_handle).queue_parallelism.max(1);
+
+        loop {
+            let job = {
+                let mut controls = self.controls.lock().unwrap();
+                if controls.len() >= parallelism {
+                    return;
+                }
+
+                let mut jobs = self.jobs.lock().unwrap();
+                let Some(job) = jobs.iter_mut().find(|j| j.status == JobStatus::Queued) else {
+                    return;
+                };
+                job.status = JobStatus::Running;
+
+                controls.insert(
+                    job.id,
+                    Arc::new(JobControl {
+                        job_id: job.id,
+                        app_handle: app_handle.clone(),
+                        paused: AtomicBool::new(false),
+                        cancelled: AtomicBool::new(false),
+                    }),
+                );
+                job.clone()
+            };
+
+            let _ = app_handle.emit("queue-progress", &job);
+            let app_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                run_job(&app_handle, job).await;
+            });
+        }
+    }
+}
+
+/// `meeting.mp3` is saved as `meeting.transcript.txt`
+fn transcript_path(path: &Path) -> PathBuf {
+    path.with_extension("transcript.txt")
+}
+
+async fn run_job(app_handle: &AppHandle, job: QueueJob) {
+    let Some(queue) = app_handle.try_state::<TranscribeQueue>() else {
+        return;
+    };
+    let Some(control) = queue.controls.lock().unwrap().get(&job.id).cloned() else {
+        return;
+    };
+    let Some(manager) = app_handle.try_state::<Arc<RecordingManager>>() else {
+        log::error!("RecordingManager not found in app state");
+        return;
+    };
+
+    let path = PathBuf::from(&job.path);
+    let result = manager
+        .transcribe_queued_file(&path, &job.model_id, job.translate, &control)
+        .await;
+
+    let output_path = match &result {
+        Ok(transcript) if job.save_transcript => {
+            let output = transcript_path(&path);
+            match std::fs::write(&output, &transcript.text) {
+                Ok(()) => Some(output.to_string_lossy().into_owned()),
+                Err(e) => {
+                    log::warn!("Failed to save transcript to {:?}: {}", output, e);
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+
+    queue.controls.lock().unwrap().remove(&job.id);
+    queue.update(app_handle, job.id, |job| match result {
+        Ok(transcript) => {
+            log::info!("Transcribed queued file {}", job.path);
+            job.status = JobStatus::Completed;
+            job.progress = 1.0;
+            job.result = Some(transcript);
+            job.output_path = output_path;
+        }
+        Err(_) if control.is_cancelled() => {
+            log::info!("Cancelled queued file {}", job.path);
+            job.status = JobStatus::Cancelled;
+        }
+        Err(e) => {
+            log::warn!("Failed to transcribe queued file {}: {}", job.path, e);
+            job.status = JobStatus::Failed {
+                error: e.to_string(),
+            };
+        }
+    });
+
+    queue.pump(app_handle);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transcript_path() {
+        assert_eq!(
+            transcript_path(Path::new("/recordings/standup.m4a")),
+            PathBuf::from("/recordings/standup.transcript.txt")
+        );
+    }
+}
@@ -1,10 +1,23 @@
 //! System tray management
 
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
 use tauri::image::Image;
-use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
 use tauri::tray::{TrayIcon, TrayIconBuilder};
 use tauri::{AppHandle, Emitter, Manager};
 
+use crate::history::HistoryManager;
+use crate::history_window;
+use crate::models::ModelManager;
+use crate::overlay;
+use crate::recording_manager::RecordingManager;
+use crate::settings;
+use crate::shortcut;
+use crate::window_state;
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum TrayIconState {
     Idle,
@@ -12,6 +25,44 @@ pub enum TrayIconState {
     Transcribing,
 }
 
+/// The state the tray icon shows, for putting it back after a blink
+static TRAY_STATE: Mutex<TrayIconState> = Mutex::new(TrayIconState::Idle);
+
+/// Whether the tray icon is blinking, so blinks don't overlap
+static BLINKING: AtomicBool = AtomicBool::new(false);
+
+/// Times the icon blinks, and how long each half of a blink lasts
+const BLINK_COUNT: usize = 2;
+const BLINK_PHASE_MS: u64 = 120;
+
+/// Latest input level while recording (0.0 to 1.0), as f32 bits
+static AUDIO_LEVEL: AtomicU32 = AtomicU32::new(0);
+
+/// Whether the recording animation is running
+static ANIMATING: AtomicBool = AtomicBool::new(false);
+
+/// Recordings started, so the animation restarts its timer for a new one
+static RECORDING_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// How often the recording animation redraws
+const ANIMATION_FRAME_MS: u64 = 250;
+
+/// Input level shown as a full meter. Speech is well below full scale.
+const FULL_METER_LEVEL: f32 = 0.25;
+
+/// Color of the level meter drawn on the recording icon (RGBA)
+const METER_COLOR: [u8; 4] = [255, 255, 255, 255];
+
+/// Transcriptions listed in the Recent submenu
+const RECENT_COUNT: usize = 5;
+
+/// Longest a transcription's label in the Recent submenu gets, in characters
+const RECENT_LABEL_CHARS: usize = 40;
+
+/// Menu item ids of recent transcriptions and models, followed by their id
+const RECENT_ID_PREFIX: &str = "recent:";
+const MODEL_ID_PREFIX: &str = "model:";
+
 fn get_icon_path(state: &TrayIconState) -> &'static str {
     match state {
         TrayIconState::Idle => "icons/tray_idle.png",
@@ -20,6 +71,15 @@ fn get_icon_path(state: &TrayIconState) -> &'static str {
     }
 }
 
+/// The tray tooltip for a state, when nothing more specific is shown
+fn state_tooltip(state: &TrayIconState) -> &'static str {
+    match state {
+        TrayIconState::Idle => "IndexVoice - Ready",
+        TrayIconState::Recording => "IndexVoice - Recording",
+        TrayIconState::Transcribing => "IndexVoice - Transcribing...",
+    }
+}
+
 /// Create the system tray icon and menu
 pub fn create_tray(app: &AppHandle) -> Result<TrayIcon, String> {
     let menu = build_tray_menu(app, &TrayIconState::Idle)?;
@@ -55,6 +115,102 @@ pub fn create_tray(app: &AppHandle) -> Result<TrayIcon, String> {
         .map_err(|e| format!("Failed to build tray icon: {}", e))
 }
 
+/// Label for a transcription in the Recent submenu: one line, shortened
+fn recent_label(text: &str) -> String {
+    let line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if line.chars().count() <= RECENT_LABEL_CHARS {
+        return line;
+    }
+    let short: String = line.chars().take(RECENT_LABEL_CHARS - 1).collect();
+    format!("{}…", short.trim_end())
+}
+
+/// Submenu of the last few transcriptions, copied again when clicked
+fn build_recent_submenu(app: &AppHandle) -> Result<Submenu<tauri::Wry>, String> {
+    let submenu =
+        Submenu::new(app, "Recent", true).map_err(|e| format!("Failed to create menu: {}", e))?;
+
+    let entries = app
+        .try_state::<Arc<HistoryManager>>()
+        .map(|history| history.get_page(0, RECENT_COUNT, None, None).entries)
+        .unwrap_or_default();
+
+    if entries.is_empty() {
+        let empty_item = MenuItem::with_id(
+            app,
+            "recent_empty",
+            "No transcriptions yet",
+            false,
+            None::<&str>,
+        )
+        .map_err(|e| format!("Failed to create menu item: {}", e))?;
+        submenu
+            .append(&empty_item)
+            .map_err(|e| format!("Failed to create menu: {}", e))?;
+    }
+
+    for entry in entries {
+        let item = MenuItem::with_id(
+            app,
+            format!("{}{}", RECENT_ID_PREFIX, entry.id),
+            recent_label(&entry.text),
+            true,
+            None::<&str>,
+        )
+        .map_err(|e| format!("Failed to create menu item: {}", e))?;
+        submenu
+            .append(&item)
+            .map_err(|e| format!("Failed to create menu: {}", e))?;
+    }
+
+    Ok(submenu)
+}
+
+/// Submenu to switch between the downloaded transcription models, with the
+/// selected one checked
+fn build_model_submenu(app: &AppHandle) -> Result<Submenu<tauri::Wry>, String> {
+    let submenu =
+        Submenu::new(app, "Model", true).map_err(|e| format!("Failed to create menu: {}", e))?;
+
+    // Listing the models before the models folder is scanned would scan it
+    // here, holding up the menu. It's rebuilt once the scan is done.
+    let models = app
+        .try_state::<Arc<ModelManager>>()
+        .filter(|manager| manager.is_scanned())
+        .map(|manager| manager.get_available_models());
+
+    let Some(mut models) = models else {
+        let loading_item =
+            MenuItem::with_id(app, "model_loading", "Loading...", false, None::<&str>)
+                .map_err(|e| format!("Failed to create menu item: {}", e))?;
+        submenu
+            .append(&loading_item)
+            .map_err(|e| format!("Failed to create menu: {}", e))?;
+        return Ok(submenu);
+    };
+
+    models.retain(|model| model.is_transcription_model() && model.is_downloaded);
+    models.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let selected = settings::get_settings(app).selected_model;
+    for model in models {
+        let item = CheckMenuItem::with_id(
+            app,
+            format!("{}{}", MODEL_ID_PREFIX, model.id),
+            &model.name,
+            true,
+            model.id == selected,
+            None::<&str>,
+        )
+        .map_err(|e| format!("Failed to create menu item: {}", e))?;
+        submenu
+            .append(&item)
+            .map_err(|e| format!("Failed to create menu: {}", e))?;
+    }
+
+    Ok(submenu)
+}
+
 fn build_tray_menu(app: &AppHandle, state: &TrayIconState) -> Result<Menu<tauri::Wry>, String> {
     let version_label = format!("IndexVoice v{}", env!("CARGO_PKG_VERSION"));
 
@@ -64,6 +220,18 @@ fn build_tray_menu(app: &AppHandle, state: &TrayIconState) -> Result<Menu<tauri:
     let settings_item = MenuItem::with_id(app, "settings", "Settings...", true, Some("Ctrl+,"))
         .map_err(|e| format!("Failed to create menu item: {}", e))?;
 
+    let history_item = MenuItem::with_id(app, "history", "History...", true, None::<&str>)
+        .map_err(|e| format!("Failed to create menu item: {}", e))?;
+
+    let position_overlay_item = MenuItem::with_id(
+        app,
+        "position_overlay",
+        "Position Overlay...",
+        true,
+        None::<&str>,
+    )
+    .map_err(|e| format!("Failed to create menu item: {}", e))?;
+
     let quit_item = MenuItem::with_id(app, "quit", "Quit", true, Some("Ctrl+Q"))
         .map_err(|e| format!("Failed to create menu item: {}", e))?;
 
@@ -75,9 +243,17 @@ fn build_tray_menu(app: &AppHandle, state: &TrayIconState) -> Result<Menu<tauri:
 
     match state {
         TrayIconState::Recording | TrayIconState::Transcribing => {
-            let cancel_item =
-                MenuItem::with_id(app, "cancel", "Cancel Recording", true, None::<&str>)
-                    .map_err(|e| format!("Failed to create menu item: {}", e))?;
+            let cancel_item = match state {
+                TrayIconState::Transcribing => MenuItem::with_id(
+                    app,
+                    "cancel_transcription",
+                    "Cancel Transcription",
+                    true,
+                    None::<&str>,
+                ),
+                _ => MenuItem::with_id(app, "cancel", "Cancel Recording", true, None::<&str>),
+            }
+            .map_err(|e| format!("Failed to create menu item: {}", e))?;
 
             Menu::with_items(
                 app,
@@ -86,17 +262,33 @@ fn build_tray_menu(app: &AppHandle, state: &TrayIconState) -> Result<Menu<tauri:
                     &separator,
                     &cancel_item,
                     &separator2,
+                    &history_item,
+                    &settings_item,
+                    &quit_item,
+                ],
+            )
+            .map_err(|e| format!("Failed to create menu: {}", e))
+        }
+        TrayIconState::Idle => {
+            let recent_submenu = build_recent_submenu(app)?;
+            let model_submenu = build_model_submenu(app)?;
+
+            Menu::with_items(
+                app,
+                &[
+                    &version_item,
+                    &separator,
+                    &recent_submenu,
+                    &model_submenu,
+                    &separator2,
+                    &history_item,
+                    &position_overlay_item,
                     &settings_item,
                     &quit_item,
                 ],
             )
             .map_err(|e| format!("Failed to create menu: {}", e))
         }
-        TrayIconState::Idle => Menu::with_items(
-            app,
-            &[&version_item, &separator, &settings_item, &quit_item],
-        )
-        .map_err(|e| format!("Failed to create menu: {}", e)),
     }
 }
 
@@ -113,32 +305,248 @@ fn handle_menu_event(app: &AppHandle, event: tauri::menu::MenuEvent) {
                 log::warn!("Main window not found!");
             }
         }
+        "history" => {
+            if let Err(e) = history_window::show_history_window(app) {
+                log::error!("{}", e);
+            }
+        }
+        "position_overlay" => {
+            if let Err(e) = overlay::start_positioning(app) {
+                log::warn!("{}", e);
+            }
+        }
         "cancel" => {
             let _ = app.emit("cancel-recording", ());
         }
+        "cancel_transcription" => shortcut::cancel_transcription(app),
         "quit" => {
+            window_state::save_main_window_layout(app);
             app.exit(0);
         }
-        _ => {}
+        id => {
+            if let Some(entry_id) = id.strip_prefix(RECENT_ID_PREFIX) {
+                copy_recent_transcription(app, entry_id);
+            } else if let Some(model_id) = id.strip_prefix(MODEL_ID_PREFIX) {
+                select_model(app, model_id.to_string());
+            }
+        }
     }
 }
 
-pub fn change_tray_icon(app: &AppHandle, state: TrayIconState) {
-    if let Some(tray) = app.tray_by_id("main") {
-        let icon_path = match app
+/// Copy a transcription from the Recent submenu to the clipboard
+fn copy_recent_transcription(app: &AppHandle, entry_id: &str) {
+    let Some(entry) = app
+        .try_state::<Arc<HistoryManager>>()
+        .and_then(|history| history.get_entry(entry_id))
+    else {
+        log::warn!("Recent transcription {} not found", entry_id);
+        return;
+    };
+    if let Err(e) = history_window::copy_text(app, &entry.text) {
+        log::error!("{}", e);
+    }
+}
+
+/// Switch to a model picked from the Model submenu, loading it in the
+/// background
+fn select_model(app: &AppHandle, model_id: String) {
+    let Some(manager) = app.try_state::<Arc<RecordingManager>>() else {
+        log::error!("RecordingManager not found in app state");
+        return;
+    };
+    let manager = Arc::clone(&manager);
+    let app = app.clone();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        set_model_loading(&app, true);
+        if let Err(e) = manager.set_selected_model(&model_id) {
+            log::error!("Failed to select model '{}': {}", model_id, e);
+            // Put the check mark back on the selected model
+            refresh_tray_menu(&app);
+        }
+        set_model_loading(&app, false);
+    });
+}
+
+/// Show whether the local model is still loading in the tray tooltip
+pub fn set_model_loading(app: &AppHandle, loading: bool) {
+    let Some(tray) = app.tray_by_id("main") else {
+        return;
+    };
+
+    let tooltip = if loading {
+        "IndexVoice - Loading model..."
+    } else {
+        "IndexVoice - Ready"
+    };
+    let _ = tray.set_tooltip(Some(tooltip));
+}
+
+/// Show in the tray tooltip that a transcription is taking long, or clear it
+pub fn set_transcription_slow(app: &AppHandle, slow: bool) {
+    let Some(tray) = app.tray_by_id("main") else {
+        return;
+    };
+
+    let tooltip = if slow {
+        "IndexVoice - Still working... (switch to a faster model?)"
+    } else {
+        "IndexVoice - Ready"
+    };
+    let _ = tray.set_tooltip(Some(tooltip));
+}
+
+/// Show a state's icon in the tray, without changing the menu
+fn set_tray_icon(app: &AppHandle, state: &TrayIconState) {
+    let Some(tray) = app.tray_by_id("main") else {
+        return;
+    };
+    let icon_path = match app
+        .path()
+        .resolve(get_icon_path(state), tauri::path::BaseDirectory::Resource)
+    {
+        Ok(p) => p,
+        Err(e) => {
+            log::error!("Failed to resolve icon path: {}", e);
+            return;
+        }
+    };
+
+    if let Ok(icon) = Image::from_path(&icon_path) {
+        let _ = tray.set_icon(Some(icon));
+    }
+}
+
+/// Note the input level, for the recording animation's meter
+pub fn set_audio_level(level: f32) {
+    AUDIO_LEVEL.store(level.to_bits(), Ordering::Relaxed);
+}
+
+/// The recording icon with a level meter along its bottom edge, filled
+/// `level` of the way across
+fn level_frame(base: &Image<'_>, level: f32) -> Image<'static> {
+    let (width, height) = (base.width() as usize, base.height() as usize);
+    let mut rgba = base.rgba().to_vec();
+    let meter_height = (height / 6).max(1);
+    let filled = (level.clamp(0.0, 1.0) * width as f32).round() as usize;
+
+    for y in height - meter_height..height {
+        for x in 0..filled {
+            let i = (y * width + x) * 4;
+            rgba[i..i + 4].copy_from_slice(&METER_COLOR);
+        }
+    }
+    Image::new_owned(rgba, width as u32, height as u32)
+}
+
+/// Animate the tray while recording: a level meter on the icon and the
+/// elapsed time. Stops once the state leaves Recording.
+fn start_recording_animation(app: &AppHandle) {
+    if ANIMATING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let base = app
             .path()
-            .resolve(get_icon_path(&state), tauri::path::BaseDirectory::Resource)
-        {
-            Ok(p) => p,
-            Err(e) => {
-                log::error!("Failed to resolve icon path: {}", e);
-                return;
+            .resolve(
+                get_icon_path(&TrayIconState::Recording),
+                tauri::path::BaseDirectory::Resource,
+            )
+            .ok()
+            .and_then(|path| Image::from_path(path).ok());
+        let mut recording = RECORDING_COUNT.load(Ordering::SeqCst);
+        let mut started = Instant::now();
+        let mut last_filled = None;
+        let mut last_secs = None;
+
+        while *TRAY_STATE.lock().unwrap() == TrayIconState::Recording {
+            let Some(tray) = app.tray_by_id("main") else {
+                break;
+            };
+
+            // A new recording started since the last frame
+            let count = RECORDING_COUNT.load(Ordering::SeqCst);
+            if count != recording {
+                recording = count;
+                started = Instant::now();
             }
-        };
 
-        if let Ok(icon) = Image::from_path(&icon_path) {
-            let _ = tray.set_icon(Some(icon));
+            // Only redraw when the meter moves, and leave blinks alone
+            let level = f32::from_bits(AUDIO_LEVEL.load(Ordering::Relaxed)) / FULL_METER_LEVEL;
+            if let Some(base) = &base {
+                let filled = (level.clamp(0.0, 1.0) * base.width() as f32).round() as u32;
+                if last_filled != Some(filled) && !BLINKING.load(Ordering::SeqCst) {
+                    let _ = tray.set_icon(Some(level_frame(base, level)));
+                    last_filled = Some(filled);
+                }
+            }
+
+            let secs = started.elapsed().as_secs();
+            if last_secs != Some(secs) {
+                let elapsed = format!("{}:{:02}", secs / 60, secs % 60);
+                let _ = tray.set_title(Some(&elapsed));
+                let _ = tray.set_tooltip(Some(&format!("IndexVoice - Recording {}", elapsed)));
+                last_secs = Some(secs);
+            }
+
+            tokio::time::sleep(Duration::from_millis(ANIMATION_FRAME_MS)).await;
+        }
+
+        // The state may have changed mid-frame
+        let current = TRAY_STATE.lock().unwrap().clone();
+        set_tray_icon(&app, &current);
+        if let Some(tray) = app.tray_by_id("main") {
+            let _ = tray.set_title(None::<&str>);
+            let _ = tray.set_tooltip(Some(state_tooltip(&current)));
+        }
+        AUDIO_LEVEL.store(0, Ordering::Relaxed);
+        ANIMATING.store(false, Ordering::SeqCst);
+
+        // A recording that started while this one wound down found the
+        // animation still running, so it's up to us to start it again
+        let recording = *TRAY_STATE.lock().unwrap() == TrayIconState::Recording;
+        if recording
+            && app.tray_by_id("main").is_some()
+            && settings::get_settings(&app).tray_animation
+        {
+            start_recording_animation(&app);
         }
+    });
+}
+
+/// Blink the tray icon, in place of a feedback sound: it briefly switches to
+/// the recording icon (or the idle one while recording) and back
+pub fn blink_tray_icon(app: &AppHandle) {
+    if BLINKING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        for _ in 0..BLINK_COUNT {
+            let blink = match *TRAY_STATE.lock().unwrap() {
+                TrayIconState::Idle => TrayIconState::Recording,
+                _ => TrayIconState::Idle,
+            };
+            set_tray_icon(&app, &blink);
+            tokio::time::sleep(Duration::from_millis(BLINK_PHASE_MS)).await;
+
+            // The state may have changed mid-blink
+            let current = TRAY_STATE.lock().unwrap().clone();
+            set_tray_icon(&app, &current);
+            tokio::time::sleep(Duration::from_millis(BLINK_PHASE_MS)).await;
+        }
+        BLINKING.store(false, Ordering::SeqCst);
+    });
+}
+
+pub fn change_tray_icon(app: &AppHandle, state: TrayIconState) {
+    *TRAY_STATE.lock().unwrap() = state.clone();
+
+    if let Some(tray) = app.tray_by_id("main") {
+        set_tray_icon(app, &state);
 
         if let Ok(menu) = build_tray_menu(app, &state) {
             let _ = tray.set_menu(Some(menu));
@@ -146,4 +554,50 @@ pub fn change_tray_icon(app: &AppHandle, state: TrayIconState) {
     } else {
         log::warn!("Tray icon not found");
     }
+
+    if state == TrayIconState::Recording && settings::get_settings(app).tray_animation {
+        RECORDING_COUNT.fetch_add(1, Ordering::SeqCst);
+        start_recording_animation(app);
+    }
+}
+
+/// Rebuild the tray menu for the current state, e.g. after the selected
+/// model or the downloaded models changed
+pub fn refresh_tray_menu(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id("main") else {
+        return;
+    };
+    let state = TRAY_STATE.lock().unwrap().clone();
+    match build_tray_menu(app, &state) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(e) => log::warn!("{}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_frame() {
+        let base = Image::new_owned(vec![0; 6 * 6 * 4], 6, 6);
+        let frame = level_frame(&base, 0.5);
+        let pixel = |x: usize, y: usize| &frame.rgba()[(y * 6 + x) * 4..(y * 6 + x) * 4 + 4];
+
+        // Half the bottom row is the meter, the rest is untouched
+        assert_eq!(pixel(2, 5), METER_COLOR);
+        assert_eq!(pixel(3, 5), [0; 4]);
+        assert_eq!(pixel(0, 4), [0; 4]);
+    }
+
+    #[test]
+    fn test_recent_label() {
+        assert_eq!(recent_label("Hello\nthere.  "), "Hello there.");
+        assert_eq!(
+            recent_label("The quick brown fox jumps over the lazy dog again."),
+            "The quick brown fox jumps over the lazy…"
+        );
+    }
 }
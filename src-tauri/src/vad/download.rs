@@ -8,6 +8,8 @@ use anyhow::Result;
 use futures_util::StreamExt;
 use tauri::{AppHandle, Emitter, Manager};
 
+use crate::event_throttle;
+
 pub const VAD_MODEL_NAME: &str = "silero_vad.onnx";
 
 pub const VAD_MODEL_URL: &str =
@@ -71,17 +73,25 @@ pub async fn ensure_vad_model(app_handle: &AppHandle) -> Result<PathBuf> {
         file.write_all(&chunk)?;
         downloaded += chunk.len() as u64;
 
-        // Emit progress event
+        // Emit progress event (coalesced by the event throttler)
         if total_size > 0 {
             let percentage = (downloaded as f64 / total_size as f64 * 100.0) as u32;
-            let _ = app_handle.emit(
-                "vad-model-download-progress",
-                serde_json::json!({
-                    "downloaded": downloaded,
-                    "total": total_size,
-                    "percentage": percentage
-                }),
-            );
+            let progress = serde_json::json!({
+                "downloaded": downloaded,
+                "total": total_size,
+                "percentage": percentage
+            });
+
+            if downloaded >= total_size {
+                event_throttle::emit_final(
+                    app_handle,
+                    "vad-model-download-progress",
+                    "vad-model-download-progress",
+                    progress,
+                );
+            } else {
+                event_throttle::emit(app_handle, "vad-model-download-progress", progress);
+            }
         }
     }
 
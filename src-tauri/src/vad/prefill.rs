@@ -0,0 +1,40 @@
+//! Persistence for the adaptive prefill learned from recordings
+
+use iv_core::vad::AdaptivePrefill;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::settings::SETTINGS_STORE_PATH;
+
+const ADAPTIVE_PREFILL_KEY: &str = "adaptive_prefill";
+
+pub fn load_adaptive_prefill(app: &AppHandle) -> AdaptivePrefill {
+    let Ok(store) = app.store(SETTINGS_STORE_PATH) else {
+        return AdaptivePrefill::default();
+    };
+
+    store
+        .get(ADAPTIVE_PREFILL_KEY)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_adaptive_prefill(app: &AppHandle, prefill: &AdaptivePrefill) {
+    let store = match app.store(SETTINGS_STORE_PATH) {
+        Ok(s) => s,
+        Err(e) => {
+            log::warn!("Failed to get settings store: {}", e);
+            return;
+        }
+    };
+
+    match serde_json::to_value(prefill) {
+        Ok(value) => {
+            store.set(ADAPTIVE_PREFILL_KEY, value);
+            if let Err(e) = store.save() {
+                log::warn!("Failed to save adaptive prefill: {}", e);
+            }
+        }
+        Err(e) => log::warn!("Failed to serialize adaptive prefill: {}", e),
+    }
+}
@@ -0,0 +1,318 @@
+//! Wake word activation
+//!
+//! Keeps a microphone stream open and listens for a spoken wake phrase
+//! ("start dictation" by default). Quiet audio is dropped by a cheap energy
+//! gate, the VAD cuts the rest into short utterances, and each utterance is
+//! transcribed with the local model and compared against the phrase. A match
+//! starts a hands-free recording that ends after a pause in speech.
+//!
+//! The listener ignores audio while a recording or transcription is in
+//! progress, so it never competes with the shortcut flow.
+
+use std::sync::{mpsc, Arc, Mutex};
+
+use tauri::{AppHandle, Manager};
+
+use iv_core::audio::{self, resample_to_16k, AudioRecorder};
+use iv_core::vad::{SileroVad, SmoothedVad, StreamingVad, VadParams, VadTransition};
+
+use crate::recording_manager::{self, ManagerState, RecordingManager};
+use crate::settings;
+use crate::shortcut;
+
+/// Utterances longer than this can't be the wake phrase
+const MAX_UTTERANCE_SECS: f32 = 3.0;
+
+/// Audio kept from before speech onset so the first syllable isn't clipped
+const PREROLL_SECS: f32 = 0.3;
+
+/// RMS below which audio is treated as silence without running the VAD
+const ENERGY_GATE: f32 = 0.005;
+
+/// Captured utterance handed to the transcription thread
+struct Utterance {
+    samples: Vec<f32>,
+    sample_rate: u32,
+}
+
+/// State of the capture callback, tracking the utterance in progress
+struct CaptureState {
+    vad: StreamingVad,
+    sample_rate: u32,
+    preroll: Vec<f32>,
+    utterance: Vec<f32>,
+    /// Utterance ran past the maximum length, skip it until silence
+    overflowed: bool,
+}
+
+impl CaptureState {
+    fn push(&mut self, samples: &[f32]) -> Option<Utterance> {
+        let max_samples = (MAX_UTTERANCE_SECS * self.sample_rate as f32) as usize;
+        let preroll_samples = (PREROLL_SECS * self.sample_rate as f32) as usize;
+
+        // Energy gate: don't wake the VAD for quiet audio between utterances
+        if !self.vad.is_speech() && rms(samples) < ENERGY_GATE {
+            self.push_preroll(samples, preroll_samples);
+            return None;
+        }
+
+        let transition = match self.vad.push_samples(samples) {
+            Ok(transition) => transition,
+            Err(e) => {
+                log::warn!("Wake word VAD error: {}", e);
+                return None;
+            }
+        };
+
+        match transition {
+            Some(VadTransition::SpeechStarted) => {
+                self.utterance = std::mem::take(&mut self.preroll);
+                self.utterance.extend_from_slice(samples);
+                self.overflowed = false;
+                None
+            }
+            Some(VadTransition::SilenceStarted) => {
+                let samples = std::mem::take(&mut self.utterance);
+                if self.overflowed || samples.is_empty() {
+                    return None;
+                }
+                Some(Utterance {
+                    samples,
+                    sample_rate: self.sample_rate,
+                })
+            }
+            None if self.vad.is_speech() => {
+                if !self.overflowed {
+                    self.utterance.extend_from_slice(samples);
+                    if self.utterance.len() > max_samples {
+                        self.overflowed = true;
+                        self.utterance.clear();
+                    }
+                }
+                None
+            }
+            None => {
+                self.push_preroll(samples, preroll_samples);
+                None
+            }
+        }
+    }
+
+    fn push_preroll(&mut self, samples: &[f32], max_len: usize) {
+        self.preroll.extend_from_slice(samples);
+        if self.preroll.len() > max_len {
+            let excess = self.preroll.len() - max_len;
+            self.preroll.drain(..excess);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.vad.reset();
+        self.preroll.clear();
+        self.utterance.clear();
+        self.overflowed = false;
+    }
+}
+
+#[derive(Default)]
+enum ListenerState {
+    #[default]
+    Stopped,
+    /// `start` is opening the microphone
+    Starting,
+    Listening(AudioRecorder),
+}
+
+#[derive(Default)]
+pub struct WakeWordListener {
+    state: Mutex<ListenerState>,
+}
+
+impl WakeWordListener {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_listening(&self) -> bool {
+        matches!(*self.state.lock().unwrap(), ListenerState::Listening(_))
+    }
+
+    /// Open the microphone and start listening for the wake phrase
+    pub async fn start(&self, app: &AppHandle) -> Result<(), anyhow::Error> {
+        // Claimed before the first await, so two starts can't both open it
+        {
+            let mut state = self.state.lock().unwrap();
+            if !matches!(*state, ListenerState::Stopped) {
+                return Ok(());
+            }
+            *state = ListenerState::Starting;
+        }
+
+        let opened = self.open(app).await;
+        let mut state = self.state.lock().unwrap();
+        match opened {
+            Ok(recorder) if matches!(*state, ListenerState::Starting) => {
+                *state = ListenerState::Listening(recorder);
+                log::info!("Listening for wake phrase");
+                Ok(())
+            }
+            // Stopped while it was opening
+            Ok(mut recorder) => {
+                let _ = recorder.stop();
+                let _ = recorder.close();
+                Ok(())
+            }
+            Err(e) => {
+                if matches!(*state, ListenerState::Starting) {
+                    *state = ListenerState::Stopped;
+                }
+                Err(e)
+            }
+        }
+    }
+
+    async fn open(&self, app: &AppHandle) -> Result<AudioRecorder, anyhow::Error> {
+        let manager = app
+            .try_state::<Arc<RecordingManager>>()
+            .map(|m| Arc::clone(&m))
+            .ok_or_else(|| anyhow::anyhow!("RecordingManager not found in app state"))?;
+
+        let vad_path = manager.ensure_vad_model().await?;
+        let params = VadParams::default();
+        let silero = SileroVad::new(&vad_path, params.threshold)?;
+        let vad = SmoothedVad::from_params(Box::new(silero), &params);
+
+        let (utterance_tx, utterance_rx) = mpsc::channel::<Utterance>();
+        spawn_matcher(app.clone(), Arc::clone(&manager), utterance_rx);
+
+        let capture: Arc<Mutex<Option<CaptureState>>> = Arc::new(Mutex::new(None));
+        let mut recorder = AudioRecorder::new()?;
+        // Audio only goes to the callback; buffering hours of it would never be used
+        recorder.set_buffer_samples(false);
+
+        let callback_capture = Arc::clone(&capture);
+        recorder.set_sample_callback(move |samples| {
+            let mut guard = callback_capture.lock().unwrap();
+            let Some(state) = guard.as_mut() else {
+                return;
+            };
+
+            // Stay out of the way while a recording is in progress
+            if manager.get_state() != ManagerState::Idle {
+                state.reset();
+                return;
+            }
+
+            if let Some(utterance) = state.push(samples) {
+                let _ = utterance_tx.send(utterance);
+            }
+        });
+
+        // The same microphone dictation uses, so its per-device config applies
+        let settings = settings::get_settings(app);
+        let (id, device) = audio::find_input_device(settings.selected_input_device.as_deref())?;
+        recorder.set_config_override(recording_manager::input_device_config(
+            &settings,
+            &id,
+            &audio::device_name(&device),
+        ));
+        recorder.open(Some(device))?;
+
+        *capture.lock().unwrap() = Some(CaptureState {
+            vad: StreamingVad::new(Box::new(vad), recorder.sample_rate()),
+            sample_rate: recorder.sample_rate(),
+            preroll: Vec::new(),
+            utterance: Vec::new(),
+            overflowed: false,
+        });
+
+        recorder.start()?;
+        Ok(recorder)
+    }
+
+    /// Stop listening and release the microphone, or stop a start under way
+    /// from listening once it's opened it
+    pub fn stop(&self) {
+        let state = std::mem::take(&mut *self.state.lock().unwrap());
+        if let ListenerState::Listening(mut recorder) = state {
+            let _ = recorder.stop();
+            let _ = recorder.close();
+            log::info!("Stopped listening for wake phrase");
+        }
+    }
+}
+
+/// Transcribe utterances off the audio thread and start recording on a match.
+/// Exits once the listener's recorder (and with it the sender) is dropped.
+fn spawn_matcher(app: AppHandle, manager: Arc<RecordingManager>, rx: mpsc::Receiver<Utterance>) {
+    std::thread::spawn(move || {
+        while let Ok(utterance) = rx.recv() {
+            let samples_16k = if utterance.sample_rate != 16000 {
+                resample_to_16k(&utterance.samples, utterance.sample_rate)
+            } else {
+                utterance.samples
+            };
+
+            let text = match manager.transcribe_snippet_locally(samples_16k) {
+                Ok(text) => text,
+                Err(e) => {
+                    log::warn!("Wake word transcription failed: {}", e);
+                    continue;
+                }
+            };
+
+            let phrase = settings::get_settings(&app).wake_phrase;
+            if matches_wake_phrase(&text, &phrase) {
+                log::info!("Wake phrase heard: '{}'", text);
+                shortcut::start_wake_word_recording(&app);
+            } else {
+                log::debug!("Ignoring utterance: '{}'", text);
+            }
+        }
+    });
+}
+
+fn normalize(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                ' '
+            }
+        })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether a transcript contains the wake phrase as whole words, ignoring
+/// case and punctuation
+fn matches_wake_phrase(text: &str, phrase: &str) -> bool {
+    let phrase = normalize(phrase);
+    !phrase.is_empty() && format!(" {} ", normalize(text)).contains(&format!(" {} ", phrase))
+}
+
+fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+/// Start or stop the listener to match the current settings
+pub async fn sync_with_settings(app: &AppHandle) {
+    let Some(listener) = app.try_state::<Arc<WakeWordListener>>() else {
+        return;
+    };
+    let listener = Arc::clone(&listener);
+
+    if settings::get_settings(app).wake_word_enabled {
+        if let Err(e) = listener.start(app).await {
+            log::error!("Failed to start wake word listener: {}", e);
+        }
+    } else {
+        listener.stop();
+    }
+}
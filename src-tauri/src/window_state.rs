@@ -0,0 +1,159 @@
+//! Main window layout persistence
+//!
+//! Remembers the main window's size, position, monitor and whether it was
+//! hidden to the tray, and restores them during setup before it is shown.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize};
+use tauri_plugin_store::StoreExt;
+
+use crate::settings::{self, SETTINGS_STORE_PATH};
+
+const WINDOW_LAYOUT_KEY: &str = "window_layout";
+
+/// How long the window has to stay put after a move or resize before the
+/// layout is saved, so dragging doesn't write the store on every step
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// Moves and resizes seen, so a pending save can tell it's been overtaken
+static LAYOUT_CHANGES: AtomicU64 = AtomicU64::new(0);
+
+/// Saved layout of the main window (physical pixels)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct WindowLayout {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    /// Name of the monitor the window was on
+    pub monitor: Option<String>,
+    pub maximized: bool,
+    /// Whether the window was hidden to the tray
+    pub hidden: bool,
+}
+
+fn load_layout(app: &AppHandle) -> Option<WindowLayout> {
+    let store = app.store(SETTINGS_STORE_PATH).ok()?;
+    let value = store.get(WINDOW_LAYOUT_KEY)?;
+    serde_json::from_value(value).ok()
+}
+
+fn write_layout(app: &AppHandle, layout: &WindowLayout) -> Result<(), String> {
+    let store = app
+        .store(SETTINGS_STORE_PATH)
+        .map_err(|e| format!("Failed to get settings store: {}", e))?;
+
+    let value = serde_json::to_value(layout)
+        .map_err(|e| format!("Failed to serialize window layout: {}", e))?;
+
+    store.set(WINDOW_LAYOUT_KEY, value);
+    store
+        .save()
+        .map_err(|e| format!("Failed to save window layout: {}", e))
+}
+
+/// Save the current layout of the main window
+pub fn save_main_window_layout(app: &AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+
+    let mut layout = load_layout(app).unwrap_or_default();
+    layout.maximized = window.is_maximized().unwrap_or(false);
+    layout.hidden = !window.is_visible().unwrap_or(true);
+
+    // Keep the restored (non-maximized) bounds so un-maximizing works after restart
+    if !layout.maximized && !window.is_minimized().unwrap_or(false) {
+        if let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) {
+            layout.x = position.x;
+            layout.y = position.y;
+            layout.width = size.width;
+            layout.height = size.height;
+        }
+        layout.monitor = window
+            .current_monitor()
+            .ok()
+            .flatten()
+            .and_then(|m| m.name().cloned());
+    }
+
+    if let Err(e) = write_layout(app, &layout) {
+        log::warn!("{}", e);
+    }
+}
+
+/// Save the layout once the main window has stopped moving or resizing
+pub fn schedule_layout_save(app: &AppHandle) {
+    let change = LAYOUT_CHANGES.fetch_add(1, Ordering::SeqCst) + 1;
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SAVE_DELAY).await;
+        if LAYOUT_CHANGES.load(Ordering::SeqCst) == change {
+            save_main_window_layout(&app);
+        }
+    });
+}
+
+/// Restore the saved layout and show the main window, unless it was hidden to
+/// the tray or the app is set to start minimized
+pub fn restore_main_window(app: &AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        log::warn!("Main window not found, cannot restore layout");
+        return;
+    };
+
+    let layout = load_layout(app);
+
+    match &layout {
+        Some(layout) => {
+            if layout.width > 0 && layout.height > 0 {
+                let _ = window.set_size(PhysicalSize::new(layout.width, layout.height));
+
+                // Only restore the position if it is still on a connected monitor
+                if is_on_available_monitor(app, layout) {
+                    let _ = window.set_position(PhysicalPosition::new(layout.x, layout.y));
+                } else {
+                    log::info!("Saved window position is off-screen, centering instead");
+                    let _ = window.center();
+                }
+            }
+
+            if layout.maximized {
+                let _ = window.maximize();
+            }
+        }
+        None => log::debug!("No saved window layout, using defaults"),
+    }
+
+    if settings::get_settings(app).start_minimized {
+        log::info!("Starting minimized to tray");
+    } else if layout.is_some_and(|l| l.hidden) {
+        log::info!("Main window was hidden to tray, keeping it hidden");
+    } else {
+        let _ = window.show();
+    }
+}
+
+fn is_on_available_monitor(app: &AppHandle, layout: &WindowLayout) -> bool {
+    let Ok(monitors) = app.available_monitors() else {
+        return false;
+    };
+
+    monitors.iter().any(|monitor| {
+        let pos = monitor.position();
+        let size = monitor.size();
+        let name_matches = match (&layout.monitor, monitor.name()) {
+            (Some(saved), Some(name)) => saved == name,
+            _ => true,
+        };
+
+        name_matches
+            && layout.x >= pos.x
+            && layout.x < pos.x + size.width as i32
+            && layout.y >= pos.y
+            && layout.y < pos.y + size.height as i32
+    })
+}
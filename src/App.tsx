@@ -1,5 +1,7 @@
 import { useCallback, useEffect, useRef, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
+import { getCurrentWebview } from "@tauri-apps/api/webview";
 import { toast, Toaster } from "sonner";
 import { Copy, Keyboard } from "lucide-react";
 import "./App.css";
@@ -96,14 +98,91 @@ export default function App() {
       });
     });
 
+    const unlistenCancelled = listen("recording-cancelled", () => {
+      setState("idle");
+      stopAudio();
+    });
+
+    // Audio streams are closed before sleep and the shortcuts registered
+    // again after it
+    const unlistenSuspending = listen("system-suspending", () => {
+      setState("idle");
+      stopAudio();
+    });
+
+    const unlistenResumed = listen<{
+      missing_device: string | null;
+      shortcuts_registered: boolean;
+    }>("system-resumed", (event) => {
+      setState("idle");
+      stopAudio();
+      if (event.payload.missing_device) {
+        toast.warning("Microphone not found after sleep", {
+          description: `${event.payload.missing_device} is disconnected, using the default input`,
+        });
+      }
+      if (!event.payload.shortcuts_registered) {
+        toast.error("Shortcuts couldn't be registered after sleep", {
+          description: "Restart IndexVoice to use them again",
+        });
+      }
+    });
+
     return () => {
       unlistenStarted.then((f) => f());
       unlistenStopped.then((f) => f());
       unlistenCompleted.then((f) => f());
       unlistenError.then((f) => f());
+      unlistenCancelled.then((f) => f());
+      unlistenSuspending.then((f) => f());
+      unlistenResumed.then((f) => f());
     };
   }, [startAudio, stopAudio]);
 
+  // Transcribe audio/video files dropped on the window
+  useEffect(() => {
+    const unlistenProgress = listen<{ chunk: number; total_chunks: number }>(
+      "file-transcription-progress",
+      (event) => {
+        const { chunk, total_chunks } = event.payload;
+        toast.loading(`Transcribing file (${chunk}/${total_chunks})`, { id: "file-transcription" });
+      },
+    );
+
+    const unlistenDrop = getCurrentWebview().onDragDropEvent(async (event) => {
+      if (event.payload.type !== "drop" || event.payload.paths.length === 0) return;
+
+      // Several files go to the background queue, saved next to the originals
+      const { paths } = event.payload;
+      if (paths.length > 1) {
+        await invoke("enqueue_transcriptions", { paths, saveTranscripts: true });
+        toast.success(`Queued ${paths.length} files for transcription`);
+        return;
+      }
+
+      const path = paths[0];
+      setState("talking");
+      toast.loading("Transcribing file...", { id: "file-transcription" });
+      try {
+        const result = await invoke<{ text: string }>("transcribe_file", { path });
+        setLastTranscription(result.text);
+        toast.success("File transcribed", { id: "file-transcription" });
+      } catch (error) {
+        toast.error("File transcription failed", {
+          id: "file-transcription",
+          description: String(error),
+        });
+      } finally {
+        setState("idle");
+      }
+    });
+
+    return () => {
+      unlistenProgress.then((f) => f());
+      unlistenDrop.then((f) => f());
+    };
+  }, []);
+
   // Simulated talking animation (for talking mode)
   const {
     levelRef: talkingLevelRef,
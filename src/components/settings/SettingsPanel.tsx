@@ -3,7 +3,7 @@ import { ChevronDown, ChevronUp, Cloud, Cpu, Download, Trash2, Check, Loader2, M
 import { cn } from "@/lib/utils";
 
 // Engine type from backend
-type EngineType = "Parakeet" | "Cloud";
+type EngineType = "Parakeet" | "Cloud" | "Diarization";
 
 // Model info from backend
 export interface ModelInfo {
@@ -16,6 +16,8 @@ export interface ModelInfo {
     is_downloaded: boolean;
     is_downloading: boolean;
     partial_size: number;
+    is_shared?: boolean;
+    is_recommended?: boolean;
     is_directory: boolean;
     engine_type: EngineType;
     accuracy_score: number;
@@ -40,7 +42,7 @@ interface SettingsPanelProps {
     vadDownloadProgress: number | null;
     onSelectModel: (modelId: string) => void;
     onDownloadModel: (modelId: string) => void;
-    onDeleteModel: (modelId: string) => void;
+    onDeleteModel: (modelId: string, removeShared?: boolean) => void;
     onToggleVad: () => void;
     error: string | null;
 }
@@ -106,22 +108,35 @@ export function SettingsPanel({
                             Transcription Model
                         </h3>
                         <div className="space-y-1">
-                            {models.map((model) => (
-                                <ModelCard
-                                    key={model.id}
-                                    model={model}
-                                    isSelected={selectedModel === model.id}
-                                    downloadProgress={
-                                        downloadProgress?.model_id === model.id
-                                            ? downloadProgress
-                                            : null
-                                    }
-                                    isModelLoading={isModelLoading && selectedModel === model.id}
-                                    onSelect={() => onSelectModel(model.id)}
-                                    onDownload={() => onDownloadModel(model.id)}
-                                    onDelete={() => onDeleteModel(model.id)}
-                                />
-                            ))}
+                            {models
+                                .filter((model) => model.engine_type !== "Diarization")
+                                .map((model) => (
+                                    <ModelCard
+                                        key={model.id}
+                                        model={model}
+                                        isSelected={selectedModel === model.id}
+                                        downloadProgress={
+                                            downloadProgress?.model_id === model.id
+                                                ? downloadProgress
+                                                : null
+                                        }
+                                        isModelLoading={isModelLoading && selectedModel === model.id}
+                                        onSelect={() => onSelectModel(model.id)}
+                                        onDownload={() => onDownloadModel(model.id)}
+                                        onDelete={() => {
+                                            // Other accounts may be using the machine-wide copy
+                                            if (!model.is_shared) {
+                                                onDeleteModel(model.id);
+                                            } else if (
+                                                window.confirm(
+                                                    `${model.name} is shared by every account on this computer. Remove it for all of them?`
+                                                )
+                                            ) {
+                                                onDeleteModel(model.id, true);
+                                            }
+                                        }}
+                                    />
+                                ))}
                         </div>
                     </div>
 
@@ -217,7 +232,10 @@ function ModelCard({
                     <div>
                         <p className="text-sm font-medium text-slate-200">{model.name}</p>
                         <p className="text-xs text-slate-400">
-                            {isCloud ? "OpenAI API" : `${model.size_mb} MB`}
+                            {isCloud
+                                ? "OpenAI API"
+                                : `${model.size_mb} MB${model.is_shared ? " · shared" : ""}`}
+                            {model.is_recommended && " · recommended by your team"}
                         </p>
                     </div>
                 </div>
@@ -0,0 +1,163 @@
+import React, { useCallback, useEffect, useState } from 'react';
+import ReactDOM from 'react-dom/client';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import './history.css';
+
+interface TranscriptSegment {
+    start: number;
+    end: number;
+    text: string;
+    speaker?: string;
+}
+
+interface HistoryEntry {
+    id: string;
+    timestamp: number;
+    text: string;
+    segments?: TranscriptSegment[];
+    model_id: string;
+    duration_secs: number;
+    audio_file: string | null;
+    source?: { device: string; sample_rate: number; stages: string[] };
+    paste_suppressed?: boolean;
+}
+
+interface HistoryPage {
+    entries: HistoryEntry[];
+    total: number;
+}
+
+const PAGE_SIZE = 25;
+
+function HistoryWindow() {
+    const [query, setQuery] = useState('');
+    const [page, setPage] = useState(0);
+    const [result, setResult] = useState<HistoryPage>({ entries: [], total: 0 });
+    const [selected, setSelected] = useState<Set<string>>(new Set());
+    const [status, setStatus] = useState('');
+
+    const load = useCallback(async () => {
+        const next = await invoke<HistoryPage>('get_history_page', {
+            offset: page * PAGE_SIZE,
+            limit: PAGE_SIZE,
+            query: query.trim() || null,
+        });
+        setResult(next);
+    }, [page, query]);
+
+    useEffect(() => {
+        load();
+    }, [load]);
+
+    useEffect(() => {
+        const unlisten = listen('history-updated', () => load());
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, [load]);
+
+    const run = async (action: Promise<unknown>, message: string) => {
+        try {
+            await action;
+            setStatus(message);
+        } catch (e) {
+            setStatus(String(e));
+        }
+    };
+
+    const toggle = (id: string) => {
+        const next = new Set(selected);
+        if (next.has(id)) {
+            next.delete(id);
+        } else {
+            next.add(id);
+        }
+        setSelected(next);
+    };
+
+    const exportSelection = () => {
+        const path = window.prompt('Export selected entries to file:', 'history-export.json');
+        if (path) {
+            run(
+                invoke('export_history_entries', { entryIds: [...selected], path }),
+                `Exported ${selected.size} entries`,
+            );
+        }
+    };
+
+    const pageCount = Math.max(1, Math.ceil(result.total / PAGE_SIZE));
+
+    return (
+        <div className="history">
+            <header>
+                <input
+                    type="search"
+                    placeholder="Search transcriptions"
+                    value={query}
+                    onChange={(e) => {
+                        setQuery(e.target.value);
+                        setPage(0);
+                    }}
+                />
+                <button disabled={selected.size === 0} onClick={exportSelection}>
+                    Export ({selected.size})
+                </button>
+            </header>
+
+            <ul>
+                {result.entries.map((entry) => (
+                    <li key={entry.id}>
+                        <input
+                            type="checkbox"
+                            checked={selected.has(entry.id)}
+                            onChange={() => toggle(entry.id)}
+                        />
+                        <div className="entry">
+                            <p>{entry.text}</p>
+                            <small>
+                                {new Date(entry.timestamp).toLocaleString()} · {entry.model_id}
+                                {entry.paste_suppressed && ' · not pasted (repeat)'}
+                            </small>
+                        </div>
+                        <div className="actions">
+                            <button
+                                onClick={() =>
+                                    run(invoke('copy_history_entry', { entryId: entry.id }), 'Copied')
+                                }
+                            >
+                                Copy
+                            </button>
+                            <button
+                                onClick={() =>
+                                    run(invoke('repaste_history_entry', { entryId: entry.id }), 'Pasted')
+                                }
+                            >
+                                Paste
+                            </button>
+                        </div>
+                    </li>
+                ))}
+                {result.entries.length === 0 && <li className="empty">No transcriptions found</li>}
+            </ul>
+
+            <footer>
+                <button disabled={page === 0} onClick={() => setPage(page - 1)}>
+                    Previous
+                </button>
+                <span>
+                    Page {page + 1} of {pageCount} · {status}
+                </span>
+                <button disabled={page + 1 >= pageCount} onClick={() => setPage(page + 1)}>
+                    Next
+                </button>
+            </footer>
+        </div>
+    );
+}
+
+ReactDOM.createRoot(document.getElementById('root')!).render(
+    <React.StrictMode>
+        <HistoryWindow />
+    </React.StrictMode>,
+);
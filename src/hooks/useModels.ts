@@ -12,7 +12,7 @@ interface UseModelsReturn {
     refreshModels: () => Promise<void>;
     selectModel: (modelId: string) => Promise<void>;
     downloadModel: (modelId: string) => Promise<void>;
-    deleteModel: (modelId: string) => Promise<void>;
+    deleteModel: (modelId: string, removeShared?: boolean) => Promise<void>;
 }
 
 export function useModels(): UseModelsReturn {
@@ -74,10 +74,10 @@ export function useModels(): UseModelsReturn {
     }, [refreshModels]);
 
     // Delete a model
-    const deleteModel = useCallback(async (modelId: string) => {
+    const deleteModel = useCallback(async (modelId: string, removeShared = false) => {
         try {
             setError(null);
-            await invoke("delete_model", { modelId });
+            await invoke("delete_model", { modelId, removeShared });
             await refreshModels();
         } catch (err) {
             console.error("Failed to delete model:", err);
@@ -130,6 +130,11 @@ export function useModels(): UseModelsReturn {
             setIsModelLoading(false);
         });
 
+        // The model can also be switched from the tray or the overlay
+        const unlistenSelected = listen<{ model_id: string }>("model-selected", (event) => {
+            setSelectedModel(event.payload.model_id);
+        });
+
         return () => {
             unlistenProgress.then((f) => f());
             unlistenStarted.then((f) => f());
@@ -137,6 +142,7 @@ export function useModels(): UseModelsReturn {
             unlistenFailed.then((f) => f());
             unlistenLoading.then((f) => f());
             unlistenLoaded.then((f) => f());
+            unlistenSelected.then((f) => f());
         };
     }, [refreshModels]);
 
@@ -1,19 +1,93 @@
 import React, { useState, useEffect } from 'react';
 import ReactDOM from 'react-dom/client';
+import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
+import { getCurrentWindow } from '@tauri-apps/api/window';
 import { Waveform } from './Waveform';
 import './overlay.css';
 
-type OverlayState = 'hidden' | 'recording' | 'transcribing';
+type OverlayErrorCode =
+    | 'no_api_key'
+    | 'model_missing'
+    | 'network'
+    | 'no_speech'
+    | 'unknown_command'
+    | 'nothing_to_edit'
+    | 'device_lost'
+    | 'interrupted'
+    | 'unknown';
+
+type OverlayStatePayload =
+    | 'hidden'
+    | 'recording'
+    | 'transcribing'
+    | 'done'
+    | 'positioning'
+    | { error: { code: OverlayErrorCode } };
+
+type OverlayState = 'hidden' | 'recording' | 'transcribing' | 'done' | 'error' | 'positioning';
+
+type RecordingTick = {
+    elapsed_secs: number;
+    partial_text: string | null;
+};
+
+type SlowTranscription = {
+    elapsed_secs: number;
+    model_id: string;
+    faster_model: { id: string; name: string } | null;
+};
+
+const ERROR_MESSAGES: Record<OverlayErrorCode, string> = {
+    no_api_key: 'API key missing or invalid',
+    model_missing: 'Model not downloaded',
+    network: 'Network unavailable',
+    no_speech: 'No speech detected',
+    unknown_command: 'Command not recognized',
+    nothing_to_edit: 'Nothing to edit',
+    device_lost: 'Microphone disconnected',
+    interrupted: 'Recording stopped by sleep',
+    unknown: 'Transcription failed',
+};
+
+/** Recording time as m:ss */
+function formatElapsed(secs: number): string {
+    const minutes = Math.floor(secs / 60);
+    const seconds = secs % 60;
+    return `${minutes}:${seconds.toString().padStart(2, '0')}`;
+}
 
 function RecordingOverlay() {
     const [state, setState] = useState<OverlayState>('hidden');
     const [audioLevel, setAudioLevel] = useState(0);
+    const [transcript, setTranscript] = useState('');
+    const [errorCode, setErrorCode] = useState<OverlayErrorCode>('unknown');
+    const [speechDetected, setSpeechDetected] = useState(false);
+    const [uploadPercent, setUploadPercent] = useState<number | null>(null);
+    const [slow, setSlow] = useState<SlowTranscription | null>(null);
+    const [flash, setFlash] = useState<string | null>(null);
+    const [elapsedSecs, setElapsedSecs] = useState(0);
+    const [partialText, setPartialText] = useState<string | null>(null);
+    const [modelId, setModelId] = useState<string | null>(null);
 
     useEffect(() => {
         // Listen for state changes from the backend
-        const unlisten = listen<OverlayState>('overlay-state-change', (event) => {
-            setState(event.payload);
+        const unlisten = listen<OverlayStatePayload>('overlay-state-change', (event) => {
+            const payload = event.payload;
+            setSlow(null);
+            if (typeof payload === 'object') {
+                setErrorCode(payload.error.code);
+                setState('error');
+            } else {
+                if (payload === 'recording') {
+                    setSpeechDetected(false);
+                    setUploadPercent(null);
+                    setElapsedSecs(0);
+                    setPartialText(null);
+                    invoke<string>('get_selected_model').then(setModelId);
+                }
+                setState(payload);
+            }
         });
 
         return () => {
@@ -32,32 +106,248 @@ function RecordingOverlay() {
         };
     }, []);
 
+    useEffect(() => {
+        // Live VAD tells us whether the mic is actually picking up speech
+        const unlistenSpeech = listen('speech-detected', () => setSpeechDetected(true));
+        const unlistenSilence = listen('silence-detected', () => setSpeechDetected(false));
+
+        return () => {
+            unlistenSpeech.then((fn) => fn());
+            unlistenSilence.then((fn) => fn());
+        };
+    }, []);
+
+    useEffect(() => {
+        // Recording time, and a preview of the transcript when it's enabled
+        const unlisten = listen<RecordingTick>('recording-tick', (event) => {
+            setElapsedSecs(event.payload.elapsed_secs);
+            setPartialText(event.payload.partial_text);
+        });
+
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, []);
+
+    useEffect(() => {
+        // Cloud uploads report progress, so long ones aren't an endless spinner
+        const unlisten = listen<{ percentage: number }>('upload-progress', (event) => {
+            setUploadPercent(event.payload.percentage);
+        });
+
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, []);
+
+    useEffect(() => {
+        // In a visual feedback mode the overlay flashes in place of each sound
+        let timeout: ReturnType<typeof setTimeout> | undefined;
+        const unlisten = listen<string>('overlay-flash', (event) => {
+            setFlash(null);
+            clearTimeout(timeout);
+            // Restart the animation on the next frame
+            requestAnimationFrame(() => setFlash(event.payload));
+            timeout = setTimeout(() => setFlash(null), 400);
+        });
+
+        return () => {
+            clearTimeout(timeout);
+            unlisten.then((fn) => fn());
+        };
+    }, []);
+
+    useEffect(() => {
+        // Long transcriptions offer a way out instead of spinning forever
+        const unlisten = listen<SlowTranscription>('transcription-slow', (event) => {
+            setSlow(event.payload);
+        });
+
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, []);
+
+    useEffect(() => {
+        // Keep the final transcript for the "done" state
+        const unlisten = listen<{ text: string }>('transcription-completed', (event) => {
+            setTranscript(event.payload.text);
+        });
+
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, []);
+
     if (state === 'hidden') {
         return null;
     }
 
+    if (state === 'done') {
+        return (
+            <div className="overlay-container done">
+                <span className="done-text" title={transcript}>
+                    {transcript.trim() || 'Done ✓'}
+                </span>
+            </div>
+        );
+    }
+
+    if (state === 'positioning') {
+        return (
+            <div
+                className="overlay-container positioning"
+                onMouseDown={(event) => {
+                    if (event.button === 0 && !(event.target instanceof HTMLButtonElement)) {
+                        getCurrentWindow().startDragging();
+                    }
+                }}
+            >
+                <span className="positioning-text">Drag to move</span>
+                <button
+                    className="positioning-done"
+                    onClick={() => invoke('finish_overlay_positioning')}
+                >
+                    Done
+                </button>
+            </div>
+        );
+    }
+
+    if (state === 'error') {
+        return (
+            <div className="overlay-container error" role="alert">
+                <span className="error-text">{ERROR_MESSAGES[errorCode]}</span>
+            </div>
+        );
+    }
+
     const isRecording = state === 'recording';
     const isTranscribing = state === 'transcribing';
+    const isUploading = isTranscribing && uploadPercent !== null && uploadPercent < 100;
+    const flashClass = flash ? ` flash flash-${flash}` : '';
+
+    if (isTranscribing && slow) {
+        const hint = slow.faster_model
+            ? `Still working… (switch to ${slow.faster_model.name}?)`
+            : 'Still working…';
+        return (
+            <div className={`overlay-container transcribing slow${flashClass}`}>
+                <span className="slow-text" title={hint}>
+                    {hint}
+                </span>
+                <button
+                    className="slow-cancel"
+                    onClick={() => invoke('cancel_transcription')}
+                    aria-label="Cancel transcription"
+                >
+                    Cancel
+                </button>
+            </div>
+        );
+    }
+
+    // Buttons inside the pill do their own thing instead of stopping
+    const stopClick = (event: React.MouseEvent) => event.stopPropagation();
 
     return (
-        <div className={`overlay-container ${state}`}>
-            <div className="waveform-wrapper">
-                <Waveform
-                    audioLevel={audioLevel}
-                    isActive={isRecording}
-                    isProcessing={isTranscribing}
-                    barWidth={3}
-                    barGap={1}
-                    sensitivity={2.5}
-                    fadeWidth={14}
+        <div
+            className={`overlay-container ${state}${flashClass}`}
+            onClick={() => isRecording && invoke('stop_recording_from_overlay')}
+            onContextMenu={(event) => {
+                event.preventDefault();
+                if (isRecording) {
+                    invoke('cancel_recording_from_overlay');
+                }
+            }}
+            title={isRecording ? 'Click to stop, right-click to cancel' : undefined}
+        >
+            {isRecording && (
+                <span
+                    className={`speech-indicator ${speechDetected ? 'active' : ''}`}
+                    title={speechDetected ? 'Speech detected' : 'No speech detected'}
                 />
-            </div>
+            )}
+            {isRecording && <span className="elapsed-text">{formatElapsed(elapsedSecs)}</span>}
+            {isUploading && <span className="upload-text">Uploading {uploadPercent}%…</span>}
+            {isRecording && partialText ? (
+                <div className="preview-wrapper" title={partialText}>
+                    <span className="preview-text">{partialText}</span>
+                </div>
+            ) : (
+                <div className="waveform-wrapper">
+                    <Waveform
+                        audioLevel={audioLevel}
+                        isActive={isRecording}
+                        isProcessing={isTranscribing}
+                        barWidth={3}
+                        barGap={1}
+                        sensitivity={2.5}
+                        fadeWidth={14}
+                    />
+                </div>
+            )}
+            {isRecording && modelId && (
+                <button
+                    className="model-badge"
+                    onClick={(event) => {
+                        stopClick(event);
+                        invoke<string>('toggle_cloud_model').then(setModelId).catch(() => {});
+                    }}
+                    aria-label="Switch between cloud and local model"
+                    title="Switch between cloud and local model"
+                >
+                    {modelId === 'cloud' ? 'Cloud' : 'Local'}
+                </button>
+            )}
+            {isRecording && (
+                <button
+                    className="cancel-button"
+                    onClick={(event) => {
+                        stopClick(event);
+                        invoke('cancel_recording_from_overlay');
+                    }}
+                    aria-label="Cancel recording"
+                    title="Cancel recording"
+                >
+                    ×
+                </button>
+            )}
         </div>
     );
 }
 
+type Announcement = { message: string; politeness: 'polite' | 'assertive' };
+
+/** Screen reader announcements from the backend, read out via live regions */
+function LiveRegion() {
+    const [announcement, setAnnouncement] = useState<Announcement | null>(null);
+
+    useEffect(() => {
+        const unlisten = listen<Announcement>('accessibility-announcement', (event) => {
+            setAnnouncement(event.payload);
+        });
+
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, []);
+
+    return (
+        <>
+            <div className="sr-only" aria-live="polite" role="status">
+                {announcement?.politeness === 'polite' ? announcement.message : ''}
+            </div>
+            <div className="sr-only" aria-live="assertive" role="alert">
+                {announcement?.politeness === 'assertive' ? announcement.message : ''}
+            </div>
+        </>
+    );
+}
+
 ReactDOM.createRoot(document.getElementById('root')!).render(
     <React.StrictMode>
         <RecordingOverlay />
+        <LiveRegion />
     </React.StrictMode>
 );
@@ -0,0 +1,114 @@
+import React, { useCallback, useEffect, useState } from 'react';
+import ReactDOM from 'react-dom/client';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import './review.css';
+
+interface TranscriptSegment {
+    start: number;
+    end: number;
+    text: string;
+    confidence?: number;
+}
+
+interface PendingReview {
+    result: { text: string; segments: TranscriptSegment[] };
+    binding_id: string;
+    timeout_secs: number;
+    confidence_threshold: number;
+}
+
+function ReviewWindow() {
+    const [review, setReview] = useState<PendingReview | null>(null);
+    const [text, setText] = useState('');
+    const [remaining, setRemaining] = useState(0);
+    // Editing stops the countdown so a correction is never thrown away
+    const [paused, setPaused] = useState(false);
+
+    const open = useCallback((next: PendingReview | null) => {
+        setReview(next);
+        setText(next?.result.text ?? '');
+        setRemaining(next?.timeout_secs ?? 0);
+        setPaused(false);
+    }, []);
+
+    useEffect(() => {
+        invoke<PendingReview | null>('get_pending_review').then(open);
+        const unlisten = listen<PendingReview>('review-requested', (event) => open(event.payload));
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, [open]);
+
+    const accept = useCallback(() => {
+        invoke('accept_review', { text }).catch(console.error);
+        setReview(null);
+    }, [text]);
+
+    const discard = useCallback(() => {
+        invoke('discard_review').catch(console.error);
+        setReview(null);
+    }, []);
+
+    useEffect(() => {
+        if (!review || paused || review.timeout_secs === 0) {
+            return;
+        }
+        if (remaining <= 0) {
+            discard();
+            return;
+        }
+        const timer = setTimeout(() => setRemaining(remaining - 1), 1000);
+        return () => clearTimeout(timer);
+    }, [review, paused, remaining, discard]);
+
+    if (!review) {
+        return null;
+    }
+
+    const unsure = review.result.segments.filter(
+        (s) => s.confidence !== undefined && s.confidence < review.confidence_threshold,
+    );
+
+    return (
+        <div
+            className="review"
+            onKeyDown={(e) => {
+                if (e.key === 'Enter' && (e.ctrlKey || e.metaKey)) {
+                    accept();
+                } else if (e.key === 'Escape') {
+                    discard();
+                }
+            }}
+        >
+            <textarea
+                autoFocus
+                value={text}
+                onChange={(e) => {
+                    setText(e.target.value);
+                    setPaused(true);
+                }}
+            />
+            {unsure.length > 0 && (
+                <p className="unsure">Unsure: {unsure.map((s) => `“${s.text}”`).join(', ')}</p>
+            )}
+            <footer>
+                <span>
+                    {review.timeout_secs > 0 && !paused
+                        ? `Discarding in ${remaining}s`
+                        : 'Ctrl+Enter to paste · Esc to discard'}
+                </span>
+                <button onClick={discard}>Discard</button>
+                <button className="primary" disabled={!text.trim()} onClick={accept}>
+                    Accept
+                </button>
+            </footer>
+        </div>
+    );
+}
+
+ReactDOM.createRoot(document.getElementById('root')!).render(
+    <React.StrictMode>
+        <ReviewWindow />
+    </React.StrictMode>,
+);
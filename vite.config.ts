@@ -20,6 +20,8 @@ export default defineConfig(async () => ({
       input: {
         main: resolve(__dirname, "index.html"),
         overlay: resolve(__dirname, "src/overlay/index.html"),
+        history: resolve(__dirname, "src/history/index.html"),
+        review: resolve(__dirname, "src/review/index.html"),
       }
     }
   },